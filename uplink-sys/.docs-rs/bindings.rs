@@ -0,0 +1,568 @@
+pub type uplink_const_char = ::std::os::raw::c_char;
+pub type GoInt = i64;
+pub type GoUint8 = u8;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkHandle {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkAccess {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkProject {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkDownload {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUpload {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkEncryptionKey {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPartUpload {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkConfig {
+    pub user_agent: *const ::std::os::raw::c_char,
+    pub dial_timeout_milliseconds: i32,
+    pub temp_directory: *const ::std::os::raw::c_char,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkBucket {
+    pub name: *mut ::std::os::raw::c_char,
+    pub created: i64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkSystemMetadata {
+    pub created: i64,
+    pub expires: i64,
+    pub content_length: i64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkCustomMetadataEntry {
+    pub key: *mut ::std::os::raw::c_char,
+    pub key_length: usize,
+    pub value: *mut ::std::os::raw::c_char,
+    pub value_length: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkCustomMetadata {
+    pub entries: *mut UplinkCustomMetadataEntry,
+    pub count: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkObject {
+    pub key: *mut ::std::os::raw::c_char,
+    pub is_prefix: bool,
+    pub system: UplinkSystemMetadata,
+    pub custom: UplinkCustomMetadata,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadOptions {
+    pub expires: i64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkDownloadOptions {
+    pub offset: i64,
+    pub length: i64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkListObjectsOptions {
+    pub prefix: *const ::std::os::raw::c_char,
+    pub cursor: *const ::std::os::raw::c_char,
+    pub recursive: bool,
+    pub system: bool,
+    pub custom: bool,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkListUploadsOptions {
+    pub prefix: *const ::std::os::raw::c_char,
+    pub cursor: *const ::std::os::raw::c_char,
+    pub recursive: bool,
+    pub system: bool,
+    pub custom: bool,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkListBucketsOptions {
+    pub cursor: *const ::std::os::raw::c_char,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkObjectIterator {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkBucketIterator {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadIterator {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPartIterator {
+    pub _handle: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPermission {
+    pub allow_download: bool,
+    pub allow_upload: bool,
+    pub allow_list: bool,
+    pub allow_delete: bool,
+    pub not_before: i64,
+    pub not_after: i64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPart {
+    pub part_number: u32,
+    pub size: usize,
+    pub modified: i64,
+    pub etag: *mut ::std::os::raw::c_char,
+    pub etag_length: usize,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkSharePrefix {
+    pub bucket: *const ::std::os::raw::c_char,
+    pub prefix: *const ::std::os::raw::c_char,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkError {
+    pub code: i32,
+    pub message: *mut ::std::os::raw::c_char,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkAccessResult {
+    pub access: *mut UplinkAccess,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkProjectResult {
+    pub project: *mut UplinkProject,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkBucketResult {
+    pub bucket: *mut UplinkBucket,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkObjectResult {
+    pub object: *mut UplinkObject,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadResult {
+    pub upload: *mut UplinkUpload,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPartUploadResult {
+    pub part_upload: *mut UplinkPartUpload,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkDownloadResult {
+    pub download: *mut UplinkDownload,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkWriteResult {
+    pub bytes_written: usize,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkReadResult {
+    pub bytes_read: usize,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkStringResult {
+    pub string: *mut ::std::os::raw::c_char,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkEncryptionKeyResult {
+    pub encryption_key: *mut UplinkEncryptionKey,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadInfo {
+    pub upload_id: *mut ::std::os::raw::c_char,
+    pub key: *mut ::std::os::raw::c_char,
+    pub is_prefix: bool,
+    pub system: UplinkSystemMetadata,
+    pub custom: UplinkCustomMetadata,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadInfoResult {
+    pub info: *mut UplinkUploadInfo,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkCommitUploadOptions {
+    pub custom_metadata: UplinkCustomMetadata,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkCommitUploadResult {
+    pub object: *mut UplinkObject,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkPartResult {
+    pub part: *mut UplinkPart,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkListUploadPartsOptions {
+    pub cursor: u32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeConfig {
+    pub auth_service_address: *const ::std::os::raw::c_char,
+    pub certificate_pem: *const ::std::os::raw::c_char,
+    pub insecure_unencrypted_connection: bool,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeRegisterAccessOptions {
+    pub is_public: bool,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeCredentials {
+    pub access_key_id: *const ::std::os::raw::c_char,
+    pub secret_key: *const ::std::os::raw::c_char,
+    pub endpoint: *const ::std::os::raw::c_char,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeCredentialsResult {
+    pub credentials: *mut EdgeCredentials,
+    pub error: *mut UplinkError,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeShareURLOptions {
+    pub raw: bool,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkMoveObjectOptions {
+    pub _address: u8,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkUploadObjectMetadataOptions {
+    pub _address: u8,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct UplinkCopyObjectOptions {
+    pub _address: u8,
+}
+pub const UPLINK_ERROR_INTERNAL: u32 = 0x02;
+pub const UPLINK_ERROR_CANCELED: u32 = 0x03;
+pub const UPLINK_ERROR_INVALID_HANDLE: u32 = 0x04;
+pub const UPLINK_ERROR_TOO_MANY_REQUESTS: u32 = 0x05;
+pub const UPLINK_ERROR_BANDWIDTH_LIMIT_EXCEEDED: u32 = 0x06;
+pub const UPLINK_ERROR_STORAGE_LIMIT_EXCEEDED: u32 = 0x07;
+pub const UPLINK_ERROR_SEGMENTS_LIMIT_EXCEEDED: u32 = 0x08;
+pub const UPLINK_ERROR_PERMISSION_DENIED: u32 = 0x09;
+pub const UPLINK_ERROR_BUCKET_NAME_INVALID: u32 = 0x10;
+pub const UPLINK_ERROR_BUCKET_ALREADY_EXISTS: u32 = 0x11;
+pub const UPLINK_ERROR_BUCKET_NOT_EMPTY: u32 = 0x12;
+pub const UPLINK_ERROR_BUCKET_NOT_FOUND: u32 = 0x13;
+pub const UPLINK_ERROR_OBJECT_KEY_INVALID: u32 = 0x20;
+pub const UPLINK_ERROR_OBJECT_NOT_FOUND: u32 = 0x21;
+pub const UPLINK_ERROR_UPLOAD_DONE: u32 = 0x22;
+pub const EDGE_ERROR_AUTH_DIAL_FAILED: u32 = 0x30;
+pub const EDGE_ERROR_REGISTER_ACCESS_FAILED: u32 = 0x31;
+extern "C" {
+    pub fn uplink_parse_access(accessString: *const uplink_const_char) -> UplinkAccessResult;
+    pub fn uplink_request_access_with_passphrase(
+        satellite_address: *const uplink_const_char,
+        api_key: *const uplink_const_char,
+        passphrase: *const uplink_const_char,
+    ) -> UplinkAccessResult;
+    pub fn uplink_access_satellite_address(access: *mut UplinkAccess) -> UplinkStringResult;
+    pub fn uplink_access_serialize(access: *mut UplinkAccess) -> UplinkStringResult;
+    pub fn uplink_access_share(
+        access: *mut UplinkAccess,
+        permission: UplinkPermission,
+        prefixes: *mut UplinkSharePrefix,
+        prefixes_count: GoInt,
+    ) -> UplinkAccessResult;
+    pub fn uplink_access_override_encryption_key(
+        access: *mut UplinkAccess,
+        bucket: *const uplink_const_char,
+        prefix: *const uplink_const_char,
+        encryptionKey: *mut UplinkEncryptionKey,
+    ) -> *mut UplinkError;
+    pub fn uplink_free_string_result(result: UplinkStringResult);
+    pub fn uplink_free_access_result(result: UplinkAccessResult);
+    pub fn uplink_stat_bucket(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+    ) -> UplinkBucketResult;
+    pub fn uplink_create_bucket(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+    ) -> UplinkBucketResult;
+    pub fn uplink_ensure_bucket(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+    ) -> UplinkBucketResult;
+    pub fn uplink_delete_bucket(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+    ) -> UplinkBucketResult;
+    pub fn uplink_delete_bucket_with_objects(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+    ) -> UplinkBucketResult;
+    pub fn uplink_free_bucket_result(result: UplinkBucketResult);
+    pub fn uplink_free_bucket(bucket: *mut UplinkBucket);
+    pub fn uplink_list_buckets(
+        project: *mut UplinkProject,
+        options: *mut UplinkListBucketsOptions,
+    ) -> *mut UplinkBucketIterator;
+    pub fn uplink_bucket_iterator_next(iterator: *mut UplinkBucketIterator) -> bool;
+    pub fn uplink_bucket_iterator_err(iterator: *mut UplinkBucketIterator) -> *mut UplinkError;
+    pub fn uplink_bucket_iterator_item(iterator: *mut UplinkBucketIterator) -> *mut UplinkBucket;
+    pub fn uplink_free_bucket_iterator(iterator: *mut UplinkBucketIterator);
+    pub fn uplink_config_request_access_with_passphrase(
+        config: UplinkConfig,
+        satellite_address: *const uplink_const_char,
+        api_key: *const uplink_const_char,
+        passphrase: *const uplink_const_char,
+    ) -> UplinkAccessResult;
+    pub fn uplink_config_open_project(
+        config: UplinkConfig,
+        access: *mut UplinkAccess,
+    ) -> UplinkProjectResult;
+    pub fn uplink_copy_object(
+        project: *mut UplinkProject,
+        old_bucket_name: *const uplink_const_char,
+        old_object_key: *const uplink_const_char,
+        new_bucket_name: *const uplink_const_char,
+        new_object_key: *const uplink_const_char,
+        options: *mut UplinkCopyObjectOptions,
+    ) -> UplinkObjectResult;
+    pub fn uplink_download_object(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        options: *mut UplinkDownloadOptions,
+    ) -> UplinkDownloadResult;
+    pub fn uplink_download_read(
+        download: *mut UplinkDownload,
+        bytes: *mut ::std::os::raw::c_void,
+        length: usize,
+    ) -> UplinkReadResult;
+    pub fn uplink_download_info(download: *mut UplinkDownload) -> UplinkObjectResult;
+    pub fn uplink_free_read_result(result: UplinkReadResult);
+    pub fn uplink_close_download(download: *mut UplinkDownload) -> *mut UplinkError;
+    pub fn uplink_free_download_result(result: UplinkDownloadResult);
+    pub fn edge_register_access(
+        config: EdgeConfig,
+        access: *mut UplinkAccess,
+        options: *mut EdgeRegisterAccessOptions,
+    ) -> EdgeCredentialsResult;
+    pub fn edge_free_credentials_result(result: EdgeCredentialsResult);
+    pub fn edge_free_credentials(credentials: *mut EdgeCredentials);
+    pub fn edge_join_share_url(
+        baseURL: *const uplink_const_char,
+        accessKeyID: *const uplink_const_char,
+        bucket: *const uplink_const_char,
+        key: *const uplink_const_char,
+        options: *mut EdgeShareURLOptions,
+    ) -> UplinkStringResult;
+    pub fn uplink_derive_encryption_key(
+        passphrase: *const uplink_const_char,
+        salt: *mut ::std::os::raw::c_void,
+        length: usize,
+    ) -> UplinkEncryptionKeyResult;
+    pub fn uplink_free_encryption_key_result(result: UplinkEncryptionKeyResult);
+    pub fn uplink_free_error(err: *mut UplinkError);
+    pub fn uplink_internal_UniverseIsEmpty() -> GoUint8;
+    pub fn uplink_move_object(
+        project: *mut UplinkProject,
+        old_bucket_name: *const uplink_const_char,
+        old_object_key: *const uplink_const_char,
+        new_bucket_name: *const uplink_const_char,
+        new_object_key: *const uplink_const_char,
+        options: *mut UplinkMoveObjectOptions,
+    ) -> *mut UplinkError;
+    pub fn uplink_begin_upload(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        options: *mut UplinkUploadOptions,
+    ) -> UplinkUploadInfoResult;
+    pub fn uplink_free_upload_info_result(result: UplinkUploadInfoResult);
+    pub fn uplink_free_upload_info(info: *mut UplinkUploadInfo);
+    pub fn uplink_commit_upload(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        upload_id: *const uplink_const_char,
+        options: *mut UplinkCommitUploadOptions,
+    ) -> UplinkCommitUploadResult;
+    pub fn uplink_free_commit_upload_result(result: UplinkCommitUploadResult);
+    pub fn uplink_abort_upload(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        upload_id: *const uplink_const_char,
+    ) -> *mut UplinkError;
+    pub fn uplink_upload_part(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        upload_id: *const uplink_const_char,
+        part_number: u32,
+    ) -> UplinkPartUploadResult;
+    pub fn uplink_part_upload_write(
+        upload: *mut UplinkPartUpload,
+        bytes: *mut ::std::os::raw::c_void,
+        length: usize,
+    ) -> UplinkWriteResult;
+    pub fn uplink_part_upload_commit(upload: *mut UplinkPartUpload) -> *mut UplinkError;
+    pub fn uplink_part_upload_abort(upload: *mut UplinkPartUpload) -> *mut UplinkError;
+    pub fn uplink_part_upload_set_etag(
+        upload: *mut UplinkPartUpload,
+        etag: *const uplink_const_char,
+    ) -> *mut UplinkError;
+    pub fn uplink_part_upload_info(upload: *mut UplinkPartUpload) -> UplinkPartResult;
+    pub fn uplink_free_part_result(result: UplinkPartResult);
+    pub fn uplink_free_part_upload_result(result: UplinkPartUploadResult);
+    pub fn uplink_free_part(part: *mut UplinkPart);
+    pub fn uplink_list_uploads(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        options: *mut UplinkListUploadsOptions,
+    ) -> *mut UplinkUploadIterator;
+    pub fn uplink_upload_iterator_next(iterator: *mut UplinkUploadIterator) -> bool;
+    pub fn uplink_upload_iterator_err(iterator: *mut UplinkUploadIterator) -> *mut UplinkError;
+    pub fn uplink_upload_iterator_item(
+        iterator: *mut UplinkUploadIterator,
+    ) -> *mut UplinkUploadInfo;
+    pub fn uplink_free_upload_iterator(iterator: *mut UplinkUploadIterator);
+    pub fn uplink_list_upload_parts(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        upload_id: *const uplink_const_char,
+        options: *mut UplinkListUploadPartsOptions,
+    ) -> *mut UplinkPartIterator;
+    pub fn uplink_part_iterator_next(iterator: *mut UplinkPartIterator) -> bool;
+    pub fn uplink_part_iterator_err(iterator: *mut UplinkPartIterator) -> *mut UplinkError;
+    pub fn uplink_part_iterator_item(iterator: *mut UplinkPartIterator) -> *mut UplinkPart;
+    pub fn uplink_free_part_iterator(iterator: *mut UplinkPartIterator);
+    pub fn uplink_stat_object(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+    ) -> UplinkObjectResult;
+    pub fn uplink_delete_object(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+    ) -> UplinkObjectResult;
+    pub fn uplink_free_object_result(obj: UplinkObjectResult);
+    pub fn uplink_free_object(obj: *mut UplinkObject);
+    pub fn uplink_update_object_metadata(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        new_metadata: UplinkCustomMetadata,
+        options: *mut UplinkUploadObjectMetadataOptions,
+    ) -> *mut UplinkError;
+    pub fn uplink_list_objects(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        options: *mut UplinkListObjectsOptions,
+    ) -> *mut UplinkObjectIterator;
+    pub fn uplink_object_iterator_next(iterator: *mut UplinkObjectIterator) -> bool;
+    pub fn uplink_object_iterator_err(iterator: *mut UplinkObjectIterator) -> *mut UplinkError;
+    pub fn uplink_object_iterator_item(iterator: *mut UplinkObjectIterator) -> *mut UplinkObject;
+    pub fn uplink_free_object_iterator(iterator: *mut UplinkObjectIterator);
+    pub fn uplink_open_project(access: *mut UplinkAccess) -> UplinkProjectResult;
+    pub fn uplink_close_project(project: *mut UplinkProject) -> *mut UplinkError;
+    pub fn uplink_revoke_access(
+        project: *mut UplinkProject,
+        access: *mut UplinkAccess,
+    ) -> *mut UplinkError;
+    pub fn uplink_free_project_result(result: UplinkProjectResult);
+    pub fn uplink_upload_object(
+        project: *mut UplinkProject,
+        bucket_name: *const uplink_const_char,
+        object_key: *const uplink_const_char,
+        options: *mut UplinkUploadOptions,
+    ) -> UplinkUploadResult;
+    pub fn uplink_upload_write(
+        upload: *mut UplinkUpload,
+        bytes: *mut ::std::os::raw::c_void,
+        length: usize,
+    ) -> UplinkWriteResult;
+    pub fn uplink_upload_commit(upload: *mut UplinkUpload) -> *mut UplinkError;
+    pub fn uplink_upload_abort(upload: *mut UplinkUpload) -> *mut UplinkError;
+    pub fn uplink_upload_info(upload: *mut UplinkUpload) -> UplinkObjectResult;
+    pub fn uplink_upload_set_custom_metadata(
+        upload: *mut UplinkUpload,
+        custom: UplinkCustomMetadata,
+    ) -> *mut UplinkError;
+    pub fn uplink_free_write_result(result: UplinkWriteResult);
+    pub fn uplink_free_upload_result(result: UplinkUploadResult);
+}
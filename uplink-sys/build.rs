@@ -1,7 +1,10 @@
 extern crate bindgen;
 
 use std::env;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
@@ -10,59 +13,61 @@ fn main() {
     // Directory containing uplink-c project source
     let uplink_c_src = PathBuf::from("uplink-c");
 
-    // Don't compile the uplink-c libraries when building the docs for not requiring Go to be
-    // installed in the Docker image for building them used by docs.rs
-    if env::var("DOCS_RS").is_err() {
-        // Build uplink-c generates precompiled lib and header files in .build directory.
-        // We execute the command in its directory because go build, from v1.18, embeds version control
-        // information and the command fails if `-bildvcs=false` isn't set. We don't want to pass the
-        // command-line flag because then it would fail when using a previous Go version.
-        // Copying and building from a copy it doesn't work because it's a git submodule, hence it uses
-        // a relative path to the superproject unless that the destination path is under the same
-        // parent tree directory and with the same depth.
-        Command::new("make")
-            .arg("build")
-            .current_dir(&uplink_c_src)
-            .status()
-            .expect("Failed to run make command from build.rs.");
+    // N.B.: `CARGO_CFG_TARGET_OS` and `CARGO_CFG_TARGET_ENV` should be read instead of
+    // `cfg(target_os = "...")` and `cfg(target_env = "...")`. The latter detect the host that is
+    // building the `build.rs` script, not the target.
+    let target_os = env::var("CARGO_CFG_TARGET_OS").expect("CARGO_CFG_TARGET_OS is not defined");
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+
+    // musl targets require a C compiler that targets musl, see `build_uplink_c`.
+    let is_musl = target_env == "musl";
+    // Go can only produce a static archive compatible with the GNU toolchain on Windows.
+    let is_windows = target_os == "windows";
+    if is_windows && target_env != "gnu" {
+        panic!(
+            "uplink-sys only supports the GNU toolchain on Windows (e.g. the \
+            x86_64-pc-windows-gnu target) because Go cannot build uplink-c as a static library \
+            compatible with the {} toolchain.",
+            target_env
+        );
+    }
+    println!("cargo:rerun-if-env-changed=UPLINK_SYS_CC");
+    println!("cargo:rerun-if-env-changed=GOFLAGS");
+    println!("cargo:rerun-if-env-changed=GOPATH");
+    println!("cargo:rerun-if-env-changed=GOMODCACHE");
+    println!("cargo:rerun-if-env-changed=GOPROXY");
+    // Rebuild when the uplink-c sources change. The headers that bindgen parses are generated in
+    // OUT_DIR on every build, so they aren't tracked.
+    println!("cargo:rerun-if-changed={}", uplink_c_src.to_string_lossy());
+    println!("cargo:rerun-if-changed=.docs-rs");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var("CARGO_FEATURE_STUB_FFI").is_ok() {
+        stub_ffi(&uplink_c_src, &out_dir);
+        return;
     }
 
     // Directory containing uplink-c project for building
     let uplink_c_dir = out_dir.join("uplink-c");
-    // Copy project to OUT_DIR for building
-    Command::new("cp")
-        .args([
-            "-Rf",
-            &uplink_c_src.to_string_lossy(),
-            &uplink_c_dir.to_string_lossy(),
-        ])
-        .status()
-        .expect("Failed to copy uplink-c directory.");
+    // Copy project to OUT_DIR for building, so the source checkout is never modified, which is
+    // required when it's read-only (e.g. the crates.io package) and for building several targets
+    // concurrently.
+    copy_uplink_c_src(&uplink_c_src, &uplink_c_dir).expect("Failed to copy uplink-c directory.");
 
     if env::var("DOCS_RS").is_ok() {
-        // Use the precompiled uplink-c libraries for building the docs by docs.rs.
-        Command::new("cp")
-            .args([
-                "-R",
-                &PathBuf::from(".docs-rs").to_string_lossy(),
-                &uplink_c_dir.join(".build").to_string_lossy(),
-            ])
-            .status()
+        // Use the precompiled uplink-c libraries for building the docs by docs.rs, so Go isn't
+        // required to be installed in the Docker image used by docs.rs.
+        copy_dir_all(Path::new(".docs-rs"), &uplink_c_dir.join(".build"))
             .expect("Failed to copy docs-rs precompiled uplink-c lib binaries");
     } else {
-        // Delete the generated build files for avoiding `cargo publish` to complain about modifying
-        // things outside of the OUT_DIR.
-        Command::new("rm")
-            .args(["-r", &uplink_c_src.join(".build").to_string_lossy()])
-            .status()
-            .expect("Failed to delete  uplink-c/.build directory.");
+        build_uplink_c(&uplink_c_src, &uplink_c_dir, is_windows, is_musl);
     }
 
     // Directory containing uplink-c build
     let uplink_c_build = uplink_c_dir.join(".build");
 
     // Header file with complete API interface
-    let uplink_c_header = uplink_c_build.join("uplink/uplink.h");
+    let uplink_c_header = uplink_c_build.join("uplink").join("uplink.h");
 
     // Link (statically) to uplink-c library during build
     println!("cargo:rustc-link-lib=static=uplink");
@@ -73,20 +78,41 @@ fn main() {
         uplink_c_build.to_string_lossy()
     );
 
-    // Make uplink-c interface header a dependency of the build
+    // Expose the uplink-c version and the Go version used to build it through the constants defined
+    // in the crate root.
+    println!("cargo:rerun-if-env-changed=UPLINK_C_VERSION");
     println!(
-        "cargo:rerun-if-changed={}",
-        uplink_c_header.to_string_lossy()
+        "cargo:rustc-env=UPLINK_C_VERSION={}",
+        uplink_c_version(&uplink_c_src)
     );
+    let go_version = if env::var("DOCS_RS").is_ok() {
+        // Go isn't installed in the docs.rs image and the precompiled libs don't keep the version.
+        String::from("unknown")
+    } else {
+        go_version()
+    };
+    println!("cargo:rustc-env=UPLINK_C_GO_VERSION={}", go_version);
 
     // Manually link to core and security libs on MacOS
-    //
-    // N.B.: `CARGO_CFG_TARGET_OS` should be read instead of `cfg(target_os = "macos")`. The latter
-    // detects the host OS that is building the `build.rs` script, not the target OS.
-    if env::var("CARGO_CFG_TARGET_OS").expect("CARGO_CFG_TARGET_OS is not defined") == "macos" {
+    if target_os == "macos" {
         println!("cargo:rustc-flags=-l framework=CoreFoundation -l framework=Security");
     }
 
+    // Manually link to the system libs that the Go runtime and the Go standard library, embedded
+    // in the uplink-c static archive, require on Windows.
+    if is_windows {
+        for lib in ["ws2_32", "bcrypt", "userenv", "ntdll", "winmm"] {
+            println!("cargo:rustc-link-lib={}", lib);
+        }
+    }
+
+    // The Go runtime embedded in the uplink-c static archive requires to link these libraries
+    // explicitly when targeting musl.
+    if is_musl {
+        println!("cargo:rustc-link-lib=pthread");
+        println!("cargo:rustc-link-lib=dl");
+    }
+
     bindgen::Builder::default()
         // Use 'allow lists' to avoid generating bindings for system header includes
         // a lot of which isn't required and can't be handled safely anyway.
@@ -107,13 +133,12 @@ fn main() {
         .allowlist_var("EDGE_ERROR_.*")
         // This header file is the main API interface and includes all other header files that are required
         // (bindgen runs c preprocessor so we don't need to include nested headers)
-        .header(
-            uplink_c_dir
-                .join(".build/uplink/uplink.h")
-                .to_string_lossy(),
-        )
-        // Also make headers included by main header dependencies of the build
-        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .header(uplink_c_header.to_string_lossy())
+        // The headers are generated on every build, so they aren't made dependencies of the
+        // build, which would rerun it every time; the uplink-c sources are tracked instead.
+        .parse_callbacks(Box::new(
+            bindgen::CargoCallbacks::new().rerun_on_header_files(false),
+        ))
         // Generate bindings
         .generate()
         .expect("Error generating bindings.")
@@ -121,3 +146,342 @@ fn main() {
         .write_to_file(out_dir.join("bindings.rs"))
         .expect("Error writing bindings to file.");
 }
+
+/// Provides the bindings pregenerated from the uplink-c headers of the `.docs-rs` directory in
+/// `out_dir` and the version constants of the crate root, without building nor linking uplink-c,
+/// for the `stub-ffi` feature.
+///
+/// Neither Go nor clang are required, so the crate and its dependents can be type-checked and
+/// documented anywhere, but the bindings only declare the uplink-c functions, hence linking any
+/// binary that calls them fails.
+fn stub_ffi(uplink_c_src: &Path, out_dir: &Path) {
+    fs::copy(".docs-rs/bindings.rs", out_dir.join("bindings.rs"))
+        .expect("Failed to copy the pregenerated bindings.");
+
+    println!("cargo:rerun-if-env-changed=UPLINK_C_VERSION");
+    println!(
+        "cargo:rustc-env=UPLINK_C_VERSION={}",
+        uplink_c_version(uplink_c_src)
+    );
+    println!("cargo:rustc-env=UPLINK_C_GO_VERSION=unknown");
+}
+
+/// Builds uplink-c from its copy in `uplink_c_dir` of the sources in `uplink_c_src`, invoking Go
+/// directly, and generates the static archive and the headers in the `.build` directory of
+/// `uplink_c_dir`, in the same paths that its Makefile does.
+///
+/// Go runs as many packages builds in parallel as cargo jobs (i.e. `NUM_JOBS`), and it doesn't
+/// embed version control information because the copy isn't a git repository (the uplink-c git
+/// submodule has a relative path to its superproject).
+///
+/// Go uses the `vendor` directory of the sources for resolving the uplink-c dependencies when it
+/// exists, and with the `offline` feature it's required and Go cannot download any module, see
+/// [`go_flags`].
+///
+/// It panics if any step fails, explaining how to vendor the Go modules when Go fails because it
+/// cannot download them.
+fn build_uplink_c(uplink_c_src: &Path, uplink_c_dir: &Path, is_windows: bool, is_musl: bool) {
+    let build_dir = uplink_c_dir.join(".build");
+    let header_dir = build_dir.join("uplink");
+    let vendored = uplink_c_dir.join("vendor").is_dir();
+    let offline = env::var_os("CARGO_FEATURE_OFFLINE").is_some();
+
+    if offline && !vendored {
+        panic!(
+            "The offline feature requires the Go modules of uplink-c to be vendored, but {} \
+            doesn't exist. Vendor them on a machine with network access running `{}`.",
+            uplink_c_src.join("vendor").display(),
+            go_mod_vendor_cmd(uplink_c_src)
+        );
+    }
+
+    let mut go = Command::new("go");
+    go.args([
+        "build",
+        "-ldflags=-s -w",
+        "-buildmode=c-archive",
+        "-o",
+        &Path::new(".build").join("libuplink.a").to_string_lossy(),
+        ".",
+    ])
+    .current_dir(uplink_c_dir)
+    .env("CGO_ENABLED", "1")
+    .env("GOFLAGS", go_flags(vendored));
+
+    // Go runs in `uplink_c_dir`, so the relative paths of the Go directories of the environment
+    // would point to other directories than the ones the user configured.
+    for var in ["GOPATH", "GOMODCACHE"] {
+        if let Some(paths) = absolute_go_paths(var) {
+            go.env(var, paths);
+        }
+    }
+
+    if offline {
+        go.env("GOPROXY", "off");
+    }
+
+    if is_windows {
+        if let Some(goarch) = go_arch() {
+            go.env("GOOS", "windows").env("GOARCH", goarch);
+        }
+
+        // Go uses `gcc` by default, which MinGW-w64 provides.
+        let cc = cgo_c_compiler("gcc", "Windows", "install MinGW-w64, which provides `gcc`");
+        go.env("CC", cc);
+    }
+
+    // musl targets (e.g. Alpine containers) require to build the uplink-c static archive with a C
+    // compiler that targets musl, otherwise CGO links it against glibc symbols that aren't
+    // available at link time.
+    if is_musl {
+        let cc = cgo_c_compiler(
+            "musl-gcc",
+            "a musl target",
+            "`apt install musl-tools` provides `musl-gcc`",
+        );
+        go.env("CC", cc);
+    }
+
+    let output = go
+        .output()
+        .expect("Failed to run go command from build.rs, Go must be installed.");
+    // Forward the Go output, which cargo shows when the build fails or with `-vv`.
+    io::stdout()
+        .write_all(&output.stdout)
+        .expect("Failed to forward go output.");
+    io::stderr()
+        .write_all(&output.stderr)
+        .expect("Failed to forward go output.");
+    if !output.status.success() {
+        if is_module_download_error(&String::from_utf8_lossy(&output.stderr)) {
+            panic!(
+                "Failed to build uplink-c because Go cannot download its modules (go exited with \
+                {}), likely because the network is unreachable. For building without network \
+                access, vendor them on a machine with network access running `{}` and build \
+                with the offline feature, or set GOMODCACHE to a module cache that contains them.",
+                output.status,
+                go_mod_vendor_cmd(uplink_c_src)
+            );
+        }
+
+        panic!(
+            "Failed to build uplink-c, go exited with {}.",
+            output.status
+        );
+    }
+
+    // Place the headers in the same paths that the Makefile does.
+    fs::create_dir_all(&header_dir).expect("Failed to create uplink-c headers directory.");
+    fs::rename(build_dir.join("libuplink.h"), header_dir.join("uplink.h"))
+        .expect("Failed to move uplink-c generated header.");
+    for header in ["uplink_definitions.h", "uplink_compat.h"] {
+        fs::copy(uplink_c_dir.join(header), header_dir.join(header))
+            .expect("Failed to copy uplink-c header.");
+    }
+}
+
+/// Returns the value of the `GOFLAGS` environment variable for building uplink-c, which extends
+/// the one of the environment, if any, limiting the parallel builds to the number of cargo jobs
+/// and disabling the embedding of version control information.
+///
+/// When the Go modules are `vendored`, it adds `-mod=vendor` unless the one of the environment
+/// already sets the `-mod` flag.
+///
+/// `-buildvcs` requires Go 1.18, which is older than the minimum version required by uplink-c.
+fn go_flags(vendored: bool) -> String {
+    let jobs = env::var("NUM_JOBS")
+        .ok()
+        .and_then(|jobs| jobs.parse::<usize>().ok())
+        .filter(|&jobs| jobs > 0)
+        .unwrap_or(1);
+
+    let mut flags = env::var("GOFLAGS").unwrap_or_default();
+    if !flags.is_empty() {
+        flags.push(' ');
+    }
+    if vendored && !flags.split_whitespace().any(|f| f.starts_with("-mod=")) {
+        flags.push_str("-mod=vendor ");
+    }
+    flags.push_str(&format!("-p={} -buildvcs=false", jobs));
+    flags
+}
+
+/// Returns the value of the `var` environment variable, which is a list of paths (e.g. `GOPATH`),
+/// with its relative paths resolved against the directory of this crate, or `None` if it isn't
+/// set or it's empty.
+///
+/// It panics if the resolved paths cannot be joined in a list.
+fn absolute_go_paths(var: &str) -> Option<OsString> {
+    let value = env::var_os(var).filter(|v| !v.is_empty())?;
+    let crate_dir = env::current_dir().expect("Failed to get the current directory.");
+    let paths = env::split_paths(&value).map(|p| crate_dir.join(p));
+
+    Some(env::join_paths(paths).unwrap_or_else(|err| panic!("Invalid {} paths: {}", var, err)))
+}
+
+/// Returns `true` if the Go error output `stderr` indicates that Go failed because it cannot
+/// download a module, either because the network is unreachable or because the module proxy is
+/// disabled.
+fn is_module_download_error(stderr: &str) -> bool {
+    const MARKERS: [&str; 8] = [
+        "dial tcp",
+        "no such host",
+        "i/o timeout",
+        "connection refused",
+        "network is unreachable",
+        "TLS handshake timeout",
+        "module lookup disabled by GOPROXY=off",
+        "cannot find module providing package",
+    ];
+
+    MARKERS.iter().any(|marker| stderr.contains(marker))
+}
+
+/// Returns the command that vendors the Go modules of the uplink-c sources in `uplink_c_src`.
+fn go_mod_vendor_cmd(uplink_c_src: &Path) -> String {
+    let dir = fs::canonicalize(uplink_c_src).unwrap_or_else(|_| uplink_c_src.to_path_buf());
+    format!("cd {} && go mod vendor", dir.display())
+}
+
+/// Returns the Go architecture (i.e. `GOARCH`) that corresponds to the target architecture or
+/// `None` if it isn't a known one, in which case Go uses the host architecture.
+fn go_arch() -> Option<&'static str> {
+    match env::var("CARGO_CFG_TARGET_ARCH").ok()?.as_str() {
+        "x86_64" => Some("amd64"),
+        "x86" => Some("386"),
+        "aarch64" => Some("arm64"),
+        _ => None,
+    }
+}
+
+/// Returns the C compiler used by CGO for building uplink-c, which is the value of the
+/// `UPLINK_SYS_CC` environment variable or `default` if it isn't set.
+///
+/// It panics with a message explaining how to install it, through `install_hint`, or to set it if
+/// the compiler cannot be executed.
+fn cgo_c_compiler(default: &str, target: &str, install_hint: &str) -> String {
+    let cc = env::var("UPLINK_SYS_CC").unwrap_or_else(|_| String::from(default));
+
+    let found = Command::new(&cc)
+        .arg("--version")
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false);
+    if !found {
+        panic!(
+            "Building uplink-c for {} requires a C compiler, but `{}` cannot be executed. \
+            Install it (e.g. {}) or set the UPLINK_SYS_CC environment variable to the compiler \
+            to use.",
+            target, cc, install_hint
+        );
+    }
+
+    cc
+}
+
+/// Returns the version of the uplink-c source in `uplink_c_src`.
+///
+/// It's the value of the `UPLINK_C_VERSION` environment variable if it's set, otherwise the output
+/// of `git describe --tags --always` when `uplink_c_src` is a checked out git submodule, otherwise
+/// (e.g. building from the crates.io package) the version tag in the `package.metadata.uplink-c`
+/// section of the `Cargo.toml` of this crate.
+///
+/// It panics if the version cannot be found.
+fn uplink_c_version(uplink_c_src: &Path) -> String {
+    if let Ok(version) = env::var("UPLINK_C_VERSION") {
+        return version;
+    }
+
+    // Check that it's a git submodule, otherwise `git` would describe the superproject.
+    if uplink_c_src.join(".git").exists() {
+        let output = Command::new("git")
+            .args(["describe", "--tags", "--always"])
+            .current_dir(uplink_c_src)
+            .output();
+        if let Ok(output) = output {
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if output.status.success() && !version.is_empty() {
+                return version;
+            }
+        }
+    }
+
+    let manifest = fs::read_to_string("Cargo.toml").expect("Failed to read Cargo.toml.");
+    let version = manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[package.metadata.uplink-c]")
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            if key.trim() != "version" {
+                return None;
+            }
+
+            value.split('"').nth(1).map(String::from)
+        })
+        .expect("Cargo.toml doesn't have the uplink-c version in package.metadata.uplink-c.");
+
+    format!("v{}", version)
+}
+
+/// Returns the version of the Go toolchain (e.g. `go1.19.13`), which is the one used for building
+/// uplink-c.
+///
+/// It panics if Go cannot be executed.
+fn go_version() -> String {
+    let output = Command::new("go")
+        .args(["env", "GOVERSION"])
+        .output()
+        .expect("Failed to run go command from build.rs, Go must be installed.");
+    if !output.status.success() {
+        panic!(
+            "Failed to get the Go version, go exited with {}.",
+            output.status
+        );
+    }
+
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Copies the uplink-c sources of the `src` directory into the `dst` directory as
+/// [`copy_dir_all`] does, except the git metadata and the `.build` directory, which a previous
+/// build from the source checkout may have left.
+fn copy_uplink_c_src(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == ".git" || name == ".build" {
+            continue;
+        }
+
+        let dst_path = dst.join(name);
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst_path)?;
+        } else {
+            fs::copy(entry.path(), dst_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies recursively the content of the `src` directory into the `dst` directory, creating it
+/// if it doesn't exist and overwriting the existing files.
+fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dst_path = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst_path)?;
+        } else {
+            fs::copy(entry.path(), dst_path)?;
+        }
+    }
+
+    Ok(())
+}
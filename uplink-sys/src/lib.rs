@@ -10,3 +10,12 @@
 
 // Include uplink-c bindings (generated by build.rs)
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// The version of uplink-c that this crate is built from (e.g. `v1.9.0`), which is its git tag, or
+/// its `git describe` output when it's built from a commit that isn't tagged.
+pub const UPLINK_C_VERSION: &str = env!("UPLINK_C_VERSION");
+
+/// The version of the Go toolchain that built uplink-c (e.g. `go1.19.13`), which is the version of
+/// the Go runtime linked into this crate, or `unknown` when it's built by docs.rs or with the
+/// `stub-ffi` feature.
+pub const UPLINK_C_GO_VERSION: &str = env!("UPLINK_C_GO_VERSION");
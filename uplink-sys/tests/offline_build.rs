@@ -0,0 +1,33 @@
+//! Smoke test of the library built with the offline feature, which doesn't require any network
+//! access nor Storj credentials, see the `test-offline` target of the Makefile.
+
+use std::ffi::{CStr, CString};
+
+#[test]
+fn parse_and_serialize_access() {
+    // This access grant is invalidated so it isn't leaking any valid access grant.
+    let serialized = "15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu";
+    let access_grant = CString::new(serialized).expect("CString::new failed");
+
+    unsafe {
+        // Parse access grant
+        let access_result = uplink_sys::uplink_parse_access(
+            access_grant.as_ptr() as *mut uplink_sys::uplink_const_char
+        );
+
+        assert_eq!(access_result.error, std::ptr::null_mut()); // verify no error
+
+        // Serialize it back
+        let string_result = uplink_sys::uplink_access_serialize(access_result.access);
+
+        assert_eq!(string_result.error, std::ptr::null_mut()); // verify no error
+        assert_eq!(
+            CStr::from_ptr(string_result.string).to_str().unwrap(),
+            serialized
+        );
+
+        // Free memory
+        uplink_sys::uplink_free_string_result(string_result);
+        uplink_sys::uplink_free_access_result(access_result);
+    }
+}
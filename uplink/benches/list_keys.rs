@@ -0,0 +1,98 @@
+//! Compares listing the objects of a bucket with all their metadata and listing only their keys,
+//! which doesn't convert the metadata of each object.
+//!
+//! It uploads to the satellite configured in the environment, see
+//! `uplink::testing::TestProject::from_env`, so start a local storj-up instance with
+//! `make integration-tests-env-up` and run it with:
+//!
+//! ```sh
+//! cargo bench --features testing --bench list_keys
+//! ```
+
+use uplink::metadata::Custom;
+use uplink::project::options;
+use uplink::testing::TestProject;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+/// The number of listed objects.
+const OBJECTS: usize = 200;
+
+/// The number of custom metadata entries of each object.
+const METADATA_ENTRIES: usize = 50;
+
+fn list_keys(c: &mut Criterion) {
+    let tp = match TestProject::from_env() {
+        Ok(tp) => tp,
+        Err(err) => {
+            eprintln!("skipping the list keys benchmarks: {}", err);
+            return;
+        }
+    };
+    let bucket = tp.create_bucket("bench-list-keys").expect("create bucket");
+
+    let mut custom = Custom::with_capacity(METADATA_ENTRIES);
+    for i in 0..METADATA_ENTRIES {
+        custom.insert(format!("key-{}", i), "v".repeat(64));
+    }
+    let upload_opts = options::Upload {
+        custom_metadata: Some(custom),
+        ..Default::default()
+    };
+    for i in 0..OBJECTS {
+        tp.project()
+            .put_object(
+                &bucket,
+                format!("dir/object-{:04}", i),
+                b"data",
+                Some(&upload_opts),
+            )
+            .expect("put object");
+    }
+
+    let list_opts = options::ListObjects::builder()
+        .recursive(true)
+        .system(true)
+        .custom(true)
+        .build()
+        .expect("valid listing options");
+
+    let mut group = c.benchmark_group("list_keys");
+    group.throughput(Throughput::Elements(OBJECTS as u64));
+    group.sample_size(10);
+
+    group.bench_function("objects", |b| {
+        b.iter(|| {
+            let objects = tp
+                .project()
+                .list_objects(&bucket, Some(&list_opts))
+                .expect("list objects");
+            let mut listed = 0;
+            for obj in objects {
+                obj.expect("listed object");
+                listed += 1;
+            }
+            assert_eq!(listed, OBJECTS, "listed objects");
+        });
+    });
+
+    group.bench_function("keys", |b| {
+        b.iter(|| {
+            let keys = tp
+                .project()
+                .list_objects_keys(&bucket, Some(&list_opts))
+                .expect("list object keys");
+            let mut listed = 0;
+            for key in keys {
+                key.expect("listed key");
+                listed += 1;
+            }
+            assert_eq!(listed, OBJECTS, "listed keys");
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, list_keys);
+criterion_main!(benches);
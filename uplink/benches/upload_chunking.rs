@@ -0,0 +1,81 @@
+//! Compares the throughput of uploading an object writing its data in chunks of different sizes,
+//! directly to the upload and through a `BufferedUpload`.
+//!
+//! It uploads to the satellite configured in the environment, see
+//! `uplink::testing::TestProject::from_env`, so start a local storj-up instance with
+//! `make integration-tests-env-up` and run it with:
+//!
+//! ```sh
+//! cargo bench --features testing --bench upload_chunking
+//! ```
+
+use uplink::object::BufferedUpload;
+use uplink::testing::TestProject;
+
+use std::io::Write;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// The size of the uploaded objects.
+const OBJECT_SIZE: usize = 4 * 1024 * 1024;
+
+/// The size of the small chunks.
+const SMALL_CHUNK: usize = 1024;
+
+fn upload_chunking(c: &mut Criterion) {
+    let tp = match TestProject::from_env() {
+        Ok(tp) => tp,
+        Err(err) => {
+            eprintln!("skipping the upload chunking benchmarks: {}", err);
+            return;
+        }
+    };
+    let bucket = tp.create_bucket("bench-chunking").expect("create bucket");
+    let data: Vec<u8> = (0..OBJECT_SIZE).map(|i| i as u8).collect();
+
+    let mut group = c.benchmark_group("upload_chunking");
+    group.throughput(Throughput::Bytes(OBJECT_SIZE as u64));
+    group.sample_size(10);
+
+    for chunk_size in [SMALL_CHUNK, BufferedUpload::DEFAULT_CAPACITY] {
+        group.bench_with_input(
+            BenchmarkId::new("unbuffered", chunk_size),
+            &chunk_size,
+            |b, &chunk_size| {
+                b.iter(|| {
+                    let mut upload = tp
+                        .project()
+                        .upload_object(&bucket, "unbuffered", None)
+                        .expect("upload object");
+                    for chunk in data.chunks(chunk_size) {
+                        upload.write_all(chunk).expect("write chunk");
+                    }
+                    upload.commit().expect("commit upload");
+                });
+            },
+        );
+    }
+
+    group.bench_with_input(
+        BenchmarkId::new("buffered", SMALL_CHUNK),
+        &SMALL_CHUNK,
+        |b, &chunk_size| {
+            b.iter(|| {
+                let mut upload = tp
+                    .project()
+                    .upload_object(&bucket, "buffered", None)
+                    .expect("upload object")
+                    .into_buffered(BufferedUpload::DEFAULT_CAPACITY);
+                for chunk in data.chunks(chunk_size) {
+                    upload.write_all(chunk).expect("write chunk");
+                }
+                upload.commit().expect("commit upload");
+            });
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, upload_chunking);
+criterion_main!(benches);
@@ -1,16 +1,29 @@
 //! Storj DCS Access Grant and bound types.
 
 use crate::config::Config;
-use crate::uplink_c::{string_from_ffi_string_result, Ensurer};
+use crate::error::BoxError;
+use crate::uplink_c::{string_from_ffi_string_result, TryEnsure};
 use crate::{helpers, EncryptionKey, Error, Result};
 
+use std::collections::HashSet;
 use std::ffi::CString;
+use std::fmt;
+use std::ops::Deref;
 use std::os::raw::c_char;
+use std::sync::Arc;
 use std::time::Duration;
 use std::vec::Vec;
 
 use uplink_sys as ulksys;
 
+#[cfg(feature = "cli-config")]
+pub mod cli_config;
+pub mod env;
+pub mod issuer;
+mod serialized;
+
+pub use issuer::{IssuedGrant, Issuer};
+
 /// Represents an access grant
 ///
 /// An access grant contains everything to access a project and specific buckets.
@@ -19,12 +32,9 @@ use uplink_sys as ulksys;
 /// information, and information about the Satellite responsible for the project's metadata.
 #[derive(Debug)]
 pub struct Grant {
-    /// The FFI access type that an instance of this struct represents and guards its lifetime
-    /// until this instance drops.
-    ///
-    /// It's an access result because it's the one that holds the access grant and allows to free
-    /// its memory.
-    inner: ulksys::UplinkAccessResult,
+    /// The FFI access type that an instance of this struct represents, which is freed when this
+    /// instance, the projects opened with it and the access grants shared from it drop.
+    inner: Arc<AccessHandle>,
 }
 
 impl Grant {
@@ -84,13 +94,12 @@ impl Grant {
         // to return the result so the result will still valid when the call to this method ends
         // which is when those strings will be dropped.
         let res = unsafe {
-            *ulksys::uplink_config_request_access_with_passphrase(
+            ulksys::uplink_config_request_access_with_passphrase(
                 config.as_ffi_config(),
                 satellite_addr.as_ptr() as *mut c_char,
                 api_key.as_ptr() as *mut c_char,
                 passphrase.as_ptr() as *mut c_char,
             )
-            .ensure()
         };
 
         Self::from_ffi_access_result(res)
@@ -101,14 +110,55 @@ impl Grant {
     /// An [`Error::new_uplink` constructor](crate::Error::new_uplink), if `ffi_result` contains a
     ///  non `NULL` pointer in the `error` field.
     fn from_ffi_access_result(ffi_result: ulksys::UplinkAccessResult) -> Result<Self> {
-        ffi_result.ensure();
+        Self::from_ffi_access_result_with_parent(ffi_result, None)
+    }
 
-        Error::new_uplink(ffi_result.error).map_or(Ok(Grant { inner: ffi_result }), |err| {
+    /// Creates a Grant instance from the FFI type that keeps `parent` alive, if any, until it
+    /// drops.
+    ///
+    /// It returns the same errors than [`Self::from_ffi_access_result`].
+    fn from_ffi_access_result_with_parent(
+        ffi_result: ulksys::UplinkAccessResult,
+        parent: Option<Arc<AccessHandle>>,
+    ) -> Result<Self> {
+        ffi_result.try_ensure()?;
+
+        if let Some(err) = Error::new_uplink(ffi_result.error) {
             // SAFETY: FFI free function doesn't free if the result fields are `NULL` and this
             // result should only be instantiated through the same FFI.
             unsafe { ulksys::uplink_free_access_result(ffi_result) };
-            Err(err)
-        })
+            return Err(err);
+        }
+
+        Ok(Self::with_handle(Arc::new(AccessHandle {
+            result: ffi_result,
+            _parent: parent,
+        })))
+    }
+
+    /// Creates a Grant instance that shares `handle`.
+    pub(crate) fn with_handle(handle: Arc<AccessHandle>) -> Self {
+        Self { inner: handle }
+    }
+
+    /// Returns the restrictions of this access grant, which are decoded from its serialized form
+    /// because the FFI doesn't expose them.
+    ///
+    /// The restricted prefixes are only known when the access grant contains the encryption
+    /// information of the prefixes (see [`GrantPrefix::prefix`]).
+    ///
+    /// It returns an [`Error::Internal`] if the serialized access grant returned by the FFI cannot
+    /// be decoded and the same errors than [`Self::satellite`] and [`Self::serialize`].
+    pub fn inspect(&self) -> Result<GrantInfo> {
+        let serialized = self.serialize()?;
+        let scope = serialized::decode(&serialized).map_err(|err| {
+            Error::new_internal(
+                "FFI returned an invalid serialized access grant",
+                BoxError::from(err),
+            )
+        })?;
+
+        Ok(GrantInfo::from_scope(self.satellite()?, scope))
     }
 
     /// Overrides the root encryption key for the prefix in bucket with the encryption key.
@@ -117,8 +167,13 @@ impl Grant {
     /// This method is useful for overriding the encryption key in user-specific access grants when
     /// implementing multitenancy in a single app bucket.
     /// See relevant information in the general crate documentation.
+    ///
+    /// The projects opened with this access grant and the access grants shared from it keep the
+    /// previous encryption key. When there is any of them alive, this access grant is replaced by
+    /// a copy of it before overriding the key, so it also returns the same errors than
+    /// [`Self::serialize`] and [`Self::new`].
     pub fn override_encryption_key(
-        &self,
+        &mut self,
         bucket: &str,
         prefix: &str,
         encryption_key: &EncryptionKey,
@@ -126,9 +181,17 @@ impl Grant {
         let bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let prefix = helpers::cstring_from_str_fn_arg("prefix", prefix)?;
 
-        // SAFETY: it's safe to pass this strings to the FFI function because it makes copies of it
-        // to return the result so the result will still valid after the call to this method ends
-        // which is when those strings will be dropped.
+        // The FFI access is shared with other instances, which may use it from other threads
+        // while it's mutated.
+        if Arc::get_mut(&mut self.inner).is_none() {
+            *self = Self::new(&self.serialize()?)?;
+        }
+
+        // SAFETY: this instance is the only reference to the FFI access because it's borrowed
+        // mutably and it isn't shared (see the check above), so nothing else uses it while it's
+        // mutated. It's safe to pass this strings to the FFI function because it makes copies of
+        // it to return the result so the result will still valid after the call to this method
+        // ends which is when those strings will be dropped.
         let uc_err = unsafe {
             ulksys::uplink_access_override_encryption_key(
                 self.inner.access,
@@ -138,9 +201,20 @@ impl Grant {
             )
         };
 
-        Error::new_uplink(uc_err).map_or(Ok(()), |err| {
-            helpers::drop_uplink_sys_error(uc_err);
-            Err(err)
+        Error::from_ffi_error(uc_err).map_or(Ok(()), Err)
+    }
+
+    /// Returns the satellite node address associated with this access grant parsed.
+    ///
+    /// It returns an [`Error::Internal`] if the address returned by the FFI isn't well formed and
+    /// the same errors than [`Self::satellite_address`].
+    pub fn satellite(&self) -> Result<SatelliteAddress> {
+        let addr = self.satellite_address()?;
+        SatelliteAddress::parse(&addr).map_err(|err| {
+            Error::new_internal(
+                "FFI returned an invalid satellite address",
+                BoxError::from(err),
+            )
         })
     }
 
@@ -170,9 +244,15 @@ impl Grant {
     /// construction chain.
     ///
     /// Prefixes restrict the access grant (and internal encryption information) to only contain
-    /// enough information to allow access to just those prefixes.
+    /// enough information to allow access to just those prefixes. `None` means that the access
+    /// grant isn't restricted to any prefix, so it has access to all the buckets. The duplicated
+    /// prefixes (i.e. same bucket and prefix) are only passed once to the FFI.
     ///
     /// To revoke an access grant see [`Project.revoke_access()`](../project/struct.Project.html#method.revoke_access).
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `prefixes` is an empty vector because the FFI
+    /// interprets it as no restriction rather than as no access; pass `None` for not restricting
+    /// the access to any prefix.
     pub fn share(
         &self,
         permission: &Permission,
@@ -180,56 +260,158 @@ impl Grant {
     ) -> Result<Grant> {
         let res;
         if let Some(prefix_list) = prefixes {
-            let mut ulk_prefixes: Vec<ulksys::UplinkSharePrefix> =
-                Vec::with_capacity(prefix_list.len());
-
-            for sp in &prefix_list {
-                ulk_prefixes.push(sp.as_ffi_share_prefix());
+            if prefix_list.is_empty() {
+                return Err(Error::new_invalid_arguments(
+                    "prefixes",
+                    "cannot be an empty list because it doesn't restrict the access to any \
+                    prefix; use None for sharing without prefix restrictions",
+                ));
             }
 
+            let mut ulk_prefixes: Vec<ulksys::UplinkSharePrefix> =
+                unique_share_prefixes(&prefix_list)
+                    .into_iter()
+                    .map(SharePrefix::as_ffi_share_prefix)
+                    .collect();
+
             // SAFETY: it's safe to pass the vector to the FFI function because it makes copies of it
             // to return the result so the result will still valid when the call to this method ends
             // which is when the vector will be dropped.
             res = unsafe {
-                *ulksys::uplink_access_share(
+                ulksys::uplink_access_share(
                     self.inner.access,
                     permission.as_ffi_permissions(),
                     ulk_prefixes.as_mut_ptr(),
                     ulk_prefixes.len() as i64,
                 )
-                .ensure()
             };
         } else {
             // SAFETY: it's safe to pass nil to the FFI function to indicate that there isn't any
             // prefix restriction.
             res = unsafe {
-                *ulksys::uplink_access_share(
+                ulksys::uplink_access_share(
                     self.inner.access,
                     permission.as_ffi_permissions(),
                     std::ptr::null_mut(),
                     0,
                 )
-                .ensure()
             };
         }
 
-        Self::from_ffi_access_result(res)
+        Self::from_ffi_access_result_with_parent(res, Some(Arc::clone(&self.inner)))
+    }
+
+    /// Returns the raw FFI access of this access grant for passing it to other C code that uses
+    /// the uplink-c API (e.g. Go extensions compiled into the same binary).
+    ///
+    /// The pointer is owned by this instance, so the caller must not free it and must not use it
+    /// after this instance is dropped. Use [`Self::from_raw`] for the reverse direction.
+    ///
+    /// ```no_run
+    /// use uplink::access::Grant;
+    ///
+    /// let grant = Grant::new("my-serialized-access-grant")?;
+    /// let raw = grant.as_raw();
+    ///
+    /// // SAFETY: `raw` is a valid access owned by `grant`, which outlives this call, and the FFI
+    /// // doesn't free it.
+    /// let res = unsafe { uplink_sys::uplink_access_satellite_address(raw) };
+    /// // SAFETY: the result is returned by the FFI and it isn't used after freeing it.
+    /// unsafe { uplink_sys::uplink_free_string_result(res) };
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn as_raw(&self) -> *mut ulksys::UplinkAccess {
+        self.inner.access
+    }
+
+    /// Creates an access grant from a raw FFI access, taking ownership of it, so it's freed when
+    /// the returned instance is dropped.
+    ///
+    /// It panics if `access` is `NULL`.
+    ///
+    /// # Safety
+    ///
+    /// `access` must be a valid access returned by the uplink-c API (e.g. the `access` field of a
+    /// successful `uplink_parse_access` result) that isn't owned by anything else, hence it must
+    /// not be freed nor wrapped by another instance, and it must not be used after the returned
+    /// instance is dropped.
+    ///
+    /// ```no_run
+    /// use std::ffi::CString;
+    ///
+    /// use uplink::access::Grant;
+    ///
+    /// let serialized = CString::new("my-serialized-access-grant").expect("no NULL bytes");
+    /// // SAFETY: `serialized` is a valid C string that outlives this call.
+    /// let res = unsafe { uplink_sys::uplink_parse_access(serialized.as_ptr() as *mut _) };
+    /// assert!(res.error.is_null(), "valid serialized access grant");
+    ///
+    /// // SAFETY: the access is returned by the FFI and the result isn't freed nor used anymore.
+    /// let grant = unsafe { Grant::from_raw(res.access) };
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub unsafe fn from_raw(access: *mut ulksys::UplinkAccess) -> Self {
+        assert!(!access.is_null(), "`access` argument cannot be NULL");
+
+        Self::with_handle(Arc::new(AccessHandle {
+            result: ulksys::UplinkAccessResult {
+                access,
+                error: std::ptr::null_mut(),
+            },
+            _parent: None,
+        }))
     }
 
     /// Returns the FFI representation of this access grant.
     pub(crate) fn as_ffi_access(&self) -> *mut ulksys::UplinkAccess {
         self.inner.access
     }
+
+    /// Returns a reference to the FFI access of this access grant for keeping it alive beyond the
+    /// lifetime of this instance.
+    pub(crate) fn access_handle(&self) -> Arc<AccessHandle> {
+        Arc::clone(&self.inner)
+    }
 }
 
-impl Drop for Grant {
+/// Owns the FFI access result and frees it when it drops.
+///
+/// It's shared by the access grant, the projects opened with it and the access grants shared from
+/// it, so the FFI access isn't freed while any of them is alive regardless of the order in which
+/// they drop.
+#[derive(Debug)]
+pub(crate) struct AccessHandle {
+    /// The FFI access result that holds the access grant and allows to free its memory.
+    result: ulksys::UplinkAccessResult,
+    /// The access grant that this one was shared from, if any.
+    _parent: Option<Arc<AccessHandle>>,
+}
+
+impl Deref for AccessHandle {
+    type Target = ulksys::UplinkAccessResult;
+
+    fn deref(&self) -> &Self::Target {
+        &self.result
+    }
+}
+
+impl Drop for AccessHandle {
     fn drop(&mut self) {
-        // SAFETY: this type implementation guarantees that the FFI value since an instance is
-        // created until it's dropped.
-        unsafe { ulksys::uplink_free_access_result(self.inner) };
+        // SAFETY: we trust that the FFI is doing correct operations when freeing a correctly
+        // created `UplinkAccessResult` value, which isn't used anymore because it's the last
+        // reference to it.
+        unsafe { ulksys::uplink_free_access_result(self.result) };
     }
 }
 
+// SAFETY: the FFI access is a handler of a Go access, which can be used and freed from any thread.
+unsafe impl Send for AccessHandle {}
+
+// SAFETY: the handler is only read when it's shared; the only operation that mutates the Go access
+// is `Grant::override_encryption_key`, which requires a mutable reference to the only instance
+// that references the handler, so no other thread can use it meanwhile.
+unsafe impl Sync for AccessHandle {}
+
 /// Represents a prefix to be shared.
 #[derive(Debug)]
 pub struct SharePrefix<'a> {
@@ -286,7 +468,7 @@ impl<'a> SharePrefix<'a> {
 /// permission is set for the shared access Grant but not to its parent, the shared access Grant
 /// won't be allowed. shared access Grant wont See
 /// [`Grant.share()`](struct.Grant.html#method.share).
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Permission {
     /// Gives permission to download the content of the objects and their associated metadata, but
     /// it does not allow listing buckets.
@@ -305,14 +487,15 @@ pub struct Permission {
     /// before not_after and the resulting access grant will not work if the satellite believes the
     /// time is before the set it  one.
     ///
-    /// The time is measured with the number of seconds since the Unix Epoch time.
+    /// The time is measured with the number of seconds since the Unix Epoch time. Sub-second
+    /// precision is rounded up when it's passed to the FFI.
     not_before: Option<Duration>,
     /// Restricts when the resulting access grant is valid for. If it is set then it must always be
     /// after not_before and the resulting access grant will not work if the satellite believes the
     /// time is after the set it one.
     ///
     /// The time is measured with the number of seconds since the Unix Epoch
-    /// time.
+    /// time. Sub-second precision is rounded down when it's passed to the FFI.
     not_after: Option<Duration>,
 }
 
@@ -371,18 +554,32 @@ impl Permission {
     }
 
     /// Sets a not before valid time for this permission or removing it when `None` is passed.
-    /// An error is returned if since is more recent or equal to the current not after valid time of
-    /// the permission, when not after is set. The time is measured with the number of seconds since
-    /// the Unix Epoch time.
+    /// The time is measured with the number of seconds since the Unix Epoch time.
+    ///
+    /// The FFI only supports whole seconds, so sub-second precision is rounded up to the next
+    /// second for never making the valid time window wider than the requested one.
+    ///
+    /// An error is returned if:
+    /// * since is zero (i.e. the Unix Epoch) because the FFI interprets it as unset.
+    /// * since, rounded up, is more recent or equal to the current not after valid time of the
+    ///   permission, rounded down, when not after is set, because the resulting access grant
+    ///   would never be valid (e.g. both are within the same second).
     pub fn set_not_before(&mut self, since: Option<Duration>) -> Result<()> {
         if let Some(since) = since {
+            if since.is_zero() {
+                return Err(Error::new_invalid_arguments(
+                    "since",
+                    "cannot be zero because it means that the not before valid time isn't set",
+                ));
+            }
+
             if let Some(until) = self.not_after {
-                if since >= until {
-                    return Err(
-                        Error::new_invalid_arguments(
-                            "since",
-                            "cannot be more recent or equal to the not after valid time of the permission",
-                        ));
+                if is_empty_time_window(since, until) {
+                    return Err(Error::new_invalid_arguments(
+                        "since",
+                        "cannot be more recent or equal to the not after valid time of the \
+                            permission when both are rounded to whole seconds",
+                    ));
                 }
             }
         }
@@ -398,20 +595,34 @@ impl Permission {
     }
 
     /// Sets a not after valid time for this permission or removing it when `None` is passed.
+    /// The time is measured with the number of seconds since the Unix Epoch time.
     ///
-    /// An error is returned if until is previous or equal to the current not before valid time of
-    /// the permission, when not before is set.
+    /// The FFI only supports whole seconds, so sub-second precision is rounded down to the
+    /// previous second for never making the valid time window wider than the requested one.
     ///
-    /// The time is measured with the number of seconds since the Unix Epoch time.
+    /// An error is returned if:
+    /// * until is less than 1 second because rounding it down makes it zero (i.e. the Unix
+    ///   Epoch), which the FFI interprets as unset.
+    /// * until, rounded down, is previous or equal to the current not before valid time of the
+    ///   permission, rounded up, when not before is set, because the resulting access grant would
+    ///   never be valid (e.g. both are within the same second).
     pub fn set_not_after(&mut self, until: Option<Duration>) -> Result<()> {
         if let Some(until) = until {
+            if until.as_secs() == 0 {
+                return Err(Error::new_invalid_arguments(
+                    "until",
+                    "cannot be less than 1 second because it means that the not after valid time \
+                    isn't set",
+                ));
+            }
+
             if let Some(since) = self.not_before {
-                if until <= since {
-                    return Err(
-                        Error::new_invalid_arguments(
-                            "until",
-                            "cannot be previous or equal to the not before valid time of the permission",
-                        ));
+                if is_empty_time_window(since, until) {
+                    return Err(Error::new_invalid_arguments(
+                        "until",
+                        "cannot be previous or equal to the not before valid time of the \
+                            permission when both are rounded to whole seconds",
+                    ));
                 }
             }
         }
@@ -427,16 +638,229 @@ impl Permission {
             allow_upload: self.allow_upload,
             allow_list: self.allow_list,
             allow_delete: self.allow_delete,
-            not_before: self.not_before.map_or(0, |d| d.as_secs()) as i64,
+            not_before: self.not_before.map_or(0, duration_secs_round_up) as i64,
             not_after: self.not_after.map_or(0, |d| d.as_secs()) as i64,
         }
     }
 }
 
+/// The restrictions of an access grant returned by [`Grant::inspect`].
+#[derive(Clone, Debug)]
+pub struct GrantInfo {
+    /// The satellite node address.
+    pub satellite: SatelliteAddress,
+    /// The effective permission, which is the intersection of the permissions of all the
+    /// [`Grant::share`] calls in the access grant construction chain.
+    pub permission: Permission,
+    /// The prefixes that the access grant is restricted to. Empty when it isn't restricted to any
+    /// prefix.
+    ///
+    /// When the access grant has been shared several times with prefixes, they are the prefixes
+    /// of the last one.
+    pub prefixes: Vec<GrantPrefix>,
+    /// Indicates that the access grant has encryption keys for specific prefixes.
+    ///
+    /// Besides [`Grant::override_encryption_key`], [`Grant::share`] also adds the encryption key
+    /// of each shared prefix, so the serialized access grant doesn't allow to tell them apart and
+    /// it's also `true` for access grants shared with prefixes.
+    pub has_encryption_overrides: bool,
+}
+
+impl GrantInfo {
+    /// Creates an instance from the satellite address and the decoded serialized access grant.
+    fn from_scope(satellite: SatelliteAddress, scope: serialized::Scope) -> Self {
+        let caveats = &scope.caveats;
+        let mut permission = Permission {
+            allow_download: !caveats.iter().any(|c| c.disallow_reads),
+            allow_upload: !caveats.iter().any(|c| c.disallow_writes),
+            allow_list: !caveats.iter().any(|c| c.disallow_lists),
+            allow_delete: !caveats.iter().any(|c| c.disallow_deletes),
+            ..Default::default()
+        };
+        permission.not_before = caveats.iter().filter_map(|c| c.not_before).max();
+        permission.not_after = caveats.iter().filter_map(|c| c.not_after).min();
+
+        let prefixes = caveats
+            .iter()
+            .rev()
+            .find(|c| !c.allowed_paths.is_empty())
+            .map(|c| {
+                c.allowed_paths
+                    .iter()
+                    .map(|p| GrantPrefix::from_allowed_path(p, &scope.store_entries))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            satellite,
+            permission,
+            prefixes,
+            has_encryption_overrides: !scope.store_entries.is_empty(),
+        }
+    }
+}
+
+/// A prefix that an access grant is restricted to, see [`GrantInfo::prefixes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrantPrefix {
+    /// The bucket name.
+    pub bucket: String,
+    /// The object key prefix. An empty prefix means all the bucket.
+    ///
+    /// The access grant restrictions contain the encrypted prefix, so it's `None` when the access
+    /// grant doesn't contain the encryption information of the prefix for decrypting it.
+    pub prefix: Option<String>,
+}
+
+impl GrantPrefix {
+    /// Creates an instance from an allowed path of the access grant restrictions, finding its
+    /// unencrypted prefix in the encryption store `entries`.
+    fn from_allowed_path(
+        path: &serialized::AllowedPath,
+        entries: &[serialized::StoreEntry],
+    ) -> Self {
+        let prefix = if path.encrypted_prefix.is_empty() {
+            Some(String::new())
+        } else {
+            entries
+                .iter()
+                .find(|e| e.bucket == path.bucket && e.encrypted_prefix == path.encrypted_prefix)
+                .map(|e| e.unencrypted_prefix.clone())
+        };
+
+        Self {
+            bucket: path.bucket.clone(),
+            prefix,
+        }
+    }
+}
+
+/// Returns the whole seconds of `d` rounding up its sub-second precision.
+fn duration_secs_round_up(d: Duration) -> u64 {
+    if d.subsec_nanos() > 0 {
+        d.as_secs().saturating_add(1)
+    } else {
+        d.as_secs()
+    }
+}
+
+/// Returns `true` if an access grant with the `since` not before and the `until` not after valid
+/// times is never valid once they are rounded to the whole seconds passed to the FFI.
+fn is_empty_time_window(since: Duration, until: Duration) -> bool {
+    duration_secs_round_up(since) >= until.as_secs()
+}
+
+/// Returns the prefixes of `prefixes` without the duplicated ones (i.e. same bucket and prefix),
+/// keeping the order of their first occurrence.
+fn unique_share_prefixes<'a, 'b>(prefixes: &'b [SharePrefix<'a>]) -> Vec<&'b SharePrefix<'a>> {
+    let mut seen = HashSet::with_capacity(prefixes.len());
+    prefixes
+        .iter()
+        .filter(|sp| seen.insert((sp.bucket, sp.prefix)))
+        .collect()
+}
+
+/// The address of a satellite node with the format `node_id@host:port`.
+///
+/// `host` can be a domain name, an IPv4 address or an IPv6 address enclosed in brackets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SatelliteAddress {
+    node_id: String,
+    host: String,
+    port: u16,
+}
+
+impl SatelliteAddress {
+    /// Parses a satellite address with the format `node_id@host:port`.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `address` doesn't have a node ID (i.e. it
+    /// doesn't contain '@' or the node ID is empty), the host is empty or it's an IPv6 address
+    /// without brackets, or the port is missing or it isn't a number between 0 and 65535.
+    pub fn parse(address: &str) -> Result<Self> {
+        let (node_id, host_port) = address
+            .split_once('@')
+            .ok_or_else(|| Error::new_invalid_arguments("address", "missing node ID ('@')"))?;
+        if node_id.is_empty() {
+            return Err(Error::new_invalid_arguments(
+                "address",
+                "node ID cannot be empty",
+            ));
+        }
+
+        let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+            let (host, port) = bracketed.split_once(']').ok_or_else(|| {
+                Error::new_invalid_arguments("address", "missing closing bracket of the IPv6 host")
+            })?;
+            let port = port.strip_prefix(':').ok_or_else(|| {
+                Error::new_invalid_arguments("address", "missing port after the host")
+            })?;
+            (host, port)
+        } else {
+            let (host, port) = host_port.rsplit_once(':').ok_or_else(|| {
+                Error::new_invalid_arguments("address", "missing port after the host")
+            })?;
+            if host.contains(':') {
+                return Err(Error::new_invalid_arguments(
+                    "address",
+                    "IPv6 host must be enclosed in brackets",
+                ));
+            }
+            (host, port)
+        };
+
+        if host.is_empty() {
+            return Err(Error::new_invalid_arguments(
+                "address",
+                "host cannot be empty",
+            ));
+        }
+
+        let port = port.parse().map_err(|_| {
+            Error::new_invalid_arguments(
+                "address",
+                &format!("port must be a number between 0 and 65535, got '{}'", port),
+            )
+        })?;
+
+        Ok(Self {
+            node_id: String::from(node_id),
+            host: String::from(host),
+            port,
+        })
+    }
+
+    /// Returns the ID of the satellite node.
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Returns the host of the satellite node. IPv6 addresses are returned without brackets.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the port of the satellite node.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl fmt::Display for SatelliteAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "{}@[{}]:{}", self.node_id, self.host, self.port)
+        } else {
+            write!(f, "{}@{}:{}", self.node_id, self.host, self.port)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::error;
+    use crate::error::UplinkCode;
 
     /*** Grant tests ***/
     #[test]
@@ -582,14 +1006,43 @@ mod test {
     #[test]
     fn test_grant_override_encryption_key() {
         // This access grant is invalidated so it isn't leaking any valid access grant.
-        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let mut grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
         let enc_key = EncryptionKey::derive("Rust test", &[0]).expect("derive encryption key");
 
         {
             // Valid arguments.
+            let handle = Arc::as_ptr(&grant.inner);
             grant
                 .override_encryption_key("a-bucket", "prefix/", &enc_key)
                 .expect("when passing a valid bucket and prefix");
+            assert_eq!(
+                Arc::as_ptr(&grant.inner),
+                handle,
+                "the FFI access isn't copied when it isn't shared"
+            );
+        }
+
+        {
+            // The FFI access is shared (e.g. with a project).
+            let shared = Grant::with_handle(grant.access_handle());
+            let serialized = shared.serialize().expect("serialize shared access grant");
+            grant
+                .override_encryption_key("b-bucket", "prefix/", &enc_key)
+                .expect("when the FFI access is shared");
+            assert!(
+                !Arc::ptr_eq(&grant.inner, &shared.inner),
+                "the FFI access is copied when it's shared"
+            );
+            assert_eq!(
+                shared.serialize().expect("serialize shared access grant"),
+                serialized,
+                "the shared FFI access isn't overridden"
+            );
+            assert_ne!(
+                grant.serialize().expect("serialize access grant"),
+                serialized,
+                "the copied FFI access is overridden"
+            );
         }
 
         {
@@ -598,7 +1051,7 @@ mod test {
                 .override_encryption_key("a-bucket", "prefix", &enc_key)
                 .expect_err("when passing a prefix without ending with slash")
             {
-                Error::Uplink(error::Uplink::Internal(_)) => {}
+                Error::Uplink(err) if err.code() == UplinkCode::Internal => {}
                 _ => panic!("expected an Uplink error"),
             }
         }
@@ -636,7 +1089,171 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_grant_as_raw_and_from_raw() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let serialized = "15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu";
+        let grant = Grant::new(serialized).expect("valid serialized access grant");
+        assert!(!grant.as_raw().is_null(), "raw access is NULL");
+        assert_eq!(grant.as_raw(), grant.as_ffi_access(), "raw access");
+
+        let c_serialized = CString::new(serialized).expect("no NULL bytes");
+        // SAFETY: we are passing a pointer to a valid CString to the FFI.
+        let res = unsafe { ulksys::uplink_parse_access(c_serialized.as_ptr() as *mut c_char) };
+        assert!(res.error.is_null(), "parse access error");
+        // SAFETY: the access is returned by the FFI and `res` isn't used anymore.
+        let grant = unsafe { Grant::from_raw(res.access) };
+        assert_eq!(grant.as_raw(), res.access, "raw access");
+        assert_eq!(
+            grant.serialize().expect("serialize access grant"),
+            serialized,
+            "serialized access grant"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`access` argument cannot be NULL")]
+    fn test_grant_from_raw_null() {
+        // SAFETY: it panics before using the pointer.
+        let _ = unsafe { Grant::from_raw(std::ptr::null_mut()) };
+    }
+
+    #[test]
+    fn test_grant_share_prefixes() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+
+        match grant.share(&Permission::full(), Some(Vec::new())) {
+            Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                assert_eq!(names, "prefixes", "invalid error argument name")
+            }
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+
+        let shared = grant
+            .share(
+                &Permission::full(),
+                Some(vec![
+                    SharePrefix::new("b", "a/").expect("share prefix"),
+                    SharePrefix::new("b", "a/").expect("share prefix"),
+                    SharePrefix::new("c", "a/").expect("share prefix"),
+                ]),
+            )
+            .expect("share with duplicated prefixes");
+        let buckets: Vec<String> = shared
+            .inspect()
+            .expect("inspect shared grant")
+            .prefixes
+            .into_iter()
+            .map(|p| p.bucket)
+            .collect();
+        assert_eq!(buckets, vec!["b", "c"], "shared prefixes' buckets");
+    }
+
+    #[test]
+    fn test_grant_access_handle_outlives_grant() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let serialized = "15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu";
+
+        for round in 0..20 {
+            let grant = Grant::new(serialized).expect("valid serialized access grant");
+            let barrier = Arc::new(std::sync::Barrier::new(9));
+            let threads: Vec<_> = (0..8)
+                .map(|i| {
+                    let handle = grant.access_handle();
+                    let barrier = Arc::clone(&barrier);
+                    std::thread::spawn(move || {
+                        // One of the threads copies the FFI access while the others use it.
+                        let enc_key = (i == 7).then(|| {
+                            EncryptionKey::derive("Rust test", &[0]).expect("derive encryption key")
+                        });
+                        barrier.wait();
+                        let mut grant = Grant::with_handle(handle);
+                        for _ in 0..10 {
+                            if let Some(enc_key) = &enc_key {
+                                grant
+                                    .override_encryption_key("a-bucket", "prefix/", enc_key)
+                                    .expect("override encryption key");
+                            } else if i % 2 == 0 {
+                                assert_eq!(
+                                    grant.serialize().expect("serialize access grant"),
+                                    serialized,
+                                    "serialized access grant"
+                                );
+                            } else {
+                                let shared = grant
+                                    .share(&Permission::read_only(), None)
+                                    .expect("share access grant");
+                                shared.serialize().expect("serialize shared access grant");
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            // The original access grant drops while the threads use its FFI access.
+            drop(grant);
+            barrier.wait();
+            for t in threads {
+                t.join()
+                    .unwrap_or_else(|_| panic!("round {}: thread panicked", round));
+            }
+        }
+    }
+
+    #[test]
+    fn test_grant_shared_outlives_parent() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let parent = grant.access_handle();
+        let shared = grant
+            .share(&Permission::read_only(), None)
+            .expect("share access grant");
+        assert_eq!(
+            Arc::strong_count(&parent),
+            3,
+            "grant, shared grant and test"
+        );
+
+        drop(grant);
+        assert_eq!(Arc::strong_count(&parent), 2, "shared grant and test");
+        shared.serialize().expect("serialize shared access grant");
+
+        let project = crate::Project::open(&shared);
+        let handle = shared.access_handle();
+        drop(shared);
+        assert_eq!(Arc::strong_count(&handle), 2, "project and test");
+        assert_eq!(Arc::strong_count(&parent), 2, "shared access and test");
+
+        drop(project);
+        assert_eq!(Arc::strong_count(&handle), 1, "test");
+        drop(handle);
+        assert_eq!(Arc::strong_count(&parent), 1, "test");
+    }
+
     /*** SharePrefix tests ***/
+    #[test]
+    fn test_unique_share_prefixes() {
+        let prefixes = [
+            SharePrefix::new("b", "a/").expect("share prefix"),
+            SharePrefix::new("b", "c/").expect("share prefix"),
+            SharePrefix::new("b", "a/").expect("share prefix"),
+            SharePrefix::new("d", "a/").expect("share prefix"),
+            SharePrefix::full_bucket("b").expect("share prefix"),
+            SharePrefix::new("b", "c/").expect("share prefix"),
+        ];
+
+        let unique: Vec<(&str, &str)> = unique_share_prefixes(&prefixes)
+            .into_iter()
+            .map(|sp| (sp.bucket(), sp.prefix()))
+            .collect();
+        assert_eq!(
+            unique,
+            vec![("b", "a/"), ("b", "c/"), ("d", "a/"), ("b", "")],
+            "unique prefixes"
+        );
+    }
+
     #[test]
     fn test_share_prefix() {
         {
@@ -696,6 +1313,162 @@ mod test {
         }
     }
 
+    /*** GrantInfo tests ***/
+    #[test]
+    fn test_grant_info_from_scope() {
+        use serialized::test::{caveat, serialized_grant, store_entry};
+
+        let satellite = SatelliteAddress::parse(
+            "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777",
+        )
+        .expect("parse satellite address");
+
+        // Root access grant.
+        let scope = serialized::decode(&serialized_grant(
+            "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777",
+            &[],
+            &[],
+        ))
+        .expect("decode root grant");
+        let info = GrantInfo::from_scope(satellite.clone(), scope);
+        assert_eq!(info.satellite, satellite, "satellite");
+        assert!(info.permission.allow_download, "allow download");
+        assert!(info.permission.allow_upload, "allow upload");
+        assert!(info.permission.allow_list, "allow list");
+        assert!(info.permission.allow_delete, "allow delete");
+        assert_eq!(info.permission.not_before(), None, "not before");
+        assert_eq!(info.permission.not_after(), None, "not after");
+        assert!(info.prefixes.is_empty(), "prefixes");
+        assert!(!info.has_encryption_overrides, "encryption overrides");
+
+        // Access grant shared twice, the last time with prefixes.
+        let scope = serialized::decode(&serialized_grant(
+            "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777",
+            &[
+                caveat(
+                    [false, true, false, false],
+                    &[("other", b"")],
+                    Some(10),
+                    Some(1000),
+                ),
+                caveat(
+                    [false, false, false, true],
+                    &[
+                        ("bucket", b"enc-logs"),
+                        ("bucket", b"enc-unknown"),
+                        ("all", b""),
+                    ],
+                    Some(20),
+                    Some(2000),
+                ),
+            ],
+            &[store_entry("bucket", "logs/", b"enc-logs")],
+        ))
+        .expect("decode shared grant");
+        let info = GrantInfo::from_scope(satellite, scope);
+        assert!(info.permission.allow_download, "allow download");
+        assert!(!info.permission.allow_upload, "allow upload");
+        assert!(info.permission.allow_list, "allow list");
+        assert!(!info.permission.allow_delete, "allow delete");
+        assert_eq!(
+            info.permission.not_before(),
+            Some(Duration::new(20, 5)),
+            "not before"
+        );
+        assert_eq!(
+            info.permission.not_after(),
+            Some(Duration::new(1000, 5)),
+            "not after"
+        );
+        assert_eq!(
+            info.prefixes,
+            vec![
+                GrantPrefix {
+                    bucket: String::from("bucket"),
+                    prefix: Some(String::from("logs/")),
+                },
+                GrantPrefix {
+                    bucket: String::from("bucket"),
+                    prefix: None,
+                },
+                GrantPrefix {
+                    bucket: String::from("all"),
+                    prefix: Some(String::new()),
+                },
+            ],
+            "prefixes"
+        );
+        assert!(info.has_encryption_overrides, "encryption overrides");
+    }
+
+    /*** SatelliteAddress tests ***/
+    #[test]
+    fn test_satellite_address_parse() {
+        {
+            // Case: Domain name.
+            let addr = "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777";
+            let sat = SatelliteAddress::parse(addr).expect("parse");
+            assert_eq!(
+                sat.node_id(),
+                "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S",
+                "node ID"
+            );
+            assert_eq!(sat.host(), "us1.storj.io", "host");
+            assert_eq!(sat.port(), 7777, "port");
+            assert_eq!(sat.to_string(), addr, "display");
+        }
+        {
+            // Case: IPv4 address.
+            let sat = SatelliteAddress::parse("node@127.0.0.1:10000").expect("parse");
+            assert_eq!(sat.host(), "127.0.0.1", "host");
+            assert_eq!(sat.port(), 10000, "port");
+        }
+        {
+            // Case: IPv6 address in brackets.
+            let addr = "node@[2001:db8::1]:7777";
+            let sat = SatelliteAddress::parse(addr).expect("parse");
+            assert_eq!(sat.node_id(), "node", "node ID");
+            assert_eq!(sat.host(), "2001:db8::1", "host");
+            assert_eq!(sat.port(), 7777, "port");
+            assert_eq!(sat.to_string(), addr, "display");
+        }
+    }
+
+    #[test]
+    fn test_satellite_address_parse_invalid() {
+        let cases = [
+            // Without node ID.
+            "us1.storj.io:7777",
+            "@us1.storj.io:7777",
+            // Without port or invalid port.
+            "node@us1.storj.io",
+            "node@us1.storj.io:",
+            "node@us1.storj.io:port",
+            "node@us1.storj.io:65536",
+            "node@us1.storj.io:-1",
+            // Without host.
+            "node@:7777",
+            "node@[]:7777",
+            // IPv6 address without brackets or with unclosed brackets.
+            "node@2001:db8::1:7777",
+            "node@[2001:db8::1:7777",
+            "node@[2001:db8::1]7777",
+            // Junk.
+            "",
+            "@",
+            "junk",
+        ];
+
+        for addr in cases {
+            match SatelliteAddress::parse(addr) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "address", "invalid argument name for {:?}", addr)
+                }
+                res => panic!("unexpected result for {:?}: {:?}", addr, res),
+            }
+        }
+    }
+
     /*** Permission tests ***/
     #[test]
     fn test_permission_default() {
@@ -762,11 +1535,11 @@ mod test {
                 "set not before"
             );
 
-            perm.set_not_after(Some(Duration::new(5, 51)))
+            perm.set_not_after(Some(Duration::new(7, 51)))
                 .expect("set not after");
             assert_eq!(
                 perm.not_after(),
-                Some(Duration::new(5, 51)),
+                Some(Duration::new(7, 51)),
                 "set not after"
             );
         }
@@ -774,13 +1547,14 @@ mod test {
         // set not before violating its constraints.
         {
             if let Error::InvalidArguments(error::Args { names, msg }) = perm
-                .set_not_before(Some(Duration::new(5, 52)))
+                .set_not_before(Some(Duration::new(7, 52)))
                 .expect_err("set not before")
             {
                 assert_eq!(names, "since", "invalid error argument name");
                 assert_eq!(
                     msg,
-                    "cannot be more recent or equal to the not after valid time of the permission",
+                    "cannot be more recent or equal to the not after valid time of the \
+                    permission when both are rounded to whole seconds",
                     "invalid error argument message"
                 );
             } else {
@@ -797,7 +1571,8 @@ mod test {
                 assert_eq!(names, "until", "invalid error argument name");
                 assert_eq!(
                     msg,
-                    "cannot be previous or equal to the not before valid time of the permission",
+                    "cannot be previous or equal to the not before valid time of the \
+                    permission when both are rounded to whole seconds",
                     "invalid error argument message"
                 );
             } else {
@@ -814,4 +1589,141 @@ mod test {
             assert_eq!(perm.not_after(), None, "removing not after");
         }
     }
+
+    #[test]
+    fn test_permission_time_boundaries_zero() {
+        let mut perm = Permission::full();
+
+        if let Error::InvalidArguments(error::Args { names, msg }) = perm
+            .set_not_before(Some(Duration::ZERO))
+            .expect_err("set zero not before")
+        {
+            assert_eq!(names, "since", "invalid error argument name");
+            assert_eq!(
+                msg, "cannot be zero because it means that the not before valid time isn't set",
+                "invalid error argument message"
+            );
+        } else {
+            panic!("expected an invalid argument error");
+        }
+        assert_eq!(perm.not_before(), None, "not before after an invalid value");
+
+        for until in [Duration::ZERO, Duration::from_millis(999)] {
+            if let Error::InvalidArguments(error::Args { names, msg }) = perm
+                .set_not_after(Some(until))
+                .expect_err("set not after less than 1 second")
+            {
+                assert_eq!(names, "until", "invalid error argument name");
+                assert_eq!(
+                    msg,
+                    "cannot be less than 1 second because it means that the not after valid time \
+                    isn't set",
+                    "invalid error argument message"
+                );
+            } else {
+                panic!("expected an invalid argument error");
+            }
+        }
+        assert_eq!(perm.not_after(), None, "not after after an invalid value");
+
+        // The smallest valid values.
+        perm.set_not_before(Some(Duration::from_nanos(1)))
+            .expect("set not before");
+        perm.set_not_after(Some(Duration::from_secs(2)))
+            .expect("set not after");
+        let uc_perm = perm.as_ffi_permissions();
+        assert_eq!(uc_perm.not_before, 1, "FFI not before");
+        assert_eq!(uc_perm.not_after, 2, "FFI not after");
+    }
+
+    #[test]
+    fn test_permission_time_boundaries_same_second() {
+        let mut perm = Permission::full();
+        perm.set_not_before(Some(Duration::from_millis(1_200)))
+            .expect("set not before");
+
+        // They are 2 and 1 seconds respectively when they are rounded.
+        if let Error::InvalidArguments(error::Args { names, .. }) = perm
+            .set_not_after(Some(Duration::from_millis(1_800)))
+            .expect_err("set not after within the same second than not before")
+        {
+            assert_eq!(names, "until", "invalid error argument name");
+        } else {
+            panic!("expected an invalid argument error");
+        }
+        assert_eq!(perm.not_after(), None, "not after after an invalid value");
+
+        // They are 2 and 2 seconds respectively when they are rounded.
+        perm.set_not_before(None).expect("remove not before");
+        perm.set_not_after(Some(Duration::from_millis(2_800)))
+            .expect("set not after");
+        if let Error::InvalidArguments(error::Args { names, .. }) = perm
+            .set_not_before(Some(Duration::from_millis(1_200)))
+            .expect_err("set not before rounding up to not after")
+        {
+            assert_eq!(names, "since", "invalid error argument name");
+        } else {
+            panic!("expected an invalid argument error");
+        }
+        assert_eq!(perm.not_before(), None, "not before after an invalid value");
+
+        // They are 2 and 3 seconds respectively when they are rounded.
+        perm.set_not_after(Some(Duration::from_millis(3_100)))
+            .expect("set not after");
+        perm.set_not_before(Some(Duration::from_millis(1_200)))
+            .expect("set not before");
+        let uc_perm = perm.as_ffi_permissions();
+        assert_eq!(uc_perm.not_before, 2, "FFI not before");
+        assert_eq!(uc_perm.not_after, 3, "FFI not after");
+    }
+
+    #[test]
+    fn test_permission_as_ffi_permissions_not_before_rounds_up() {
+        let mut perm = Permission::full();
+        assert_eq!(perm.as_ffi_permissions().not_before, 0, "unset not before");
+
+        perm.set_not_before(Some(Duration::new(5, 0)))
+            .expect("set not before");
+        assert_eq!(
+            perm.as_ffi_permissions().not_before,
+            5,
+            "whole seconds not before"
+        );
+
+        for nanos in [1, 500_000_000, 999_999_999] {
+            perm.set_not_before(Some(Duration::new(5, nanos)))
+                .expect("set not before");
+            assert_eq!(
+                perm.as_ffi_permissions().not_before,
+                6,
+                "not before with {} nanoseconds",
+                nanos
+            );
+        }
+    }
+
+    #[test]
+    fn test_permission_as_ffi_permissions_not_after_rounds_down() {
+        let mut perm = Permission::full();
+        assert_eq!(perm.as_ffi_permissions().not_after, 0, "unset not after");
+
+        perm.set_not_after(Some(Duration::new(7, 0)))
+            .expect("set not after");
+        assert_eq!(
+            perm.as_ffi_permissions().not_after,
+            7,
+            "whole seconds not after"
+        );
+
+        for nanos in [1, 500_000_000, 999_999_999] {
+            perm.set_not_after(Some(Duration::new(7, nanos)))
+                .expect("set not after");
+            assert_eq!(
+                perm.as_ffi_permissions().not_after,
+                7,
+                "not after with {} nanoseconds",
+                nanos
+            );
+        }
+    }
 }
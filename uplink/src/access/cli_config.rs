@@ -0,0 +1,311 @@
+//! Access grants of the configuration files of the [uplink CLI](https://docs.storj.io/dcs/api/uplink-cli).
+//!
+//! The uplink CLI saves the access grants that it imports or creates in the `access.json` file of
+//! its configuration directory, naming each of them and marking one as the default. This module
+//! reads that file for creating a [`Grant`] through [`Grant::from_uplink_config`], so an
+//! application can use the same access grants than the uplink CLI without asking for them.
+//!
+//! The access grants of the configuration files of the uplink CLI versions previous to the
+//! `access.json` file (i.e. the `access` fields of `config.yaml`) aren't read.
+
+use super::Grant;
+use crate::error::BoxError;
+use crate::{Error, Result};
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The name of the file of the uplink CLI's configuration directory that has the access grants.
+const ACCESS_FILE_NAME: &str = "access.json";
+
+/// The environment variable that the uplink CLI uses for overriding its configuration directory.
+const CONFIG_DIR_ENV: &str = "UPLINK_CONFIG_DIR";
+
+/// The context message of the [`Error::Internal`] whose inner error is a [`ConfigError`].
+const CONFIG_ERROR_CTX: &str = "invalid uplink CLI configuration";
+
+impl Grant {
+    /// Creates an access grant from the access named `name` of the uplink CLI's configuration or
+    /// from its default access when `name` is `None`.
+    ///
+    /// The configuration directory is the one of the `UPLINK_CONFIG_DIR` environment variable, if
+    /// it's set, otherwise the default one of the uplink CLI for the OS, see [`config_dir`].
+    ///
+    /// It returns an [`Error::Internal`] whose inner error is a [`ConfigError`] if the access file
+    /// doesn't exist, it's malformed or it doesn't have the access, which can be got with
+    /// `std::error::Error::source` and `downcast_ref`. It returns an [`Error::Internal`] if the
+    /// configuration directory cannot be determined or the access file cannot be read and the same
+    /// errors than [`Grant::new`].
+    pub fn from_uplink_config(name: Option<&str>) -> Result<Grant> {
+        let dir = config_dir().ok_or_else(|| {
+            Error::new_internal(
+                "cannot determine the uplink CLI configuration directory",
+                BoxError::from("neither the configuration nor the home directory is set"),
+            )
+        })?;
+
+        Grant::new(&serialized_access(&dir, name)?)
+    }
+}
+
+/// Returns the uplink CLI's configuration directory, which is the `UPLINK_CONFIG_DIR` environment
+/// variable or the default one for the OS:
+///
+/// * Linux and other Unix: `$XDG_CONFIG_HOME/storj/uplink` or `$HOME/.config/storj/uplink`.
+/// * macOS: `$HOME/Library/Application Support/Storj/Uplink`.
+/// * Windows: `%AppData%\Storj\Uplink`.
+///
+/// It returns `None` if none of the environment variables that it depends on is set.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_path(CONFIG_DIR_ENV) {
+        return Some(dir);
+    }
+
+    if cfg!(target_os = "windows") {
+        ["AppData", "AppDataLocal", "UserProfile", "Home"]
+            .into_iter()
+            .find_map(env_path)
+            .map(|dir| dir.join("Storj").join("Uplink"))
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| {
+            home.join("Library")
+                .join("Application Support")
+                .join("Storj")
+                .join("Uplink")
+        })
+    } else {
+        env_path("XDG_CONFIG_HOME")
+            .or_else(|| env_path("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join("storj").join("uplink"))
+    }
+}
+
+/// Returns the path of the environment variable `name` if it's set and not empty.
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|val| !val.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The content of the uplink CLI's access file.
+#[derive(serde::Deserialize)]
+struct AccessFile {
+    /// The name of the default access.
+    #[serde(default)]
+    default: String,
+    /// The serialized access grants by name.
+    #[serde(default)]
+    accesses: HashMap<String, String>,
+}
+
+/// Returns the serialized access grant named `name`, or the default one if `name` is `None`, of
+/// the access file of the uplink CLI's configuration directory `dir`.
+///
+/// It returns the same errors than [`Grant::from_uplink_config`] except the ones of
+/// [`Grant::new`].
+fn serialized_access(dir: &Path, name: Option<&str>) -> Result<String> {
+    let path = dir.join(ACCESS_FILE_NAME);
+    let content = fs::read(&path).map_err(|err| {
+        if err.kind() == io::ErrorKind::NotFound {
+            ConfigError::FileNotFound { path: path.clone() }.into()
+        } else {
+            Error::new_internal(
+                "cannot read the uplink CLI access file",
+                BoxError::from(err),
+            )
+        }
+    })?;
+
+    let mut file: AccessFile =
+        serde_json::from_slice(&content).map_err(|err| ConfigError::Malformed {
+            path: path.clone(),
+            reason: err.to_string(),
+        })?;
+
+    let name = match name {
+        Some(name) => name,
+        None if file.default.is_empty() => {
+            return Err(ConfigError::Malformed {
+                path,
+                reason: String::from("there isn't a default access"),
+            }
+            .into())
+        }
+        None => &file.default,
+    };
+
+    file.accesses.remove(name).ok_or_else(|| {
+        ConfigError::AccessNotFound {
+            name: String::from(name),
+        }
+        .into()
+    })
+}
+
+/// The errors of the uplink CLI's configuration, which are the inner error of the
+/// [`Error::Internal`] returned by [`Grant::from_uplink_config`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The access file doesn't exist.
+    FileNotFound {
+        /// The path of the access file.
+        path: PathBuf,
+    },
+    /// The access file doesn't have an access with the requested or the default name.
+    AccessNotFound {
+        /// The name of the access.
+        name: String,
+    },
+    /// The access file isn't a valid access file.
+    Malformed {
+        /// The path of the access file.
+        path: PathBuf,
+        /// The reason why it isn't valid.
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FileNotFound { path } => {
+                write!(f, "access file '{}' doesn't exist", path.display())
+            }
+            Self::AccessNotFound { name } => write!(f, "access '{}' doesn't exist", name),
+            Self::Malformed { path, reason } => {
+                write!(
+                    f,
+                    "access file '{}' is malformed: {}",
+                    path.display(),
+                    reason
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::new_internal(CONFIG_ERROR_CTX, BoxError::from(err))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A temporary uplink CLI's configuration directory that is removed when it drops.
+    struct ConfigDir(PathBuf);
+
+    impl ConfigDir {
+        /// Creates the directory named `name` in the OS temporary directory with `access_file` as
+        /// the content of the access file, unless it's `None`.
+        fn new(name: &str, access_file: Option<&str>) -> Self {
+            let dir =
+                env::temp_dir().join(format!("uplink-cli-config-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&dir).expect("create configuration directory");
+            if let Some(content) = access_file {
+                fs::write(dir.join(ACCESS_FILE_NAME), content).expect("write access file");
+            }
+
+            Self(dir)
+        }
+    }
+
+    impl Drop for ConfigDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const ACCESS_FILE: &str =
+        r#"{"default": "main", "accesses": {"main": "main-access", "backup": "backup-access"}}"#;
+
+    fn config_error(res: Result<String>) -> ConfigError {
+        let err = res.expect_err("configuration error");
+        assert!(
+            matches!(&err, Error::Internal(internal) if internal.ctx_msg == CONFIG_ERROR_CTX),
+            "unexpected error: {:?}",
+            err
+        );
+        std::error::Error::source(&err)
+            .and_then(|inner| inner.downcast_ref::<ConfigError>())
+            .cloned()
+            .expect("inner configuration error")
+    }
+
+    #[test]
+    fn test_serialized_access() {
+        let dir = ConfigDir::new("select", Some(ACCESS_FILE));
+        assert_eq!(
+            serialized_access(&dir.0, None).expect("default access"),
+            "main-access",
+            "default access"
+        );
+        assert_eq!(
+            serialized_access(&dir.0, Some("backup")).expect("named access"),
+            "backup-access",
+            "named access"
+        );
+    }
+
+    #[test]
+    fn test_serialized_access_errors() {
+        let dir = ConfigDir::new("missing-file", None);
+        assert_eq!(
+            config_error(serialized_access(&dir.0, None)),
+            ConfigError::FileNotFound {
+                path: dir.0.join(ACCESS_FILE_NAME)
+            },
+            "missing file"
+        );
+
+        let dir = ConfigDir::new("missing-access", Some(ACCESS_FILE));
+        assert_eq!(
+            config_error(serialized_access(&dir.0, Some("other"))),
+            ConfigError::AccessNotFound {
+                name: String::from("other")
+            },
+            "missing named access"
+        );
+
+        let dir = ConfigDir::new(
+            "missing-default",
+            Some(r#"{"default": "other", "accesses": {"main": "main-access"}}"#),
+        );
+        assert_eq!(
+            config_error(serialized_access(&dir.0, None)),
+            ConfigError::AccessNotFound {
+                name: String::from("other")
+            },
+            "missing default access"
+        );
+
+        for (name, content) in [
+            ("malformed-json", r#"{"default": "main", "accesses": "#),
+            (
+                "malformed-accesses",
+                r#"{"default": "main", "accesses": ["main"]}"#,
+            ),
+            (
+                "malformed-no-default",
+                r#"{"accesses": {"main": "main-access"}}"#,
+            ),
+        ] {
+            let dir = ConfigDir::new(name, Some(content));
+            match config_error(serialized_access(&dir.0, None)) {
+                ConfigError::Malformed { path, .. } => {
+                    assert_eq!(path, dir.0.join(ACCESS_FILE_NAME), "{}: path", name)
+                }
+                err => panic!("{}: unexpected error: {:?}", name, err),
+            }
+        }
+    }
+}
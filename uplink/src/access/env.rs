@@ -0,0 +1,234 @@
+//! Access grants configured through environment variables.
+//!
+//! [`Grant::from_env`] creates an access grant from the environment variables of this module with
+//! the following precedence:
+//!
+//! 1. [`ACCESS_VAR`], which is a serialized access grant.
+//! 2. [`SATELLITE_URL_VAR`], [`API_KEY_VAR`] and [`PASSPHRASE_VAR`], which request an access grant
+//!    to the satellite through [`Grant::request_access_with_passphrase`]. The three must be set.
+//!
+//! The variables set to an empty value are considered unset.
+
+use super::Grant;
+use crate::{Error, Result};
+
+use std::env;
+
+/// The environment variable with a serialized access grant.
+pub const ACCESS_VAR: &str = "UPLINK_ACCESS";
+
+/// The environment variable with the satellite address for requesting an access grant.
+pub const SATELLITE_URL_VAR: &str = "UPLINK_SATELLITE_URL";
+
+/// The environment variable with the API key for requesting an access grant.
+pub const API_KEY_VAR: &str = "UPLINK_API_KEY";
+
+/// The environment variable with the passphrase for requesting an access grant.
+pub const PASSPHRASE_VAR: &str = "UPLINK_PASSPHRASE";
+
+impl Grant {
+    /// Creates an access grant from the environment variables with the precedence described in
+    /// the [module documentation](self).
+    ///
+    /// It returns an [`Error::InvalidArguments`] whose names are the missing environment
+    /// variables if [`ACCESS_VAR`] isn't set and any of [`SATELLITE_URL_VAR`], [`API_KEY_VAR`] and
+    /// [`PASSPHRASE_VAR`] isn't set either, which includes [`ACCESS_VAR`] when none is set, or
+    /// whose name is the environment variable whose value isn't valid UTF-8. It returns the same errors than [`Grant::new`] and
+    /// [`Grant::request_access_with_passphrase`].
+    pub fn from_env() -> Result<Grant> {
+        match source()? {
+            Source::Serialized(access) => Grant::new(&access),
+            Source::Passphrase {
+                satellite_addr,
+                api_key,
+                passphrase,
+            } => Grant::request_access_with_passphrase(&satellite_addr, &api_key, &passphrase),
+        }
+    }
+}
+
+/// The configuration of the access grant read from the environment variables.
+#[derive(Debug, PartialEq, Eq)]
+enum Source {
+    /// A serialized access grant.
+    Serialized(String),
+    /// The parameters for requesting an access grant to the satellite.
+    Passphrase {
+        /// The satellite address.
+        satellite_addr: String,
+        /// The API key.
+        api_key: String,
+        /// The passphrase.
+        passphrase: String,
+    },
+}
+
+/// Returns the configuration of the access grant of the environment variables applying the
+/// precedence described in the [module documentation](self).
+fn source() -> Result<Source> {
+    if let Some(access) = var(ACCESS_VAR)? {
+        return Ok(Source::Serialized(access));
+    }
+
+    let satellite_addr = var(SATELLITE_URL_VAR)?;
+    let api_key = var(API_KEY_VAR)?;
+    let passphrase = var(PASSPHRASE_VAR)?;
+    match (satellite_addr, api_key, passphrase) {
+        (Some(satellite_addr), Some(api_key), Some(passphrase)) => Ok(Source::Passphrase {
+            satellite_addr,
+            api_key,
+            passphrase,
+        }),
+        (satellite_addr, api_key, passphrase) => {
+            let missing: Vec<&str> = [
+                (SATELLITE_URL_VAR, satellite_addr.is_none()),
+                (API_KEY_VAR, api_key.is_none()),
+                (PASSPHRASE_VAR, passphrase.is_none()),
+            ]
+            .into_iter()
+            .filter_map(|(name, missing)| missing.then_some(name))
+            .collect();
+
+            if missing.len() == 3 {
+                return Err(Error::new_invalid_arguments(
+                    &format!("({}, {})", ACCESS_VAR, missing.join(", ")),
+                    &format!(
+                        "{} or the other variables must be set for configuring the access grant",
+                        ACCESS_VAR
+                    ),
+                ));
+            }
+
+            let names = if missing.len() == 1 {
+                String::from(missing[0])
+            } else {
+                format!("({})", missing.join(", "))
+            };
+            let msg = "must be set because the access grant is partially configured";
+            Err(Error::new_invalid_arguments(&names, msg))
+        }
+    }
+}
+
+/// Returns the value of the environment variable `name` or `None` if it isn't set or it's empty.
+///
+/// It returns an [`Error::InvalidArguments`] if its value isn't valid UTF-8.
+fn var(name: &str) -> Result<Option<String>> {
+    match env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => {
+            Err(Error::new_invalid_arguments(name, "must be valid UTF-8"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    use std::sync::{Mutex, PoisonError};
+
+    /// Serializes the tests that set the environment variables of this module.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Sets the environment variables of this module to `values`, unsetting the ones that are
+    /// `None`, in the order [`ACCESS_VAR`], [`SATELLITE_URL_VAR`], [`API_KEY_VAR`] and
+    /// [`PASSPHRASE_VAR`].
+    fn set_vars(values: [Option<&str>; 4]) {
+        for (name, value) in [ACCESS_VAR, SATELLITE_URL_VAR, API_KEY_VAR, PASSPHRASE_VAR]
+            .into_iter()
+            .zip(values)
+        {
+            match value {
+                Some(v) => env::set_var(name, v),
+                None => env::remove_var(name),
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_env_precedence() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        set_vars([None, Some("sat:7777"), Some("key"), Some("secret")]);
+        assert_eq!(
+            source().expect("passphrase source"),
+            Source::Passphrase {
+                satellite_addr: String::from("sat:7777"),
+                api_key: String::from("key"),
+                passphrase: String::from("secret"),
+            },
+            "passphrase"
+        );
+
+        set_vars([
+            Some("access"),
+            Some("sat:7777"),
+            Some("key"),
+            Some("secret"),
+        ]);
+        assert_eq!(
+            source().expect("serialized source"),
+            Source::Serialized(String::from("access")),
+            "serialized access grant wins"
+        );
+
+        set_vars([Some(""), Some("sat:7777"), Some("key"), Some("secret")]);
+        assert!(
+            matches!(source(), Ok(Source::Passphrase { .. })),
+            "empty serialized access grant is unset"
+        );
+
+        const ACCESS: &str = "15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu";
+        set_vars([Some(ACCESS), None, None, None]);
+        let grant = Grant::from_env().expect("access grant from the environment");
+        assert_eq!(
+            grant.serialize().expect("serialize access grant"),
+            ACCESS,
+            "serialized access grant"
+        );
+
+        set_vars([None, None, None, None]);
+    }
+
+    #[test]
+    fn test_from_env_missing_vars() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for (name, values, want) in [
+            (
+                "nothing set",
+                [None, None, None, None],
+                "(UPLINK_ACCESS, UPLINK_SATELLITE_URL, UPLINK_API_KEY, UPLINK_PASSPHRASE)",
+            ),
+            (
+                "empty values",
+                [Some(""), Some(""), Some(""), Some("")],
+                "(UPLINK_ACCESS, UPLINK_SATELLITE_URL, UPLINK_API_KEY, UPLINK_PASSPHRASE)",
+            ),
+            (
+                "only satellite",
+                [None, Some("sat:7777"), None, None],
+                "(UPLINK_API_KEY, UPLINK_PASSPHRASE)",
+            ),
+            (
+                "missing passphrase",
+                [None, Some("sat:7777"), Some("key"), None],
+                "UPLINK_PASSPHRASE",
+            ),
+        ] {
+            set_vars(values);
+            match Grant::from_env() {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, want, "{}: invalid argument names", name)
+                }
+                res => panic!("{}: unexpected result: {:?}", name, res),
+            }
+        }
+
+        set_vars([None, None, None, None]);
+    }
+}
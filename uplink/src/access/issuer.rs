@@ -0,0 +1,231 @@
+//! Issuance and revocation of temporary access grants.
+//!
+//! [`Issuer`] shares its root access grant with a valid time window that starts when it's issued
+//! and revokes the issued access grants before they expire when they must not be used anymore.
+//!
+//! ```no_run
+//! use uplink::access::{Grant, Issuer, Permission, SharePrefix};
+//!
+//! use std::time::Duration;
+//!
+//! let issuer = Issuer::new(Grant::new("my-serialized-access-grant")?);
+//! let issued = issuer.issue(
+//!     &Permission::read_only(),
+//!     Some(vec![SharePrefix::new("my-bucket", "reports/")?]),
+//!     Duration::from_secs(3600),
+//! )?;
+//!
+//! // Hand `issued.serialized()` to the client and keep `issued` for revoking it.
+//! issuer.revoke(&issued)?;
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use super::{serialized, Grant, Permission, SharePrefix};
+use crate::error::BoxError;
+use crate::{helpers, Error, Project, Result};
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Issues temporary access grants shared from a root access grant and revokes them.
+pub struct Issuer {
+    /// The access grant from which the access grants are shared.
+    root: Grant,
+    /// The project opened with `root`, which revokes the issued access grants.
+    project: Project,
+}
+
+impl Issuer {
+    /// Creates an issuer of access grants shared from `root`, opening a project with it for
+    /// revoking them.
+    pub fn new(root: Grant) -> Self {
+        let project = Project::open(&root);
+        Self { root, project }
+    }
+
+    /// Returns the access grant from which the access grants are shared.
+    pub fn root(&self) -> &Grant {
+        &self.root
+    }
+
+    /// Returns the project opened with the root access grant.
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// Issues an access grant shared from the root access grant with `permission` and `prefixes`
+    /// that is valid from now until `ttl` elapses.
+    ///
+    /// The not before and not after valid times of `permission` are replaced. The FFI only
+    /// supports whole seconds, so the not before valid time is rounded down to the current second
+    /// for the access grant to be valid immediately.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `ttl` is less than 1 second or `permission`
+    /// doesn't allow any operation, an [`Error::Internal`] if the system time is before the Unix
+    /// Epoch or the shared access grant cannot be decoded and the same errors than
+    /// [`Grant::share`] and [`Grant::serialize`].
+    pub fn issue(
+        &self,
+        permission: &Permission,
+        prefixes: Option<Vec<SharePrefix>>,
+        ttl: Duration,
+    ) -> Result<IssuedGrant> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| {
+                Error::new_internal("system time before Unix Epoch", BoxError::from(err))
+            })?;
+        let permission = temporary_permission(permission, ttl, now)?;
+
+        let serialized = self.root.share(&permission, prefixes)?.serialize()?;
+        let scope = serialized::decode(&serialized).map_err(|err| {
+            Error::new_internal(
+                "FFI returned an invalid serialized access grant",
+                BoxError::from(err),
+            )
+        })?;
+
+        Ok(IssuedGrant {
+            id: helpers::hex(&scope.api_key_tail),
+            serialized,
+            expires_at: now + ttl,
+        })
+    }
+
+    /// Revokes `issued`, which must have been issued by this issuer, see
+    /// [`Project::revoke_access`] about when the revocation applies.
+    ///
+    /// It returns the same errors than [`Grant::new`] and [`Project::revoke_access`].
+    pub fn revoke(&self, issued: &IssuedGrant) -> Result<()> {
+        let grant = Grant::new(&issued.serialized)?;
+        self.project.revoke_access(&grant)
+    }
+}
+
+impl fmt::Debug for Issuer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Issuer")
+            .field("root", &self.root)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An access grant issued by [`Issuer::issue`].
+#[derive(Clone, Debug)]
+pub struct IssuedGrant {
+    /// The opaque identifier of the access grant.
+    id: String,
+    /// The serialized access grant.
+    serialized: String,
+    /// The time since the Unix Epoch after which the access grant isn't valid.
+    expires_at: Duration,
+}
+
+impl IssuedGrant {
+    /// Returns an opaque identifier of the access grant, which is unique for each issued access
+    /// grant and doesn't give access to anything, so it can be logged or stored for tracking the
+    /// issued access grants.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the serialized access grant.
+    pub fn serialized(&self) -> &str {
+        &self.serialized
+    }
+
+    /// Returns the time since the Unix Epoch after which the access grant isn't valid.
+    pub fn expires_at(&self) -> Duration {
+        self.expires_at
+    }
+}
+
+/// Returns a copy of `permission` valid from `now` until `ttl` elapses, rounding `now` down to
+/// the whole second.
+///
+/// It returns an [`Error::InvalidArguments`] if `ttl` is less than 1 second or `permission`
+/// doesn't allow any operation.
+fn temporary_permission(
+    permission: &Permission,
+    ttl: Duration,
+    now: Duration,
+) -> Result<Permission> {
+    if ttl.as_secs() == 0 {
+        return Err(Error::new_invalid_arguments(
+            "ttl",
+            "cannot be less than 1 second",
+        ));
+    }
+
+    if !(permission.allow_download
+        || permission.allow_upload
+        || permission.allow_list
+        || permission.allow_delete)
+    {
+        return Err(Error::new_invalid_arguments(
+            "permission",
+            "must allow at least one operation",
+        ));
+    }
+
+    let mut permission = permission.clone();
+    permission.set_not_before(None)?;
+    permission.set_not_after(Some(now + ttl))?;
+    permission.set_not_before(Some(Duration::from_secs(now.as_secs())))?;
+    Ok(permission)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_temporary_permission() {
+        let mut perm = Permission::read_only();
+        perm.set_not_before(Some(Duration::from_secs(1)))
+            .expect("set not before");
+        perm.set_not_after(Some(Duration::from_secs(2)))
+            .expect("set not after");
+
+        let now = Duration::new(1_000, 500_000_000);
+        let temp = temporary_permission(&perm, Duration::from_secs(60), now)
+            .expect("temporary permission");
+        assert!(temp.allow_download, "allow download");
+        assert!(temp.allow_list, "allow list");
+        assert!(!temp.allow_upload, "allow upload");
+        assert!(!temp.allow_delete, "allow delete");
+        assert_eq!(
+            temp.not_before(),
+            Some(Duration::from_secs(1_000)),
+            "not before"
+        );
+        assert_eq!(
+            temp.not_after(),
+            Some(Duration::new(1_060, 500_000_000)),
+            "not after"
+        );
+        assert_eq!(
+            perm.not_before(),
+            Some(Duration::from_secs(1)),
+            "original permission untouched"
+        );
+    }
+
+    #[test]
+    fn test_temporary_permission_invalid() {
+        let now = Duration::from_secs(1_000);
+        for (perm, ttl, arg_name) in [
+            (Permission::full(), Duration::ZERO, "ttl"),
+            (Permission::full(), Duration::from_millis(999), "ttl"),
+            (Permission::new(), Duration::from_secs(60), "permission"),
+        ] {
+            match temporary_permission(&perm, ttl, now) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, arg_name, "invalid error argument name")
+                }
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+    }
+}
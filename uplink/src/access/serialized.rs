@@ -0,0 +1,623 @@
+//! Decoder of the serialized access grant format, whose content isn't exposed by the FFI.
+//!
+//! A serialized access grant is a Base58Check encoded (version byte 0) protocol buffers `Scope`
+//! message, which contains the satellite address, the API key, which is a macaroon whose caveats
+//! are protocol buffers `Caveat` messages, and the encryption access.
+//!
+//! Only the fields used by [`super::Grant::inspect`] are decoded and the unknown ones are
+//! skipped.
+
+use std::time::Duration;
+
+/// The decoded content of a serialized access grant.
+#[derive(Debug, Default)]
+pub(crate) struct Scope {
+    /// The satellite node address.
+    pub(crate) satellite_addr: String,
+    /// The caveats of the API key in the order that they were added.
+    pub(crate) caveats: Vec<Caveat>,
+    /// The signature of the API key, which is different for each API key derived from the same
+    /// root API key. Empty when the API key doesn't have it.
+    pub(crate) api_key_tail: Vec<u8>,
+    /// The entries of the encryption store, which are the prefixes that have their own
+    /// encryption key.
+    pub(crate) store_entries: Vec<StoreEntry>,
+}
+
+/// A restriction of the API key.
+#[derive(Debug, Default)]
+pub(crate) struct Caveat {
+    /// Disallows downloading.
+    pub(crate) disallow_reads: bool,
+    /// Disallows uploading.
+    pub(crate) disallow_writes: bool,
+    /// Disallows listing.
+    pub(crate) disallow_lists: bool,
+    /// Disallows deleting.
+    pub(crate) disallow_deletes: bool,
+    /// The paths that are allowed. Empty when all of them are allowed.
+    pub(crate) allowed_paths: Vec<AllowedPath>,
+    /// The time since the Unix Epoch after which the API key isn't valid.
+    pub(crate) not_after: Option<Duration>,
+    /// The time since the Unix Epoch before which the API key isn't valid.
+    pub(crate) not_before: Option<Duration>,
+}
+
+/// A path allowed by a caveat.
+#[derive(Debug, Default)]
+pub(crate) struct AllowedPath {
+    /// The bucket name.
+    pub(crate) bucket: String,
+    /// The encrypted object key prefix. Empty when all the bucket is allowed.
+    pub(crate) encrypted_prefix: Vec<u8>,
+}
+
+/// An entry of the encryption store.
+#[derive(Debug, Default)]
+pub(crate) struct StoreEntry {
+    /// The bucket name.
+    pub(crate) bucket: String,
+    /// The unencrypted object key prefix.
+    pub(crate) unencrypted_prefix: String,
+    /// The encrypted object key prefix.
+    pub(crate) encrypted_prefix: Vec<u8>,
+}
+
+/// The result of the functions of this module, whose error is a description of the malformed
+/// part.
+type DecodeResult<T> = std::result::Result<T, String>;
+
+/// Decodes the serialized access grant `serialized`.
+///
+/// The Base58Check checksum isn't verified because this module only decodes access grants
+/// serialized by the FFI.
+pub(crate) fn decode(serialized: &str) -> DecodeResult<Scope> {
+    let data = base58_decode(serialized)?;
+    if data.len() < 5 {
+        return Err(String::from("too short"));
+    }
+    if data[0] != 0 {
+        return Err(format!("unknown version {}", data[0]));
+    }
+
+    decode_scope(&data[1..data.len() - 4])
+}
+
+/// Decodes the protocol buffers `Scope` message.
+fn decode_scope(data: &[u8]) -> DecodeResult<Scope> {
+    let mut scope = Scope::default();
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Bytes(b)) => scope.satellite_addr = utf8("satellite address", b)?,
+            (2, Value::Bytes(b)) => {
+                (scope.caveats, scope.api_key_tail) = decode_macaroon(b)?;
+            }
+            (3, Value::Bytes(b)) => scope.store_entries = decode_encryption_access(b)?,
+            _ => {}
+        }
+    }
+
+    Ok(scope)
+}
+
+/// Decodes the caveats and the signature (i.e. tail) of a serialized macaroon, which is the API
+/// key.
+///
+/// A serialized macaroon is a version byte followed by sections of packets ended by an
+/// end-of-section packet: the first section contains the head, each following one a caveat, and
+/// an empty one ends them. A signature packet ends the macaroon.
+fn decode_macaroon(data: &[u8]) -> DecodeResult<(Vec<Caveat>, Vec<u8>)> {
+    /// The field type of the packets that contain the location.
+    const FIELD_LOCATION: u64 = 1;
+    /// The field type of the packets that contain the head or a caveat.
+    const FIELD_IDENTIFIER: u64 = 2;
+    /// The field type of the packet that contains the signature.
+    const FIELD_SIGNATURE: u64 = 6;
+
+    let mut data = data.get(1..).ok_or_else(|| String::from("empty API key"))?;
+
+    let mut caveats = Vec::new();
+    let mut is_head = true;
+    loop {
+        let (rest, mut section) = macaroon_section(data)?;
+        data = rest;
+        if section.is_empty() {
+            if is_head {
+                return Err(String::from("API key without head"));
+            }
+            break;
+        }
+
+        if section[0].0 == FIELD_LOCATION {
+            section.remove(0);
+        }
+        let identifier = match section.first() {
+            Some((FIELD_IDENTIFIER, identifier)) => identifier,
+            _ => return Err(String::from("API key with an invalid section")),
+        };
+
+        if is_head {
+            is_head = false;
+        } else {
+            caveats.push(decode_caveat(identifier)?);
+        }
+    }
+
+    if data.is_empty() {
+        return Ok((caveats, Vec::new()));
+    }
+    if varint(&mut data)? != FIELD_SIGNATURE {
+        return Err(String::from("API key with an invalid signature"));
+    }
+    let len = usize::try_from(varint(&mut data)?).map_err(|err| err.to_string())?;
+    let tail = take(&mut data, len)?;
+
+    Ok((caveats, tail.to_vec()))
+}
+
+/// A packet of a serialized macaroon, which is its field type and its data.
+type Packet<'a> = (u64, &'a [u8]);
+
+/// Decodes the packets of a macaroon section, returning the data after the end of the section
+/// and its packets.
+fn macaroon_section(mut data: &[u8]) -> DecodeResult<(&[u8], Vec<Packet<'_>>)> {
+    let mut packets = Vec::new();
+    loop {
+        let field_type = varint(&mut data)?;
+        if field_type == 0 {
+            return Ok((data, packets));
+        }
+
+        let len = usize::try_from(varint(&mut data)?).map_err(|err| err.to_string())?;
+        let packet = take(&mut data, len)?;
+        packets.push((field_type, packet));
+    }
+}
+
+/// Decodes the protocol buffers `Caveat` message.
+fn decode_caveat(data: &[u8]) -> DecodeResult<Caveat> {
+    let mut caveat = Caveat::default();
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Varint(v)) => caveat.disallow_reads = v != 0,
+            (2, Value::Varint(v)) => caveat.disallow_writes = v != 0,
+            (3, Value::Varint(v)) => caveat.disallow_lists = v != 0,
+            (4, Value::Varint(v)) => caveat.disallow_deletes = v != 0,
+            (10, Value::Bytes(b)) => caveat.allowed_paths.push(decode_allowed_path(b)?),
+            (20, Value::Bytes(b)) => caveat.not_after = Some(decode_timestamp(b)?),
+            (21, Value::Bytes(b)) => caveat.not_before = Some(decode_timestamp(b)?),
+            _ => {}
+        }
+    }
+
+    Ok(caveat)
+}
+
+/// Decodes the protocol buffers `Caveat.Path` message.
+fn decode_allowed_path(data: &[u8]) -> DecodeResult<AllowedPath> {
+    let mut path = AllowedPath::default();
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Bytes(b)) => path.bucket = utf8("bucket", b)?,
+            (2, Value::Bytes(b)) => path.encrypted_prefix = b.to_vec(),
+            _ => {}
+        }
+    }
+
+    Ok(path)
+}
+
+/// Decodes the protocol buffers `Timestamp` and `Duration` messages, which have the same
+/// fields, as a duration. Negative values are clamped to zero.
+fn decode_timestamp(data: &[u8]) -> DecodeResult<Duration> {
+    let mut secs = 0i64;
+    let mut nanos = 0i32;
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Varint(v)) => secs = v as i64,
+            (2, Value::Varint(v)) => nanos = v as i32,
+            _ => {}
+        }
+    }
+
+    if secs < 0 {
+        return Ok(Duration::ZERO);
+    }
+
+    Ok(Duration::new(
+        secs as u64,
+        nanos.clamp(0, 999_999_999) as u32,
+    ))
+}
+
+/// Decodes the entries of the encryption store of the protocol buffers `EncryptionAccess`
+/// message.
+fn decode_encryption_access(data: &[u8]) -> DecodeResult<Vec<StoreEntry>> {
+    let mut entries = Vec::new();
+    for field in Fields::new(data) {
+        if let (2, Value::Bytes(b)) = field? {
+            entries.push(decode_store_entry(b)?);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Decodes the protocol buffers `EncryptionAccess.StoreEntry` message.
+fn decode_store_entry(data: &[u8]) -> DecodeResult<StoreEntry> {
+    let mut entry = StoreEntry::default();
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Bytes(b)) => entry.bucket = utf8("bucket", b)?,
+            (2, Value::Bytes(b)) => entry.unencrypted_prefix = utf8("unencrypted path", b)?,
+            (3, Value::Bytes(b)) => entry.encrypted_prefix = b.to_vec(),
+            _ => {}
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Converts `data` to a `String` returning an error which mentions `name` if it isn't valid
+/// UTF-8.
+fn utf8(name: &str, data: &[u8]) -> DecodeResult<String> {
+    String::from_utf8(data.to_vec()).map_err(|_| format!("{} isn't valid UTF-8", name))
+}
+
+/// A value of a protocol buffers field.
+#[derive(Debug, PartialEq)]
+enum Value<'a> {
+    /// A varint encoded value (e.g. `int64`, `bool`, enumerations).
+    Varint(u64),
+    /// A length-delimited value (e.g. `string`, `bytes`, embedded messages).
+    Bytes(&'a [u8]),
+    /// A 64 bits fixed value, which isn't used by any decoded field.
+    Fixed64,
+    /// A 32 bits fixed value, which isn't used by any decoded field.
+    Fixed32,
+}
+
+/// Iterator over the fields of a protocol buffers message, which yields their number and value.
+struct Fields<'a> {
+    /// The part of the message that isn't decoded yet.
+    data: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    /// Creates an iterator over the fields of the message `data`.
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Decodes the next field.
+    fn decode_next(&mut self) -> DecodeResult<(u64, Value<'a>)> {
+        let key = varint(&mut self.data)?;
+        let value = match key & 0x7 {
+            0 => Value::Varint(varint(&mut self.data)?),
+            1 => {
+                take(&mut self.data, 8)?;
+                Value::Fixed64
+            }
+            2 => {
+                let len =
+                    usize::try_from(varint(&mut self.data)?).map_err(|err| err.to_string())?;
+                Value::Bytes(take(&mut self.data, len)?)
+            }
+            5 => {
+                take(&mut self.data, 4)?;
+                Value::Fixed32
+            }
+            wire_type => return Err(format!("unsupported protobuf wire type {}", wire_type)),
+        };
+
+        Ok((key >> 3, value))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = DecodeResult<(u64, Value<'a>)>;
+
+    /// It doesn't yield more fields after an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let res = self.decode_next();
+        if res.is_err() {
+            self.data = &[];
+        }
+        Some(res)
+    }
+}
+
+/// Decodes a varint from the beginning of `data` and advances it after the varint.
+fn varint(data: &mut &[u8]) -> DecodeResult<u64> {
+    let mut value = 0u64;
+    for (i, b) in data.iter().enumerate().take(10) {
+        value |= u64::from(b & 0x7f) << (7 * i);
+        if b & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Ok(value);
+        }
+    }
+
+    Err(String::from("invalid varint"))
+}
+
+/// Returns the first `len` bytes of `data` and advances it after them.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> DecodeResult<&'a [u8]> {
+    if data.len() < len {
+        return Err(String::from("value extends past the end of the data"));
+    }
+
+    let (value, rest) = data.split_at(len);
+    *data = rest;
+    Ok(value)
+}
+
+/// The Bitcoin Base58 alphabet.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Decodes the Base58 encoded string `s`.
+fn base58_decode(s: &str) -> DecodeResult<Vec<u8>> {
+    // Little-endian base 256 digits.
+    let mut digits: Vec<u8> = Vec::with_capacity(s.len());
+    for c in s.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| format!("invalid Base58 character {:?}", c as char))?
+            as u32;
+
+        for d in digits.iter_mut() {
+            carry += u32::from(*d) * 58;
+            *d = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            digits.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Each leading '1' is a leading zero byte.
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    let mut decoded = vec![0; zeros];
+    decoded.extend(digits.iter().rev());
+    Ok(decoded)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Encodes `data` in Base58.
+    fn base58_encode(data: &[u8]) -> String {
+        // Little-endian base 58 digits.
+        let mut digits: Vec<u8> = Vec::new();
+        for &b in data {
+            let mut carry = u32::from(b);
+            for d in digits.iter_mut() {
+                carry += u32::from(*d) << 8;
+                *d = (carry % 58) as u8;
+                carry /= 58;
+            }
+            while carry > 0 {
+                digits.push((carry % 58) as u8);
+                carry /= 58;
+            }
+        }
+
+        let zeros = data.iter().take_while(|&&b| b == 0).count();
+        let mut encoded = "1".repeat(zeros);
+        encoded.extend(
+            digits
+                .iter()
+                .rev()
+                .map(|&d| BASE58_ALPHABET[d as usize] as char),
+        );
+        encoded
+    }
+
+    /// Appends the varint encoding of `v` to `buf`.
+    fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        buf.push(v as u8);
+    }
+
+    /// Appends the protobuf varint field `num` to `buf`.
+    fn put_varint_field(buf: &mut Vec<u8>, num: u64, v: u64) {
+        put_varint(buf, num << 3);
+        put_varint(buf, v);
+    }
+
+    /// Appends the protobuf length-delimited field `num` to `buf`.
+    fn put_bytes_field(buf: &mut Vec<u8>, num: u64, v: &[u8]) {
+        put_varint(buf, num << 3 | 2);
+        put_varint(buf, v.len() as u64);
+        buf.extend_from_slice(v);
+    }
+
+    /// Appends a macaroon section with one identifier packet of `data` to `buf`.
+    fn put_macaroon_section(buf: &mut Vec<u8>, data: &[u8]) {
+        put_varint(buf, 2);
+        put_varint(buf, data.len() as u64);
+        buf.extend_from_slice(data);
+        buf.push(0);
+    }
+
+    /// Returns a serialized access grant for the satellite `satellite_addr` with the API key
+    /// caveats `caveats` and the encryption store entries `entries`, which are protobuf encoded
+    /// messages.
+    pub(crate) fn serialized_grant(
+        satellite_addr: &str,
+        caveats: &[Vec<u8>],
+        entries: &[Vec<u8>],
+    ) -> String {
+        let mut macaroon = vec![2];
+        put_macaroon_section(&mut macaroon, b"head");
+        for caveat in caveats {
+            put_macaroon_section(&mut macaroon, caveat);
+        }
+        macaroon.push(0);
+        put_varint(&mut macaroon, 6);
+        put_varint(&mut macaroon, 4);
+        macaroon.extend_from_slice(b"tail");
+
+        let mut encryption_access = Vec::new();
+        put_bytes_field(&mut encryption_access, 1, b"default key");
+        for entry in entries {
+            put_bytes_field(&mut encryption_access, 2, entry);
+        }
+
+        let mut scope = vec![0];
+        put_bytes_field(&mut scope, 1, satellite_addr.as_bytes());
+        put_bytes_field(&mut scope, 2, &macaroon);
+        put_bytes_field(&mut scope, 3, &encryption_access);
+        // Checksum, which isn't verified.
+        scope.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        base58_encode(&scope)
+    }
+
+    /// Returns a protobuf encoded `Caveat` message.
+    pub(crate) fn caveat(
+        disallow: [bool; 4],
+        allowed_paths: &[(&str, &[u8])],
+        not_before_secs: Option<u64>,
+        not_after_secs: Option<u64>,
+    ) -> Vec<u8> {
+        let mut caveat = Vec::new();
+        for (i, d) in disallow.iter().enumerate() {
+            if *d {
+                put_varint_field(&mut caveat, i as u64 + 1, 1);
+            }
+        }
+        for (bucket, prefix) in allowed_paths {
+            let mut path = Vec::new();
+            put_bytes_field(&mut path, 1, bucket.as_bytes());
+            put_bytes_field(&mut path, 2, prefix);
+            put_bytes_field(&mut caveat, 10, &path);
+        }
+        for (num, secs) in [(20, not_after_secs), (21, not_before_secs)] {
+            if let Some(secs) = secs {
+                let mut ts = Vec::new();
+                put_varint_field(&mut ts, 1, secs);
+                put_varint_field(&mut ts, 2, 5);
+                put_bytes_field(&mut caveat, num, &ts);
+            }
+        }
+        put_bytes_field(&mut caveat, 30, b"nonce");
+        caveat
+    }
+
+    /// Returns a protobuf encoded `EncryptionAccess.StoreEntry` message.
+    pub(crate) fn store_entry(bucket: &str, unencrypted: &str, encrypted: &[u8]) -> Vec<u8> {
+        let mut entry = Vec::new();
+        put_bytes_field(&mut entry, 1, bucket.as_bytes());
+        put_bytes_field(&mut entry, 2, unencrypted.as_bytes());
+        put_bytes_field(&mut entry, 3, encrypted);
+        put_bytes_field(&mut entry, 4, b"key");
+        entry
+    }
+
+    #[test]
+    fn test_base58_decode() {
+        assert_eq!(base58_decode("").expect("empty"), Vec::<u8>::new(), "empty");
+        assert_eq!(base58_decode("1").expect("zero"), vec![0], "zero");
+        assert_eq!(
+            base58_decode("StV1DL6CwTryKyV").expect("hello world"),
+            b"hello world",
+            "hello world"
+        );
+        base58_decode("0OIl").expect_err("invalid characters");
+
+        let data = [0, 0, 1, 2, 255, 128, 0, 7];
+        assert_eq!(
+            base58_decode(&base58_encode(&data)).expect("round trip"),
+            data,
+            "round trip"
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let serialized = serialized_grant(
+            "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777",
+            &[
+                caveat([false, true, false, true], &[], Some(10), Some(100)),
+                caveat(
+                    [false; 4],
+                    &[("bucket-a", b"enc"), ("bucket-b", b"")],
+                    None,
+                    None,
+                ),
+            ],
+            &[store_entry("bucket-a", "logs/", b"enc")],
+        );
+
+        let scope = decode(&serialized).expect("decode");
+        assert_eq!(
+            scope.satellite_addr,
+            "12EayRS2V1kEsWESU9QMRseFhdxYxKicsiFmxrsLZHeLUtdps3S@us1.storj.io:7777",
+            "satellite address"
+        );
+
+        assert_eq!(scope.api_key_tail, b"tail", "API key tail");
+        assert_eq!(scope.caveats.len(), 2, "number of caveats");
+        let c = &scope.caveats[0];
+        assert!(!c.disallow_reads, "disallow reads");
+        assert!(c.disallow_writes, "disallow writes");
+        assert!(!c.disallow_lists, "disallow lists");
+        assert!(c.disallow_deletes, "disallow deletes");
+        assert!(c.allowed_paths.is_empty(), "allowed paths");
+        assert_eq!(c.not_before, Some(Duration::new(10, 5)), "not before");
+        assert_eq!(c.not_after, Some(Duration::new(100, 5)), "not after");
+
+        let c = &scope.caveats[1];
+        assert_eq!(c.allowed_paths.len(), 2, "number of allowed paths");
+        assert_eq!(c.allowed_paths[0].bucket, "bucket-a", "allowed path bucket");
+        assert_eq!(
+            c.allowed_paths[0].encrypted_prefix, b"enc",
+            "allowed path prefix"
+        );
+        assert_eq!(c.allowed_paths[1].bucket, "bucket-b", "allowed path bucket");
+        assert!(
+            c.allowed_paths[1].encrypted_prefix.is_empty(),
+            "allowed path prefix"
+        );
+
+        assert_eq!(scope.store_entries.len(), 1, "number of store entries");
+        let e = &scope.store_entries[0];
+        assert_eq!(e.bucket, "bucket-a", "store entry bucket");
+        assert_eq!(
+            e.unencrypted_prefix, "logs/",
+            "store entry unencrypted prefix"
+        );
+        assert_eq!(e.encrypted_prefix, b"enc", "store entry encrypted prefix");
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        decode("").expect_err("empty");
+        decode("0").expect_err("invalid Base58");
+        decode(&base58_encode(&[1, 0, 0, 0, 0, 0])).expect_err("unknown version");
+
+        // A scope whose API key field extends past the end of the data.
+        let mut scope = vec![0];
+        put_varint(&mut scope, 2 << 3 | 2);
+        put_varint(&mut scope, 100);
+        scope.extend_from_slice(&[0; 8]);
+        decode(&base58_encode(&scope)).expect_err("truncated field");
+
+        // An API key without head.
+        let mut scope = vec![0];
+        put_bytes_field(&mut scope, 2, &[2, 0]);
+        scope.extend_from_slice(&[0; 4]);
+        decode(&base58_encode(&scope)).expect_err("API key without head");
+    }
+}
@@ -0,0 +1,108 @@
+//! Abstraction of the core operations to manage buckets and objects.
+//!
+//! The [`ObjectStore`] trait allows to write code that doesn't depend on a specific backend, so it
+//! can use a [`Project`](crate::Project), which performs the operations on the Storj DCS network,
+//! or, when the `testing` feature is enabled, a `MemoryStore`, which performs them in memory for
+//! unit testing the code without a satellite.
+//!
+//! ```no_run
+//! use uplink::api::ObjectStore;
+//!
+//! fn count_objects(store: &impl ObjectStore, bucket: &str) -> uplink::Result<usize> {
+//!     Ok(store.list(bucket, "", true)?.len())
+//! }
+//! ```
+
+#[cfg(feature = "testing")]
+pub mod memory;
+
+#[cfg(feature = "testing")]
+pub use memory::MemoryStore;
+
+use crate::project::options;
+use crate::{Bucket, Object, Project, Result};
+
+/// The core operations to manage buckets and objects.
+///
+/// All the implementations must return the same [`Error`](crate::Error) variants for the same
+/// failures that [`Project`] returns.
+pub trait ObjectStore {
+    /// Creates the bucket if it doesn't exist and returns it.
+    fn make_bucket(&self, bucket: &str) -> Result<Bucket>;
+
+    /// Returns the information of the bucket.
+    fn stat_bucket(&self, bucket: &str) -> Result<Bucket>;
+
+    /// Deletes the bucket, which must be empty, and returns it.
+    fn delete_bucket(&self, bucket: &str) -> Result<Bucket>;
+
+    /// Returns all the buckets.
+    fn buckets(&self) -> Result<Vec<Bucket>>;
+
+    /// Uploads `data` as the object inside of `bucket` and referenced by `key` and returns it.
+    fn put(&self, bucket: &str, key: &str, data: &[u8]) -> Result<Object>;
+
+    /// Returns the data of the object inside of `bucket` and referenced by `key`.
+    fn get(&self, bucket: &str, key: &str) -> Result<Vec<u8>>;
+
+    /// Returns the information of the object inside of `bucket` and referenced by `key`.
+    fn stat(&self, bucket: &str, key: &str) -> Result<Object>;
+
+    /// Returns the objects inside of `bucket` whose key starts with `prefix`, which is empty or it
+    /// ends with '/', sorted by key.
+    ///
+    /// When `recursive` is `false`, the objects whose key contains a '/' after `prefix` are
+    /// collapsed into one prefix object whose key is the key until that '/' included.
+    fn list(&self, bucket: &str, prefix: &str, recursive: bool) -> Result<Vec<Object>>;
+
+    /// Deletes the object inside of `bucket` and referenced by `key`. It doesn't return an error if
+    /// the object doesn't exist.
+    fn delete(&self, bucket: &str, key: &str) -> Result<()>;
+}
+
+impl ObjectStore for Project {
+    fn make_bucket(&self, bucket: &str) -> Result<Bucket> {
+        self.ensure_bucket(bucket)
+    }
+
+    fn stat_bucket(&self, bucket: &str) -> Result<Bucket> {
+        Project::stat_bucket(self, bucket)
+    }
+
+    fn delete_bucket(&self, bucket: &str) -> Result<Bucket> {
+        Project::delete_bucket(self, bucket)
+    }
+
+    fn buckets(&self) -> Result<Vec<Bucket>> {
+        self.list_buckets(None)?.collect()
+    }
+
+    fn put(&self, bucket: &str, key: &str, data: &[u8]) -> Result<Object> {
+        self.put_object(bucket, key, data, None)
+    }
+
+    fn get(&self, bucket: &str, key: &str) -> Result<Vec<u8>> {
+        self.get_object(bucket, key, None)
+    }
+
+    fn stat(&self, bucket: &str, key: &str) -> Result<Object> {
+        self.stat_object(bucket, key)
+    }
+
+    fn list(&self, bucket: &str, prefix: &str, recursive: bool) -> Result<Vec<Object>> {
+        let opts = options::ListObjects::builder()
+            .prefix(prefix)
+            .recursive(recursive)
+            .system(true)
+            .custom(true)
+            .build()?;
+
+        self.list_objects(bucket, Some(&opts))?.collect()
+    }
+
+    fn delete(&self, bucket: &str, key: &str) -> Result<()> {
+        // The outcome folds the object not found error, which is only returned when the access
+        // grant can read the object.
+        self.delete_object_outcome(bucket, key).map(|_| ())
+    }
+}
@@ -0,0 +1,463 @@
+//! In-memory implementation of [`ObjectStore`] for unit testing.
+
+use super::ObjectStore;
+use crate::error::UplinkCode;
+use crate::{bucket, error, metadata, Bucket, Error, Object, Result};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+/// An [`ObjectStore`] that keeps the buckets and objects in memory.
+///
+/// It mimics the semantics of the Storj DCS network for the supported operations, returning the
+/// same [`Error`] variants, so it allows to unit test code that uses an [`ObjectStore`] without a
+/// satellite. It validates the bucket names as [`Project`](crate::Project) does with the default
+/// configuration (see [`bucket::validate_name`]) but it doesn't enforce any of the network limits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    /// The buckets indexed by name.
+    buckets: Mutex<BTreeMap<String, MemoryBucket>>,
+}
+
+/// A bucket stored in a [`MemoryStore`].
+#[derive(Debug)]
+struct MemoryBucket {
+    /// Unix Epoch time when the bucket was created.
+    created_at: Duration,
+    /// The objects indexed by key.
+    objects: BTreeMap<String, MemoryObject>,
+}
+
+/// An object stored in a [`MemoryStore`].
+#[derive(Debug)]
+struct MemoryObject {
+    /// Unix Epoch time when the object was created.
+    created: Duration,
+    /// The object's data.
+    data: Vec<u8>,
+}
+
+impl MemoryObject {
+    /// Returns the object's information with the specified key.
+    fn to_object(&self, key: &str) -> Object {
+        Object {
+            key: String::from(key),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: self.created,
+                expires: None,
+                content_length: Some(self.data.len() as u64),
+            },
+            metadata_custom: metadata::Custom::default(),
+            has_system: true,
+            has_custom: true,
+        }
+    }
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the buckets locking them for the caller.
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, MemoryBucket>> {
+        // A poisoned lock only happens if a thread panicked while holding it and no operation
+        // leaves the buckets in an inconsistent state, so it's safe to use them.
+        self.buckets.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Returns the bucket with `name` from `buckets` or a bucket not found error.
+fn get_bucket<'a>(
+    buckets: &'a mut BTreeMap<String, MemoryBucket>,
+    name: &str,
+) -> Result<&'a mut MemoryBucket> {
+    buckets.get_mut(name).ok_or_else(|| {
+        Error::Uplink(error::Uplink::with_code(
+            UplinkCode::BucketNotFound,
+            &format!("{:?}", name),
+        ))
+    })
+}
+
+/// Returns the current time since the Unix Epoch.
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+impl ObjectStore for MemoryStore {
+    fn make_bucket(&self, bucket: &str) -> Result<Bucket> {
+        bucket::validate_name(bucket)?;
+
+        let mut buckets = self.lock();
+        let b = buckets
+            .entry(String::from(bucket))
+            .or_insert_with(|| MemoryBucket {
+                created_at: now(),
+                objects: BTreeMap::new(),
+            });
+
+        Ok(Bucket {
+            name: String::from(bucket),
+            created_at: b.created_at,
+        })
+    }
+
+    fn stat_bucket(&self, bucket: &str) -> Result<Bucket> {
+        bucket::validate_name(bucket)?;
+
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+
+        Ok(Bucket {
+            name: String::from(bucket),
+            created_at: b.created_at,
+        })
+    }
+
+    fn delete_bucket(&self, bucket: &str) -> Result<Bucket> {
+        bucket::validate_name(bucket)?;
+
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+        if !b.objects.is_empty() {
+            return Err(Error::Uplink(error::Uplink::with_code(
+                UplinkCode::BucketNotEmpty,
+                &format!("{:?}", bucket),
+            )));
+        }
+
+        let b = buckets
+            .remove(bucket)
+            .expect("BUG: bucket existence checked just above");
+        Ok(Bucket {
+            name: String::from(bucket),
+            created_at: b.created_at,
+        })
+    }
+
+    fn buckets(&self) -> Result<Vec<Bucket>> {
+        Ok(self
+            .lock()
+            .iter()
+            .map(|(name, b)| Bucket {
+                name: name.clone(),
+                created_at: b.created_at,
+            })
+            .collect())
+    }
+
+    fn put(&self, bucket: &str, key: &str, data: &[u8]) -> Result<Object> {
+        if key.is_empty() {
+            return Err(Error::Uplink(error::Uplink::with_code(
+                UplinkCode::ObjectKeyInvalid,
+                "\"\"",
+            )));
+        }
+
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+        let obj = MemoryObject {
+            created: now(),
+            data: data.to_vec(),
+        };
+        let info = obj.to_object(key);
+        b.objects.insert(String::from(key), obj);
+
+        Ok(info)
+    }
+
+    fn get(&self, bucket: &str, key: &str) -> Result<Vec<u8>> {
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+        b.objects.get(key).map(|o| o.data.clone()).ok_or_else(|| {
+            Error::Uplink(error::Uplink::with_code(
+                UplinkCode::ObjectNotFound,
+                &format!("{:?}", key),
+            ))
+        })
+    }
+
+    fn stat(&self, bucket: &str, key: &str) -> Result<Object> {
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+        b.objects.get(key).map(|o| o.to_object(key)).ok_or_else(|| {
+            Error::Uplink(error::Uplink::with_code(
+                UplinkCode::ObjectNotFound,
+                &format!("{:?}", key),
+            ))
+        })
+    }
+
+    fn list(&self, bucket: &str, prefix: &str, recursive: bool) -> Result<Vec<Object>> {
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            return Err(Error::new_invalid_arguments(
+                "prefix",
+                "cannot be empty and must end with '/'",
+            ));
+        }
+
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+
+        let mut objects = Vec::new();
+        let mut prefixes = BTreeSet::new();
+        for (key, obj) in b.objects.range(String::from(prefix)..) {
+            let Some(rest) = key.strip_prefix(prefix) else {
+                break;
+            };
+
+            if !recursive {
+                if let Some(i) = rest.find('/') {
+                    let sub_prefix = &key[..prefix.len() + i + 1];
+                    if prefixes.insert(sub_prefix) {
+                        objects.push(Object {
+                            key: String::from(sub_prefix),
+                            is_prefix: true,
+                            metadata_system: metadata::System {
+                                created: Duration::ZERO,
+                                expires: None,
+                                content_length: Some(0),
+                            },
+                            metadata_custom: metadata::Custom::default(),
+                            has_system: false,
+                            has_custom: false,
+                        });
+                    }
+
+                    continue;
+                }
+            }
+
+            objects.push(obj.to_object(key));
+        }
+
+        Ok(objects)
+    }
+
+    fn delete(&self, bucket: &str, key: &str) -> Result<()> {
+        let mut buckets = self.lock();
+        let b = get_bucket(&mut buckets, bucket)?;
+        b.objects.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the keys of the objects and if they are prefixes.
+    fn keys(objects: &[Object]) -> Vec<(&str, bool)> {
+        objects
+            .iter()
+            .map(|o| (o.key.as_str(), o.is_prefix))
+            .collect()
+    }
+
+    #[test]
+    fn test_memory_store_buckets() {
+        let store = MemoryStore::new();
+
+        let b1 = store.make_bucket("bucket").expect("make bucket");
+        let b2 = store
+            .make_bucket("bucket")
+            .expect("make an existing bucket");
+        assert_eq!(b1.name, b2.name, "bucket names");
+        assert_eq!(b1.created_at, b2.created_at, "bucket creation times");
+
+        let b3 = store.stat_bucket("bucket").expect("stat bucket");
+        assert_eq!(b1.created_at, b3.created_at, "stat bucket creation time");
+
+        match store.stat_bucket("does-not-exist") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        for name in ["", "ab", "My_Bucket", "192.168.5.4"] {
+            for res in [
+                store.make_bucket(name),
+                store.stat_bucket(name),
+                store.delete_bucket(name),
+            ] {
+                match res {
+                    Err(Error::InvalidArguments(args)) => {
+                        assert_eq!(args.names, "bucket", "{:?}: invalid argument name", name)
+                    }
+                    res => panic!("{:?}: unexpected result: {:?}", name, res),
+                }
+            }
+        }
+
+        store.make_bucket("another").expect("make another bucket");
+        let names: Vec<String> = store
+            .buckets()
+            .expect("list buckets")
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(names, vec!["another", "bucket"], "listed buckets");
+
+        store.put("bucket", "key", b"data").expect("put object");
+        match store.delete_bucket("bucket") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotEmpty => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        store.delete("bucket", "key").expect("delete object");
+        store.delete_bucket("bucket").expect("delete empty bucket");
+        match store.delete_bucket("bucket") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_memory_store_objects() {
+        let store = MemoryStore::new();
+
+        match store.put("does-not-exist", "key", b"data") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        store.make_bucket("bucket").expect("make bucket");
+        match store.put("bucket", "", b"data") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectKeyInvalid => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let obj = store.put("bucket", "key", b"data").expect("put object");
+        assert_eq!(obj.key, "key", "put object key");
+        assert!(!obj.is_prefix, "put object is prefix");
+        assert_eq!(
+            obj.metadata_system.content_length,
+            Some(4),
+            "put content length"
+        );
+
+        assert_eq!(
+            store.get("bucket", "key").expect("get object"),
+            b"data",
+            "object data"
+        );
+        let obj = store.stat("bucket", "key").expect("stat object");
+        assert_eq!(
+            obj.metadata_system.content_length,
+            Some(4),
+            "stat content length"
+        );
+
+        store
+            .put("bucket", "key", b"new")
+            .expect("overwrite object");
+        assert_eq!(
+            store.get("bucket", "key").expect("get overwritten object"),
+            b"new",
+            "overwritten object data"
+        );
+
+        store.delete("bucket", "key").expect("delete object");
+        store
+            .delete("bucket", "key")
+            .expect("delete a non-existing object");
+        match store.get("bucket", "key") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match store.stat("bucket", "key") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match store.delete("does-not-exist", "key") {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_memory_store_list() {
+        let store = MemoryStore::new();
+        store.make_bucket("bucket").expect("make bucket");
+        for key in [
+            "a.txt",
+            "b/1.txt",
+            "b/2.txt",
+            "b/c/3.txt",
+            "ba.txt",
+            "d/4.txt",
+        ] {
+            store.put("bucket", key, b"data").expect("put object");
+        }
+
+        // Not recursive without prefix collapses the prefixes.
+        let objects = store.list("bucket", "", false).expect("list objects");
+        assert_eq!(
+            keys(&objects),
+            vec![
+                ("a.txt", false),
+                ("b/", true),
+                ("ba.txt", false),
+                ("d/", true)
+            ],
+            "not recursive without prefix"
+        );
+
+        // Not recursive with prefix collapses the nested prefixes.
+        let objects = store.list("bucket", "b/", false).expect("list objects");
+        assert_eq!(
+            keys(&objects),
+            vec![("b/1.txt", false), ("b/2.txt", false), ("b/c/", true)],
+            "not recursive with prefix"
+        );
+
+        // Recursive without prefix.
+        let objects = store.list("bucket", "", true).expect("list objects");
+        assert_eq!(
+            keys(&objects),
+            vec![
+                ("a.txt", false),
+                ("b/1.txt", false),
+                ("b/2.txt", false),
+                ("b/c/3.txt", false),
+                ("ba.txt", false),
+                ("d/4.txt", false)
+            ],
+            "recursive without prefix"
+        );
+
+        // Recursive with prefix.
+        let objects = store.list("bucket", "b/", true).expect("list objects");
+        assert_eq!(
+            keys(&objects),
+            vec![("b/1.txt", false), ("b/2.txt", false), ("b/c/3.txt", false)],
+            "recursive with prefix"
+        );
+
+        // Prefix without objects.
+        assert!(
+            store
+                .list("bucket", "z/", true)
+                .expect("list objects")
+                .is_empty(),
+            "prefix without objects"
+        );
+
+        // Invalid prefix.
+        match store.list("bucket", "b", false) {
+            Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "prefix", "names"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        // Non-existing bucket.
+        match store.list("does-not-exist", "", false) {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
@@ -1,15 +1,18 @@
 //! Storj DSC Bucket and related types.
 
-use crate::uplink_c::Ensurer;
-use crate::{Error, Result};
+use crate::error::{BoxError, UplinkCode};
+use crate::uplink_c::{ffi_guard, TryEnsure};
+use crate::{error, helpers, Error, Object, Result};
 
 use std::ffi::{CStr, CString};
+use std::net::Ipv4Addr;
 use std::time::Duration;
 
 use uplink_sys as ulksys;
 
 /// Contains information about a specific bucket.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Bucket {
     /// Name of the bucket.
     pub name: String,
@@ -18,44 +21,73 @@ pub struct Bucket {
 }
 
 impl Bucket {
-    /// Creates a Bucket instance from the type exposed by the FFI.
+    /// Returns the creation time as a UTC date time.
+    #[cfg(feature = "chrono")]
+    pub fn created_datetime(&self) -> chrono::DateTime<chrono::Utc> {
+        helpers::datetime_from_unix_time(self.created_at)
+    }
+
+    /// Creates a Bucket instance from the type exposed by the FFI and frees it.
     ///
-    /// It returns an [`Error:Internal`](crate::Error::Internal) if `uc_bucket`'s name invalid
-    /// UTF-8.
+    /// It returns an [`Error:Internal`](crate::Error::Internal) if `uc_bucket` is `NULL`, its
+    /// name is invalid UTF-8 or its conversion panics.
     pub(crate) fn from_ffi_bucket(uc_bucket: *mut ulksys::UplinkBucket) -> Result<Self> {
-        assert!(
-            !uc_bucket.is_null(),
-            "BUG: `uc_bucket` argument cannot be NULL"
-        );
+        if uc_bucket.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL bucket",
+                BoxError::from("NULL pointer"),
+            ));
+        }
 
-        let uc_bucket_ptr = uc_bucket;
         // SAFETY: We have checked just above that the pointer isn't NULL.
-        let uc_bucket = unsafe { *uc_bucket_ptr };
-        uc_bucket.ensure();
+        let uc_bucket_val = unsafe { *uc_bucket };
+        if let Err(err) = uc_bucket_val.try_ensure() {
+            // SAFETY: we trust the FFI is safe freeing the memory of a pointer that it returned.
+            unsafe { ulksys::uplink_free_bucket(uc_bucket) };
+            return Err(err);
+        }
 
-        let name;
-        let created_at: Duration;
-        // SAFETY: we have check that the `uc_bucket` doesn't have fields with NULL pointers through
-        // the `ensure` method.
+        // SAFETY: we have check that the `uc_bucket_val` doesn't have fields with NULL pointers
+        // through the `try_ensure` method. The conversion copies the name, so we free the memory of
+        // the FFI bucket to not leak memory.
+        // The conversion is guarded for freeing the memory even if it panics.
         unsafe {
-            // User create buckets and satellites and/or client libraries or applications don't
-            // likely allow to use invalid UTF-8 characters in their names. Nonetheless, we don't
-            // panic if they contain some and we return an internal error because we see it's a
-            // limitation of Rust and C interoperability and consumers of this crate would have a
-            // chance to deal with them appropriately.
-            let cs = CString::from(CStr::from_ptr(uc_bucket.name));
-            name = cs.into_string().map_err(|err| {
-                ulksys::uplink_free_bucket(uc_bucket_ptr);
+            let res = ffi_guard("FFI returned an invalid bucket", || {
+                Self::unchecked_from_ffi_bucket_ref(&uc_bucket_val)
+            });
+            ulksys::uplink_free_bucket(uc_bucket);
+            res
+        }
+    }
+
+    /// Creates a Bucket instance from a reference to the type exposed by the FFI without freeing
+    /// it.
+    ///
+    /// It returns an [`Error:Internal`](crate::Error::Internal) if `uc_bucket`'s name invalid
+    /// UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `uc_bucket` must not have fields with NULL pointers.
+    unsafe fn unchecked_from_ffi_bucket_ref(uc_bucket: &ulksys::UplinkBucket) -> Result<Self> {
+        // User create buckets and satellites and/or client libraries or applications don't likely
+        // allow to use invalid UTF-8 characters in their names. Nonetheless, we don't panic if
+        // they contain some and we return an internal error because we see it's a limitation of
+        // Rust and C interoperability and consumers of this crate would have a chance to deal with
+        // them appropriately.
+        let name = CString::from(CStr::from_ptr(uc_bucket.name))
+            .into_string()
+            .map_err(|err| {
                 Error::new_internal(
                     "FFI returned an invalid bucket's name; it contains invalid UTF-8 characters",
                     err.into(),
                 )
             })?;
-            created_at = Duration::new(uc_bucket.created as u64, 0);
-            ulksys::uplink_free_bucket(uc_bucket_ptr);
-        }
 
-        Ok(Bucket { name, created_at })
+        Ok(Bucket {
+            name,
+            created_at: Duration::new(uc_bucket.created as u64, 0),
+        })
     }
 
     /// Creates a new instance from the FFI representation for a bucket's result.
@@ -66,10 +98,11 @@ impl Bucket {
     /// * an [`Error::Internal`](crate::Error::Internal) if `uc_result.bucket`'s name contains
     ///   invalid UTF-8 characters.
     pub(crate) fn from_ffi_bucket_result(uc_result: ulksys::UplinkBucketResult) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
-            // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
+            // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer. It frees the
+            // error and the bucket, if it isn't `NULL`, so nothing leaks.
             unsafe { ulksys::uplink_free_bucket_result(uc_result) };
             return Err(err);
         }
@@ -80,24 +113,219 @@ impl Bucket {
         // without doing anything if it's `NULL`).
         Self::from_ffi_bucket(uc_result.bucket)
     }
+
+    /// Creates a new instance from the FFI representation for a create bucket's result.
+    ///
+    /// It returns the bucket and `true` when it's created or `false` if it already existed. The
+    /// bucket is `None` when it already existed and the FFI didn't return it, which happens on
+    /// some uplink-c versions when several clients create the same bucket concurrently.
+    ///
+    /// It returns the same errors than [`Self::from_ffi_bucket_result`] except the
+    /// [`UplinkCode::BucketAlreadyExists`] one.
+    pub(crate) fn from_ffi_create_bucket_result(
+        uc_result: ulksys::UplinkBucketResult,
+    ) -> Result<(Option<Self>, bool)> {
+        uc_result.try_ensure()?;
+
+        // The error is freed here, so the result is freed without it when it's needed.
+        if let Some(err) = Error::from_ffi_error(uc_result.error) {
+            if err.uplink_code() == Some(UplinkCode::BucketAlreadyExists) {
+                if uc_result.bucket.is_null() {
+                    return Ok((None, false));
+                }
+
+                return Self::from_ffi_bucket(uc_result.bucket).map(|b| (Some(b), false));
+            }
+
+            // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer and the
+            // error, which is already freed, is replaced by `NULL`, which isn't freed.
+            unsafe {
+                ulksys::uplink_free_bucket_result(ulksys::UplinkBucketResult {
+                    error: std::ptr::null_mut(),
+                    ..uc_result
+                })
+            };
+            return Err(err);
+        }
+
+        // At this point we don't need to free the `uc_result`. See the comment in
+        // `from_ffi_bucket_result`.
+        Self::from_ffi_bucket(uc_result.bucket).map(|b| (Some(b), true))
+    }
+}
+
+impl std::fmt::Display for Bucket {
+    /// Formats the name and the creation time as an RFC 3339 timestamp (e.g.
+    /// `my-bucket (created 2024-05-01T12:00:00Z)`).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (created {})",
+            self.name,
+            helpers::format_unix_time(self.created_at)
+        )
+    }
+}
+
+/// The orders of the buckets returned by
+/// [`Project::list_buckets_sorted`](crate::Project::list_buckets_sorted).
+///
+/// The buckets created at the same time, which is measured in seconds, are sorted by their names
+/// in the same direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Ascending order of the names, which is the order of [`Iterator`].
+    NameAsc,
+    /// Descending order of the names.
+    NameDesc,
+    /// Ascending order of the creation times, so the oldest bucket is the first.
+    CreatedAsc,
+    /// Descending order of the creation times, so the most recently created bucket is the first.
+    CreatedDesc,
+}
+
+impl SortOrder {
+    /// Sorts `buckets` in this order.
+    pub(crate) fn sort(self, buckets: &mut [Bucket]) {
+        match self {
+            Self::NameAsc => buckets.sort_unstable_by(|a, b| a.name.cmp(&b.name)),
+            Self::NameDesc => buckets.sort_unstable_by(|a, b| b.name.cmp(&a.name)),
+            Self::CreatedAsc => buckets
+                .sort_unstable_by(|a, b| (a.created_at, &a.name).cmp(&(b.created_at, &b.name))),
+            Self::CreatedDesc => buckets
+                .sort_unstable_by(|a, b| (b.created_at, &b.name).cmp(&(a.created_at, &a.name))),
+        }
+    }
+}
+
+/// The objects stored in a bucket and their size, returned by
+/// [`Project::bucket_usage`](crate::Project::bucket_usage).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// The number of objects.
+    pub objects: u64,
+    /// The total size, in bytes, of the objects' data.
+    pub bytes: u64,
+}
+
+impl Usage {
+    /// Sums the usage of the objects of `objects`, calling `progress` with the usage summed so
+    /// far after each object.
+    ///
+    /// The prefixes aren't objects, so they are skipped, and the unknown content lengths count
+    /// as zero bytes. It returns the first error of `objects`.
+    pub(crate) fn sum<I>(objects: I, mut progress: impl FnMut(&Self)) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<Object>>,
+    {
+        let mut usage = Self::default();
+        for res in objects {
+            let object = res?;
+            if object.is_prefix {
+                continue;
+            }
+
+            usage.objects += 1;
+            usage.bytes += object.metadata_system.content_length.unwrap_or(0);
+            progress(&usage);
+        }
+
+        Ok(usage)
+    }
 }
 
 /// Iterates over a collection of buckets.
+#[derive(Debug)]
 pub struct Iterator {
     /// The bucket iterator type of the FFI that an instance of this struct represents and guards
     /// its lifetime until this instance drops.
     inner: *mut ulksys::UplinkBucketIterator,
+    /// The name of the last yielded bucket.
+    last_seen: Option<String>,
+    /// The number of yielded buckets.
+    yielded_count: usize,
+    /// Indicates that the FFI iterator has finished, successfully or with an error.
+    finished: bool,
+    /// The error that finished the FFI iterator.
+    error: Option<Error>,
 }
 
 impl Iterator {
     /// Creates a new instance from the type exposed by the FFI.
-    pub(crate) fn from_ffi_bucket_iterator(uc_iterator: *mut ulksys::UplinkBucketIterator) -> Self {
-        assert!(
-            !uc_iterator.is_null(),
-            "BUG: `uc_iterator` argument cannot be NULL"
-        );
+    ///
+    /// It returns the following errors:
+    /// * an [`Error::Internal`](crate::Error::Internal) if `uc_iterator` is `NULL`.
+    /// * an [`Error::from_ffi_error` constructor](crate::Error::from_ffi_error), if the FFI
+    ///   iterator is in an error state, in which case the FFI iterator is freed.
+    pub(crate) fn from_ffi_bucket_iterator(
+        uc_iterator: *mut ulksys::UplinkBucketIterator,
+    ) -> Result<Self> {
+        if uc_iterator.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL bucket iterator",
+                BoxError::from("NULL pointer"),
+            ));
+        }
 
-        Iterator { inner: uc_iterator }
+        // SAFETY: we have checked above that the pointer isn't `NULL` and we trust the FFI
+        // functions don't panic when called with an instance returned by them.
+        let uc_error = unsafe { ulksys::uplink_bucket_iterator_err(uc_iterator) };
+        if let Some(err) = Error::from_ffi_error(uc_error) {
+            // SAFETY: we trust the FFI is safe freeing the memory of a correct pointer.
+            unsafe { ulksys::uplink_free_bucket_iterator(uc_iterator) };
+            return Err(err);
+        }
+
+        Ok(Iterator {
+            inner: uc_iterator,
+            last_seen: None,
+            yielded_count: 0,
+            finished: false,
+            error: None,
+        })
+    }
+
+    /// Returns the name of the last bucket yielded by this iterator or `None` if it hasn't yielded
+    /// any yet.
+    ///
+    /// It can be passed to [`ListBuckets::with_cursor`](crate::project::options::ListBuckets::with_cursor)
+    /// for resuming the listing after this bucket with a new iterator.
+    pub fn last_seen(&self) -> Option<&str> {
+        self.last_seen.as_deref()
+    }
+
+    /// Returns the number of buckets yielded by this iterator without counting the yielded
+    /// errors.
+    pub fn yielded_count(&self) -> usize {
+        self.yielded_count
+    }
+
+    /// Returns the error that finished the iterator or `None` if it hasn't finished or it
+    /// finished because there aren't more buckets.
+    ///
+    /// It allows to know if a `for` loop over the iterator ended because of an error without
+    /// collecting the items. The same error is yielded as the last item of the iterator.
+    pub fn finished_with_error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Records the result of an item retrieved from the FFI iterator and returns it.
+    fn track_item(&mut self, res: Result<Bucket>) -> Result<Bucket> {
+        if let Ok(b) = &res {
+            self.last_seen = Some(b.name.clone());
+            self.yielded_count += 1;
+        }
+
+        res
+    }
+
+    /// Records that the FFI iterator has finished with the error `uc_error`, if it isn't `NULL`,
+    /// and returns it. It takes ownership of `uc_error`.
+    fn track_end(&mut self, uc_error: *mut ulksys::UplinkError) -> Option<Result<Bucket>> {
+        self.finished = true;
+        let err = error::Uplink::from_ffi_error(uc_error)?;
+        self.error = Some(Error::Uplink(err.clone()));
+        Some(Err(Error::Uplink(err)))
     }
 }
 
@@ -108,19 +336,28 @@ impl std::iter::Iterator for Iterator {
     ///
     /// * [`Error::Uplink`](crate::Error::Uplink) when FFI returns an error when retrieving the
     ///   item.
-    /// * [`Error:Internal`](crate::Error::Internal) if `uc_bucket`'s name invalid UTF-8.
+    /// * [`Error:Internal`](crate::Error::Internal) if `uc_bucket`'s name invalid UTF-8, in which
+    ///   case the iteration can continue with the next buckets.
+    ///
+    /// It always returns `None` after returning an [`Error::Uplink`](crate::Error::Uplink).
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
         // SAFETY: we trust that the FFI functions don't panic when called with an instance returned
         // by them and they don't return invalid memory references or `null` if next returns `true`.
         unsafe {
             if !ulksys::uplink_bucket_iterator_next(self.inner) {
                 let uc_error = ulksys::uplink_bucket_iterator_err(self.inner);
-                return Error::new_uplink(uc_error).map(Err);
+                return self.track_end(uc_error);
             }
 
-            Some(Bucket::from_ffi_bucket(
-                ulksys::uplink_bucket_iterator_item(self.inner),
-            ))
+            let uc_bucket = ulksys::uplink_bucket_iterator_item(self.inner);
+            let res = ffi_guard("FFI returned an invalid bucket iterator item", || {
+                Bucket::from_ffi_bucket(uc_bucket)
+            });
+            Some(self.track_item(res))
         }
     }
 }
@@ -134,3 +371,428 @@ impl Drop for Iterator {
         }
     }
 }
+
+/// Validates that `name` follows the bucket naming rules of the satellites, which are:
+/// * Be between 3 and 63 characters long.
+/// * Only contain lowercase letters, numbers, hyphens and dots.
+/// * Start and end with a lowercase letter or a number.
+/// * Not contain adjacent dots nor a dot adjacent to a hyphen.
+/// * Not be formatted as an IPv4 address (e.g. `192.168.5.4`).
+///
+/// [`Project`](crate::Project) calls it before sending any request with a bucket name to the
+/// satellite unless it's disabled through
+/// [`Config::set_validate_bucket_names`](crate::Config::set_validate_bucket_names).
+///
+/// It returns an [`Error::InvalidArguments`] with the violated rule if `name` is invalid.
+pub fn validate_name(name: &str) -> Result<()> {
+    let len = name.chars().count();
+    if !(3..=63).contains(&len) {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "must be between 3 and 63 characters long",
+        ));
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+    {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "must only contain lowercase letters, numbers, hyphens and dots",
+        ));
+    }
+
+    let is_alphanumeric = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if !is_alphanumeric(name.chars().next()) || !is_alphanumeric(name.chars().last()) {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "must start and end with a lowercase letter or a number",
+        ));
+    }
+
+    if name.contains("..") {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "cannot contain adjacent dots",
+        ));
+    }
+
+    if name.contains(".-") || name.contains("-.") {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "cannot contain a dot adjacent to a hyphen",
+        ));
+    }
+
+    if name.parse::<Ipv4Addr>().is_ok() {
+        return Err(Error::new_invalid_arguments(
+            "bucket",
+            "cannot be formatted as an IP address",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helpers::test::ffi_error;
+    use crate::metadata;
+
+    use std::ptr;
+
+    #[test]
+    fn test_from_ffi_bucket_null() {
+        match Bucket::from_ffi_bucket(ptr::null_mut()) {
+            Err(Error::Internal(internal)) => {
+                assert_eq!(
+                    internal.ctx_msg, "FFI returned a NULL bucket",
+                    "context message"
+                )
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_usage_sum() {
+        let object = |key: &str, is_prefix: bool, content_length: Option<u64>| {
+            Ok(Object {
+                key: String::from(key),
+                is_prefix,
+                metadata_system: metadata::System {
+                    created: Duration::ZERO,
+                    expires: None,
+                    content_length,
+                },
+                metadata_custom: metadata::Custom::default(),
+                has_system: true,
+                has_custom: true,
+            })
+        };
+
+        let mut reports = Vec::new();
+        let usage = Usage::sum(
+            vec![
+                object("a.txt", false, Some(10)),
+                object("dir/", true, Some(0)),
+                object("dir/b.txt", false, Some(1_000)),
+                object("dir/empty", false, Some(0)),
+                object("dir/unknown", false, None),
+            ],
+            |u| reports.push(*u),
+        )
+        .expect("sum usage");
+
+        assert_eq!(
+            usage,
+            Usage {
+                objects: 4,
+                bytes: 1_010
+            },
+            "usage"
+        );
+        assert_eq!(
+            reports
+                .iter()
+                .map(|u| (u.objects, u.bytes))
+                .collect::<Vec<_>>(),
+            vec![(1, 10), (2, 1_010), (3, 1_010), (4, 1_010)],
+            "progress reports"
+        );
+
+        assert_eq!(
+            Usage::sum(Vec::new(), |_| panic!("no progress reports")).expect("sum empty"),
+            Usage::default(),
+            "empty usage"
+        );
+
+        let res = Usage::sum(
+            vec![
+                object("a.txt", false, Some(10)),
+                Err(Error::Uplink(error::Uplink::with_code(
+                    UplinkCode::PermissionDenied,
+                    "denied",
+                ))),
+                object("b.txt", false, Some(10)),
+            ],
+            |_| {},
+        );
+        match res {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_bucket_display() {
+        let bucket = Bucket {
+            name: String::from("my-bucket"),
+            created_at: Duration::from_secs(1_714_564_800),
+        };
+        assert_eq!(
+            bucket.to_string(),
+            "my-bucket (created 2024-05-01T12:00:00Z)",
+            "display"
+        );
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let mut buckets: Vec<Bucket> = [("b", 20), ("d", 10), ("a", 30), ("c", 20)]
+            .into_iter()
+            .map(|(name, created)| Bucket {
+                name: String::from(name),
+                created_at: Duration::from_secs(created),
+            })
+            .collect();
+
+        for (order, want) in [
+            (SortOrder::NameAsc, ["a", "b", "c", "d"]),
+            (SortOrder::NameDesc, ["d", "c", "b", "a"]),
+            (SortOrder::CreatedAsc, ["d", "b", "c", "a"]),
+            (SortOrder::CreatedDesc, ["a", "c", "b", "d"]),
+        ] {
+            order.sort(&mut buckets);
+            let names: Vec<&str> = buckets.iter().map(|b| b.name.as_str()).collect();
+            assert_eq!(names, want, "{:?}", order);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_bucket_created_datetime() {
+        let mut bucket = Bucket {
+            name: String::from("my-bucket"),
+            created_at: Duration::ZERO,
+        };
+        assert_eq!(bucket.created_datetime().timestamp(), 0, "epoch");
+
+        bucket.created_at = Duration::from_secs(1_714_564_800);
+        assert_eq!(
+            bucket.created_datetime().to_rfc3339(),
+            "2024-05-01T12:00:00+00:00",
+            "created"
+        );
+    }
+
+    #[test]
+    fn test_bucket_invalid_utf8_name() {
+        let name = CString::new(b"\xffbucket".to_vec()).unwrap();
+        let uc_bucket = ulksys::UplinkBucket {
+            name: name.as_ptr() as *mut std::os::raw::c_char,
+            created: 3600,
+        };
+
+        // SAFETY: the bucket doesn't have fields with NULL pointers and the memory that it
+        // references lives until the end of the test.
+        match unsafe { Bucket::unchecked_from_ffi_bucket_ref(&uc_bucket) } {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let name = CString::new("bucket").unwrap();
+        let uc_bucket = ulksys::UplinkBucket {
+            name: name.as_ptr() as *mut std::os::raw::c_char,
+            created: 3600,
+        };
+        // SAFETY: the bucket doesn't have fields with NULL pointers and the memory that it
+        // references lives until the end of the test.
+        let bucket = unsafe { Bucket::unchecked_from_ffi_bucket_ref(&uc_bucket) }.expect("bucket");
+        assert_eq!(bucket.name, "bucket", "name");
+        assert_eq!(bucket.created_at, Duration::from_secs(3600), "created at");
+    }
+
+    #[test]
+    fn test_iterator_from_ffi_bucket_iterator_null() {
+        match Iterator::from_ffi_bucket_iterator(ptr::null_mut()).map(|_| ()) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_iterator_state_tracking() {
+        // The FFI iterator is never used, so it's `NULL` and the instance must be forgotten.
+        let mut it = Iterator {
+            inner: ptr::null_mut(),
+            last_seen: None,
+            yielded_count: 0,
+            finished: false,
+            error: None,
+        };
+
+        for name in ["bucket-a", "bucket-b"] {
+            it.track_item(Ok(Bucket {
+                name: String::from(name),
+                created_at: Duration::ZERO,
+            }))
+            .expect("tracked item");
+        }
+        it.track_item(Err(Error::new_internal(
+            "invalid bucket",
+            BoxError::from("test"),
+        )))
+        .expect_err("tracked error item");
+        assert_eq!(it.yielded_count(), 2, "yielded count");
+        assert_eq!(it.last_seen(), Some("bucket-b"), "last seen");
+        assert!(it.finished_with_error().is_none(), "not finished");
+
+        match it.track_end(ffi_error(ulksys::UPLINK_ERROR_PERMISSION_DENIED)) {
+            Some(Err(Error::Uplink(err))) if err.code() == UplinkCode::PermissionDenied => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match it.finished_with_error() {
+            Some(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+            err => panic!("unexpected finished error: {:?}", err),
+        }
+        assert!(it.next().is_none(), "no more items after the error");
+        assert_eq!(it.yielded_count(), 2, "yielded count after the error");
+
+        std::mem::forget(it);
+    }
+
+    #[test]
+    fn test_iterator_state_tracking_clean_end() {
+        // The FFI iterator is never used, so it's `NULL` and the instance must be forgotten.
+        let mut it = Iterator {
+            inner: ptr::null_mut(),
+            last_seen: None,
+            yielded_count: 0,
+            finished: false,
+            error: None,
+        };
+
+        assert!(it.track_end(ptr::null_mut()).is_none(), "clean end");
+        assert!(it.finished_with_error().is_none(), "finished without error");
+        assert!(it.next().is_none(), "no more items");
+        assert_eq!(it.yielded_count(), 0, "yielded count");
+
+        std::mem::forget(it);
+    }
+
+    #[test]
+    fn test_bucket_from_ffi_bucket_result_error() {
+        let uc_res = ulksys::UplinkBucketResult {
+            bucket: ptr::null_mut(),
+            error: ffi_error(ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND),
+        };
+
+        match Bucket::from_ffi_bucket_result(uc_res).expect_err("bucket not found error") {
+            Error::Uplink(err) if err.code() == UplinkCode::BucketNotFound => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_bucket_from_ffi_create_bucket_result() {
+        {
+            // Bucket already exists and the FFI doesn't return it.
+            let uc_res = ulksys::UplinkBucketResult {
+                bucket: ptr::null_mut(),
+                error: ffi_error(ulksys::UPLINK_ERROR_BUCKET_ALREADY_EXISTS),
+            };
+
+            let (bucket, created) = Bucket::from_ffi_create_bucket_result(uc_res)
+                .expect("already exists error isn't returned");
+            assert!(bucket.is_none(), "bucket");
+            assert!(!created, "created");
+        }
+        {
+            // Any other error.
+            let uc_res = ulksys::UplinkBucketResult {
+                bucket: ptr::null_mut(),
+                error: ffi_error(ulksys::UPLINK_ERROR_BUCKET_NAME_INVALID),
+            };
+
+            match Bucket::from_ffi_create_bucket_result(uc_res)
+                .expect_err("bucket name invalid error is returned")
+            {
+                Error::Uplink(err) if err.code() == UplinkCode::BucketNameInvalid => {}
+                err => panic!("unexpected error: {:?}", err),
+            }
+        }
+    }
+
+    /// Asserts that `validate_name` rejects `name` with the `msg` invalid arguments error.
+    fn assert_invalid_name(name: &str, msg: &str) {
+        match validate_name(name) {
+            Err(Error::InvalidArguments(error::Args { names, msg: m })) => {
+                assert_eq!(
+                    names, "bucket",
+                    "invalid error argument name for {:?}",
+                    name
+                );
+                assert_eq!(m, msg, "invalid error argument message for {:?}", name);
+            }
+            res => panic!("unexpected result for {:?}: {:?}", name, res),
+        }
+    }
+
+    #[test]
+    fn test_validate_name_valid() {
+        for name in [
+            "abc",
+            "my-bucket",
+            "my.bucket.01",
+            "0bucket9",
+            "1.2.3",
+            "1.2.3.4.5",
+            "a-b.c-d",
+            &"a".repeat(63),
+        ] {
+            validate_name(name).unwrap_or_else(|err| panic!("valid name {:?}: {}", name, err));
+        }
+    }
+
+    #[test]
+    fn test_validate_name_length() {
+        let msg = "must be between 3 and 63 characters long";
+        assert_invalid_name("", msg);
+        assert_invalid_name("ab", msg);
+        assert_invalid_name(&"a".repeat(64), msg);
+        // The length is measured in characters, not bytes.
+        assert_invalid_name("éé", msg);
+        assert_invalid_name(&"é".repeat(64), msg);
+    }
+
+    #[test]
+    fn test_validate_name_characters() {
+        let msg = "must only contain lowercase letters, numbers, hyphens and dots";
+        assert_invalid_name("MyBucket", msg);
+        assert_invalid_name("my_bucket", msg);
+        assert_invalid_name("my bucket", msg);
+        assert_invalid_name("my/bucket", msg);
+        assert_invalid_name("bücket", msg);
+        assert_invalid_name("ｂucket", msg);
+        assert_invalid_name("buck\0et", msg);
+        // Unicode names whose length in bytes is more than 63 but not in characters.
+        assert_invalid_name(&"é".repeat(40), msg);
+    }
+
+    #[test]
+    fn test_validate_name_start_end() {
+        let msg = "must start and end with a lowercase letter or a number";
+        assert_invalid_name("-bucket", msg);
+        assert_invalid_name("bucket-", msg);
+        assert_invalid_name(".bucket", msg);
+        assert_invalid_name("bucket.", msg);
+    }
+
+    #[test]
+    fn test_validate_name_dots() {
+        assert_invalid_name("my..bucket", "cannot contain adjacent dots");
+        assert_invalid_name("my.-bucket", "cannot contain a dot adjacent to a hyphen");
+        assert_invalid_name("my-.bucket", "cannot contain a dot adjacent to a hyphen");
+    }
+
+    #[test]
+    fn test_validate_name_ip_address() {
+        let msg = "cannot be formatted as an IP address";
+        assert_invalid_name("192.168.5.4", msg);
+        assert_invalid_name("0.0.0.0", msg);
+        assert_invalid_name("255.255.255.255", msg);
+    }
+}
@@ -0,0 +1,148 @@
+//! High-level client for the most common Storj DCS operations.
+//!
+//! It's a convenient facade over the [`Project`](crate::Project) API for users that only need to
+//! upload, download, list and delete objects with sane defaults. For any other operation or for a
+//! fine-grained control use the [`Project`](crate::Project) API, which is available through
+//! [`Storage::project`].
+
+use crate::access::Grant;
+use crate::project::options;
+use crate::{helpers, object, Bucket, Error, Object, Project, Result};
+
+use std::io::{self, Read};
+
+/// Provides the most common operations to manage buckets and objects with sane defaults.
+pub struct Storage {
+    /// The project where all the operations are performed.
+    project: Project,
+}
+
+impl Storage {
+    /// Connects to the Storj DCS project of the passed serialized access grant.
+    ///
+    /// It returns the same errors than [`Grant::new`].
+    ///
+    /// ```no_run
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn connect(serialized_grant: &str) -> Result<Self> {
+        let grant = Grant::new(serialized_grant)?;
+        Ok(Self {
+            project: Project::open(&grant),
+        })
+    }
+
+    /// Returns the project where the operations are performed for accessing the operations that
+    /// this facade doesn't provide.
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// Creates the bucket with the specified `name` if it doesn't exist and returns it.
+    ///
+    /// It returns the same errors than [`Project::ensure_bucket`].
+    ///
+    /// ```no_run
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// let bucket = storage.make_bucket("my-bucket")?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn make_bucket(&self, name: &str) -> Result<Bucket> {
+        self.project.ensure_bucket(name)
+    }
+
+    /// Uploads all the data read from `data` as the object inside of `bucket` and referenced by
+    /// `key`, and returns the uploaded object.
+    ///
+    /// The upload is aborted if reading from `data` or any upload operation fails. It returns the
+    /// same errors than [`Project::upload_object`], [`object::Upload::commit`] and
+    /// [`object::Upload::info`], and an [`Error::Internal`] if reading from `data` fails.
+    ///
+    /// ```no_run
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// let object = storage.upload("my-bucket", "hello.txt", "Hello world!".as_bytes())?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn upload(&self, bucket: &str, key: &str, mut data: impl Read) -> Result<Object> {
+        let mut upload = self.project.upload_object(bucket, key, None)?;
+
+        let res = io::copy(&mut data, &mut upload)
+            .map_err(Error::from_io_error)
+            .and_then(|_| upload.commit());
+        helpers::undo_on_error(res, || upload.abort())?;
+
+        upload.info()
+    }
+
+    /// Downloads the object inside of `bucket` and referenced by `key`. The returned value
+    /// implements [`std::io::Read`] for reading the object's data.
+    ///
+    /// It returns the same errors than [`Project::download_object`].
+    ///
+    /// ```no_run
+    /// use std::io::Read;
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// let mut data = String::new();
+    /// storage
+    ///     .download("my-bucket", "hello.txt")?
+    ///     .read_to_string(&mut data)
+    ///     .expect("read object's data");
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn download(&self, bucket: &str, key: &str) -> Result<object::Download> {
+        self.project.download_object(bucket, key, None)
+    }
+
+    /// Lists the objects inside of `bucket` whose key starts with `prefix`, which is empty or it
+    /// ends with '/'. The listing isn't recursive and the objects have their system and custom
+    /// metadata.
+    ///
+    /// It returns the same errors than [`options::ListObjects::with_prefix`] when `prefix` isn't
+    /// empty and [`Project::list_objects`] and the errors returned by iterating the objects.
+    ///
+    /// ```no_run
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// for object in storage.list("my-bucket", "photos/")? {
+    ///     println!("{}", object.key);
+    /// }
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn list(&self, bucket: &str, prefix: &str) -> Result<Vec<Object>> {
+        let mut opts = if prefix.is_empty() {
+            options::ListObjects::default()
+        } else {
+            options::ListObjects::with_prefix(prefix)?
+        };
+        opts.system = true;
+        opts.custom = true;
+
+        self.project.list_objects(bucket, Some(&opts))?.collect()
+    }
+
+    /// Deletes the object inside of `bucket` and referenced by `key`. It doesn't return an error if
+    /// the object doesn't exist.
+    ///
+    /// It returns the same errors than [`Project::delete_object`].
+    ///
+    /// ```no_run
+    /// use uplink::client::Storage;
+    ///
+    /// let storage = Storage::connect("my-serialized-access-grant")?;
+    /// storage.delete("my-bucket", "hello.txt")?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn delete(&self, bucket: &str, key: &str) -> Result<()> {
+        self.project.delete_object(bucket, key).map(|_| ())
+    }
+}
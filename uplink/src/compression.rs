@@ -0,0 +1,265 @@
+//! On the fly compression and decompression of the objects' data.
+//!
+//! See [`Project::upload_object_compressed`](crate::Project::upload_object_compressed) and
+//! [`Project::download_object_decompressed`](crate::Project::download_object_decompressed).
+
+use crate::error::BoxError;
+use crate::object::Download;
+use crate::{metadata, Error, Result};
+
+use std::io::{self, Read, Write};
+
+/// The reserved custom metadata key where the compression algorithm of the objects uploaded by
+/// [`Project::upload_object_compressed`](crate::Project::upload_object_compressed) is recorded.
+pub const METADATA_KEY_ALGORITHM: &str = "uplink-rust-compression";
+
+/// The reserved custom metadata key where the uncompressed size, in bytes, of the objects
+/// uploaded by [`Project::upload_object_compressed`](crate::Project::upload_object_compressed) is
+/// recorded. The system metadata content length is the compressed size.
+pub const METADATA_KEY_UNCOMPRESSED_SIZE: &str = "uplink-rust-uncompressed-size";
+
+/// The compression algorithms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Gzip with the default compression level.
+    Gzip,
+    /// Zstandard with the default compression level.
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the name of the algorithm, which is the value recorded in the
+    /// [`METADATA_KEY_ALGORITHM`] custom metadata key.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the algorithm whose name is `name` or `None` if there isn't any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gzip" => Some(Self::Gzip),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Returns the algorithm recorded in `metadata` or `None` if it isn't recorded or it's
+    /// unknown.
+    pub fn from_metadata(metadata: &metadata::Custom) -> Option<Self> {
+        metadata
+            .get(METADATA_KEY_ALGORITHM)
+            .and_then(|name| Self::from_name(name))
+    }
+}
+
+/// Returns the uncompressed size recorded in `metadata` or `None` if it isn't recorded or it
+/// isn't a number.
+pub fn uncompressed_size(metadata: &metadata::Custom) -> Option<u64> {
+    metadata
+        .get(METADATA_KEY_UNCOMPRESSED_SIZE)
+        .and_then(|size| size.parse().ok())
+}
+
+/// Returns the custom metadata that records `compression` and the uncompressed size `size`.
+pub(crate) fn metadata(compression: Compression, size: u64) -> metadata::Custom {
+    let mut metadata = metadata::Custom::with_capacity(2);
+    metadata.insert(
+        String::from(METADATA_KEY_ALGORITHM),
+        String::from(compression.name()),
+    );
+    metadata.insert(
+        String::from(METADATA_KEY_UNCOMPRESSED_SIZE),
+        size.to_string(),
+    );
+    metadata
+}
+
+/// Compresses all the data read from `reader` with `compression` into `writer` and returns the
+/// number of bytes read from `reader`, which is the uncompressed size.
+///
+/// It streams the data, so the memory usage doesn't depend on its size.
+pub(crate) fn compress<R, W>(reader: &mut R, writer: W, compression: Compression) -> io::Result<u64>
+where
+    R: Read + ?Sized,
+    W: Write,
+{
+    match compression {
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            let size = io::copy(reader, &mut encoder)?;
+            encoder.finish()?;
+            Ok(size)
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::stream::write::Encoder::new(writer, 0)?;
+            let size = io::copy(reader, &mut encoder)?;
+            encoder.finish()?;
+            Ok(size)
+        }
+    }
+}
+
+/// A download that decompresses the object's data on the fly, returned by
+/// [`Project::download_object_decompressed`](crate::Project::download_object_decompressed).
+///
+/// It reads the data from the [`std::io::BufRead`] implementation of [`Download`], so the memory
+/// usage doesn't depend on the object's size.
+pub struct DecompressedDownload {
+    /// The decoder of the download.
+    decoder: Decoder,
+}
+
+/// The decoders of the compression algorithms.
+enum Decoder {
+    /// Gzip decoder.
+    Gzip(flate2::bufread::GzDecoder<Download>),
+    /// Zstandard decoder.
+    Zstd(zstd::stream::read::Decoder<'static, Download>),
+}
+
+impl DecompressedDownload {
+    /// Creates a new instance that decompresses `download` with `compression`.
+    ///
+    /// It returns an [`Error::Internal`] if the decoder cannot be created.
+    pub(crate) fn new(download: Download, compression: Compression) -> Result<Self> {
+        let decoder = match compression {
+            Compression::Gzip => Decoder::Gzip(flate2::bufread::GzDecoder::new(download)),
+            Compression::Zstd => {
+                Decoder::Zstd(zstd::stream::read::Decoder::with_buffer(download).map_err(
+                    |err| Error::new_internal("creating the zstd decoder", BoxError::from(err)),
+                )?)
+            }
+        };
+
+        Ok(Self { decoder })
+    }
+
+    /// Returns a reference to the download, for example, for getting the object's information.
+    ///
+    /// Reading directly from the download corrupts the decompressed data.
+    pub fn get_ref(&self) -> &Download {
+        match &self.decoder {
+            Decoder::Gzip(d) => d.get_ref(),
+            Decoder::Zstd(d) => d.get_ref(),
+        }
+    }
+
+    /// Unwraps this instance returning the download.
+    pub fn into_inner(self) -> Download {
+        match self.decoder {
+            Decoder::Gzip(d) => d.into_inner(),
+            Decoder::Zstd(d) => d.finish(),
+        }
+    }
+}
+
+impl Read for DecompressedDownload {
+    /// Reads the decompressed data into `buf`.
+    ///
+    /// It returns the same errors than [`Download`] and the decompression errors of each decoder,
+    /// which usually have the [`io::ErrorKind::InvalidData`] kind.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.decoder {
+            Decoder::Gzip(d) => d.read(buf),
+            Decoder::Zstd(d) => d.read(buf),
+        }
+    }
+}
+
+impl std::fmt::Debug for DecompressedDownload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let compression = match self.decoder {
+            Decoder::Gzip(_) => Compression::Gzip,
+            Decoder::Zstd(_) => Compression::Zstd,
+        };
+
+        f.debug_struct("DecompressedDownload")
+            .field("compression", &compression)
+            .field("download", self.get_ref())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compression_names() {
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            assert_eq!(
+                Compression::from_name(compression.name()),
+                Some(compression),
+                "name round trip"
+            );
+        }
+        assert_eq!(Compression::from_name("brotli"), None, "unknown name");
+    }
+
+    #[test]
+    fn test_metadata() {
+        let metadata = metadata(Compression::Zstd, 1234);
+        assert_eq!(
+            Compression::from_metadata(&metadata),
+            Some(Compression::Zstd),
+            "compression"
+        );
+        assert_eq!(
+            uncompressed_size(&metadata),
+            Some(1234),
+            "uncompressed size"
+        );
+
+        let mut metadata = metadata::Custom::default();
+        assert_eq!(
+            Compression::from_metadata(&metadata),
+            None,
+            "no compression"
+        );
+        assert_eq!(uncompressed_size(&metadata), None, "no uncompressed size");
+
+        metadata.insert(
+            String::from(METADATA_KEY_ALGORITHM),
+            String::from("unknown"),
+        );
+        metadata.insert(
+            String::from(METADATA_KEY_UNCOMPRESSED_SIZE),
+            String::from("-1"),
+        );
+        assert_eq!(
+            Compression::from_metadata(&metadata),
+            None,
+            "unknown compression"
+        );
+        assert_eq!(
+            uncompressed_size(&metadata),
+            None,
+            "invalid uncompressed size"
+        );
+    }
+
+    #[test]
+    fn test_compress() {
+        let data = b"uplink rust compression ".repeat(100_000);
+
+        let mut compressed = Vec::new();
+        let size = compress(&mut &data[..], &mut compressed, Compression::Gzip).expect("gzip");
+        assert_eq!(size, data.len() as u64, "gzip uncompressed size");
+        assert!(compressed.len() < data.len() / 10, "gzip compressed size");
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_end(&mut decompressed)
+            .expect("gzip decompress");
+        assert_eq!(decompressed, data, "gzip round trip");
+
+        let mut compressed = Vec::new();
+        let size = compress(&mut &data[..], &mut compressed, Compression::Zstd).expect("zstd");
+        assert_eq!(size, data.len() as u64, "zstd uncompressed size");
+        assert!(compressed.len() < data.len() / 10, "zstd compressed size");
+        let decompressed = zstd::stream::decode_all(&compressed[..]).expect("zstd decompress");
+        assert_eq!(decompressed, data, "zstd round trip");
+    }
+}
@@ -1,13 +1,19 @@
 //! Storj DCS Uplink configuration.
 
-use crate::{helpers, Result};
+use crate::metadata::MetadataLimits;
+use crate::{helpers, Error, Result};
 
 use std::ffi::CString;
+use std::iter::Peekable;
+use std::str::Chars;
 use std::time::Duration;
 
 use uplink_sys as ulksys;
 
 /// Defines configuration for using Uplink library.
+///
+/// The FFI configuration doesn't have any setting for the size of the parts nor the concurrency of
+/// the uploads; see the [`tuning`](crate::tuning) module for tuning the multipart uploads.
 #[derive(Debug)]
 pub struct Config<'a> {
     /// The configuration type of the FFI that an instance of this struct represents and guard its
@@ -25,9 +31,26 @@ pub struct Config<'a> {
     temp_dir: Option<&'a str>,
     /// Specifies to only operates using memory, hence it doesn't off-load data to disk.
     in_memory: bool,
+    /// Specifies to validate the bucket names before calling the FFI, see
+    /// [`crate::bucket::validate_name`].
+    validate_bucket_names: bool,
+    /// The allowance for the skew of the system clock when validating the expiration time of the
+    /// uploads.
+    expiration_skew: Duration,
+    /// The default timeout of the operations of the projects.
+    operation_timeout: Option<Duration>,
+    /// The size up to which the small objects are written and committed back-to-back.
+    inline_segment_threshold: usize,
+    /// The limits used for validating the custom metadata before sending it to the network.
+    metadata_limits: MetadataLimits,
 }
 
 impl<'a> Config<'a> {
+    /// The default size, in bytes, up to which
+    /// [`Project::put_object`](crate::Project::put_object) writes and commits the data
+    /// back-to-back, see [`Self::set_inline_segment_threshold`].
+    pub const DEFAULT_INLINE_SEGMENT_THRESHOLD: usize = 4 * 1024;
+
     /// Creates a configuration with the specific user agent, dial timeout and using a specific
     /// directory path for creating temporary files.
     ///
@@ -64,6 +87,11 @@ impl<'a> Config<'a> {
             dial_timeout,
             temp_dir,
             in_memory: false,
+            validate_bucket_names: true,
+            expiration_skew: Duration::ZERO,
+            operation_timeout: None,
+            inline_segment_threshold: Self::DEFAULT_INLINE_SEGMENT_THRESHOLD,
+            metadata_limits: MetadataLimits::default(),
         })
     }
 
@@ -89,6 +117,11 @@ impl<'a> Config<'a> {
             dial_timeout,
             temp_dir: None,
             in_memory: true,
+            validate_bucket_names: true,
+            expiration_skew: Duration::ZERO,
+            operation_timeout: None,
+            inline_segment_threshold: Self::DEFAULT_INLINE_SEGMENT_THRESHOLD,
+            metadata_limits: MetadataLimits::default(),
         })
     }
 
@@ -115,6 +148,106 @@ impl<'a> Config<'a> {
         self.user_agent
     }
 
+    /// Returns if the projects opened with this configuration validate the bucket names before
+    /// sending the requests to the satellite. It's `true` by default.
+    pub fn validate_bucket_names(&self) -> bool {
+        self.validate_bucket_names
+    }
+
+    /// Sets if the projects opened with this configuration validate the bucket names, with
+    /// [`crate::bucket::validate_name`], before sending the requests to the satellite.
+    ///
+    /// Disabling it is an escape hatch for when the satellite rules diverge from the ones enforced
+    /// by this crate, so the satellite is the only one that validates them.
+    pub fn set_validate_bucket_names(&mut self, validate: bool) {
+        self.validate_bucket_names = validate;
+    }
+
+    /// Returns the allowance for the skew of the system clock when the projects opened with this
+    /// configuration validate the expiration time of the uploads. It's zero by default.
+    pub fn expiration_skew(&self) -> Duration {
+        self.expiration_skew
+    }
+
+    /// Sets the allowance for the skew of the system clock when the projects opened with this
+    /// configuration validate the expiration time of the uploads.
+    ///
+    /// [`Project::upload_object`](crate::Project::upload_object) and
+    /// [`Project::begin_upload`](crate::Project::begin_upload) reject the expiration times before
+    /// the current system time minus `skew` before sending any request to the satellite.
+    pub fn set_expiration_skew(&mut self, skew: Duration) {
+        self.expiration_skew = skew;
+    }
+
+    /// Returns the default timeout of the operations of the projects opened with this
+    /// configuration. It's `None` by default.
+    pub fn operation_timeout(&self) -> Option<Duration> {
+        self.operation_timeout
+    }
+
+    /// Sets the default timeout of the operations of the projects opened with this configuration.
+    /// Unlike the dial timeout, it bounds the whole operation, so a hung satellite or storage node
+    /// doesn't stall it indefinitely.
+    ///
+    /// The operations that it bounds are [`Project::stat_bucket`](crate::Project::stat_bucket),
+    /// [`Project::stat_object`](crate::Project::stat_object),
+    /// [`Project::delete_object`](crate::Project::delete_object),
+    /// [`Project::commit_upload`](crate::Project::commit_upload) and starting a download with
+    /// [`Project::download_object`](crate::Project::download_object), and the methods that use
+    /// them. They return an [`UplinkCode::Canceled`](crate::error::UplinkCode::Canceled) error when
+    /// the timeout elapses. [`options::CommitUpload::set_timeout`](crate::project::options::CommitUpload::set_timeout)
+    /// and [`options::Download::timeout`](crate::project::options::Download::timeout) override it
+    /// for a commit and a download; the stat and delete operations don't have options, so they
+    /// always use this one.
+    ///
+    /// The rest of the operations aren't bounded. That includes the listings, whose FFI iterators
+    /// request their pages while iterating and cannot be used from other threads, and reading
+    /// and writing the data of the downloads and uploads.
+    ///
+    /// The bundled uplink-c version doesn't allow to cancel the operations, so a timeout abandons
+    /// them rather than cancelling them: they run in a separate thread that keeps running in the
+    /// background until the satellite responds, hence an abandoned delete or commit may still be
+    /// applied. Each bounded call spawns an OS thread, even when the operation returns
+    /// immediately, so a timeout adds the cost of spawning a thread to each of them.
+    pub fn set_operation_timeout(&mut self, timeout: Option<Duration>) {
+        self.operation_timeout = timeout;
+    }
+
+    /// Returns the size, in bytes, up to which the projects opened with this configuration upload
+    /// the small objects writing and committing their data back-to-back. It's
+    /// [`Self::DEFAULT_INLINE_SEGMENT_THRESHOLD`] by default.
+    pub fn inline_segment_threshold(&self) -> usize {
+        self.inline_segment_threshold
+    }
+
+    /// Sets the size, in bytes, up to which [`Project::put_object`](crate::Project::put_object)
+    /// of the projects opened with this configuration writes the data of an object with
+    /// consecutive calls to the FFI and commits it right after, without going through the
+    /// [`std::io::Write`] implementation of the upload. Zero only applies it to empty objects.
+    ///
+    /// It's a client-side knob for reducing the FFI round trips of the tiny objects. The satellite
+    /// decides itself which segments are stored inline in its metadata, so it doesn't change how
+    /// the objects are stored.
+    pub fn set_inline_segment_threshold(&mut self, threshold: usize) {
+        self.inline_segment_threshold = threshold;
+    }
+
+    /// Returns the limits used by the projects opened with this configuration for validating the
+    /// custom metadata. They are the [`MetadataLimits::default`] ones by default.
+    pub fn metadata_limits(&self) -> MetadataLimits {
+        self.metadata_limits
+    }
+
+    /// Sets the limits used by the projects opened with this configuration for validating the
+    /// custom metadata before sending it to the network, see
+    /// [`metadata::Custom::validate`](crate::metadata::Custom::validate).
+    ///
+    /// The default ones match the network defaults, so it's only needed when the satellite is
+    /// configured with different ones.
+    pub fn set_metadata_limits(&mut self, limits: MetadataLimits) {
+        self.metadata_limits = limits;
+    }
+
     /// Returns the FFI representation of this configuration.
     pub(crate) fn as_ffi_config(&self) -> ulksys::UplinkConfig {
         self.inner
@@ -145,11 +278,133 @@ impl Drop for Config<'_> {
     }
 }
 
+/// Validates that `user_agent` is a user agent as the HTTP `User-Agent` header defines it (RFC
+/// 7231), which is how the satellites parse it for attributing the buckets: a product (e.g.
+/// `partner/1.0`) followed by products or comments (e.g. `(linux)`) separated by spaces.
+///
+/// It returns an [`Error::InvalidArguments`] if `user_agent` is invalid.
+pub(crate) fn validate_user_agent(user_agent: &str) -> Result<()> {
+    let is_space = |c: &char| *c == ' ' || *c == '\t';
+
+    let mut chars = user_agent.chars().peekable();
+    skip_user_agent_product(&mut chars)?;
+    while chars.peek().is_some() {
+        if chars.next_if(is_space).is_none() {
+            return Err(Error::new_invalid_arguments(
+                "user_agent",
+                "must separate its products and comments with spaces",
+            ));
+        }
+
+        while chars.next_if(is_space).is_some() {}
+        match chars.peek() {
+            None => break,
+            Some('(') => skip_user_agent_comment(&mut chars)?,
+            Some(_) => skip_user_agent_product(&mut chars)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Advances `chars` past the user agent product at its start, which is a name and an optional
+/// version separated by `/`, see [`validate_user_agent`].
+fn skip_user_agent_product(chars: &mut Peekable<Chars>) -> Result<()> {
+    let is_token = |c: &char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(*c);
+
+    if chars.next_if(is_token).is_none() {
+        return Err(Error::new_invalid_arguments(
+            "user_agent",
+            "must start with a product and its products must have a name",
+        ));
+    }
+    while chars.next_if(is_token).is_some() {}
+
+    if chars.next_if_eq(&'/').is_some() {
+        if chars.next_if(is_token).is_none() {
+            return Err(Error::new_invalid_arguments(
+                "user_agent",
+                "cannot have products with an empty version",
+            ));
+        }
+        while chars.next_if(is_token).is_some() {}
+    }
+
+    Ok(())
+}
+
+/// Advances `chars` past the user agent comment at its start, which is enclosed in parentheses and
+/// may have nested comments and escaped characters, see [`validate_user_agent`].
+fn skip_user_agent_comment(chars: &mut Peekable<Chars>) -> Result<()> {
+    let mut depth = 0;
+    loop {
+        match chars.next() {
+            Some('(') => depth += 1,
+            Some(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            }
+            Some('\\') => {
+                chars.next();
+            }
+            Some(c) if !c.is_ascii_control() => {}
+            Some(_) => {
+                return Err(Error::new_invalid_arguments(
+                    "user_agent",
+                    "cannot contain control characters",
+                ))
+            }
+            None => {
+                return Err(Error::new_invalid_arguments(
+                    "user_agent",
+                    "must close its comments",
+                ))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error;
     use crate::helpers::test::assert_c_string;
-    use crate::{error, Error};
+
+    #[test]
+    fn test_validate_user_agent() {
+        for user_agent in [
+            "uplink-rust",
+            "partner/1.0",
+            "partner/1.0 (linux; x64) other",
+            "partner/1.0 (nested (comment) \\)) other/2",
+        ] {
+            validate_user_agent(user_agent)
+                .unwrap_or_else(|err| panic!("{:?}: unexpected error: {}", user_agent, err));
+        }
+
+        for user_agent in [
+            "",
+            "/1.0",
+            "partner/",
+            "partner/1.0 (unclosed",
+            "partner/1.0 (bell\x07)",
+            "partner/1.0(linux)",
+            "a\0b",
+        ] {
+            match validate_user_agent(user_agent) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(
+                        names, "user_agent",
+                        "{:?}: invalid argument name",
+                        user_agent
+                    )
+                }
+                res => panic!("{:?}: unexpected result: {:?}", user_agent, res),
+            }
+        }
+    }
 
     #[test]
     fn test_new() {
@@ -322,4 +577,69 @@ mod test {
 
         assert_eq!(config.user_agent(), "rust-uplink", "user_agent");
     }
+
+    #[test]
+    fn test_validate_bucket_names() {
+        let mut config = Config::new("rust-uplink", Duration::new(1, 0), None)
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert!(config.validate_bucket_names(), "enabled by default");
+
+        config.set_validate_bucket_names(false);
+        assert!(!config.validate_bucket_names(), "disabled");
+
+        let config = Config::new_inmemory("rust-uplink", Duration::new(1, 0))
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert!(
+            config.validate_bucket_names(),
+            "enabled by default in memory"
+        );
+    }
+
+    #[test]
+    fn test_inline_segment_threshold() {
+        let mut config = Config::new("rust-uplink", Duration::new(1, 0), None)
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert_eq!(
+            config.inline_segment_threshold(),
+            Config::DEFAULT_INLINE_SEGMENT_THRESHOLD,
+            "default"
+        );
+
+        config.set_inline_segment_threshold(0);
+        assert_eq!(config.inline_segment_threshold(), 0, "disabled");
+
+        let config = Config::new_inmemory("rust-uplink", Duration::new(1, 0))
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert_eq!(
+            config.inline_segment_threshold(),
+            Config::DEFAULT_INLINE_SEGMENT_THRESHOLD,
+            "default in memory"
+        );
+    }
+
+    #[test]
+    fn test_metadata_limits() {
+        let mut config = Config::new("rust-uplink", Duration::new(1, 0), None)
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert_eq!(
+            config.metadata_limits(),
+            MetadataLimits::default(),
+            "default"
+        );
+
+        let limits = MetadataLimits {
+            max_total_size: 10,
+            max_key_length: 4,
+        };
+        config.set_metadata_limits(limits);
+        assert_eq!(config.metadata_limits(), limits, "set limits");
+
+        let config = Config::new_inmemory("rust-uplink", Duration::new(1, 0))
+            .expect("new shouldn't fail when 'user agent' doesn't contain any null character");
+        assert_eq!(
+            config.metadata_limits(),
+            MetadataLimits::default(),
+            "default in memory"
+        );
+    }
 }
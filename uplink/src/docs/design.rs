@@ -16,7 +16,8 @@
 //! Consumers can make a decision according to the [`Error`](crate::Error) variant, however, for the
 //! [`Error::Uplink` variant](crate::Error::Uplink), which represents a Storj DCS error returned by
 //! they FFI crate and the majority of them returned by the Storj DCS network, they can make a
-//! decision based on the [`error::Uplink` variants](crate::error::Uplink).
+//! decision based on the [`error::UplinkCode` variants](crate::error::UplinkCode) returned by
+//! [`Error::uplink_code`](crate::Error::uplink_code).
 //!
 //! ### Panics
 //!
@@ -51,14 +52,15 @@
 //! The differences between the names is conventional. If they take ownership or not of the passed
 //! FFI values. See [FFI values "ownership"](#ffi-values-ownership) for knowing about it.
 //!
-//! These constructors panic when the passed FFI value is invalid, for example:
+//! These constructors panic when the passed parameter is a raw pointer and it's `NULL` because
+//! it's a bug of this crate. These panics are part of
+//! [the explicit documented panics](#panics).
 //!
-//! * If the parameter is a raw pointer, the value must not be `NULL`.
-//! * If the FFI type is a result (i.e. `Uplink<type_name>Result`), the raw pointer field to the
-//!   type (i.e. `Uplink<type_name>`) must not be `NULL` when the raw pointer field to the error
-//!   (i.e. `UplinkError`) is `NULL`. See [the `Ensurer` trait](#the-ensurer-trait).
-//!
-//! These panics are part of [the explicit documented panics](#panics).
+//! They return an [`Error::Internal` variant](crate::Error::Internal) when the FFI value is
+//! invalid, for example if the FFI type is a result (i.e. `Uplink<type_name>Result`), the raw
+//! pointer field to the type (i.e. `Uplink<type_name>`) must not be `NULL` when the raw pointer
+//! field to the error (i.e. `UplinkError`) is `NULL`. See
+//! [the `TryEnsure` trait](#the-tryensure-trait).
 //!
 //! Some of this constructors return a [`Result`](crate::Result) because a "valid" FFI value may
 //! contain data values which are not expected, for example:
@@ -97,9 +99,9 @@
 //! public types, the FFI value must not be used further because the public type instance is the
 //! owner of it and it takes care of freeing it at the right time.
 //!
-//! ### The `Ensurer` trait
+//! ### The `TryEnsure` trait
 //!
-//! This crate defines a trait named `Ensurer` which is only visible inside of the crate.
+//! This crate defines a trait named `TryEnsure` which is only visible inside of the crate.
 //!
 //! The trait is implemented for all the FFI types that their field values could contain an
 //! inconsistency, for example the result types (i.e. `Uplink<type_name>Result`) shouldn't never
@@ -110,8 +112,11 @@
 //! to avoid that an inconsistency cause an ugly panic. An inconsistency should only happen in the
 //! case of bug in the FFI (Rust or C part).
 //!
-//! The method doesn't save of panicking, it explicitly panics when the value is inconsistent
-//! following what we specify in our [panics section](#panics).
+//! The method returns an [`Error::Internal` variant](crate::Error::Internal), which indicates the
+//! FFI type and its `NULL` fields, when the value is inconsistent rather than panicking, so a
+//! misbehaving uplink-c version doesn't abort a long-running application.
+//!
+//! The tests use the `Ensurer` trait, which panics with the same message, for asserting it.
 //!
 //! ### Exceptions
 //!
@@ -136,6 +141,12 @@
 //!   errors more idiomatically than having to always check by `NULL` inequality for calling or not
 //!   the constructor.
 //!
+//! The errors that the FFI returns without a "_result_" struct (e.g. the one of
+//! [`uplink_abort_upload`](uplink_sys::uplink_abort_upload)) are owned by the caller, so they are
+//! always converted with the [`from_ffi_error`](crate::error::Error::from_ffi_error) constructor,
+//! which takes ownership and frees them. It's the only place that frees them, so the tests count
+//! the freed errors for asserting that each of them is freed exactly once.
+//!
 //! ## From Rust to FFI
 //!
 //! When needed, the public types of this crate have a method to return their FFI representation,
@@ -147,7 +147,7 @@
 //! use uplink::access::Grant;
 //! use uplink::EncryptionKey;
 //!
-//! let user_grant  = Grant::new(serialized_user_access).unwrap();
+//! let mut user_grant = Grant::new(serialized_user_access).unwrap();
 //! let salted_user_key = EncryptionKey::derive(user_passphrase, user_salt).unwrap();
 //! user_grant.override_encryption_key(app_bucket, &format!("{user_id}/"),
 //! &salted_user_key).unwrap();
@@ -180,7 +180,7 @@
 //! items.
 //!
 //! ```ignore
-//! let mut buckets = project.list_buckets(None);
+//! let mut buckets = project.list_buckets(None).unwrap();
 //!
 //! for res in buckets {
 //!      match res {
@@ -227,7 +227,7 @@
 //! let mut download = project.download_object(
 //!     "logs",
 //!     "2020-04-18/webserver.log",
-//!     &options::Download{offset: 10, length: 10},
+//!     Some(&options::Download::new(10, 10)),
 //! ).unwrap();
 //!
 //! let mut writer: Vec<u8> = vec![];
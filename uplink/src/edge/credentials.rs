@@ -1,6 +1,6 @@
 //! Storj DCS Edge services credentials.
 
-use crate::uplink_c::Ensurer;
+use crate::uplink_c::TryEnsure;
 use crate::{Error, Result};
 
 use std::ffi::CStr;
@@ -23,7 +23,7 @@ impl Gateway {
     pub(crate) fn from_ffi_credentials_result(
         uc_result: ulksys::EdgeCredentialsResult,
     ) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
@@ -35,8 +35,8 @@ impl Gateway {
         let secret_key: String;
         let endpoint: String;
         // SAFETY: we have checked that the `uc_result` isn't an error so `credentials` field isn't
-        // NULL through the `ensure` method of the result. Inside of the block we check with the
-        // credentials ensure method that their fields aren't NULL, so we are not accessing to any
+        // NULL through the `try_ensure` method of the result. Inside of the block we check with the
+        // credentials `try_ensure` method that their fields aren't NULL, so we are not accessing to any
         // NULL pointer.
         unsafe {
             // Likely these values shouldn't contain invalid UTF-8 characters, but we don't panic
@@ -44,7 +44,10 @@ impl Gateway {
             // of Rust and C interoperability and consumers of this crate would have a chance to
             // deal with them appropriately.
             let creds = *uc_result.credentials;
-            creds.ensure();
+            if let Err(err) = creds.try_ensure() {
+                ulksys::edge_free_credentials_result(uc_result);
+                return Err(err);
+            }
 
             access_key_id = CStr::from_ptr(creds.access_key_id)
                 .to_str()
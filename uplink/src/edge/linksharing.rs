@@ -1,6 +1,6 @@
 //! Storj DCS linksharing service operations and related types.
 
-use crate::uplink_c::{string_from_ffi_string_result, Ensurer};
+use crate::uplink_c::string_from_ffi_string_result;
 use crate::{helpers, Result};
 
 use std::ffi::CString;
@@ -71,7 +71,6 @@ pub fn share_url(
         let _ = CString::from_raw(c_key);
     }
 
-    res.ensure();
     string_from_ffi_string_result(res)
 }
 
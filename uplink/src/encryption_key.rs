@@ -1,11 +1,20 @@
 //! Storj DCS Encryption key.
 
-use crate::uplink_c::Ensurer;
+use crate::uplink_c::TryEnsure;
 use crate::{helpers, Error, Result};
 
 use uplink_sys as ulksys;
 
 /// Represents a key for encrypting and decrypting data.
+///
+/// An encryption key can only be created by deriving it (see [`Self::derive`]) and its key
+/// material can't be exported, because the bundled uplink-c version doesn't expose any function
+/// to get the raw bytes of a key nor to create a key from them. Applications that need to
+/// persist per-user keys have to store, securely, the passphrase and the salt used for deriving
+/// them and derive them again when they need them, which is a costly operation, so it's
+/// recommended to keep the derived keys in memory while they're used.
+// TODO: add `from_bytes` and `to_bytes` methods when uplink-c exposes functions for creating an
+// encryption key from its raw bytes and for getting them.
 #[derive(Debug)]
 pub struct EncryptionKey {
     /// The encryption key type of the FFI that an instance of this struct represents and guards its
@@ -21,6 +30,9 @@ impl EncryptionKey {
     /// It's mostly useful for implementing multitenancy in a single app bucket.
     /// See [Multitenancy in a Single Application Bucket](https://pkg.go.dev/storj.io/uplink#hdr-Multitenancy_in_a_Single_Application_Bucket)
     /// section in the original Uplink library.
+    ///
+    /// Deriving a key is intentionally costly, so derive it once and reuse it rather than
+    /// deriving it for every operation.
     pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self> {
         use std::ffi::c_void;
         use std::os::raw::c_char;
@@ -40,7 +52,7 @@ impl EncryptionKey {
             )
         };
 
-        uc_res.ensure();
+        uc_res.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_res.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
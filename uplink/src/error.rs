@@ -1,11 +1,41 @@
 //! Errors returned by this crate.
+//!
+//! [`Error`] is `Send`, `Sync` and `'static`, so it converts into the error types of the crates
+//! that type-erase errors, like `anyhow`, through the `?` operator. The code of the
+//! [`Uplink` variant](Error::Uplink) is the source of the error, so it's kept in their error chains:
+//!
+//! ```
+//! use uplink::error::{self, UplinkCode};
+//! use uplink::Error;
+//!
+//! fn stat() -> uplink::Result<()> {
+//!     Err(Error::Uplink(error::Uplink::with_code(UplinkCode::ObjectNotFound, "my-key")))
+//! }
+//!
+//! fn app() -> anyhow::Result<()> {
+//!     stat()?;
+//!     Ok(())
+//! }
+//!
+//! let err = app().expect_err("stat fails");
+//! let code = err.chain().find_map(|e| e.downcast_ref::<UplinkCode>());
+//! assert_eq!(code, Some(&UplinkCode::ObjectNotFound));
+//! assert!(err.downcast_ref::<Error>().is_some());
+//! ```
 
 use std::error as stderr;
 use std::ffi::CStr;
 use std::fmt;
+use std::io;
 
 use uplink_sys as ulksys;
 
+#[cfg(any(test, feature = "testing"))]
+thread_local! {
+    /// The number of FFI errors freed by [`free_ffi_error`] in the current thread.
+    static FFI_ERRORS_FREED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 /// Convenient type alias to shorten the signature on every usage.
 pub(crate) type BoxError = Box<dyn stderr::Error + Send + Sync>;
 
@@ -20,6 +50,8 @@ pub enum Error {
     InvalidArguments(Args),
     /// Identifies a native error returned by the FFI.
     Uplink(Uplink),
+    /// Identifies an operation that the bundled uplink-c version doesn't support.
+    Unsupported(Unsupported),
 }
 
 impl Error {
@@ -42,6 +74,14 @@ impl Error {
         Self::InvalidArguments(Args::new(names, msg))
     }
 
+    /// Convenient constructor for creating an [`Unsupported` variant](Self::Unsupported) Error
+    /// for the unsupported `capability`.
+    pub(crate) fn new_unsupported(capability: &str) -> Self {
+        Self::Unsupported(Unsupported {
+            capability: String::from(capability),
+        })
+    }
+
     /// Convenient constructor for creating an [`Uplink` variant](Self::Uplink).
     /// It returns None if `err` is `NULL`.
     ///
@@ -59,18 +99,137 @@ impl Error {
     pub(crate) fn from_ffi_error(err: *mut ulksys::UplinkError) -> Option<Self> {
         Uplink::from_ffi_error(err).map(Self::Uplink)
     }
+
+    /// Returns the code of the error if it's an [`Uplink` variant](Self::Uplink), so it can be
+    /// matched with `matches!` (e.g.
+    /// `matches!(err.uplink_code(), Some(UplinkCode::BucketNotFound))`).
+    pub fn uplink_code(&self) -> Option<UplinkCode> {
+        match self {
+            Self::Uplink(err) => Some(err.code()),
+            _ => None,
+        }
+    }
+
+    /// Converts an I/O error returned by the [`std::io::Read`] and [`std::io::Write`]
+    /// implementations of this crate into the error of this crate that it wraps.
+    ///
+    /// It returns an [`Internal` variant](Self::Internal) if `err` doesn't wrap an error of this
+    /// crate.
+    pub(crate) fn from_io_error(err: io::Error) -> Self {
+        if err.get_ref().is_some_and(|e| e.is::<Self>()) {
+            let inner = err
+                .into_inner()
+                .expect("BUG: I/O error payload verified just above");
+            return *inner
+                .downcast::<Self>()
+                .expect("BUG: I/O error payload type verified just above");
+        }
+
+        Self::new_internal("unexpected I/O error", BoxError::from(err))
+    }
+
+    /// Returns the error of this crate that an I/O error wraps, if any.
+    ///
+    /// It looks for it in the I/O error's payload and in the chain of its sources, so it allows to
+    /// recover the error returned by the [`std::io::Read`] and [`std::io::Write`] implementations
+    /// of this crate when they are used through functions such as [`std::io::copy`].
+    pub fn from_io_chain(err: &io::Error) -> Option<&Self> {
+        let mut current: Option<&(dyn stderr::Error + 'static)> = match err.get_ref() {
+            Some(e) => Some(e),
+            None => None,
+        };
+
+        while let Some(e) = current {
+            if let Some(uerr) = e.downcast_ref::<Self>() {
+                return Some(uerr);
+            }
+
+            // The source of an I/O error is the source of its payload rather than its payload, so
+            // we have to get the payload for not skipping it.
+            current = match e.downcast_ref::<io::Error>() {
+                Some(ioerr) => match ioerr.get_ref() {
+                    Some(e) => Some(e),
+                    None => None,
+                },
+                None => e.source(),
+            };
+        }
+
+        None
+    }
+}
+
+impl From<Error> for io::Error {
+    /// Converts into an I/O error whose payload is `err` and its kind is the one that matches the
+    /// most with `err`:
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] for [`Error::InvalidArguments`].
+    /// * [`io::ErrorKind::NotFound`] for [`UplinkCode::BucketNotFound`] and
+    ///   [`UplinkCode::ObjectNotFound`].
+    /// * [`io::ErrorKind::AlreadyExists`] for [`UplinkCode::BucketAlreadyExists`] and
+    ///   [`UplinkCode::ObjectAlreadyExists`].
+    /// * [`io::ErrorKind::PermissionDenied`] for [`UplinkCode::PermissionDenied`].
+    /// * [`io::ErrorKind::TimedOut`] for [`UplinkCode::Canceled`].
+    /// * [`io::ErrorKind::StorageFull`] for [`UplinkCode::StorageLimitExceeded`].
+    /// * [`io::ErrorKind::Unsupported`] for [`Error::Unsupported`].
+    /// * [`io::ErrorKind::Other`] for the rest.
+    fn from(err: Error) -> Self {
+        let kind = match &err {
+            Error::InvalidArguments(_) => io::ErrorKind::InvalidInput,
+            Error::Uplink(details) => match details.code() {
+                UplinkCode::BucketNotFound | UplinkCode::ObjectNotFound => io::ErrorKind::NotFound,
+                UplinkCode::BucketAlreadyExists | UplinkCode::ObjectAlreadyExists => {
+                    io::ErrorKind::AlreadyExists
+                }
+                UplinkCode::PermissionDenied => io::ErrorKind::PermissionDenied,
+                UplinkCode::Canceled => io::ErrorKind::TimedOut,
+                UplinkCode::StorageLimitExceeded => io::ErrorKind::StorageFull,
+                _ => io::ErrorKind::Other,
+            },
+            Error::Internal(_) => io::ErrorKind::Other,
+            Error::Unsupported(_) => io::ErrorKind::Unsupported,
+        };
+
+        io::Error::new(kind, err)
+    }
 }
 
 impl stderr::Error for Error {
+    /// Returns the inner error of the [`Internal` variant](Self::Internal) and the code of the
+    /// [`Uplink` variant](Self::Uplink), see [`Uplink`]'s `source`.
     fn source(&self) -> Option<&(dyn stderr::Error + 'static)> {
         match self {
-            Error::InvalidArguments { .. } => None,
-            Error::Uplink { .. } => None,
+            Error::InvalidArguments { .. } | Error::Unsupported(_) => None,
+            Error::Uplink(details) => details.source(),
             Error::Internal(Internal { inner, .. }) => Some(inner.as_ref()),
         }
     }
 }
 
+impl From<Args> for Error {
+    fn from(args: Args) -> Self {
+        Error::InvalidArguments(args)
+    }
+}
+
+impl From<Internal> for Error {
+    fn from(details: Internal) -> Self {
+        Error::Internal(details)
+    }
+}
+
+impl From<Unsupported> for Error {
+    fn from(details: Unsupported) -> Self {
+        Error::Unsupported(details)
+    }
+}
+
+impl From<Uplink> for Error {
+    fn from(details: Uplink) -> Self {
+        Error::Uplink(details)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
@@ -83,6 +242,9 @@ impl fmt::Display for Error {
             Error::Internal(details) => {
                 write!(f, "{}", details)
             }
+            Error::Unsupported(details) => {
+                write!(f, "{}", details)
+            }
         }
     }
 }
@@ -166,61 +328,36 @@ impl fmt::Display for Args {
     }
 }
 
-/// Wraps a native error returned by the FFI providing the access to its details.
-#[derive(Debug)]
-pub enum Uplink {
-    /// A Storj DCS network internal error.
-    Internal(String),
-    /// A Storj DCS network cancellation error.
-    Canceled(String),
-    /// An invalid handle is passed to the FFI. This error shouldn't happen and
-    /// when it does, it's likely due to a bug in the FFI.
-    InvalidHandle(String),
-    /// Storj DCS network rejected the operation because the client over passed the rate-limit
-    /// allowance.
-    TooManyRequests(String),
-    /// Storj DCS network rejected the operation because the bandwidth limit of the client has
-    /// reached all the user account's bandwidth. User should upgrade its account to a another plan
-    /// or they are not able then, reach Storj DCS support.
-    BandwidthLimitExceeded(String),
-    /// Storj DCS network rejected the operation because the bucket's name contains non-allowed
-    /// characters.
-    BucketNameInvalid(String),
-    /// Storj DCS network rejected the operation because the bucket already exists.
-    BucketAlreadyExists(String),
-    /// Storj DCS network rejected the operation because the bucket still contains some objects.
-    BucketNotEmpty(String),
-    /// Storj DCS network rejected the operation because the bucket doens't exist.
-    BucketNotFound(String),
-    /// Storj DCS network rejected the operation because the object's key contains non-allowed
-    /// characters.
-    ObjectKeyInvalid(String),
-    /// Storj DCS network rejected the operation because it doesn't exists an object in the
-    /// specified bucket and key.
-    ObjectNotFound(String),
-    /// Storj DCS network rejected the operation because there isn't valid permissions for
-    /// conducting the requested operation.
-    PermissionDenied(String),
-    /// Storj DCS network rejected the operation because it would exceed the account's segments
-    /// limit.
-    SegmentsLimitExceeded(String),
-    /// Storj DCS network rejected the operation because it would exceed the account's storage
-    /// limit.
-    StorageLimitExceeded(String),
-    /// Storj DCS network rejected the operation because the specified upload was already completed
-    /// or aborted.
-    UploadDone(String),
-
-    // Edge service error codes
-    /// An Edge Auth service network or protocol error.
-    EdgeAuthDialFailed(String),
-    /// An Edge Auth service internal error.
-    EdgeRegisterAccessFailed(String),
+impl stderr::Error for Args {}
 
-    /// Unknowns isn't an actual code in the FFI constants. It's mostly used to map a code when it
-    /// doesn't match any and have not to panic. Callers should report this as a BUG that may be
-    /// due to not having updated the FFI to the last version.
-    Unknown(String),
+/// Wraps a native error returned by the FFI providing the access to its code and message.
+///
+/// # Migrating from the enum
+///
+/// This type was an enum with a variant per code that only carried the message. The variants are
+/// now the ones of [`UplinkCode`], so the patterns that matched them have to match the code
+/// instead:
+///
+/// ```
+/// use uplink::error::{self, UplinkCode};
+/// use uplink::Error;
+///
+/// fn is_missing(err: &Error) -> bool {
+///     // Before: matches!(err, Error::Uplink(error::Uplink::BucketNotFound(_)))
+///     matches!(err.uplink_code(), Some(UplinkCode::BucketNotFound))
+/// }
+///
+/// let err = Error::Uplink(error::Uplink::with_code(UplinkCode::BucketNotFound, "my-bucket"));
+/// assert!(is_missing(&err));
+/// ```
+///
+/// The message that the variants carried is returned by [`Self::message`].
+#[derive(Clone, Debug)]
+pub struct Uplink {
+    /// The code of the error.
+    code: UplinkCode,
+    /// The message of the error, which is empty if the FFI didn't return any.
+    message: String,
 }
 
 impl Uplink {
@@ -238,7 +375,7 @@ impl Uplink {
         // SAFETY: We have checked just above that the pointer isn't NULL.
         let ulkerr = unsafe { *err };
 
-        let msg = if ulkerr.message.is_null() {
+        let message = if ulkerr.message.is_null() {
             String::new()
         } else {
             // SAFETY: We trust the FFI that the error contains valid C strings.
@@ -250,25 +387,9 @@ impl Uplink {
             }
         };
 
-        Some(match ulkerr.code as u32 {
-            ulksys::UPLINK_ERROR_INTERNAL => Self::Internal(msg),
-            ulksys::UPLINK_ERROR_CANCELED => Self::Canceled(msg),
-            ulksys::UPLINK_ERROR_INVALID_HANDLE => Self::InvalidHandle(msg),
-            ulksys::UPLINK_ERROR_TOO_MANY_REQUESTS => Self::TooManyRequests(msg),
-            ulksys::UPLINK_ERROR_BANDWIDTH_LIMIT_EXCEEDED => Self::BandwidthLimitExceeded(msg),
-            ulksys::UPLINK_ERROR_BUCKET_NAME_INVALID => Self::BucketNameInvalid(msg),
-            ulksys::UPLINK_ERROR_BUCKET_ALREADY_EXISTS => Self::BucketAlreadyExists(msg),
-            ulksys::UPLINK_ERROR_BUCKET_NOT_EMPTY => Self::BucketNotEmpty(msg),
-            ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND => Self::BucketNotFound(msg),
-            ulksys::UPLINK_ERROR_OBJECT_KEY_INVALID => Self::ObjectKeyInvalid(msg),
-            ulksys::UPLINK_ERROR_OBJECT_NOT_FOUND => Self::ObjectNotFound(msg),
-            ulksys::UPLINK_ERROR_PERMISSION_DENIED => Self::PermissionDenied(msg),
-            ulksys::UPLINK_ERROR_SEGMENTS_LIMIT_EXCEEDED => Self::SegmentsLimitExceeded(msg),
-            ulksys::UPLINK_ERROR_STORAGE_LIMIT_EXCEEDED => Self::StorageLimitExceeded(msg),
-            ulksys::UPLINK_ERROR_UPLOAD_DONE => Self::UploadDone(msg),
-            ulksys::EDGE_ERROR_AUTH_DIAL_FAILED => Self::EdgeAuthDialFailed(msg),
-            ulksys::EDGE_ERROR_REGISTER_ACCESS_FAILED => Self::EdgeRegisterAccessFailed(msg),
-            _ => Self::Unknown(msg),
+        Some(Self {
+            code: UplinkCode::from_ffi_code(ulkerr.code as u32),
+            message,
         })
     }
 
@@ -277,43 +398,250 @@ impl Uplink {
     pub(crate) fn from_ffi_error(err: *mut ulksys::UplinkError) -> Option<Self> {
         let opt = Self::new(err);
         if opt.is_some() {
-            // SAFETY: at this point we know that pointer wasn't `NULL` because the option contains
-            // an error so we can free the memory. We trust that FFI safely free the memory of
-            // pointers allocated by itself.
-            unsafe { ulksys::uplink_free_error(err) };
+            free_ffi_error(err);
         }
 
         opt
     }
+
+    /// Creates a new instance with `code` and `message`.
+    ///
+    /// It's useful for implementations of the [`api`](crate::api) traits that have to return the
+    /// same errors than the FFI.
+    pub fn with_code(code: UplinkCode, message: &str) -> Self {
+        Self {
+            code,
+            message: String::from(message),
+        }
+    }
+
+    /// Returns the code of the error.
+    pub fn code(&self) -> UplinkCode {
+        self.code
+    }
+
+    /// Returns the message of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl fmt::Display for Uplink {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        let (code, details) = match self {
-            Self::Internal(msg) => ("internal", msg),
-            Self::Canceled(msg) => ("canceled", msg),
-            Self::InvalidHandle(msg) => ("invalid handle", msg),
-            Self::TooManyRequests(msg) => ("too many requests", msg),
-            Self::BandwidthLimitExceeded(msg) => ("bandwidth limit exceeded", msg),
-            Self::BucketNameInvalid(msg) => ("bucket name invalid", msg),
-            Self::BucketAlreadyExists(msg) => ("bucket already exists", msg),
-            Self::BucketNotEmpty(msg) => ("bucket not empty", msg),
-            Self::BucketNotFound(msg) => ("bucket not found", msg),
-            Self::ObjectKeyInvalid(msg) => ("object key invalid", msg),
-            Self::ObjectNotFound(msg) => ("object not found", msg),
-            Self::PermissionDenied(msg) => ("permission denied", msg),
-            Self::SegmentsLimitExceeded(msg) => ("segments limit exceeded", msg),
-            Self::StorageLimitExceeded(msg) => ("storage limit exceeded", msg),
-            Self::UploadDone(msg) => ("upload done", msg),
-            Self::EdgeAuthDialFailed(msg) => ("dial to auth service failed", msg),
-            Self::EdgeRegisterAccessFailed(msg) => ("register access for edge service failed", msg),
-            Self::Unknown(msg) => ("unknown", msg),
+        match self.code.ffi_code() {
+            Some(num) => write!(
+                f,
+                r#"code: "{}" ({:#04x}), details: "{}""#,
+                self.code.as_str(),
+                num,
+                self.message
+            ),
+            None => write!(
+                f,
+                r#"code: "{}", details: "{}""#,
+                self.code.as_str(),
+                self.message
+            ),
+        }
+    }
+}
+
+impl stderr::Error for Uplink {
+    /// Returns the code of the error, so the error chains that contain it can be searched for its
+    /// categorization without knowing the types that wrap it.
+    fn source(&self) -> Option<&(dyn stderr::Error + 'static)> {
+        Some(&self.code)
+    }
+}
+
+/// The code of an [`Uplink`] error.
+///
+/// It's an error itself because it's the source of the [`Uplink`] errors.
+///
+/// It's non exhaustive because newer FFI versions may add codes, which are
+/// [`Self::Unknown`] until this crate maps them.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UplinkCode {
+    /// A Storj DCS network internal error.
+    Internal,
+    /// A Storj DCS network cancellation error.
+    Canceled,
+    /// An invalid handle is passed to the FFI. This error shouldn't happen and
+    /// when it does, it's likely due to a bug in the FFI.
+    InvalidHandle,
+    /// Storj DCS network rejected the operation because the client over passed the rate-limit
+    /// allowance.
+    TooManyRequests,
+    /// Storj DCS network rejected the operation because the bandwidth limit of the client has
+    /// reached all the user account's bandwidth. User should upgrade its account to a another plan
+    /// or they are not able then, reach Storj DCS support.
+    BandwidthLimitExceeded,
+    /// Storj DCS network rejected the operation because the bucket's name contains non-allowed
+    /// characters.
+    BucketNameInvalid,
+    /// Storj DCS network rejected the operation because the bucket already exists.
+    BucketAlreadyExists,
+    /// Storj DCS network rejected the operation because the bucket still contains some objects.
+    BucketNotEmpty,
+    /// Storj DCS network rejected the operation because the bucket doens't exist.
+    BucketNotFound,
+    /// The object already exists. It isn't an FFI error, so it doesn't have an FFI code; it's
+    /// returned by the conditional operations of this crate (e.g.
+    /// [`Project::upload_object_if_absent`](crate::Project::upload_object_if_absent)).
+    ObjectAlreadyExists,
+    /// Storj DCS network rejected the operation because the object's key contains non-allowed
+    /// characters.
+    ObjectKeyInvalid,
+    /// Storj DCS network rejected the operation because it doesn't exists an object in the
+    /// specified bucket and key.
+    ObjectNotFound,
+    /// Storj DCS network rejected the operation because there isn't valid permissions for
+    /// conducting the requested operation.
+    PermissionDenied,
+    /// Storj DCS network rejected the operation because it would exceed the account's segments
+    /// limit.
+    SegmentsLimitExceeded,
+    /// Storj DCS network rejected the operation because it would exceed the account's storage
+    /// limit.
+    StorageLimitExceeded,
+    /// Storj DCS network rejected the operation because the specified upload was already completed
+    /// or aborted.
+    UploadDone,
+    /// The object doesn't exist because its multipart upload is pending, so it isn't committed
+    /// yet. It isn't an FFI error, so it doesn't have an FFI code; it's returned by the operations
+    /// of this crate that detect it (e.g. [`Project::move_object`](crate::Project::move_object)).
+    UploadPending,
+
+    // Edge service error codes
+    /// An Edge Auth service network or protocol error.
+    EdgeAuthDialFailed,
+    /// An Edge Auth service internal error.
+    EdgeRegisterAccessFailed,
+
+    /// A code that doesn't match any of the FFI constants, which it contains. Callers should
+    /// report this as a BUG that may be due to not having updated the FFI to the last version.
+    Unknown(u32),
+}
+
+impl UplinkCode {
+    /// The codes that map to an FFI constant.
+    #[cfg(test)]
+    const KNOWN_FFI: [Self; 17] = [
+        Self::Internal,
+        Self::Canceled,
+        Self::InvalidHandle,
+        Self::TooManyRequests,
+        Self::BandwidthLimitExceeded,
+        Self::BucketNameInvalid,
+        Self::BucketAlreadyExists,
+        Self::BucketNotEmpty,
+        Self::BucketNotFound,
+        Self::ObjectKeyInvalid,
+        Self::ObjectNotFound,
+        Self::PermissionDenied,
+        Self::SegmentsLimitExceeded,
+        Self::StorageLimitExceeded,
+        Self::UploadDone,
+        Self::EdgeAuthDialFailed,
+        Self::EdgeRegisterAccessFailed,
+    ];
+
+    /// Maps the numeric code of an FFI error to its code, which is [`Self::Unknown`] if it
+    /// doesn't match any of the FFI constants.
+    pub(crate) fn from_ffi_code(code: u32) -> Self {
+        match code {
+            ulksys::UPLINK_ERROR_INTERNAL => Self::Internal,
+            ulksys::UPLINK_ERROR_CANCELED => Self::Canceled,
+            ulksys::UPLINK_ERROR_INVALID_HANDLE => Self::InvalidHandle,
+            ulksys::UPLINK_ERROR_TOO_MANY_REQUESTS => Self::TooManyRequests,
+            ulksys::UPLINK_ERROR_BANDWIDTH_LIMIT_EXCEEDED => Self::BandwidthLimitExceeded,
+            ulksys::UPLINK_ERROR_BUCKET_NAME_INVALID => Self::BucketNameInvalid,
+            ulksys::UPLINK_ERROR_BUCKET_ALREADY_EXISTS => Self::BucketAlreadyExists,
+            ulksys::UPLINK_ERROR_BUCKET_NOT_EMPTY => Self::BucketNotEmpty,
+            ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND => Self::BucketNotFound,
+            ulksys::UPLINK_ERROR_OBJECT_KEY_INVALID => Self::ObjectKeyInvalid,
+            ulksys::UPLINK_ERROR_OBJECT_NOT_FOUND => Self::ObjectNotFound,
+            ulksys::UPLINK_ERROR_PERMISSION_DENIED => Self::PermissionDenied,
+            ulksys::UPLINK_ERROR_SEGMENTS_LIMIT_EXCEEDED => Self::SegmentsLimitExceeded,
+            ulksys::UPLINK_ERROR_STORAGE_LIMIT_EXCEEDED => Self::StorageLimitExceeded,
+            ulksys::UPLINK_ERROR_UPLOAD_DONE => Self::UploadDone,
+            ulksys::EDGE_ERROR_AUTH_DIAL_FAILED => Self::EdgeAuthDialFailed,
+            ulksys::EDGE_ERROR_REGISTER_ACCESS_FAILED => Self::EdgeRegisterAccessFailed,
+            _ => Self::Unknown(code),
+        }
+    }
+
+    /// Returns the numeric code of the FFI error, which is `None` for
+    /// [`Self::ObjectAlreadyExists`] and [`Self::UploadPending`] because they aren't FFI errors.
+    pub fn ffi_code(self) -> Option<u32> {
+        let code = match self {
+            Self::Internal => ulksys::UPLINK_ERROR_INTERNAL,
+            Self::Canceled => ulksys::UPLINK_ERROR_CANCELED,
+            Self::InvalidHandle => ulksys::UPLINK_ERROR_INVALID_HANDLE,
+            Self::TooManyRequests => ulksys::UPLINK_ERROR_TOO_MANY_REQUESTS,
+            Self::BandwidthLimitExceeded => ulksys::UPLINK_ERROR_BANDWIDTH_LIMIT_EXCEEDED,
+            Self::BucketNameInvalid => ulksys::UPLINK_ERROR_BUCKET_NAME_INVALID,
+            Self::BucketAlreadyExists => ulksys::UPLINK_ERROR_BUCKET_ALREADY_EXISTS,
+            Self::BucketNotEmpty => ulksys::UPLINK_ERROR_BUCKET_NOT_EMPTY,
+            Self::BucketNotFound => ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND,
+            Self::ObjectAlreadyExists => return None,
+            Self::ObjectKeyInvalid => ulksys::UPLINK_ERROR_OBJECT_KEY_INVALID,
+            Self::ObjectNotFound => ulksys::UPLINK_ERROR_OBJECT_NOT_FOUND,
+            Self::PermissionDenied => ulksys::UPLINK_ERROR_PERMISSION_DENIED,
+            Self::SegmentsLimitExceeded => ulksys::UPLINK_ERROR_SEGMENTS_LIMIT_EXCEEDED,
+            Self::StorageLimitExceeded => ulksys::UPLINK_ERROR_STORAGE_LIMIT_EXCEEDED,
+            Self::UploadDone => ulksys::UPLINK_ERROR_UPLOAD_DONE,
+            Self::UploadPending => return None,
+            Self::EdgeAuthDialFailed => ulksys::EDGE_ERROR_AUTH_DIAL_FAILED,
+            Self::EdgeRegisterAccessFailed => ulksys::EDGE_ERROR_REGISTER_ACCESS_FAILED,
+            Self::Unknown(code) => code,
         };
 
-        write!(f, r#"code: "{}", details: "{}""#, code, details)
+        Some(code)
+    }
+
+    /// Returns a short human friendly description of the code (e.g. `bucket not found`), which
+    /// doesn't change between versions, so it can be used as a structured logging field.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Internal => "internal",
+            Self::Canceled => "canceled",
+            Self::InvalidHandle => "invalid handle",
+            Self::TooManyRequests => "too many requests",
+            Self::BandwidthLimitExceeded => "bandwidth limit exceeded",
+            Self::BucketNameInvalid => "bucket name invalid",
+            Self::BucketAlreadyExists => "bucket already exists",
+            Self::BucketNotEmpty => "bucket not empty",
+            Self::BucketNotFound => "bucket not found",
+            Self::ObjectAlreadyExists => "object already exists",
+            Self::ObjectKeyInvalid => "object key invalid",
+            Self::ObjectNotFound => "object not found",
+            Self::PermissionDenied => "permission denied",
+            Self::SegmentsLimitExceeded => "segments limit exceeded",
+            Self::StorageLimitExceeded => "storage limit exceeded",
+            Self::UploadDone => "upload done",
+            Self::UploadPending => "upload pending",
+            Self::EdgeAuthDialFailed => "dial to auth service failed",
+            Self::EdgeRegisterAccessFailed => "register access for edge service failed",
+            Self::Unknown(_) => "unknown",
+        }
     }
 }
 
+impl fmt::Display for UplinkCode {
+    /// Writes the description of the code, see [`Self::as_str`], followed by the numeric code for
+    /// [`Self::Unknown`], so it isn't lost when only the source of an [`Uplink`] error is reported.
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Unknown(code) => write!(f, "{} ({:#04x})", self.as_str(), code),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+impl stderr::Error for UplinkCode {}
+
 /// Represents an error that happen because of the violation of an internal assumption.
 ///
 /// An assumption can be violated by the use of a function that returns an error when it should
@@ -340,3 +668,416 @@ impl stderr::Error for Internal {
         Some(self.inner.as_ref())
     }
 }
+
+/// Represents an operation that the bundled uplink-c version doesn't support, so it cannot be
+/// performed until this crate bundles a version that supports it.
+#[derive(Debug)]
+pub struct Unsupported {
+    /// The name of the unsupported capability (e.g. `project limits`).
+    pub capability: String,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{} isn't supported by the bundled uplink-c version",
+            self.capability
+        )
+    }
+}
+
+impl stderr::Error for Unsupported {}
+
+/// The error returned by the methods that list and collect all the items (e.g.
+/// [`Project::list_objects_all`](crate::Project::list_objects_all)), which contains the items
+/// successfully listed before the error happened.
+///
+/// The last item can be used for resuming the listing with a cursor.
+#[derive(Debug)]
+pub struct ListError<T> {
+    /// The items listed before the error happened, in the order that they were listed.
+    pub items: Vec<T>,
+    /// The error that stopped the listing.
+    pub error: Error,
+}
+
+impl<T> ListError<T> {
+    /// Collects all the items of `iter`, stopping at the first error and returning it with the
+    /// items collected until then.
+    pub(crate) fn collect<I>(iter: I) -> Result<Vec<T>, Self>
+    where
+        I: IntoIterator<Item = crate::Result<T>>,
+    {
+        let mut items = Vec::new();
+        for res in iter {
+            match res {
+                Ok(item) => items.push(item),
+                Err(error) => return Err(Self { items, error }),
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+impl<T> fmt::Display for ListError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "listing failed after {} items: {}",
+            self.items.len(),
+            self.error
+        )
+    }
+}
+
+impl<T: fmt::Debug> stderr::Error for ListError<T> {
+    fn source(&self) -> Option<&(dyn stderr::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Frees `err`, which must not be `NULL` and must be owned by the caller.
+///
+/// It's the only function that frees the FFI errors that aren't part of an FFI result, so the
+/// tests can assert that each of them is freed exactly once through [`ffi_errors_freed`].
+fn free_ffi_error(err: *mut ulksys::UplinkError) {
+    debug_assert!(!err.is_null(), "BUG: freeing a NULL FFI error");
+
+    // SAFETY: the caller guarantees that `err` isn't `NULL` and that it owns it, so it isn't
+    // freed by anything else. We trust that FFI safely free the memory of pointers allocated by
+    // itself.
+    unsafe { ulksys::uplink_free_error(err) };
+
+    #[cfg(any(test, feature = "testing"))]
+    FFI_ERRORS_FREED.with(|freed| freed.set(freed.get() + 1));
+}
+
+/// Returns the number of FFI errors freed in the current thread since it started.
+#[cfg(any(test, feature = "testing"))]
+pub(crate) fn ffi_errors_freed() -> usize {
+    FFI_ERRORS_FREED.with(|freed| freed.get())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helpers::test::ffi_error;
+
+    #[test]
+    fn test_list_error_collect() {
+        let res = ListError::collect(vec![Ok(1), Ok(2), Ok(3)]);
+        assert_eq!(res.expect("collect"), vec![1, 2, 3], "collected items");
+
+        let res = ListError::collect(vec![
+            Ok(1),
+            Ok(2),
+            Err(Error::Uplink(Uplink::with_code(
+                UplinkCode::PermissionDenied,
+                "denied",
+            ))),
+            Ok(3),
+        ]);
+        let err = res.expect_err("collect with an error");
+        assert_eq!(err.items, vec![1, 2], "items listed before the error");
+        match &err.error {
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert!(
+            stderr::Error::source(&err).is_some_and(|e| e.is::<Error>()),
+            "source is the listing error"
+        );
+        assert!(
+            err.to_string()
+                .starts_with("listing failed after 2 items: "),
+            "display: {}",
+            err
+        );
+
+        let err =
+            ListError::<i32>::collect(vec![Err(Error::new_invalid_arguments("arg", "invalid"))])
+                .expect_err("collect with an error as first item");
+        assert!(err.items.is_empty(), "no items listed before the error");
+    }
+
+    #[test]
+    fn test_uplink_code_ffi_round_trip() {
+        for code in UplinkCode::KNOWN_FFI {
+            let num = code.ffi_code().expect("known codes have an FFI code");
+            assert_eq!(
+                UplinkCode::from_ffi_code(num),
+                code,
+                "round trip of {:?}",
+                code
+            );
+            assert_ne!(code.as_str(), "unknown", "description of {:?}", code);
+        }
+
+        for num in [0, 0x01, 0x23, 0xff, u32::MAX] {
+            let code = UplinkCode::from_ffi_code(num);
+            assert_eq!(code, UplinkCode::Unknown(num), "unknown code {:#x}", num);
+            assert_eq!(code.ffi_code(), Some(num), "round trip of {:#x}", num);
+        }
+
+        for code in [UplinkCode::ObjectAlreadyExists, UplinkCode::UploadPending] {
+            assert_eq!(code.ffi_code(), None, "{:?} without FFI code", code);
+        }
+    }
+
+    #[test]
+    fn test_uplink_from_ffi_error() {
+        let err = Uplink::from_ffi_error(ffi_error(ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND))
+            .expect("non NULL error");
+        assert_eq!(err.code(), UplinkCode::BucketNotFound, "code");
+        assert!(!err.message().is_empty(), "message");
+
+        let err = Uplink::from_ffi_error(ffi_error(0x99)).expect("non NULL error");
+        assert_eq!(err.code(), UplinkCode::Unknown(0x99), "unknown code");
+
+        assert!(
+            Uplink::from_ffi_error(std::ptr::null_mut()).is_none(),
+            "NULL error"
+        );
+    }
+
+    #[test]
+    fn test_from_ffi_error_frees_once() {
+        let freed = ffi_errors_freed();
+        let err = ffi_error(ulksys::UPLINK_ERROR_BUCKET_NOT_FOUND);
+        let _ = Uplink::new(err).expect("non NULL error");
+        assert_eq!(ffi_errors_freed(), freed, "new doesn't free the error");
+
+        let _ = Error::from_ffi_error(err).expect("non NULL error");
+        assert_eq!(
+            ffi_errors_freed(),
+            freed + 1,
+            "from_ffi_error frees the error"
+        );
+
+        let _ = Error::from_ffi_error(std::ptr::null_mut());
+        assert_eq!(ffi_errors_freed(), freed + 1, "NULL error isn't freed");
+    }
+
+    #[test]
+    fn test_uplink_display() {
+        let err = Uplink::with_code(UplinkCode::BucketNotFound, "my-bucket");
+        assert_eq!(
+            err.to_string(),
+            r#"code: "bucket not found" (0x13), details: "my-bucket""#,
+            "FFI code"
+        );
+
+        let err = Uplink::with_code(UplinkCode::Unknown(0x99), "eof");
+        assert_eq!(
+            err.to_string(),
+            r#"code: "unknown" (0x99), details: "eof""#,
+            "unknown code"
+        );
+        assert_eq!(
+            UplinkCode::Unknown(0x99).to_string(),
+            "unknown (0x99)",
+            "unknown code source"
+        );
+        assert_eq!(
+            UplinkCode::BucketNotFound.to_string(),
+            "bucket not found",
+            "known code source"
+        );
+
+        let err = Uplink::with_code(UplinkCode::ObjectAlreadyExists, "my-key");
+        assert_eq!(
+            err.to_string(),
+            r#"code: "object already exists", details: "my-key""#,
+            "code without FFI code"
+        );
+    }
+
+    #[test]
+    fn test_error_uplink_code() {
+        let err = Error::Uplink(Uplink::with_code(UplinkCode::ObjectNotFound, "my-key"));
+        assert!(
+            matches!(
+                err.uplink_code(),
+                Some(UplinkCode::BucketNotFound | UplinkCode::ObjectNotFound)
+            ),
+            "uplink error"
+        );
+        assert_eq!(
+            Error::new_invalid_arguments("arg", "invalid").uplink_code(),
+            None,
+            "other error"
+        );
+    }
+
+    #[test]
+    fn test_error_send_sync_static() {
+        fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+
+        assert_send_sync_static::<Error>();
+        assert_send_sync_static::<Internal>();
+        assert_send_sync_static::<Args>();
+        assert_send_sync_static::<Uplink>();
+        assert_send_sync_static::<ListError<String>>();
+    }
+
+    #[test]
+    fn test_error_source() {
+        let err = Error::from(Uplink::with_code(UplinkCode::BucketNotFound, "my-bucket"));
+        let source = stderr::Error::source(&err).expect("uplink error source");
+        assert_eq!(
+            source.downcast_ref::<UplinkCode>(),
+            Some(&UplinkCode::BucketNotFound),
+            "uplink error source is its code"
+        );
+        assert_eq!(source.to_string(), "bucket not found", "code display");
+
+        let err = Error::from(Args::new("arg", "invalid"));
+        assert!(
+            matches!(err, Error::InvalidArguments(_)),
+            "from arguments error"
+        );
+        assert!(
+            stderr::Error::source(&err).is_none(),
+            "invalid arguments error source"
+        );
+
+        let err = Error::from(Internal {
+            ctx_msg: String::from("ctx"),
+            inner: BoxError::from("inner"),
+        });
+        assert_eq!(
+            stderr::Error::source(&err).map(|e| e.to_string()),
+            Some(String::from("inner")),
+            "internal error source"
+        );
+
+        let err = Error::new_unsupported("project limits");
+        assert_eq!(
+            err.to_string(),
+            "project limits isn't supported by the bundled uplink-c version",
+            "unsupported error display"
+        );
+        assert!(
+            stderr::Error::source(&err).is_none(),
+            "unsupported error source"
+        );
+    }
+
+    #[test]
+    fn test_from_error_for_io_error() {
+        let cases = [
+            (
+                Error::new_invalid_arguments("arg", "invalid"),
+                io::ErrorKind::InvalidInput,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::BucketNotFound, "not found")),
+                io::ErrorKind::NotFound,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::ObjectNotFound, "not found")),
+                io::ErrorKind::NotFound,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::BucketAlreadyExists, "exists")),
+                io::ErrorKind::AlreadyExists,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::ObjectAlreadyExists, "exists")),
+                io::ErrorKind::AlreadyExists,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::PermissionDenied, "denied")),
+                io::ErrorKind::PermissionDenied,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::Canceled, "canceled")),
+                io::ErrorKind::TimedOut,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(
+                    UplinkCode::StorageLimitExceeded,
+                    "exceeded",
+                )),
+                io::ErrorKind::StorageFull,
+            ),
+            (
+                Error::Uplink(Uplink::with_code(UplinkCode::Internal, "internal")),
+                io::ErrorKind::Other,
+            ),
+            (
+                Error::new_internal("internal", BoxError::from("cause")),
+                io::ErrorKind::Other,
+            ),
+            (
+                Error::new_unsupported("capability"),
+                io::ErrorKind::Unsupported,
+            ),
+        ];
+
+        for (err, kind) in cases {
+            let msg = err.to_string();
+            let ioerr = io::Error::from(err);
+            assert_eq!(ioerr.kind(), kind, "kind for: {}", msg);
+            assert_eq!(ioerr.to_string(), msg, "message");
+        }
+    }
+
+    #[test]
+    fn test_error_from_io_error() {
+        let ioerr = io::Error::from(Error::Uplink(Uplink::with_code(
+            UplinkCode::BucketNotFound,
+            "not found",
+        )));
+        match Error::from_io_error(ioerr) {
+            Error::Uplink(err) if err.code() == UplinkCode::BucketNotFound => {
+                assert_eq!(err.message(), "not found", "message")
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let ioerr = io::Error::from(io::ErrorKind::UnexpectedEof);
+        match Error::from_io_error(ioerr) {
+            Error::Internal(_) => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_error_from_io_chain() {
+        {
+            // Payload.
+            let ioerr = io::Error::from(Error::Uplink(Uplink::with_code(
+                UplinkCode::ObjectNotFound,
+                "not found",
+            )));
+            match Error::from_io_chain(&ioerr) {
+                Some(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {
+                    assert_eq!(err.message(), "not found", "message")
+                }
+                other => panic!("unexpected value: {:?}", other),
+            }
+        }
+        {
+            // Source of the payload.
+            let payload = io::Error::from(Error::new_invalid_arguments("arg", "invalid"));
+            let ioerr = io::Error::other(payload);
+            match Error::from_io_chain(&ioerr) {
+                Some(Error::InvalidArguments(args)) => assert_eq!(args.names, "arg", "names"),
+                other => panic!("unexpected value: {:?}", other),
+            }
+        }
+        {
+            // No payload.
+            let ioerr = io::Error::from(io::ErrorKind::UnexpectedEof);
+            assert!(Error::from_io_chain(&ioerr).is_none(), "no payload");
+        }
+        {
+            // Payload of another type.
+            let ioerr = io::Error::other("other");
+            assert!(Error::from_io_chain(&ioerr).is_none(), "another payload");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Exports inventories of the objects of a bucket.
+//!
+//! [`write_inventory`] streams a recursive listing of a bucket into a writer as CSV or NDJSON,
+//! with a row for each object, without collecting the listing in memory.
+//!
+//! ```no_run
+//! use uplink::export::{self, InventoryFormat, InventoryOptions};
+//! use uplink::Project;
+//!
+//! use std::fs::File;
+//! use std::io::BufWriter;
+//!
+//! let project = Project::open_from_serialized("my-serialized-access-grant")?;
+//! let opts = InventoryOptions {
+//!     format: InventoryFormat::Csv,
+//!     metadata_keys: vec![String::from("content-type")],
+//! };
+//! let file = File::create("inventory.csv").expect("create the inventory file");
+//! let mut out = BufWriter::new(file);
+//! let exported = export::write_inventory(&project, "my-bucket", &opts, &mut out)?;
+//! println!("exported {} objects", exported);
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::project::options;
+use crate::{helpers, Error, Object, Project, Result};
+
+use std::io::{self, Write};
+
+/// The format of an inventory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InventoryFormat {
+    /// Comma-separated values (RFC 4180) with a header row and lines terminated by `\n`.
+    ///
+    /// The fields that contain commas, double quotes or line breaks are enclosed in double
+    /// quotes, doubling the double quotes that they contain, and the missing values are empty.
+    #[default]
+    Csv,
+    /// Newline-delimited JSON, with a JSON object for each object and without a header.
+    ///
+    /// The objects have the `key`, `size`, `created` and `expires` fields and the `metadata`
+    /// field, which is an object with the exported custom metadata keys. The missing values are
+    /// `null`.
+    Ndjson,
+}
+
+/// Options for exporting an inventory with [`write_inventory`].
+#[derive(Clone, Debug, Default)]
+pub struct InventoryOptions {
+    /// The format of the inventory.
+    pub format: InventoryFormat,
+    /// The keys of the custom metadata exported after the rest of the columns, in this order.
+    ///
+    /// The custom metadata isn't retrieved when it's empty.
+    pub metadata_keys: Vec<String>,
+}
+
+/// Writes the inventory of all the objects of `bucket` into `out` with the format of `opts` and
+/// returns the number of exported objects.
+///
+/// The objects are listed recursively with their system metadata, ordered by key, and they are
+/// written as they are listed, so the memory usage doesn't depend on the number of objects. The
+/// columns are the object's key, its size in bytes, its creation time and its expiration time,
+/// both as RFC 3339 UTC timestamps, followed by the custom metadata keys of `opts`. The size is
+/// missing when it's unknown and the expiration time when the object never expires.
+///
+/// `out` receives many small writes, so it should be buffered (e.g. [`io::BufWriter`]), and it
+/// isn't flushed.
+///
+/// It returns the same errors than [`Project::list_objects`] and the errors returned by writing
+/// into `out`, whose payload is returned back as [`Error::from_io_error`] documents. The objects
+/// exported before an error are already written.
+pub fn write_inventory(
+    project: &Project,
+    bucket: &str,
+    opts: &InventoryOptions,
+    out: &mut impl Write,
+) -> Result<u64> {
+    let list_opts = options::ListObjects::builder()
+        .recursive(true)
+        .system(true)
+        .custom(!opts.metadata_keys.is_empty())
+        .build()?;
+    let objects = project.list_objects(bucket, Some(&list_opts))?;
+
+    write_header(out, opts).map_err(Error::from_io_error)?;
+    let mut exported = 0;
+    for obj in objects {
+        write_object(out, opts, &obj?).map_err(Error::from_io_error)?;
+        exported += 1;
+    }
+
+    Ok(exported)
+}
+
+/// Writes the header of the inventory with the format of `opts` into `out`, if the format has one.
+fn write_header(out: &mut impl Write, opts: &InventoryOptions) -> io::Result<()> {
+    match opts.format {
+        InventoryFormat::Csv => {
+            let columns = ["key", "size", "created", "expires"]
+                .into_iter()
+                .chain(opts.metadata_keys.iter().map(String::as_str));
+            write_csv_row(out, columns.map(Some))
+        }
+        InventoryFormat::Ndjson => Ok(()),
+    }
+}
+
+/// Writes the row of `obj` with the format of `opts` into `out`.
+fn write_object(out: &mut impl Write, opts: &InventoryOptions, obj: &Object) -> io::Result<()> {
+    let system = &obj.metadata_system;
+    let size = system.content_length.map(|l| l.to_string());
+    let created = helpers::format_unix_time(system.created);
+    let expires = system.expires.map(helpers::format_unix_time);
+    let metadata = opts
+        .metadata_keys
+        .iter()
+        .map(|k| (k.as_str(), obj.metadata_custom.get(k).map(String::as_str)));
+
+    match opts.format {
+        InventoryFormat::Csv => {
+            let fields = [
+                Some(obj.key.as_str()),
+                size.as_deref(),
+                Some(created.as_str()),
+                expires.as_deref(),
+            ];
+            write_csv_row(out, fields.into_iter().chain(metadata.map(|(_, v)| v)))
+        }
+        InventoryFormat::Ndjson => {
+            out.write_all(b"{\"key\":")?;
+            write_json_string(out, &obj.key)?;
+            out.write_all(b",\"size\":")?;
+            out.write_all(size.as_deref().unwrap_or("null").as_bytes())?;
+            out.write_all(b",\"created\":")?;
+            write_json_string(out, &created)?;
+            out.write_all(b",\"expires\":")?;
+            write_json_value(out, expires.as_deref())?;
+            out.write_all(b",\"metadata\":{")?;
+            for (i, (key, value)) in metadata.enumerate() {
+                if i > 0 {
+                    out.write_all(b",")?;
+                }
+                write_json_string(out, key)?;
+                out.write_all(b":")?;
+                write_json_value(out, value)?;
+            }
+            out.write_all(b"}}\n")
+        }
+    }
+}
+
+/// Writes a CSV row with `fields` into `out`, writing the `None` ones as empty fields.
+fn write_csv_row<'a>(
+    out: &mut impl Write,
+    fields: impl Iterator<Item = Option<&'a str>>,
+) -> io::Result<()> {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+
+        let field = field.unwrap_or_default();
+        if field.contains([',', '"', '\r', '\n']) {
+            out.write_all(b"\"")?;
+            out.write_all(field.replace('"', "\"\"").as_bytes())?;
+            out.write_all(b"\"")?;
+        } else {
+            out.write_all(field.as_bytes())?;
+        }
+    }
+
+    out.write_all(b"\n")
+}
+
+/// Writes `value` as a JSON string into `out` or `null` if it's `None`.
+fn write_json_value(out: &mut impl Write, value: Option<&str>) -> io::Result<()> {
+    match value {
+        Some(v) => write_json_string(out, v),
+        None => out.write_all(b"null"),
+    }
+}
+
+/// Writes `value` as a JSON string into `out`, escaping the double quotes, the backslashes and the
+/// control characters.
+fn write_json_string(out: &mut impl Write, value: &str) -> io::Result<()> {
+    out.write_all(b"\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_all(b"\\\"")?,
+            '\\' => out.write_all(b"\\\\")?,
+            '\n' => out.write_all(b"\\n")?,
+            '\r' => out.write_all(b"\\r")?,
+            '\t' => out.write_all(b"\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_all(c.encode_utf8(&mut [0; 4]).as_bytes())?,
+        }
+    }
+    out.write_all(b"\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metadata;
+
+    use std::time::Duration;
+
+    /// Returns the objects of the inventory tests, which have keys that need escaping.
+    fn objects() -> Vec<Object> {
+        let mut custom = metadata::Custom::default();
+        custom.insert(String::from("content-type"), String::from("text/csv"));
+        custom.insert(String::from("owner"), String::from("ops, \"infra\""));
+
+        vec![
+            Object {
+                key: String::from("reports/2024,Q1 \"final\".csv"),
+                is_prefix: false,
+                metadata_system: metadata::System {
+                    created: Duration::from_secs(1_714_564_800),
+                    expires: Some(Duration::from_secs(1_717_243_200)),
+                    content_length: Some(1_024),
+                },
+                metadata_custom: custom,
+                has_system: true,
+                has_custom: true,
+            },
+            Object {
+                key: String::from("plain.txt\n\\"),
+                is_prefix: false,
+                metadata_system: metadata::System {
+                    created: Duration::from_secs(86_400),
+                    expires: None,
+                    content_length: None,
+                },
+                metadata_custom: metadata::Custom::default(),
+                has_system: true,
+                has_custom: true,
+            },
+        ]
+    }
+
+    /// Returns the inventory of [`objects`] exported with `opts`.
+    fn export(opts: &InventoryOptions) -> String {
+        let mut out = Vec::new();
+        write_header(&mut out, opts).expect("write header");
+        for obj in objects() {
+            write_object(&mut out, opts, &obj).expect("write object");
+        }
+        String::from_utf8(out).expect("UTF-8 inventory")
+    }
+
+    #[test]
+    fn test_write_inventory_csv() {
+        let opts = InventoryOptions {
+            format: InventoryFormat::Csv,
+            metadata_keys: vec![String::from("owner"), String::from("content-type")],
+        };
+        assert_eq!(
+            export(&opts),
+            concat!(
+                "key,size,created,expires,owner,content-type\n",
+                "\"reports/2024,Q1 \"\"final\"\".csv\",1024,2024-05-01T12:00:00Z,",
+                "2024-06-01T12:00:00Z,\"ops, \"\"infra\"\"\",text/csv\n",
+                "\"plain.txt\n\\\",,1970-01-02T00:00:00Z,,,\n",
+            ),
+        );
+
+        assert_eq!(
+            export(&InventoryOptions::default()),
+            concat!(
+                "key,size,created,expires\n",
+                "\"reports/2024,Q1 \"\"final\"\".csv\",1024,2024-05-01T12:00:00Z,",
+                "2024-06-01T12:00:00Z\n",
+                "\"plain.txt\n\\\",,1970-01-02T00:00:00Z,\n",
+            ),
+            "without metadata keys"
+        );
+    }
+
+    #[test]
+    fn test_write_inventory_ndjson() {
+        let opts = InventoryOptions {
+            format: InventoryFormat::Ndjson,
+            metadata_keys: vec![String::from("owner"), String::from("content-type")],
+        };
+        let inventory = export(&opts);
+        assert_eq!(
+            inventory,
+            concat!(
+                r#"{"key":"reports/2024,Q1 \"final\".csv","size":1024,"#,
+                r#""created":"2024-05-01T12:00:00Z","expires":"2024-06-01T12:00:00Z","#,
+                r#""metadata":{"owner":"ops, \"infra\"","content-type":"text/csv"}}"#,
+                "\n",
+                r#"{"key":"plain.txt\n\\","size":null,"created":"1970-01-02T00:00:00Z","#,
+                r#""expires":null,"metadata":{"owner":null,"content-type":null}}"#,
+                "\n",
+            ),
+        );
+
+        for (line, obj) in inventory.lines().zip(objects()) {
+            let json: serde_json::Value = serde_json::from_str(line).expect("valid JSON line");
+            assert_eq!(json["key"], obj.key.as_str(), "parsed key");
+        }
+    }
+}
@@ -1,11 +1,12 @@
 //! Helper functions which are used across the modules of this crate.
 
+use crate::error::BoxError;
 use crate::Error;
 
 use std::ffi::CString;
+use std::fmt::Write;
 use std::os::raw::c_char;
-
-use uplink_sys as ulksys;
+use std::time::{Duration, SystemTime};
 
 /// creates a CString from a function &str function argument and if there is an
 /// error it returns an Error::InvalidArguments with the passed argument's
@@ -22,43 +23,135 @@ pub fn cstring_from_str_fn_arg(arg_name: &str, arg_val: &str) -> Result<CString,
     })
 }
 
-/// Create a String from a C string of the specified length.
+/// Copies the bytes of a C string of the specified length.
 ///
 /// The function is unsafe because:
 /// * It doesn't check for the end NULL byte as it doesn't stop if a NULL byte is before the end of
 ///   the string.
-/// * It doesn't check the characters to be UTF-8 valid, if the string contains
-///   invalid UTF-8 bytes. Hence the resulting `String` would have non-deterministic character
-///   value on their position.
 /// * It will read all the bytes of memory region from pointer to length, so if length is larger
 ///   than the region, some garbage bytes will be read or a runtime panic may happen.
-pub unsafe fn unchecked_ptr_c_char_and_length_to_string(
+pub unsafe fn unchecked_ptr_c_char_and_length_to_bytes(
     c_chars: *const c_char,
     length: usize,
-) -> String {
-    let mut chars = String::with_capacity(length);
+) -> Vec<u8> {
+    if length == 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(c_chars as *const u8, length).to_vec()
+}
+
+/// Returns the current system time measured with the duration since the Unix Epoch time.
+///
+/// It returns an [`Error::Internal`] if the system time is before the Unix Epoch.
+pub fn unix_time_now() -> Result<Duration, Error> {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|err| Error::new_internal("system time before Unix Epoch", BoxError::from(err)))
+}
 
-    for i in 0..length as isize {
-        chars.push(*c_chars.offset(i) as u8 as char)
+/// Returns the lowercase hexadecimal representation of `data`.
+pub fn hex(data: &[u8]) -> String {
+    data.iter()
+        .fold(String::with_capacity(data.len() * 2), |mut s, b| {
+            // Writing to a String never fails.
+            let _ = write!(s, "{:02x}", b);
+            s
+        })
+}
+
+/// Returns `res`, calling `undo` before if it's an error for undoing what the failed operation
+/// left behind (e.g. aborting an upload or removing a partially written file).
+///
+/// The error returned by `undo` is discarded because the original error is the relevant one.
+pub fn undo_on_error<T, E>(
+    res: Result<T, Error>,
+    undo: impl FnOnce() -> Result<(), E>,
+) -> Result<T, Error> {
+    if res.is_err() {
+        let _ = undo();
     }
 
-    chars
+    res
 }
 
-/// Calls, only if `error` is not null, the associated `free` FFI function for releasing the
-/// associated resources with `error` and to free the memory pointed by it.
-pub fn drop_uplink_sys_error(error: *mut ulksys::UplinkError) {
-    if !error.is_null() {
-        // SAFETY: We just checked that the pointer is not null and we trust that the FFI is safe
-        // freeing its associated resources and itself.
-        unsafe {
-            ulksys::uplink_free_error(error);
-        }
+/// Formats a time, measured with the duration since the Unix Epoch time, as an RFC 3339 UTC
+/// timestamp (e.g. `2024-05-01T12:00:00Z`).
+///
+/// The fractional seconds are only formatted when the nanoseconds aren't zero.
+pub fn format_unix_time(since_epoch: Duration) -> String {
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Converts the days since the Unix Epoch to a date of the proleptic Gregorian calendar
+    // (http://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    let mut formatted = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    );
+    if since_epoch.subsec_nanos() != 0 {
+        formatted.push_str(&format!(".{:09}", since_epoch.subsec_nanos()));
     }
+    formatted.push('Z');
+    formatted
+}
+
+/// Converts a time, measured with the duration since the Unix Epoch time, to a UTC date time.
+///
+/// Times beyond the maximum date time supported by `chrono` are clamped to it.
+#[cfg(feature = "chrono")]
+pub fn datetime_from_unix_time(since_epoch: Duration) -> chrono::DateTime<chrono::Utc> {
+    i64::try_from(since_epoch.as_secs())
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, since_epoch.subsec_nanos()))
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+}
+
+/// Converts a UTC date time to the duration since the Unix Epoch time.
+///
+/// It returns `None` if the date time is before the Unix Epoch time.
+#[cfg(feature = "chrono")]
+pub fn unix_time_from_datetime(datetime: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let secs = u64::try_from(datetime.timestamp()).ok()?;
+    Some(Duration::new(secs, datetime.timestamp_subsec_nanos()))
 }
 
 #[cfg(test)]
 pub(crate) mod test {
+    /// Returns an error allocated by the FFI with the specified `code`, so it can be used for
+    /// building FFI results in tests which are freed through the FFI.
+    ///
+    /// It gets the error from parsing an invalid serialized access grant, which doesn't require
+    /// any network connection, and replaces its code.
+    pub(crate) fn ffi_error(code: u32) -> *mut ulksys::UplinkError {
+        let saccess = CString::new("invalid").unwrap();
+        // SAFETY: we pass a valid C string and the FFI always returns an error for an invalid
+        // serialized access grant, so the access field is `NULL` and we only have to take care of
+        // the error, whose ownership is transferred to the caller.
+        unsafe {
+            let res = ulksys::uplink_parse_access(saccess.as_ptr() as *mut c_char);
+            assert!(res.access.is_null(), "parsing an invalid access grant");
+            assert!(!res.error.is_null(), "parsing an invalid access grant");
+            (*res.error).code = code as i32;
+            res.error
+        }
+    }
+
     /// Asserts that a C string has the same value than the passed `&str`.
     /// It internally uses `compare_c_string`, panicking when it returns `Some`.
     /// Read its docs for the implications of this function.
@@ -142,6 +235,8 @@ pub(crate) mod test {
     use super::*;
     use std::ffi::CStr;
 
+    use uplink_sys as ulksys;
+
     #[test]
     fn test_cstring_from_str_fn_arg() {
         let val = cstring_from_str_fn_arg("some", "this is fine")
@@ -169,7 +264,7 @@ pub(crate) mod test {
     }
 
     #[test]
-    fn test_unchecked_ptr_c_char_and_length_to_string() {
+    fn test_unchecked_ptr_c_char_and_length_to_bytes() {
         // SAFETY: The function under test is unsafe so everything is wrapped inside of unsafe
         // because there is a minimal logic for each test case.
         unsafe {
@@ -179,9 +274,9 @@ pub(crate) mod test {
                 let cstr = CStr::from_bytes_with_nul_unchecked(expected.as_bytes());
                 let chars = cstr.as_ptr();
                 assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()),
-                    expected,
-                    "str value doesn't match"
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, expected.len()),
+                    expected.as_bytes(),
+                    "bytes don't match"
                 );
             }
             {
@@ -191,34 +286,21 @@ pub(crate) mod test {
                 let chars = cstr.as_ptr();
 
                 assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()).as_ref(),
-                    expected,
-                    "str value doesn't match"
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, expected.len()),
+                    expected.as_bytes(),
+                    "bytes don't match"
                 );
             }
             {
-                // Case: Exact length and with interior NULL chars.
-                let expected = String::from("Storj Uplink\0 Ru\0st");
-                let cstr = CStr::from_bytes_with_nul_unchecked(expected.as_bytes());
-                let chars = cstr.as_ptr();
-
-                assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()).as_ref(),
-                    expected,
-                    "str value doesn't match"
-                );
-            }
-            {
-                // Case: Exact length and with interior and interior and terminated
-                // NULL chars.
+                // Case: Exact length and with interior and terminated NULL chars.
                 let expected = String::from("Storj Uplink\0 Ru\0st\0");
                 let cstr = CStr::from_bytes_with_nul_unchecked(expected.as_bytes());
                 let chars = cstr.as_ptr();
 
                 assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()).as_ref(),
-                    expected,
-                    "str value doesn't match"
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, expected.len()),
+                    expected.as_bytes(),
+                    "bytes don't match"
                 );
             }
             {
@@ -226,46 +308,146 @@ pub(crate) mod test {
                 let passed = String::from("Storj Uplink Rust");
                 let cstr = CStr::from_bytes_with_nul_unchecked(passed.as_bytes());
                 let chars = cstr.as_ptr();
-                let mut expected = passed.clone();
-                expected.truncate(passed.len() - 1);
 
                 assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, passed.len() - 1),
-                    expected,
-                    "str value doesn't match"
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, passed.len() - 1),
+                    &passed.as_bytes()[..passed.len() - 1],
+                    "bytes don't match"
                 );
             }
             {
-                // Case: Larger length.
-                let expected = String::from("Storj Uplink Rust OUT");
+                // Case: Multi-byte UTF-8 characters.
+                let expected = String::from("Storj Uplink \u{FFFD} Rüst");
                 let cstr = CStr::from_bytes_with_nul_unchecked(expected.as_bytes());
                 let chars = cstr.as_ptr();
 
-                // Because the pointer points to `expected` despite that chars only pointed to the
-                // firsts 17 characters, the function is receiving a greater length
-                // value that it should be so it reads the continuous memory.
                 assert_eq!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()),
-                    expected,
-                    "str value doesn't match"
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, expected.len()),
+                    expected.as_bytes(),
+                    "bytes don't match"
                 );
             }
             {
-                // Case: Invalid UTF-8
-                let expected = String::from("Storj Uplink \u{FFFD} Rust");
-                let cstr = CStr::from_bytes_with_nul_unchecked(expected.as_bytes());
-                let chars = cstr.as_ptr();
+                // Case: Invalid UTF-8.
+                let passed = b"Storj Uplink \xFF\xFE Rust";
+                let chars = passed.as_ptr() as *const c_char;
 
-                // The values aren't equal because non valid UTF-8 bytes produce a
-                // non-deterministic output.
-                assert_ne!(
-                    unchecked_ptr_c_char_and_length_to_string(chars, expected.len()),
-                    expected,
+                assert_eq!(
+                    unchecked_ptr_c_char_and_length_to_bytes(chars, passed.len()),
+                    passed.to_vec(),
+                    "bytes don't match"
+                );
+            }
+            {
+                // Case: Zero length with a NULL pointer.
+                assert!(
+                    unchecked_ptr_c_char_and_length_to_bytes(std::ptr::null(), 0).is_empty(),
+                    "bytes aren't empty"
                 );
             }
         }
     }
 
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex(&[]), "", "empty");
+        assert_eq!(hex(&[0x00, 0x0f, 0xab, 0xff]), "000fabff", "bytes");
+    }
+
+    #[test]
+    fn test_undo_on_error() {
+        let mut undone = false;
+        let res = undo_on_error(Ok(1), || -> Result<(), Error> {
+            undone = true;
+            Ok(())
+        });
+        assert_eq!(res.ok(), Some(1), "success");
+        assert!(!undone, "undo on success");
+
+        let res: Result<(), Error> = undo_on_error(
+            Err(Error::new_invalid_arguments("arg", "invalid")),
+            || -> Result<(), Error> {
+                undone = true;
+                Err(Error::new_internal("undo", BoxError::from("failed")))
+            },
+        );
+        assert!(undone, "undo on error");
+        match res {
+            Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "arg", "original error"),
+            _ => panic!("expected the original error"),
+        }
+    }
+
+    #[test]
+    fn test_format_unix_time() {
+        for (since_epoch, want) in [
+            (Duration::ZERO, "1970-01-01T00:00:00Z"),
+            (Duration::from_secs(1_714_564_800), "2024-05-01T12:00:00Z"),
+            (Duration::from_secs(951_825_599), "2000-02-29T11:59:59Z"),
+            (Duration::from_secs(4_107_542_399), "2100-02-28T23:59:59Z"),
+            (
+                Duration::new(1_700_000_000, 5),
+                "2023-11-14T22:13:20.000000005Z",
+            ),
+        ] {
+            assert_eq!(format_unix_time(since_epoch), want, "{:?}", since_epoch);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_datetime_from_unix_time() {
+        use chrono::{DateTime, Utc};
+
+        assert_eq!(
+            datetime_from_unix_time(Duration::ZERO),
+            DateTime::from_timestamp(0, 0).unwrap(),
+            "epoch"
+        );
+        assert_eq!(
+            datetime_from_unix_time(Duration::new(1_714_564_800, 5)).to_rfc3339(),
+            "2024-05-01T12:00:00.000000005+00:00",
+            "regular time"
+        );
+        assert_eq!(
+            datetime_from_unix_time(Duration::from_secs(i64::MAX as u64)),
+            DateTime::<Utc>::MAX_UTC,
+            "clamped to the maximum"
+        );
+        assert_eq!(
+            datetime_from_unix_time(Duration::MAX),
+            DateTime::<Utc>::MAX_UTC,
+            "clamped beyond i64"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_unix_time_from_datetime() {
+        use chrono::{DateTime, Utc};
+
+        assert_eq!(
+            unix_time_from_datetime(DateTime::from_timestamp(0, 0).unwrap()),
+            Some(Duration::ZERO),
+            "epoch"
+        );
+        assert_eq!(
+            unix_time_from_datetime(DateTime::from_timestamp(1_714_564_800, 5).unwrap()),
+            Some(Duration::new(1_714_564_800, 5)),
+            "regular time"
+        );
+        assert_eq!(
+            unix_time_from_datetime(DateTime::from_timestamp(-1, 0).unwrap()),
+            None,
+            "before the epoch"
+        );
+        assert_eq!(
+            unix_time_from_datetime(DateTime::<Utc>::MIN_UTC),
+            None,
+            "minimum"
+        );
+    }
+
     #[test]
     fn test_assert_c_string() {
         {
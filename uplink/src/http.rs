@@ -0,0 +1,469 @@
+//! Objects' data served through HTTP.
+//!
+//! [`DownloadBody`] streams a [`Download`] as a response body of the HTTP frameworks built on
+//! [`http_body::Body`] (e.g. axum and hyper), reading the download in a blocking task of the Tokio
+//! runtime that polls the body. [`DownloadBody::from_object`] downloads the range of an object
+//! requested by an HTTP `Range` header.
+//!
+//! ```no_run
+//! use uplink::access::Grant;
+//! use uplink::http::DownloadBody;
+//! use uplink::Project;
+//!
+//! let project = Project::open(&Grant::new("my-serialized-access-grant")?);
+//! let (body, range) = DownloadBody::from_object(
+//!     &project,
+//!     "my-bucket",
+//!     "videos/intro.mp4",
+//!     Some("bytes=0-1023"),
+//!     64 * 1024,
+//! )?;
+//!
+//! // Respond with `206 Partial Content` and the `Content-Range` header when it's a range.
+//! if let Some(range) = range {
+//!     println!("Content-Range: {}", range.content_range());
+//! }
+//! # drop(body);
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::error::BoxError;
+use crate::object::Download;
+use crate::project::options;
+use crate::{Error, Project, Result};
+
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http_body::{Body, Frame, SizeHint};
+use tokio::sync::mpsc;
+
+/// The number of chunks that the blocking task reads ahead of the ones yielded by the body.
+const CHANNEL_DEPTH: usize = 2;
+
+/// A [`Download`] as an [`http_body::Body`], see the [module documentation](self).
+///
+/// Its size hint is exact, so the HTTP frameworks set the `Content-Length` header of the response
+/// from it, unless the object's length is unknown. The errors of the download are yielded as the body's errors, which finish the body.
+pub struct DownloadBody {
+    /// The state of the reading of the download.
+    state: State,
+    /// The number of bytes that the body still has to yield. `None` when it's unknown.
+    remaining: Option<u64>,
+}
+
+/// The state of the reading of a [`DownloadBody`].
+enum State {
+    /// The reading hasn't started because the body hasn't been polled yet, so it may be created
+    /// outside of a Tokio runtime.
+    Pending {
+        /// The reader of the data.
+        reader: Box<dyn Read + Send>,
+        /// The maximum size of the chunks.
+        chunk_size: usize,
+    },
+    /// The blocking task reads the data and sends the chunks through the channel.
+    Reading(mpsc::Receiver<io::Result<Bytes>>),
+    /// The body has finished, successfully or with an error.
+    Finished,
+}
+
+impl DownloadBody {
+    /// Creates a body that yields the data of `download` in chunks of up to `chunk_size` bytes.
+    ///
+    /// `download` must download the whole object because the size hint is the object's content
+    /// length; use [`Self::from_object`] for downloading a range.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `chunk_size` is zero and the same errors than
+    /// [`Download::info`].
+    pub fn new(download: Download, chunk_size: usize) -> Result<Self> {
+        let content_length = download.info()?.metadata_system.content_length;
+        Self::with_reader(download, chunk_size, content_length)
+    }
+
+    /// Downloads the object with `key` in `bucket` of `project` as a body that yields its data in
+    /// chunks of up to `chunk_size` bytes.
+    ///
+    /// When `range` is `Some`, it's the value of an HTTP `Range` header, and only the requested
+    /// range is downloaded, which is returned for responding with `206 Partial Content` and
+    /// [`ByteRange::content_range`].
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `chunk_size` is zero or `range` isn't
+    /// satisfiable (see [`ByteRange::parse`]) or the object's length is unknown, which should be responded with
+    /// `416 Range Not Satisfiable`, and the same errors than [`Project::stat_object`] and
+    /// [`Project::download_object`].
+    pub fn from_object(
+        project: &Project,
+        bucket: &str,
+        key: impl AsRef<str>,
+        range: Option<&str>,
+        chunk_size: usize,
+    ) -> Result<(Self, Option<ByteRange>)> {
+        let key = key.as_ref();
+        if chunk_size == 0 {
+            return Err(Error::new_invalid_arguments("chunk_size", "cannot be zero"));
+        }
+
+        let header = match range {
+            None => {
+                let download = project.download_object(bucket, key, None)?;
+                return Ok((Self::new(download, chunk_size)?, None));
+            }
+            Some(h) => h,
+        };
+
+        let size = project
+            .stat_object(bucket, key)?
+            .metadata_system
+            .content_length
+            .ok_or_else(|| {
+                Error::new_invalid_arguments(
+                    "range",
+                    "isn't satisfiable because the object's length is unknown",
+                )
+            })?;
+        let range = ByteRange::parse(header, size)?;
+        let length = i64::try_from(range.len())
+            .map_err(|err| Error::new_internal("range length overflow", BoxError::from(err)))?;
+        let opts = options::Download::new(range.start as i64, length);
+        let download = project.download_object(bucket, key, Some(&opts))?;
+
+        Ok((
+            Self::with_reader(download, chunk_size, Some(range.len()))?,
+            Some(range),
+        ))
+    }
+
+    /// Creates a body that yields the `length` bytes of `reader`, which is `None` when it's
+    /// unknown, in chunks of up to `chunk_size` bytes.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `chunk_size` is zero.
+    fn with_reader(
+        reader: impl Read + Send + 'static,
+        chunk_size: usize,
+        length: Option<u64>,
+    ) -> Result<Self> {
+        if chunk_size == 0 {
+            return Err(Error::new_invalid_arguments("chunk_size", "cannot be zero"));
+        }
+
+        Ok(Self {
+            state: State::Pending {
+                reader: Box::new(reader),
+                chunk_size,
+            },
+            remaining: length,
+        })
+    }
+}
+
+impl Body for DownloadBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>>>> {
+        let this = self.get_mut();
+        this.state = match std::mem::replace(&mut this.state, State::Finished) {
+            State::Pending { reader, chunk_size } => {
+                State::Reading(read_blocking(reader, chunk_size))
+            }
+            state => state,
+        };
+
+        let rx = match &mut this.state {
+            State::Reading(rx) => rx,
+            _ => return Poll::Ready(None),
+        };
+
+        match rx.poll_recv(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Some(Ok(chunk))) => {
+                this.remaining = this.remaining.map(|r| r.saturating_sub(chunk.len() as u64));
+                Poll::Ready(Some(Ok(Frame::data(chunk))))
+            }
+            Poll::Ready(Some(Err(err))) => {
+                this.state = State::Finished;
+                Poll::Ready(Some(Err(Error::from_io_error(err))))
+            }
+            Poll::Ready(None) => {
+                this.state = State::Finished;
+                Poll::Ready(None)
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        matches!(self.state, State::Finished)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.remaining
+            .map_or_else(SizeHint::default, SizeHint::with_exact)
+    }
+}
+
+impl std::fmt::Debug for DownloadBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DownloadBody")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads `reader` in a blocking task, sending its chunks of up to `chunk_size` bytes through the
+/// returned channel, which is closed when `reader` ends or after sending its error.
+///
+/// It panics if it isn't called from a Tokio runtime.
+fn read_blocking(
+    mut reader: Box<dyn Read + Send>,
+    chunk_size: usize,
+) -> mpsc::Receiver<io::Result<Bytes>> {
+    let (tx, rx) = mpsc::channel(CHANNEL_DEPTH);
+    // The task isn't awaited; it finishes when `reader` ends or the body drops the receiver.
+    drop(tokio::task::spawn_blocking(move || loop {
+        let mut chunk = vec![0; chunk_size];
+        match reader.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(n) => {
+                chunk.truncate(n);
+                // Sending only fails when the receiver is dropped because the body is dropped.
+                if tx.blocking_send(Ok(Bytes::from(chunk))).is_err() {
+                    return;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                let _ = tx.blocking_send(Err(err));
+                return;
+            }
+        }
+    }));
+
+    rx
+}
+
+/// A range of bytes of an object requested by an HTTP `Range` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByteRange {
+    /// The position of the first byte of the range.
+    pub start: u64,
+    /// The position of the last byte of the range, which is inclusive as in the HTTP headers.
+    pub end: u64,
+    /// The size of the object.
+    pub size: u64,
+}
+
+impl ByteRange {
+    /// Parses the value of an HTTP `Range` header with a single range of bytes (e.g.
+    /// `bytes=0-499`, `bytes=500-` or `bytes=-500`) of an object of `size` bytes.
+    ///
+    /// The end of the range is truncated to the end of the object as RFC 9110 specifies.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `header` isn't a single range of bytes, which
+    /// includes the ranges of other units and multiple ranges because they aren't supported, or
+    /// the range isn't satisfiable (i.e. it starts after the end of the object or it's an empty
+    /// suffix).
+    pub fn parse(header: &str, size: u64) -> Result<Self> {
+        let spec = header
+            .trim()
+            .strip_prefix("bytes=")
+            .ok_or_else(|| Error::new_invalid_arguments("range", "must be a range of bytes"))?;
+        if spec.contains(',') {
+            return Err(Error::new_invalid_arguments(
+                "range",
+                "cannot have multiple ranges because they aren't supported",
+            ));
+        }
+
+        let (first, last) = spec
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| Error::new_invalid_arguments("range", "must have a '-' separator"))?;
+        let position = |value: &str| {
+            value.parse::<u64>().map_err(|_| {
+                Error::new_invalid_arguments(
+                    "range",
+                    &format!("{:?} isn't a valid byte position", value),
+                )
+            })
+        };
+
+        let (start, end) = match (first.trim(), last.trim()) {
+            ("", "") => {
+                return Err(Error::new_invalid_arguments(
+                    "range",
+                    "must have a first byte position or a suffix length",
+                ))
+            }
+            ("", suffix) => {
+                let suffix = position(suffix)?;
+                if suffix == 0 || size == 0 {
+                    return Err(Error::new_invalid_arguments(
+                        "range",
+                        "isn't satisfiable because the suffix is empty",
+                    ));
+                }
+                (size.saturating_sub(suffix), size - 1)
+            }
+            (first, "") => (position(first)?, size.saturating_sub(1)),
+            (first, last) => {
+                let (first, last) = (position(first)?, position(last)?);
+                if last < first {
+                    return Err(Error::new_invalid_arguments(
+                        "range",
+                        "the last byte position cannot be less than the first one",
+                    ));
+                }
+                (first, last.min(size.saturating_sub(1)))
+            }
+        };
+
+        if start >= size {
+            return Err(Error::new_invalid_arguments(
+                "range",
+                &format!(
+                    "isn't satisfiable because it starts at {} and the object's size is {}",
+                    start, size
+                ),
+            ));
+        }
+
+        Ok(Self { start, end, size })
+    }
+
+    /// Returns the number of bytes of the range.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Returns the value of the HTTP `Content-Range` header of the response (e.g.
+    /// `bytes 0-499/1234`).
+    pub fn content_range(&self) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, self.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::{self, UplinkCode};
+
+    /// Collects the frames of `body`, returning the data and the error that finished it, if any.
+    fn collect(mut body: DownloadBody) -> (Vec<u8>, Option<Error>) {
+        tokio_test::block_on(async {
+            let mut data = Vec::new();
+            while let Some(frame) =
+                std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await
+            {
+                match frame {
+                    Ok(frame) => data.extend_from_slice(
+                        &frame.into_data().expect("the body only yields data frames"),
+                    ),
+                    Err(err) => return (data, Some(err)),
+                }
+            }
+
+            assert!(body.is_end_stream(), "end of stream");
+            (data, None)
+        })
+    }
+
+    #[test]
+    fn test_download_body() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let body = DownloadBody::with_reader(io::Cursor::new(data.clone()), 1024, Some(10_000))
+            .expect("body");
+        assert_eq!(body.size_hint().exact(), Some(10_000), "size hint");
+        assert!(!body.is_end_stream(), "end of stream before polling");
+
+        let (collected, err) = collect(body);
+        assert!(err.is_none(), "unexpected error: {:?}", err);
+        assert_eq!(collected, data, "data");
+    }
+
+    #[test]
+    fn test_download_body_reader_error() {
+        let reader = io::Cursor::new(vec![7; 100]).chain(FailingReader);
+        let body = DownloadBody::with_reader(reader, 64, None).expect("body");
+        assert_eq!(body.size_hint().exact(), None, "unknown size hint");
+
+        match collect(body) {
+            (data, Some(Error::Uplink(err))) => {
+                assert_eq!(err.code(), UplinkCode::Internal, "error code");
+                assert_eq!(data, vec![7; 100], "data before the error");
+            }
+            (_, err) => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_download_body_invalid_chunk_size() {
+        match DownloadBody::with_reader(io::empty(), 0, Some(0)) {
+            Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                assert_eq!(names, "chunk_size", "invalid argument name")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_byte_range_parse() {
+        for (header, want) in [
+            ("bytes=0-499", (0, 499)),
+            ("bytes=500-999", (500, 999)),
+            ("bytes=500-", (500, 999)),
+            ("bytes=-300", (700, 999)),
+            ("bytes=-5000", (0, 999)),
+            ("bytes=900-5000", (900, 999)),
+            (" bytes= 0 - 0 ", (0, 0)),
+        ] {
+            let range = ByteRange::parse(header, 1000).expect(header);
+            assert_eq!((range.start, range.end), want, "{}", header);
+            assert_eq!(range.len(), want.1 - want.0 + 1, "{}: length", header);
+            assert_eq!(
+                range.content_range(),
+                format!("bytes {}-{}/1000", want.0, want.1),
+                "{}: content range",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_byte_range_parse_invalid() {
+        for (header, size) in [
+            ("items=0-1", 1000),
+            ("bytes=0-1,5-6", 1000),
+            ("bytes=10", 1000),
+            ("bytes=-", 1000),
+            ("bytes=a-1", 1000),
+            ("bytes=5-1", 1000),
+            ("bytes=1000-", 1000),
+            ("bytes=-0", 1000),
+            ("bytes=-10", 0),
+            ("bytes=0-", 0),
+        ] {
+            match ByteRange::parse(header, size) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "range", "{}: invalid argument name", header)
+                }
+                res => panic!("{} (size {}): unexpected result: {:?}", header, size, res),
+            }
+        }
+    }
+
+    /// Reader that always fails with an error of this crate.
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(Error::Uplink(error::Uplink::with_code(UplinkCode::Internal, "reader")).into())
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! I/O utilities for the types of this crate that implement the standard I/O traits.
+
+use crate::error::BoxError;
+use crate::object::upload::PartUpload;
+use crate::object::Upload;
+use crate::{Error, Result};
+
+use std::any::Any;
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Wraps a writer, usually an [`Upload`] or a [`PartUpload`], reporting the progress of the
+/// written bytes through a callback.
+///
+/// The callback receives the cumulative number of written bytes after each successful write to
+/// the wrapped writer, it's never called when a write fails. [`Self::commit`] calls it one last
+/// time with the total number of written bytes after committing successfully.
+///
+/// A panic in the callback doesn't propagate because the bytes are already written when it's
+/// called. The write that called the callback returns the number of written bytes as usual and
+/// the next writes and [`Self::commit`] return an error, so the upload can be aborted through
+/// [`Self::get_mut`]. The callback isn't called anymore after panicking. Nonetheless, the panic
+/// hook is invoked as for any other panic, so the default one prints the panic message.
+///
+/// ```no_run
+/// use std::io::Write;
+///
+/// use uplink::access::Grant;
+/// use uplink::io_util::ProgressWrite;
+/// use uplink::Project;
+///
+/// let grant = Grant::new("my-serialized-access-grant")?;
+/// let project = Project::open(&grant);
+/// let upload = project.upload_object("my-bucket", "hello.txt", None)?;
+///
+/// let mut upload = ProgressWrite::new(upload, |written| println!("uploaded {written} bytes"));
+/// upload.write_all(b"Hello world!")?;
+/// upload.commit()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ProgressWrite<W, F> {
+    /// The wrapped writer.
+    inner: W,
+    /// The callback that receives the cumulative number of written bytes.
+    callback: F,
+    /// The cumulative number of written bytes.
+    written: u64,
+    /// The message of the panic of the callback if it has panicked.
+    callback_panic: Option<String>,
+}
+
+impl<W, F> ProgressWrite<W, F>
+where
+    W: Write,
+    F: FnMut(u64) + Send,
+{
+    /// Creates a new instance that wraps `inner` and reports the progress to `callback`.
+    pub fn new(inner: W, callback: F) -> Self {
+        Self {
+            inner,
+            callback,
+            written: 0,
+            callback_panic: None,
+        }
+    }
+
+    /// Returns the cumulative number of written bytes.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// The bytes written directly to the wrapped writer aren't reported.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this instance returning the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Calls the callback with the cumulative number of written bytes unless it has previously
+    /// panicked, in which case it does nothing.
+    fn report(&mut self) {
+        if self.callback_panic.is_some() {
+            return;
+        }
+
+        let written = self.written;
+        let callback = &mut self.callback;
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| callback(written))) {
+            self.callback_panic = Some(panic_message(payload));
+        }
+    }
+
+    /// Returns an [`Error::Internal`] if the callback has panicked.
+    fn check_callback(&self) -> Result<()> {
+        match &self.callback_panic {
+            Some(msg) => Err(Error::new_internal(
+                "progress callback panicked",
+                BoxError::from(msg.clone()),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Calls `commit` with the wrapped writer and, if it succeeds, it calls the callback with the
+    /// total number of written bytes.
+    ///
+    /// It returns an [`Error::Internal`] without calling `commit` if the callback has panicked.
+    fn commit_with(&mut self, commit: impl FnOnce(&mut W) -> Result<()>) -> Result<()> {
+        self.check_callback()?;
+        commit(&mut self.inner)?;
+        self.report();
+        Ok(())
+    }
+}
+
+impl<F> ProgressWrite<Upload, F>
+where
+    F: FnMut(u64) + Send,
+{
+    /// Commits the upload and calls the callback with the total number of written bytes.
+    ///
+    /// It returns an [`Error::Internal`] without committing if the callback has panicked and the
+    /// same errors than [`Upload::commit`].
+    pub fn commit(&mut self) -> Result<()> {
+        self.commit_with(Upload::commit)
+    }
+}
+
+impl<F> ProgressWrite<PartUpload, F>
+where
+    F: FnMut(u64) + Send,
+{
+    /// Commits the part upload and calls the callback with the total number of written bytes.
+    ///
+    /// It returns an [`Error::Internal`] without committing if the callback has panicked and the
+    /// same errors than [`PartUpload::commit`].
+    pub fn commit(&mut self) -> Result<()> {
+        self.commit_with(PartUpload::commit)
+    }
+}
+
+impl<W, F> Write for ProgressWrite<W, F>
+where
+    W: Write,
+    F: FnMut(u64) + Send,
+{
+    /// Writes `buf` into the wrapped writer and calls the callback if it writes successfully.
+    ///
+    /// It returns an error whose payload is an [`Error::Internal`] without writing if the
+    /// callback has panicked.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check_callback()?;
+
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        self.report();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Limits the throughput of the transfers that use it to a number of bytes per second with a
+/// token bucket.
+///
+/// It's shared through an `Arc` by the [`Throttled`] readers and writers, usually created with
+/// [`Upload::throttled`] and [`Download::throttled`](crate::Download::throttled), so all of them
+/// collectively respect the limit.
+///
+/// The bucket starts full and holds up to one second of bytes, which is also the maximum number
+/// of bytes that a single read or write transfers, so the transfers can burst up to one second of
+/// bytes after being idle.
+///
+/// ```no_run
+/// use std::io::Write;
+/// use std::sync::Arc;
+///
+/// use uplink::access::Grant;
+/// use uplink::io_util::Throttle;
+/// use uplink::Project;
+///
+/// let grant = Grant::new("my-serialized-access-grant")?;
+/// let project = Project::open(&grant);
+///
+/// // 1 MiB/s shared by all the uploads.
+/// let throttle = Arc::new(Throttle::new(1024 * 1024)?);
+/// let mut upload = project
+///     .upload_object("my-bucket", "backup.tar", None)?
+///     .throttled(Arc::clone(&throttle));
+/// upload.write_all(b"Hello world!")?;
+/// upload.commit()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct Throttle {
+    /// The maximum number of bytes per second.
+    bytes_per_sec: u64,
+    /// The token bucket shared by the transfers.
+    bucket: Mutex<TokenBucket>,
+}
+
+impl Throttle {
+    /// Creates a throttle that limits the transfers to `bytes_per_sec`.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `bytes_per_sec` is zero.
+    pub fn new(bytes_per_sec: u64) -> Result<Self> {
+        if bytes_per_sec == 0 {
+            return Err(Error::new_invalid_arguments(
+                "bytes_per_sec",
+                "cannot be zero",
+            ));
+        }
+
+        Ok(Self {
+            bytes_per_sec,
+            bucket: Mutex::new(TokenBucket {
+                tokens: bytes_per_sec as f64,
+                refilled_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Returns the maximum number of bytes per second.
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec
+    }
+
+    /// Returns the maximum number of bytes that a single read or write transfers.
+    fn burst(&self) -> usize {
+        usize::try_from(self.bytes_per_sec).unwrap_or(usize::MAX)
+    }
+
+    /// Acquires `n` bytes, which must not be more than [`Self::burst`], sleeping until the bucket
+    /// has them.
+    ///
+    /// The bytes are taken from the bucket before sleeping, so the bucket goes into debt and the
+    /// next acquisitions, from any transfer, wait for it to be paid.
+    fn acquire(&self, n: usize) {
+        let rate = self.bytes_per_sec as f64;
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            bucket.refill(rate);
+            bucket.tokens -= n as f64;
+            if bucket.tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-bucket.tokens / rate)
+            }
+        };
+
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Returns `n` acquired bytes that haven't been transferred to the bucket.
+    fn release(&self, n: usize) {
+        if n == 0 {
+            return;
+        }
+
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        bucket.tokens = (bucket.tokens + n as f64).min(self.bytes_per_sec as f64);
+    }
+}
+
+/// The state of the token bucket of a [`Throttle`].
+#[derive(Debug)]
+struct TokenBucket {
+    /// The available bytes, which are negative while the bucket is in debt.
+    tokens: f64,
+    /// The last time that the bucket was refilled.
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Adds the bytes accrued at `rate` bytes per second since the last refill, without exceeding
+    /// one second of bytes.
+    fn refill(&mut self, rate: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.refilled_at = now;
+    }
+}
+
+/// Wraps a reader or a writer, usually a [`Download`](crate::Download) or an [`Upload`], limiting
+/// its throughput with a [`Throttle`].
+///
+/// Each read or write acquires from the throttle the bytes that it may transfer, sleeping as
+/// needed, before delegating to the wrapped reader or writer, and returns the ones that it
+/// doesn't transfer.
+pub struct Throttled<T> {
+    /// The wrapped reader or writer.
+    inner: T,
+    /// The throttle that limits the throughput.
+    throttle: Arc<Throttle>,
+}
+
+impl<T> Throttled<T> {
+    /// Creates a new instance that wraps `inner` and limits its throughput with `throttle`.
+    pub fn new(inner: T, throttle: Arc<Throttle>) -> Self {
+        Self { inner, throttle }
+    }
+
+    /// Returns the throttle that limits the throughput.
+    pub fn throttle(&self) -> &Arc<Throttle> {
+        &self.throttle
+    }
+
+    /// Returns a reference to the wrapped reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader or writer.
+    ///
+    /// The bytes read or written directly with it aren't throttled.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this instance returning the wrapped reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Calls `op` with at most [`Throttle::burst`] bytes of `buf` after acquiring them and
+    /// releases the ones that `op` doesn't transfer.
+    fn throttle_with<B>(
+        &mut self,
+        buf: B,
+        len: usize,
+        op: impl FnOnce(&mut T, B, usize) -> io::Result<usize>,
+    ) -> io::Result<usize> {
+        let len = len.min(self.throttle.burst());
+        if len == 0 {
+            return op(&mut self.inner, buf, 0);
+        }
+
+        self.throttle.acquire(len);
+        let res = op(&mut self.inner, buf, len);
+        let transferred = res.as_ref().map_or(0, |n| (*n).min(len));
+        self.throttle.release(len - transferred);
+        res
+    }
+}
+
+impl Throttled<Upload> {
+    /// Commits the upload.
+    ///
+    /// It returns the same errors than [`Upload::commit`].
+    pub fn commit(&mut self) -> Result<()> {
+        self.inner.commit()
+    }
+}
+
+impl<W: Write> Write for Throttled<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.throttle_with(buf, buf.len(), |inner, buf, len| inner.write(&buf[..len]))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Read for Throttled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len();
+        self.throttle_with(buf, len, |inner, buf, len| inner.read(&mut buf[..len]))
+    }
+}
+
+/// Returns the message of a panic payload, which is usually a `&str` or a `String`.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        return String::from(*msg);
+    }
+
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(_) => String::from("unknown panic payload"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Writer that writes at most `chunk_size` bytes per call and fails after `fail_after` calls.
+    struct ChunkedWriter {
+        data: Vec<u8>,
+        chunk_size: usize,
+        calls: usize,
+        fail_after: usize,
+    }
+
+    impl Write for ChunkedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.calls >= self.fail_after {
+                return Err(io::Error::other("writer"));
+            }
+
+            self.calls += 1;
+            let n = buf.len().min(self.chunk_size);
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_progress_write_multi_chunk() {
+        let writer = ChunkedWriter {
+            data: Vec::new(),
+            chunk_size: 4,
+            calls: 0,
+            fail_after: usize::MAX,
+        };
+        let mut reported = Vec::new();
+        let mut pw = ProgressWrite::new(writer, |n| reported.push(n));
+
+        pw.write_all(b"0123456789").expect("write all");
+        assert_eq!(pw.written(), 10, "written bytes");
+        assert_eq!(pw.get_ref().data, b"0123456789", "written data");
+        pw.commit_with(|_| Ok(())).expect("commit");
+        drop(pw);
+
+        assert_eq!(reported, vec![4, 8, 10, 10], "callback invocations");
+    }
+
+    #[test]
+    fn test_progress_write_failed_write() {
+        let writer = ChunkedWriter {
+            data: Vec::new(),
+            chunk_size: 4,
+            calls: 0,
+            fail_after: 2,
+        };
+        let mut calls = 0;
+        let mut pw = ProgressWrite::new(writer, |_| calls += 1);
+
+        pw.write_all(b"0123456789")
+            .expect_err("write all with a failing writer");
+        assert_eq!(pw.written(), 8, "written bytes");
+        drop(pw);
+
+        assert_eq!(calls, 2, "callback invocations");
+    }
+
+    #[test]
+    fn test_progress_write_callback_panic() {
+        let writer = ChunkedWriter {
+            data: Vec::new(),
+            chunk_size: 4,
+            calls: 0,
+            fail_after: usize::MAX,
+        };
+        let mut calls = 0;
+        let mut pw = ProgressWrite::new(writer, |n| {
+            calls += 1;
+            if n > 4 {
+                panic!("callback");
+            }
+        });
+
+        assert_eq!(
+            pw.write(b"0123").expect("write before the panic"),
+            4,
+            "written bytes"
+        );
+        assert_eq!(
+            pw.write(b"4567").expect("write that panics"),
+            4,
+            "written bytes by the write whose callback panics"
+        );
+
+        let err = pw.write(b"89").expect_err("write after the panic");
+        match Error::from_io_chain(&err) {
+            Some(Error::Internal(_)) => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(pw.written(), 8, "written bytes");
+        assert_eq!(
+            pw.commit_with(|_| Ok(()))
+                .map_err(|err| err.to_string())
+                .expect_err("commit after the panic"),
+            Error::new_internal("progress callback panicked", BoxError::from("callback"))
+                .to_string(),
+            "commit error"
+        );
+        drop(pw);
+
+        assert_eq!(calls, 2, "callback invocations");
+    }
+
+    #[test]
+    fn test_throttle_new() {
+        let throttle = Throttle::new(1024).expect("throttle");
+        assert_eq!(throttle.bytes_per_sec(), 1024, "bytes per second");
+
+        match Throttle::new(0) {
+            Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "bytes_per_sec", "names"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_throttled_write() {
+        // The bucket starts with 20,000 bytes, so writing 30,000 bytes waits for 10,000 bytes
+        // accrued at 20,000 bytes per second.
+        let throttle = Arc::new(Throttle::new(20_000).expect("throttle"));
+        let mut writer = Throttled::new(Vec::new(), throttle);
+
+        let start = Instant::now();
+        writer.write_all(&[7; 30_000]).expect("write all");
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_millis(450),
+            "elapsed time lower bound, got {:?}",
+            elapsed
+        );
+        assert_eq!(writer.into_inner(), vec![7; 30_000], "written data");
+    }
+
+    #[test]
+    fn test_throttled_read() {
+        let throttle = Arc::new(Throttle::new(20_000).expect("throttle"));
+        let data = vec![7; 30_000];
+        let mut reader = Throttled::new(data.as_slice(), throttle);
+
+        let start = Instant::now();
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).expect("read to end");
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_millis(450),
+            "elapsed time lower bound, got {:?}",
+            elapsed
+        );
+        assert_eq!(read, data, "read data");
+    }
+
+    #[test]
+    fn test_throttled_partial_write_releases_bytes() {
+        let writer = ChunkedWriter {
+            data: Vec::new(),
+            chunk_size: 4,
+            calls: 0,
+            fail_after: usize::MAX,
+        };
+        let throttle = Arc::new(Throttle::new(10).expect("throttle"));
+        let mut writer = Throttled::new(writer, Arc::clone(&throttle));
+
+        // The bucket has 10 bytes and each write only transfers 4 of them, so the 6 released
+        // bytes allow writing the 10 bytes without waiting.
+        let start = Instant::now();
+        writer.write_all(b"0123456789").expect("write all");
+        assert!(
+            start.elapsed() < Duration::from_millis(300),
+            "writes without waiting, got {:?}",
+            start.elapsed()
+        );
+        assert_eq!(writer.get_ref().data, b"0123456789", "written data");
+    }
+
+    #[test]
+    fn test_throttled_shared() {
+        // Both writers share the 20,000 bytes per second and the initial 20,000 bytes, so writing
+        // 30,000 bytes between them waits for 10,000 bytes.
+        let throttle = Arc::new(Throttle::new(20_000).expect("throttle"));
+
+        let start = Instant::now();
+        thread::scope(|scope| {
+            for _ in 0..2 {
+                let throttle = Arc::clone(&throttle);
+                scope.spawn(move || {
+                    let mut writer = Throttled::new(Vec::new(), throttle);
+                    for _ in 0..15 {
+                        writer.write_all(&[7; 1_000]).expect("write all");
+                    }
+                    assert_eq!(writer.into_inner().len(), 15_000, "written bytes");
+                });
+            }
+        });
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_millis(450),
+            "elapsed time lower bound, got {:?}",
+            elapsed
+        );
+    }
+}
@@ -6,29 +6,50 @@
 //! It wraps the low level crate `uplink-sys`, auto-generated by
 //! [bindgen](https://github.com/rust-lang/rust-bindgen). We use the term FFI through all this crate
 //! documentation and error messages when referencing the low level crate.
+//!
+//! The [`prelude`] module re-exports the most common types for importing them with
+//! `use uplink::prelude::*`.
 
 #![deny(missing_docs)]
 #![deny(clippy::undocumented_unsafe_blocks)]
 
 pub mod access;
+pub mod api;
 pub mod bucket;
+pub mod client;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub(crate) mod config;
 pub mod docs;
 pub mod edge;
 pub(crate) mod encryption_key;
 pub mod error;
+pub mod export;
 pub(crate) mod helpers;
+#[cfg(feature = "http-body")]
+pub mod http;
+pub mod io_util;
 pub mod metadata;
 pub mod object;
+pub mod pagination;
+pub mod prelude;
 pub mod project;
+pub mod share;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transfer;
+pub mod tuning;
+pub mod types;
 mod uplink_c;
+mod version;
 
 pub use bucket::Bucket;
 pub use config::Config;
 pub use encryption_key::EncryptionKey;
 pub use error::Error;
-pub use object::Object;
+pub use object::{Download, Object, Upload};
 pub use project::Project;
+pub use version::{version, Version};
 
 /// A specialized [`Result`](https://doc.rust-lang.org/std/result/enum.Result.html)
 /// type for Storj Uplink operations.
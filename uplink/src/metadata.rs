@@ -1,32 +1,94 @@
 //! Storj DCS metadata types.
 
-use std::collections::HashMap;
+pub mod http;
+
+use crate::error::BoxError;
+use crate::{helpers, Error, Result};
+
+use std::collections::{HashMap, HashSet};
 use std::ffi::c_char;
+use std::marker::PhantomData;
 use std::ptr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::vec::Vec;
 
 use uplink_sys as ulksys;
 
+/// The reserved custom metadata key where [`Project::upload_file`](crate::Project::upload_file)
+/// records the modification time of the uploaded file and from where
+/// [`Project::download_file`](crate::Project::download_file) restores it.
+///
+/// The value is the number of seconds since the Unix Epoch time with the nanoseconds as decimals
+/// (e.g. `1700000000.123456789`).
+pub const KEY_MTIME: &str = "uplink-rust-mtime";
+
+/// Returns the value of the [`KEY_MTIME`] custom metadata entry for `mtime` or `None` if it's
+/// before the Unix Epoch time.
+pub(crate) fn mtime_to_value(mtime: SystemTime) -> Option<String> {
+    let since_epoch = mtime.duration_since(UNIX_EPOCH).ok()?;
+    Some(format!(
+        "{}.{:09}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_nanos()
+    ))
+}
+
+/// Returns the modification time of a [`KEY_MTIME`] custom metadata entry value or `None` if it
+/// isn't well-formed.
+pub(crate) fn mtime_from_value(value: &str) -> Option<SystemTime> {
+    let (secs, nanos) = value.split_once('.')?;
+    if nanos.len() != 9 || !(secs.bytes().chain(nanos.bytes())).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let since_epoch = Duration::new(secs.parse().ok()?, nanos.parse().ok()?);
+    UNIX_EPOCH.checked_add(since_epoch)
+}
+
+/// The reserved custom metadata key where
+/// [`MultipartUpload::parts_manifest`](crate::object::upload::MultipartUpload::parts_manifest)
+/// records the parts of a multipart upload.
+///
+/// The value is a JSON array with an object for each part sorted by part number, which has the
+/// `part_number`, the `size` in bytes and the entity tag as lowercase hexadecimal in `etag_hex`
+/// (e.g. `[{"part_number":1,"size":5242880,"etag_hex":"0a1b"}]`).
+pub const KEY_PARTS_MANIFEST: &str = "uplink-rust-parts-manifest";
+
+/// The maximum number of entries of the custom metadata received from the FFI. Custom metadata
+/// with more entries is considered corrupted because the network limits are far lower.
+pub const FFI_MAX_ENTRIES: usize = 10_000;
+
+/// The maximum sum of the length, in bytes, of the keys and values of the custom metadata received
+/// from the FFI. Custom metadata with a greater size is considered corrupted because the network
+/// limits are far lower.
+pub const FFI_MAX_TOTAL_SIZE: usize = 16 * 1024 * 1024;
+
 /// It's a container for custom information of a specific "item".
 /// It's provided by the users as key-value pairs which must only contain valid
 /// UTF-8 characters. Keys are unique, so only one value can be associated with
 /// it.
 ///
+/// Other clients (e.g. Go or C) may store keys and values with arbitrary bytes. When they aren't
+/// valid UTF-8, the `String` ones exposed by this type have the invalid sequences replaced by the
+/// `U+FFFD REPLACEMENT CHARACTER`, but the original bytes are retained, so they can be retrieved
+/// with [`Self::get_raw`] and they are sent back unaltered to the network unless the entry is
+/// replaced or deleted.
+///
 /// By convention an application that stores metadata should prepend to the keys
 /// a prefix, for example an application named "Image Board" might use the
 /// "image-board:" prefix and a key could be "image-board:title".
-#[derive(Default, Debug)]
+///
+/// The operations that send it to the network validate it against the [`MetadataLimits`] of the
+/// project before calling the FFI, see [`Self::validate`] and
+/// [`Config::set_metadata_limits`](crate::Config::set_metadata_limits).
+#[derive(Clone, Default, Debug)]
 pub struct Custom {
     /// The key-value pairs.
     entries: HashMap<String, String>,
 
-    /// Cached FFI representation of this instance that guards its lifetime while it's hold by this
-    /// field or this instance drops.
-    ///
-    /// It's an option because it's only created when calling [`Self::to_ffi_custom_metadata`] and
-    /// hold it meanwhile this instance isn't mutated.
-    inner: Option<UplinkCustomMetadataWrapper>,
+    /// The original bytes of the entries whose key or value isn't valid UTF-8, indexed by the key
+    /// used in `entries`.
+    raw_entries: HashMap<String, RawEntry>,
 }
 
 impl Custom {
@@ -36,43 +98,79 @@ impl Custom {
 
         Self {
             entries: map,
-            inner: None,
+            raw_entries: HashMap::new(),
         }
     }
 
     /// Creates a custom metadata instance from type exposed by the FFI.
     ///
-    /// NOTE this method assumes `uc_custom` only contains key-value pairs that have valid UTF-8
-    /// bytes. In the case that it doesn't then the mapped key-value may not have the same value in
-    /// that byte position and it isn't either guarantee that the same invalid UTF-8 byte produces
-    /// the same mapped value.
-    pub(crate) fn with_ffi_custom_metadata(uc_custom: &ulksys::UplinkCustomMetadata) -> Self {
+    /// The key-value pairs that aren't valid UTF-8 retain their original bytes, see
+    /// [`Self::get_raw`].
+    ///
+    /// It returns an [`Error::Internal`] without copying any entry if the FFI value has more than
+    /// [`FFI_MAX_ENTRIES`] entries, the sum of the length of its keys and values is greater than
+    /// [`FFI_MAX_TOTAL_SIZE`] or its entries are `NULL` while its count isn't zero, so a corrupted
+    /// or an adversarial value doesn't make the process to abort allocating enormous amounts of
+    /// memory.
+    pub(crate) fn with_ffi_custom_metadata(
+        uc_custom: &ulksys::UplinkCustomMetadata,
+    ) -> Result<Self> {
         if uc_custom.count == 0 {
-            return Default::default();
+            return Ok(Default::default());
+        }
+
+        let invalid = |reason: String| {
+            Error::new_internal(
+                "FFI returned invalid custom metadata",
+                BoxError::from(reason),
+            )
+        };
+        if uc_custom.count > FFI_MAX_ENTRIES {
+            return Err(invalid(format!(
+                "it has {} entries and the maximum allowed is {}",
+                uc_custom.count, FFI_MAX_ENTRIES
+            )));
+        }
+        if uc_custom.entries.is_null() {
+            return Err(invalid(format!(
+                "it has {} entries but they are NULL",
+                uc_custom.count
+            )));
+        }
+
+        // SAFETY: we have checked that the entries aren't `NULL` and we trust that the FFI
+        // contains a valid pointer to entries and the counter has the exact number of entries.
+        let entries = unsafe { std::slice::from_raw_parts(uc_custom.entries, uc_custom.count) };
+        let total_size = entries.iter().try_fold(0usize, |total, entry| {
+            total
+                .checked_add(entry.key_length)
+                .and_then(|t| t.checked_add(entry.value_length))
+                .filter(|t| *t <= FFI_MAX_TOTAL_SIZE)
+        });
+        if total_size.is_none() {
+            return Err(invalid(format!(
+                "the total size of its keys and values exceeds the maximum allowed of {} bytes",
+                FFI_MAX_TOTAL_SIZE
+            )));
         }
 
         let mut custom = Self::with_capacity(uc_custom.count);
-        // SAFETY: we trust that the FFI contains a valid pointer to entries and the counter has
-        // the exact number of entries, and each entry has a key-value C string with exactly the
-        // length specified without leaning that they end with the NULL byte because they could
-        // contain NULL bytes.
+        // SAFETY: we trust that each entry has a key-value C string with exactly the length
+        // specified without leaning that they end with the NULL byte because they could contain
+        // NULL bytes.
         unsafe {
-            use crate::helpers::unchecked_ptr_c_char_and_length_to_string;
-
-            for i in 0..uc_custom.count as isize {
-                let entry = uc_custom.entries.offset(i) as *const ulksys::UplinkCustomMetadataEntry;
-                let key =
-                    unchecked_ptr_c_char_and_length_to_string((*entry).key, (*entry).key_length);
-                let value = unchecked_ptr_c_char_and_length_to_string(
-                    (*entry).value,
-                    (*entry).value_length,
-                );
-
-                custom.insert(key, value);
+            use crate::helpers::unchecked_ptr_c_char_and_length_to_bytes;
+
+            for entry in entries {
+                let key = unchecked_ptr_c_char_and_length_to_bytes(entry.key, entry.key_length);
+                let value =
+                    unchecked_ptr_c_char_and_length_to_bytes(entry.value, entry.value_length);
+
+                custom.insert_bytes(key, value);
             }
         }
 
-        custom
+        Ok(custom)
     }
 
     /// Returns the current number of entries (i.e. key-value pairs).
@@ -89,14 +187,62 @@ impl Custom {
         }
     }
 
+    /// Gets the original bytes of the entry's value associated with the passed key. Returns none
+    /// if there isn't any entry associated to the key.
+    ///
+    /// The bytes only differ from the ones of [`Self::get`] when the entry was received from the
+    /// network and its key or value isn't valid UTF-8. In the case of an invalid UTF-8 key, `key`
+    /// is the one with the invalid sequences replaced, as it's exposed by [`Self::iter`].
+    pub fn get_raw(&self, key: &str) -> Option<&[u8]> {
+        match self.raw_entries.get(key) {
+            Some(raw) => Some(&raw.value),
+            None => self.entries.get(key).map(|v| v.as_bytes()),
+        }
+    }
+
     /// Inserts a new entry with the specified key and value, returning false if
     /// the key didn't exit, otherwise true and replace the value associated to
     /// the key.
     pub fn insert(&mut self, key: String, value: String) -> bool {
-        self.inner = None;
+        self.raw_entries.remove(&key);
         self.entries.insert(key, value).is_some()
     }
 
+    /// Inserts a new entry from the bytes of a key and a value, which may not be valid UTF-8,
+    /// retaining their original bytes. It returns the same than [`Self::insert`].
+    ///
+    /// Two keys with different invalid UTF-8 sequences may have the same replaced key, in which
+    /// case the last inserted one prevails.
+    fn insert_bytes(&mut self, key: Vec<u8>, value: Vec<u8>) -> bool {
+        let (key, value) = match (String::from_utf8(key), String::from_utf8(value)) {
+            (Ok(k), Ok(v)) => return self.insert(k, v),
+            (k, v) => (
+                k.map(String::into_bytes)
+                    .unwrap_or_else(|err| err.into_bytes()),
+                v.map(String::into_bytes)
+                    .unwrap_or_else(|err| err.into_bytes()),
+            ),
+        };
+
+        let lossy_key = String::from_utf8_lossy(&key).into_owned();
+        let lossy_value = String::from_utf8_lossy(&value).into_owned();
+        let existed = self.insert(lossy_key.clone(), lossy_value);
+        self.raw_entries.insert(lossy_key, RawEntry { key, value });
+        existed
+    }
+
+    /// Returns the original bytes of the key and value of the entry associated with the passed
+    /// key. See [`Self::get_raw`].
+    fn entry_bytes<'a>(&'a self, key: &'a str) -> Option<(&'a [u8], &'a [u8])> {
+        match self.raw_entries.get(key) {
+            Some(raw) => Some((raw.key.as_slice(), raw.value.as_slice())),
+            None => self
+                .entries
+                .get(key)
+                .map(|v| (key.as_bytes(), v.as_bytes())),
+        }
+    }
+
     /// An iterator for visiting all the metadata key-value pairs.
     pub fn iter(&self) -> impl std::iter::Iterator<Item = (&String, &String)> {
         self.entries.iter()
@@ -105,85 +251,277 @@ impl Custom {
     /// Deletes the entry with the associated key, returning false if the key
     /// didn't exist, otherwise true.
     pub fn delete(&mut self, key: &str) -> bool {
-        self.inner = None;
+        self.raw_entries.remove(key);
         self.entries.remove(key).is_some()
     }
 
-    /// Returns the FFI representation of this custom metadata container which is valid as long as
-    /// `self` isn't mutated or dropped.
+    /// Returns a deterministic encoding of the entries for signing or comparing them, which
+    /// doesn't depend on the insertion order.
+    ///
+    /// The entries are sorted lexicographically by the bytes of their keys and each one is
+    /// encoded as the length of the key, the key, the length of the value and the value, where
+    /// the lengths are 64-bit big-endian unsigned integers. The keys and values are their original
+    /// bytes (see [`Self::get_raw`]), which are UTF-8 unless they were received from the network
+    /// with invalid UTF-8 sequences. An empty custom metadata is encoded as no bytes.
+    ///
+    /// The encoding is stable across versions of this crate, so signatures computed from it
+    /// remain valid after upgrading it.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut entries: Vec<(&[u8], &[u8])> = self
+            .entries
+            .keys()
+            .map(|k| {
+                self.entry_bytes(k)
+                    .expect("BUG: key obtained from the entries")
+            })
+            .collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+
+        let size = entries
+            .iter()
+            .map(|(key, value)| 16 + key.len() + value.len())
+            .sum();
+        let mut bytes = Vec::with_capacity(size);
+        for (key, value) in entries {
+            bytes.extend_from_slice(&(key.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(key);
+            bytes.extend_from_slice(&(value.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(value);
+        }
+
+        bytes
+    }
+
+    /// Returns the SHA-256 digest of [`Self::canonical_bytes`].
+    #[cfg(feature = "checksum")]
+    pub fn digest_sha256(&self) -> [u8; 32] {
+        use sha2::Digest;
+
+        sha2::Sha256::digest(self.canonical_bytes()).into()
+    }
+
+    /// Validates this custom metadata against `limits`.
+    ///
+    /// It returns an [`Error::InvalidArguments`] which indicates the offending key when:
+    /// * A key is longer than [`MetadataLimits::max_key_length`].
+    /// * The sum of the length of all the keys and values is greater than
+    ///   [`MetadataLimits::max_total_size`]; the offending key is the one that makes the total
+    ///   size to exceed the limit when visiting the entries sorted by key.
+    pub fn validate(&self, limits: &MetadataLimits) -> Result<()> {
+        self.validate_fn_arg("metadata", limits)
+    }
+
+    /// Validates this custom metadata against `limits` returning an
+    /// [`Error::InvalidArguments`] for the `arg_name` function argument.
+    ///
+    /// See [`Self::validate`] for knowing which validations are done.
+    pub(crate) fn validate_fn_arg(&self, arg_name: &str, limits: &MetadataLimits) -> Result<()> {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort_unstable();
+
+        let mut total_size = 0;
+        for k in keys {
+            let (key, value) = self
+                .entry_bytes(k)
+                .expect("BUG: key obtained from the entries");
+            if key.len() > limits.max_key_length {
+                return Err(Error::new_invalid_arguments(
+                    arg_name,
+                    &format!(
+                        "key '{}' has {} bytes and the maximum allowed is {}",
+                        k,
+                        key.len(),
+                        limits.max_key_length,
+                    ),
+                ));
+            }
+
+            total_size += key.len() + value.len();
+            if total_size > limits.max_total_size {
+                return Err(Error::new_invalid_arguments(
+                    arg_name,
+                    &format!(
+                        "key '{}' makes the total size of keys and values to exceed the maximum allowed of {} bytes",
+                        k, limits.max_total_size,
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the FFI representation of this custom metadata container, which borrows it so it
+    /// cannot be mutated, moved or dropped while the returned value is alive.
     ///
-    /// When this method is called more than once and `self` isn't mutated in between, the calls
-    /// after the first are very cheap because the returned value is cached.
+    /// It's created on each call, so the FFI value must be obtained from the returned wrapper
+    /// (see [`UplinkCustomMetadataWrapper::as_ffi_custom_metadata`]) and the wrapper must be kept
+    /// alive meanwhile the FFI uses it.
     #[allow(clippy::wrong_self_convention)]
-    pub(crate) fn to_ffi_custom_metadata(&mut self) -> ulksys::UplinkCustomMetadata {
-        if self.inner.is_none() {
-            self.inner = Some(UplinkCustomMetadataWrapper::from_custom(self));
+    pub(crate) fn to_ffi_custom_metadata(&self) -> UplinkCustomMetadataWrapper<'_> {
+        UplinkCustomMetadataWrapper::from_custom(self)
+    }
+}
+
+/// Two custom metadata are equal when they have the same entries, regardless of their insertion
+/// order.
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.raw_entries == other.raw_entries
+    }
+}
+
+impl Eq for Custom {}
+
+/// It's serialized as a map of its entries, whose keys and values have the invalid UTF-8
+/// sequences replaced.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Custom {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(&self.entries)
+    }
+}
+
+/// The original bytes of a [`Custom`] metadata entry whose key or value isn't valid UTF-8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RawEntry {
+    /// The bytes of the key.
+    key: Vec<u8>,
+    /// The bytes of the value.
+    value: Vec<u8>,
+}
+
+/// A set of changes to apply to a [`Custom`] metadata.
+///
+/// A key can only be upserted or removed, so the last call to [`Self::upsert`] or
+/// [`Self::remove`] for the same key is the one that prevails.
+#[derive(Default, Debug, Clone)]
+pub struct MetadataPatch {
+    /// The key-value pairs to insert or update.
+    upserts: HashMap<String, String>,
+    /// The keys to remove.
+    removals: HashSet<String>,
+}
+
+impl MetadataPatch {
+    /// Creates a patch without changes.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Inserts the key with the specified value or updates its value if the key already exists.
+    pub fn upsert(&mut self, key: String, value: String) -> &mut Self {
+        self.removals.remove(&key);
+        self.upserts.insert(key, value);
+        self
+    }
+
+    /// Removes the key if it exists.
+    pub fn remove(&mut self, key: String) -> &mut Self {
+        self.upserts.remove(&key);
+        self.removals.insert(key);
+        self
+    }
+
+    /// Returns true if the patch doesn't have any change.
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.removals.is_empty()
+    }
+
+    /// Applies the changes to `custom`.
+    pub(crate) fn apply(self, custom: &mut Custom) {
+        for k in &self.removals {
+            custom.delete(k);
         }
 
-        // We have ensured that `inner` is not None just above so `unwrap` will never panic.
-        self.inner.as_ref().unwrap().custom_metadata
+        for (k, v) in self.upserts {
+            custom.insert(k, v);
+        }
     }
 }
 
-impl Clone for Custom {
-    fn clone(&self) -> Self {
+/// The limits that the network enforces to the custom metadata.
+///
+/// The default values match the network defaults at the moment of writing, but they are
+/// configured by each satellite, so they can be overridden through
+/// [`Config::set_metadata_limits`](crate::Config::set_metadata_limits).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MetadataLimits {
+    /// The maximum sum of the length, in bytes, of all the keys and values.
+    pub max_total_size: usize,
+    /// The maximum length, in bytes, of a key.
+    pub max_key_length: usize,
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
         Self {
-            entries: self.entries.clone(),
-            inner: None,
+            max_total_size: 2048,
+            max_key_length: 1024,
         }
     }
 }
 
-/// It allows to create an [`uplink_sys::UplinkCustomMetadata`] instance that
-/// guards the used memory of its list of items during the lifetime of the
-/// instance of this struct.
+/// It allows to create an [`uplink_sys::UplinkCustomMetadata`] instance that guards the used
+/// memory of its list of items during the lifetime of the instance of this struct.
+///
+/// It borrows the [`Custom`] that it represents because the entries point to its keys and values,
+/// and it doesn't cache the FFI value, which is built on each access with the pointer to the
+/// current location of the entries.
 #[derive(Debug)]
-struct UplinkCustomMetadataWrapper {
-    /// The [`uplink_sys::UplinkCustomMetadata`] instance that `self`
-    /// represents.
-    custom_metadata: ulksys::UplinkCustomMetadata,
-    /// The allocated memory of the list of entries referenced by the FFI value in the field
-    /// `custom_metadata` and whose lifetime is guarded by an instance of `Self`.
-    _entries: Vec<ulksys::UplinkCustomMetadataEntry>,
+pub(crate) struct UplinkCustomMetadataWrapper<'a> {
+    /// The list of entries referenced by the FFI values returned by
+    /// [`Self::as_ffi_custom_metadata`].
+    ///
+    /// It's a boxed slice because its allocation never changes once it's created.
+    entries: Box<[ulksys::UplinkCustomMetadataEntry]>,
+    /// The custom metadata that owns the keys and values referenced by the entries.
+    _custom: PhantomData<&'a Custom>,
 }
 
-impl UplinkCustomMetadataWrapper {
-    /// Creates a wrapped [`uplink_sys::UplinkCustomMetadata`]  which represents
-    /// the passed [`Custom`].
-    fn from_custom(custom: &Custom) -> Self {
-        let num_entries = custom.count();
-        if num_entries == 0 {
-            return Default::default();
-        }
-
-        let mut entries = Vec::with_capacity(num_entries);
-        for (k, v) in custom.iter() {
-            entries.push(ulksys::UplinkCustomMetadataEntry {
-                key: k.as_ptr() as *mut c_char,
-                key_length: k.len(),
-                value: v.as_ptr() as *mut c_char,
-                value_length: v.len(),
-            });
-        }
+impl<'a> UplinkCustomMetadataWrapper<'a> {
+    /// Creates a wrapped [`uplink_sys::UplinkCustomMetadata`] which represents the passed
+    /// [`Custom`].
+    fn from_custom(custom: &'a Custom) -> Self {
+        let entries = custom
+            .entries
+            .keys()
+            .map(|k| {
+                let (k, v) = custom
+                    .entry_bytes(k)
+                    .expect("BUG: key obtained from the entries");
+                ulksys::UplinkCustomMetadataEntry {
+                    key: k.as_ptr() as *mut c_char,
+                    key_length: k.len(),
+                    value: v.as_ptr() as *mut c_char,
+                    value_length: v.len(),
+                }
+            })
+            .collect();
 
         UplinkCustomMetadataWrapper {
-            custom_metadata: ulksys::UplinkCustomMetadata {
-                entries: entries.as_mut_ptr(),
-                count: entries.len(),
-            },
-            _entries: entries,
+            entries,
+            _custom: PhantomData,
         }
     }
-}
 
-impl Default for UplinkCustomMetadataWrapper {
-    fn default() -> Self {
-        UplinkCustomMetadataWrapper {
-            custom_metadata: ulksys::UplinkCustomMetadata {
+    /// Returns the FFI representation of the custom metadata, which is valid as long as `self`
+    /// isn't dropped.
+    pub(crate) fn as_ffi_custom_metadata(&self) -> ulksys::UplinkCustomMetadata {
+        if self.entries.is_empty() {
+            return ulksys::UplinkCustomMetadata {
                 entries: ptr::null_mut(),
                 count: 0,
-            },
-            _entries: Vec::new(),
+            };
+        }
+
+        ulksys::UplinkCustomMetadata {
+            // The FFI doesn't mutate the entries despite taking a mutable pointer.
+            entries: self.entries.as_ptr() as *mut ulksys::UplinkCustomMetadataEntry,
+            count: self.entries.len(),
         }
     }
 }
@@ -191,6 +529,7 @@ impl Default for UplinkCustomMetadataWrapper {
 /// It's a container of system information of a specific "item".
 /// It's provided by the service and only the service can alter it.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct System {
     /// When the associated "item" was created.
     ///
@@ -202,12 +541,12 @@ pub struct System {
     /// The time is measured with the number of seconds since the Unix Epoch
     /// time.
     pub expires: Option<Duration>,
-    /// Then length of the data associated to this metadata.
+    /// Then length of the data associated to this metadata. `None` when it's unknown, which is
+    /// when the FFI reports a negative length.
     ///
-    /// NOTE it's a signed integer because the original library uses a signed
-    /// integer, so it may be the case now or in the future that negatives
-    /// numbers are used.
-    pub content_length: i64,
+    /// NOTE it was an `i64`, as the FFI reports it, see [`Self::content_length_i64`] for the
+    /// previous behavior.
+    pub content_length: Option<u64>,
 }
 
 impl System {
@@ -233,15 +572,173 @@ impl System {
         Self {
             created,
             expires,
-            content_length: uc_system.content_length,
+            content_length: u64::try_from(uc_system.content_length).ok(),
         }
     }
+
+    /// Returns the length of the data as a signed integer, which is `-1` when it's unknown, as
+    /// the `content_length` field was before it became an `Option<u64>`.
+    ///
+    /// The lengths that don't fit in an `i64` are saturated to [`i64::MAX`].
+    #[deprecated(
+        since = "0.11.0",
+        note = "use the `content_length` field, which is `None` when the length is unknown"
+    )]
+    pub fn content_length_i64(&self) -> i64 {
+        self.content_length
+            .map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX))
+    }
+
+    /// Returns the creation time as a UTC date time.
+    ///
+    /// It's the Unix Epoch time when the creation time is zero, which happens when the system
+    /// metadata wasn't retrieved.
+    #[cfg(feature = "chrono")]
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        helpers::datetime_from_unix_time(self.created)
+    }
+
+    /// Returns the expiration time as a UTC date time or `None` if the "item" never expires.
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.expires.map(helpers::datetime_from_unix_time)
+    }
+}
+
+impl std::fmt::Display for System {
+    /// Formats the content length and the creation and expiration times as RFC 3339 timestamps
+    /// (e.g. `1234 bytes, created 2024-05-01T12:00:00Z, expires 2024-06-01T12:00:00Z`).
+    ///
+    /// The content length is formatted as `unknown length` when it's unknown.
+    ///
+    /// The creation time is omitted when it's zero, which happens when the system metadata
+    /// wasn't retrieved, and the expiration time when the "item" never expires.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.content_length {
+            Some(1) => write!(f, "1 byte")?,
+            Some(l) => write!(f, "{} bytes", l)?,
+            None => write!(f, "unknown length")?,
+        }
+
+        if !self.created.is_zero() {
+            write!(f, ", created {}", helpers::format_unix_time(self.created))?;
+        }
+
+        if let Some(expires) = self.expires {
+            write!(f, ", expires {}", helpers::format_unix_time(expires))?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_system_display() {
+        for (system, want) in [
+            (
+                System {
+                    created: Duration::from_secs(1_714_564_800),
+                    expires: Some(Duration::from_secs(1_717_243_200)),
+                    content_length: Some(1234),
+                },
+                "1234 bytes, created 2024-05-01T12:00:00Z, expires 2024-06-01T12:00:00Z",
+            ),
+            (
+                System {
+                    created: Duration::from_secs(1_714_564_800),
+                    expires: None,
+                    content_length: Some(1),
+                },
+                "1 byte, created 2024-05-01T12:00:00Z",
+            ),
+            (
+                System {
+                    created: Duration::ZERO,
+                    expires: None,
+                    content_length: Some(0),
+                },
+                "0 bytes",
+            ),
+            (
+                System {
+                    created: Duration::ZERO,
+                    expires: None,
+                    content_length: None,
+                },
+                "unknown length",
+            ),
+        ] {
+            assert_eq!(system.to_string(), want, "display");
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_system_content_length() {
+        for (ffi_length, want) in [
+            (-1, None),
+            (i64::MIN, None),
+            (0, Some(0)),
+            (1234, Some(1234)),
+            (i64::MAX, Some(i64::MAX as u64)),
+        ] {
+            let system = System::with_ffi_system_metadata(&ulksys::UplinkSystemMetadata {
+                created: 0,
+                expires: 0,
+                content_length: ffi_length,
+            });
+            assert_eq!(
+                system.content_length, want,
+                "{}: content length",
+                ffi_length
+            );
+            assert_eq!(
+                system.content_length_i64(),
+                ffi_length.max(-1),
+                "{}: signed content length",
+                ffi_length
+            );
+        }
+
+        let system = System {
+            created: Duration::ZERO,
+            expires: None,
+            content_length: Some(u64::MAX),
+        };
+        assert_eq!(system.content_length_i64(), i64::MAX, "saturated length");
+    }
+
+    #[test]
+    fn test_mtime_value() {
+        let mtime = UNIX_EPOCH + Duration::new(1_700_000_000, 5);
+        let value = mtime_to_value(mtime).expect("mtime after the epoch");
+        assert_eq!(value, "1700000000.000000005", "value");
+        assert_eq!(mtime_from_value(&value), Some(mtime), "round trip");
+
+        assert_eq!(
+            mtime_to_value(UNIX_EPOCH - Duration::from_secs(1)),
+            None,
+            "mtime before the epoch"
+        );
+
+        for value in [
+            "",
+            "1700000000",
+            "1700000000.5",
+            "1700000000.0000000005",
+            "-1.000000000",
+            "+1.000000000",
+            "a.000000000",
+            "1.00000000a",
+        ] {
+            assert_eq!(mtime_from_value(value), None, "invalid value: {:?}", value);
+        }
+    }
+
     #[test]
     fn test_custom_with_entries() {
         let key1 = "key-a";
@@ -440,7 +937,10 @@ mod test {
             let mut to = Custom::with_capacity(2);
             to.insert(String::from(key1), String::from(val1));
             to.insert(String::from(key2), String::from(val2));
-            from = Custom::with_ffi_custom_metadata(&to.to_ffi_custom_metadata());
+            from = Custom::with_ffi_custom_metadata(
+                &to.to_ffi_custom_metadata().as_ffi_custom_metadata(),
+            )
+            .expect("custom metadata from the FFI");
 
             assert_eq!(from.count(), 2, "count");
             assert_eq!(from.get(key1), Some(&String::from(val1)), "get: 'key1'");
@@ -457,6 +957,171 @@ mod test {
         assert_eq!(from.get(key2), Some(&String::from(val2)), "get: 'key2'");
     }
 
+    #[test]
+    fn test_custom_with_ffi_custom_metadata_caps() {
+        // Returns the context message of the error of `res`.
+        fn internal_ctx(res: Result<Custom>) -> String {
+            match res {
+                Err(Error::Internal(internal)) => internal.ctx_msg,
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        let key = b"key";
+        let value = b"value";
+        let mut entry = ulksys::UplinkCustomMetadataEntry {
+            key: key.as_ptr() as *mut c_char,
+            key_length: key.len(),
+            value: value.as_ptr() as *mut c_char,
+            value_length: value.len(),
+        };
+
+        // The entries are never read because the count is rejected first.
+        for count in [FFI_MAX_ENTRIES + 1, usize::MAX] {
+            let uc_custom = ulksys::UplinkCustomMetadata {
+                entries: ptr::addr_of_mut!(entry),
+                count,
+            };
+            assert_eq!(
+                internal_ctx(Custom::with_ffi_custom_metadata(&uc_custom)),
+                "FFI returned invalid custom metadata",
+                "{} entries",
+                count
+            );
+        }
+
+        let uc_custom = ulksys::UplinkCustomMetadata {
+            entries: ptr::null_mut(),
+            count: 1,
+        };
+        assert_eq!(
+            internal_ctx(Custom::with_ffi_custom_metadata(&uc_custom)),
+            "FFI returned invalid custom metadata",
+            "NULL entries"
+        );
+
+        // The keys and values are never read because their lengths are rejected first.
+        for (key_length, value_length) in [
+            (FFI_MAX_TOTAL_SIZE, 1),
+            (1, FFI_MAX_TOTAL_SIZE),
+            (usize::MAX, usize::MAX),
+        ] {
+            let mut entries = [entry, entry];
+            entries[1].key_length = key_length;
+            entries[1].value_length = value_length;
+            let uc_custom = ulksys::UplinkCustomMetadata {
+                entries: entries.as_mut_ptr(),
+                count: entries.len(),
+            };
+            assert_eq!(
+                internal_ctx(Custom::with_ffi_custom_metadata(&uc_custom)),
+                "FFI returned invalid custom metadata",
+                "key length {} and value length {}",
+                key_length,
+                value_length
+            );
+        }
+
+        // Within the caps.
+        let uc_custom = ulksys::UplinkCustomMetadata {
+            entries: ptr::addr_of_mut!(entry),
+            count: 1,
+        };
+        let custom = Custom::with_ffi_custom_metadata(&uc_custom).expect("valid custom metadata");
+        assert_eq!(custom.count(), 1, "count");
+        assert_eq!(
+            custom.get("key").map(String::as_str),
+            Some("value"),
+            "entry"
+        );
+    }
+
+    #[test]
+    fn test_custom_ffi_invalid_utf8_round_trip() {
+        let key_valid = b"key-a".to_vec();
+        let val_invalid = b"val-\xFF\xFE".to_vec();
+        let key_invalid = b"key-\xFF".to_vec();
+        let val_valid = "väl-b".as_bytes().to_vec();
+
+        let mut custom = Custom::default();
+        custom.insert_bytes(key_valid.clone(), val_invalid.clone());
+        custom.insert_bytes(key_invalid.clone(), val_valid.clone());
+
+        assert_eq!(
+            custom.get("key-a"),
+            Some(&String::from("val-\u{FFFD}\u{FFFD}")),
+            "get: lossy value"
+        );
+        assert_eq!(
+            custom.get_raw("key-a"),
+            Some(val_invalid.as_slice()),
+            "get raw: invalid value"
+        );
+        assert_eq!(
+            custom.get_raw("key-\u{FFFD}"),
+            Some(val_valid.as_slice()),
+            "get raw: value of an invalid key"
+        );
+
+        // Round-trip twice through the FFI representation.
+        let from = Custom::with_ffi_custom_metadata(
+            &custom.to_ffi_custom_metadata().as_ffi_custom_metadata(),
+        )
+        .expect("custom metadata from the FFI");
+        let from = Custom::with_ffi_custom_metadata(
+            &from.to_ffi_custom_metadata().as_ffi_custom_metadata(),
+        )
+        .expect("custom metadata from the FFI");
+        drop(custom);
+
+        assert_eq!(from.count(), 2, "count");
+        assert_eq!(
+            from.get_raw("key-a"),
+            Some(val_invalid.as_slice()),
+            "get raw: invalid value after the round-trip"
+        );
+        let (key, value) = from
+            .entry_bytes("key-\u{FFFD}")
+            .expect("entry of an invalid key after the round-trip");
+        assert_eq!(
+            key,
+            key_invalid.as_slice(),
+            "invalid key after the round-trip"
+        );
+        assert_eq!(value, val_valid.as_slice(), "value after the round-trip");
+    }
+
+    #[test]
+    fn test_custom_insert_replaces_raw() {
+        let mut custom = Custom::default();
+        custom.insert_bytes(b"key".to_vec(), b"\xFF".to_vec());
+        assert_eq!(custom.get_raw("key"), Some(&b"\xFF"[..]), "get raw");
+
+        assert!(
+            custom.insert(String::from("key"), String::from("value")),
+            "insert an existing key"
+        );
+        assert_eq!(custom.get_raw("key"), Some(&b"value"[..]), "get raw");
+
+        custom.insert_bytes(b"key".to_vec(), b"\xFF".to_vec());
+        assert!(custom.delete("key"), "delete");
+        assert_eq!(custom.get_raw("key"), None, "get raw after deleting");
+    }
+
+    #[test]
+    fn test_custom_validate_raw_length() {
+        // The replaced value has 3 bytes but the original has 1 byte.
+        let mut custom = Custom::default();
+        custom.insert_bytes(b"k".to_vec(), b"\xFF".to_vec());
+
+        custom
+            .validate(&MetadataLimits {
+                max_total_size: 2,
+                max_key_length: 1,
+            })
+            .expect("validate with the original length");
+    }
+
     #[test]
     fn test_custom_to_ffi_custom_metadata() {
         let key1 = "key-a";
@@ -468,7 +1133,8 @@ mod test {
         custom.insert(String::from(key1), String::from(val1));
         custom.insert(String::from(key2), String::from(val2));
 
-        let c_custom = custom.to_ffi_custom_metadata();
+        let uc_custom = custom.to_ffi_custom_metadata();
+        let c_custom = uc_custom.as_ffi_custom_metadata();
         assert_eq!(c_custom.count, 2, "count");
 
         let c_entries = c_custom.entries as *const ulksys::UplinkCustomMetadataEntry;
@@ -492,9 +1158,11 @@ mod test {
 
         // Modify the custom metadata and verify that the methods returns an
         // UplinkCustomMetadata which reflets the current custom metadata state.
+        drop(uc_custom);
         custom.delete(key1);
 
-        let c_custom = custom.to_ffi_custom_metadata();
+        let uc_custom = custom.to_ffi_custom_metadata();
+        let c_custom = uc_custom.as_ffi_custom_metadata();
         assert_eq!(c_custom.count, 1, "count");
 
         let c_entries = c_custom.entries as *const ulksys::UplinkCustomMetadataEntry;
@@ -505,6 +1173,256 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_custom_to_ffi_custom_metadata_after_move() {
+        /// Returns the key-value pairs of the FFI representation sorted by key.
+        fn ffi_entries(c_custom: &ulksys::UplinkCustomMetadata) -> Vec<(Vec<u8>, Vec<u8>)> {
+            let mut entries: Vec<(Vec<u8>, Vec<u8>)> = (0..c_custom.count)
+                .map(|i| {
+                    // SAFETY: the entries are valid because the wrapper that owns them is alive
+                    // while this function is called.
+                    unsafe {
+                        let entry = *c_custom.entries.add(i);
+                        (
+                            helpers::unchecked_ptr_c_char_and_length_to_bytes(
+                                entry.key,
+                                entry.key_length,
+                            ),
+                            helpers::unchecked_ptr_c_char_and_length_to_bytes(
+                                entry.value,
+                                entry.value_length,
+                            ),
+                        )
+                    }
+                })
+                .collect();
+            entries.sort();
+            entries
+        }
+
+        let expected = vec![
+            (b"key-a".to_vec(), b"val-a".to_vec()),
+            (b"key-b".to_vec(), b"val-b".to_vec()),
+        ];
+
+        let mut custom = Custom::default();
+        custom.insert(String::from("key-a"), String::from("val-a"));
+        custom.insert(String::from("key-b"), String::from("val-b"));
+
+        // Convert, drop the FFI representation and move the custom metadata afterwards, which
+        // used to leave a cached FFI representation pointing to the moved instance.
+        let uc_custom = custom.to_ffi_custom_metadata();
+        assert_eq!(
+            ffi_entries(&uc_custom.as_ffi_custom_metadata()),
+            expected,
+            "entries before moving"
+        );
+        drop(uc_custom);
+
+        let moved = Box::new(custom);
+        let uc_custom = moved.to_ffi_custom_metadata();
+        assert_eq!(
+            ffi_entries(&uc_custom.as_ffi_custom_metadata()),
+            expected,
+            "entries after moving the custom metadata"
+        );
+
+        // Moving the wrapper doesn't invalidate the FFI representation because it's built on each
+        // access.
+        let moved_wrapper = Box::new(uc_custom);
+        assert_eq!(
+            ffi_entries(&moved_wrapper.as_ffi_custom_metadata()),
+            expected,
+            "entries after moving the wrapper"
+        );
+
+        let empty = Custom::default();
+        let c_empty = empty.to_ffi_custom_metadata().as_ffi_custom_metadata();
+        assert_eq!(c_empty.count, 0, "empty count");
+        assert!(c_empty.entries.is_null(), "empty entries");
+    }
+
+    /// Returns the custom metadata of the golden vector of [`Custom::canonical_bytes`].
+    fn golden_custom() -> Custom {
+        let mut custom = Custom::default();
+        custom.insert(String::from("content-type"), String::from("text/plain"));
+        custom.insert(String::from("app:ñ"), String::from("✓"));
+        custom.insert(String::from("a"), String::new());
+        custom
+    }
+
+    #[test]
+    fn test_custom_canonical_bytes() {
+        // The encoding must never change because applications store signatures computed from it.
+        assert_eq!(
+            helpers::hex(&golden_custom().canonical_bytes()),
+            concat!(
+                "0000000000000001",
+                "61",
+                "0000000000000000",
+                "0000000000000006",
+                "6170703ac3b1",
+                "0000000000000003",
+                "e29c93",
+                "000000000000000c",
+                "636f6e74656e742d74797065",
+                "000000000000000a",
+                "746578742f706c61696e",
+            ),
+            "golden vector"
+        );
+        assert!(
+            Custom::default().canonical_bytes().is_empty(),
+            "empty custom metadata"
+        );
+
+        let mut reordered = Custom::with_capacity(10);
+        reordered.insert(String::from("a"), String::new());
+        reordered.insert(String::from("content-type"), String::from("text/plain"));
+        reordered.insert(String::from("app:ñ"), String::from("✓"));
+        assert_eq!(
+            reordered.canonical_bytes(),
+            golden_custom().canonical_bytes(),
+            "insertion order"
+        );
+
+        let mut raw = Custom::default();
+        raw.insert_bytes(vec![b'k', 0xff], vec![0xfe]);
+        assert_eq!(
+            helpers::hex(&raw.canonical_bytes()),
+            "00000000000000026bff0000000000000001fe",
+            "original bytes"
+        );
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_custom_digest_sha256() {
+        assert_eq!(
+            helpers::hex(&golden_custom().digest_sha256()),
+            "cd772cf8d5fed89b3f332b6d7b919b55a5fa94f51749eb8d742ed4df0ec2673e",
+            "golden vector"
+        );
+        assert_eq!(
+            helpers::hex(&Custom::default().digest_sha256()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "empty custom metadata"
+        );
+    }
+
+    #[test]
+    fn test_custom_eq() {
+        let mut other = Custom::with_capacity(10);
+        other.insert(String::from("a"), String::new());
+        other.insert(String::from("app:ñ"), String::from("✓"));
+        other.insert(String::from("content-type"), String::from("text/plain"));
+        assert_eq!(golden_custom(), other, "insertion order");
+
+        other.insert(String::from("a"), String::from("b"));
+        assert_ne!(golden_custom(), other, "different value");
+        other.insert(String::from("a"), String::new());
+        other.insert(String::from("b"), String::new());
+        assert_ne!(golden_custom(), other, "extra entry");
+
+        let mut raw = Custom::default();
+        raw.insert_bytes(b"key".to_vec(), vec![0xff]);
+        let mut lossy = Custom::default();
+        lossy.insert(String::from("key"), String::from("\u{fffd}"));
+        assert_ne!(raw, lossy, "original bytes");
+    }
+
+    #[test]
+    fn test_custom_validate() {
+        let limits = MetadataLimits {
+            max_total_size: 10,
+            max_key_length: 4,
+        };
+
+        {
+            // Empty.
+            let custom = Custom::default();
+            custom.validate(&limits).expect("empty");
+        }
+        {
+            // Below the limits.
+            let mut custom = Custom::default();
+            custom.insert(String::from("abc"), String::from("de"));
+            custom.insert(String::from("f"), String::from("g"));
+            custom.validate(&limits).expect("below the limits");
+        }
+        {
+            // Exactly at the limits.
+            let mut custom = Custom::default();
+            custom.insert(String::from("abcd"), String::from("e"));
+            custom.insert(String::from("fghi"), String::from("j"));
+            custom.validate(&limits).expect("at the limits");
+        }
+        {
+            // Key above the limit.
+            let mut custom = Custom::default();
+            custom.insert(String::from("a"), String::from("b"));
+            custom.insert(String::from("abcde"), String::from(""));
+
+            if let Error::InvalidArguments(args) =
+                custom.validate(&limits).expect_err("key above the limit")
+            {
+                assert_eq!(args.names, "metadata", "invalid argument name");
+                assert!(args.msg.contains("'abcde'"), "offending key: {}", args.msg);
+            } else {
+                panic!("expected an invalid arguments error");
+            }
+        }
+        {
+            // Total size above the limit.
+            let mut custom = Custom::default();
+            custom.insert(String::from("abcd"), String::from("e"));
+            custom.insert(String::from("fghi"), String::from("jk"));
+
+            if let Error::InvalidArguments(args) = custom
+                .validate(&limits)
+                .expect_err("total size above the limit")
+            {
+                assert_eq!(args.names, "metadata", "invalid argument name");
+                assert!(args.msg.contains("'fghi'"), "offending key: {}", args.msg);
+            } else {
+                panic!("expected an invalid arguments error");
+            }
+        }
+    }
+
+    #[test]
+    fn test_metadata_patch() {
+        let mut custom = Custom::default();
+        custom.insert(String::from("keep"), String::from("v-keep"));
+        custom.insert(String::from("update"), String::from("v-update"));
+        custom.insert(String::from("remove"), String::from("v-remove"));
+
+        let mut patch = MetadataPatch::new();
+        assert!(patch.is_empty(), "new patch is empty");
+        patch
+            .upsert(String::from("update"), String::from("v-updated"))
+            .upsert(String::from("new"), String::from("v-new"))
+            .remove(String::from("remove"))
+            .remove(String::from("unexisting"))
+            .upsert(String::from("upsert-then-remove"), String::from("v"))
+            .remove(String::from("upsert-then-remove"))
+            .remove(String::from("keep"))
+            .upsert(String::from("keep"), String::from("v-keep"));
+        assert!(!patch.is_empty(), "patch with changes isn't empty");
+
+        patch.apply(&mut custom);
+        assert_eq!(custom.count(), 3, "count");
+        assert_eq!(custom.get("keep"), Some(&String::from("v-keep")), "keep");
+        assert_eq!(
+            custom.get("update"),
+            Some(&String::from("v-updated")),
+            "update"
+        );
+        assert_eq!(custom.get("new"), Some(&String::from("v-new")), "new");
+        assert_eq!(custom.get("remove"), None, "remove");
+        assert_eq!(custom.get("upsert-then-remove"), None, "upsert then remove");
+    }
+
     #[test]
     fn test_system_with_ffi_system_metadata() {
         {
@@ -523,7 +1441,8 @@ mod test {
             );
             assert_eq!(sysm.expires, None, "zero expires");
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -548,7 +1467,8 @@ mod test {
                 "positive expires"
             );
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -573,7 +1493,8 @@ mod test {
                 "positive expires before created"
             );
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -598,7 +1519,8 @@ mod test {
                 "positive expires"
             );
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "zero content length"
             );
         }
@@ -622,10 +1544,7 @@ mod test {
                 Some(Duration::from_secs(uc_sysm.expires as u64)),
                 "positive expires"
             );
-            assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
-                "negative content length"
-            );
+            assert_eq!(sysm.content_length, None, "negative content length");
         }
 
         {
@@ -644,7 +1563,8 @@ mod test {
                 "positive expires"
             );
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -665,7 +1585,8 @@ mod test {
                 "positive expires"
             );
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -686,7 +1607,8 @@ mod test {
             );
             assert_eq!(sysm.expires, None, "zero expires");
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
@@ -707,9 +1629,38 @@ mod test {
             );
             assert_eq!(sysm.expires, None, "negative expires");
             assert_eq!(
-                sysm.content_length, uc_sysm.content_length,
+                sysm.content_length,
+                Some(uc_sysm.content_length as u64),
                 "positive content length"
             );
         }
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_system_datetimes() {
+        let system = System {
+            created: Duration::ZERO,
+            expires: None,
+            content_length: None,
+        };
+        assert_eq!(system.created_at().timestamp(), 0, "zero created");
+        assert_eq!(system.expires_at(), None, "never expires");
+
+        let system = System {
+            created: Duration::from_secs(1_714_564_800),
+            expires: Some(Duration::from_secs(1_717_243_200)),
+            content_length: None,
+        };
+        assert_eq!(
+            system.created_at().to_rfc3339(),
+            "2024-05-01T12:00:00+00:00",
+            "created"
+        );
+        assert_eq!(
+            system.expires_at().map(|e| e.to_rfc3339()),
+            Some(String::from("2024-06-01T12:00:00+00:00")),
+            "expires"
+        );
+    }
 }
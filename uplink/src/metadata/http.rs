@@ -0,0 +1,203 @@
+//! Typed access to the custom metadata entries that carry HTTP headers.
+//!
+//! The Storj linksharing service and the S3 compatible gateway serve the objects with the
+//! `Content-Type`, `Cache-Control` and `Content-Encoding` headers taken from these custom
+//! metadata entries, so they must be set with the exact keys that those services expect.
+//!
+//! ```
+//! use uplink::metadata::Custom;
+//!
+//! let mut custom = Custom::default();
+//! custom.set_content_type("text/html; charset=utf-8")?;
+//! assert_eq!(custom.content_type(), Some("text/html; charset=utf-8"));
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use super::Custom;
+use crate::{Error, Result};
+
+/// The custom metadata key of the `Content-Type` header.
+pub const KEY_CONTENT_TYPE: &str = "content-type";
+
+/// The custom metadata key of the `Cache-Control` header.
+pub const KEY_CACHE_CONTROL: &str = "cache-control";
+
+/// The custom metadata key of the `Content-Encoding` header.
+pub const KEY_CONTENT_ENCODING: &str = "content-encoding";
+
+/// The reserved custom metadata key where the S3 compatible gateway records the entity tag of the
+/// objects, which is the value of their `ETag` header.
+pub const KEY_ETAG: &str = "s3:etag";
+
+/// The custom metadata keys of the HTTP headers paired with the canonical names of the headers.
+pub(crate) const HEADERS: [(&str, &str); 3] = [
+    (KEY_CONTENT_TYPE, "Content-Type"),
+    (KEY_CACHE_CONTROL, "Cache-Control"),
+    (KEY_CONTENT_ENCODING, "Content-Encoding"),
+];
+
+impl Custom {
+    /// Sets the MIME type of the object's content (e.g. `image/png`) under the
+    /// [`KEY_CONTENT_TYPE`] key.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `mime` isn't a valid header value, see
+    /// [`validate_header_value`].
+    pub fn set_content_type(&mut self, mime: &str) -> Result<()> {
+        self.insert_header("mime", KEY_CONTENT_TYPE, mime)
+    }
+
+    /// Returns the MIME type of the object's content or `None` if it isn't set.
+    pub fn content_type(&self) -> Option<&str> {
+        self.get(KEY_CONTENT_TYPE).map(String::as_str)
+    }
+
+    /// Sets the caching directives of the object (e.g. `max-age=3600`) under the
+    /// [`KEY_CACHE_CONTROL`] key.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `directives` isn't a valid header value, see
+    /// [`validate_header_value`].
+    pub fn set_cache_control(&mut self, directives: &str) -> Result<()> {
+        self.insert_header("directives", KEY_CACHE_CONTROL, directives)
+    }
+
+    /// Returns the caching directives of the object or `None` if they aren't set.
+    pub fn cache_control(&self) -> Option<&str> {
+        self.get(KEY_CACHE_CONTROL).map(String::as_str)
+    }
+
+    /// Sets the encodings applied to the object's content (e.g. `gzip`) under the
+    /// [`KEY_CONTENT_ENCODING`] key.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `encoding` isn't a valid header value, see
+    /// [`validate_header_value`].
+    pub fn set_content_encoding(&mut self, encoding: &str) -> Result<()> {
+        self.insert_header("encoding", KEY_CONTENT_ENCODING, encoding)
+    }
+
+    /// Returns the encodings applied to the object's content or `None` if they aren't set.
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.get(KEY_CONTENT_ENCODING).map(String::as_str)
+    }
+
+    /// Inserts the entry with `key` and `value` after validating that `value`, which is the
+    /// `arg_name` function argument, is a valid header value.
+    fn insert_header(&mut self, arg_name: &str, key: &str, value: &str) -> Result<()> {
+        validate_header_value(arg_name, value)?;
+        self.insert(String::from(key), String::from(value));
+        Ok(())
+    }
+}
+
+/// Validates that `value`, which is the `arg_name` function argument, is a valid HTTP header
+/// value.
+///
+/// It returns an [`Error::InvalidArguments`] if `value` is empty, starts or ends with whitespace or
+/// it contains characters other than visible ASCII characters, spaces and horizontal tabs, which
+/// could break the headers of the responses (e.g. line breaks).
+pub fn validate_header_value(arg_name: &str, value: &str) -> Result<()> {
+    if value.is_empty() {
+        return Err(Error::new_invalid_arguments(arg_name, "cannot be empty"));
+    }
+
+    if value.starts_with([' ', '\t']) || value.ends_with([' ', '\t']) {
+        return Err(Error::new_invalid_arguments(
+            arg_name,
+            "cannot start or end with whitespace",
+        ));
+    }
+
+    if let Some(i) = value
+        .bytes()
+        .position(|b| !(b.is_ascii_graphic() || b == b' ' || b == b'\t'))
+    {
+        return Err(Error::new_invalid_arguments(
+            arg_name,
+            &format!("contains an invalid header value character at {}", i),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_custom_http_setters() {
+        let mut custom = Custom::default();
+        assert_eq!(custom.content_type(), None, "content type not set");
+        assert_eq!(custom.cache_control(), None, "cache control not set");
+        assert_eq!(custom.content_encoding(), None, "content encoding not set");
+
+        custom
+            .set_content_type("text/html; charset=utf-8")
+            .expect("set content type");
+        custom
+            .set_cache_control("public, max-age=3600")
+            .expect("set cache control");
+        custom
+            .set_content_encoding("gzip")
+            .expect("set content encoding");
+
+        assert_eq!(custom.count(), 3, "number of entries");
+        assert_eq!(
+            custom.get("content-type").map(String::as_str),
+            Some("text/html; charset=utf-8"),
+            "content type key"
+        );
+        assert_eq!(
+            custom.get("cache-control").map(String::as_str),
+            Some("public, max-age=3600"),
+            "cache control key"
+        );
+        assert_eq!(
+            custom.get("content-encoding").map(String::as_str),
+            Some("gzip"),
+            "content encoding key"
+        );
+        assert_eq!(
+            custom.content_type(),
+            Some("text/html; charset=utf-8"),
+            "content type"
+        );
+        assert_eq!(
+            custom.cache_control(),
+            Some("public, max-age=3600"),
+            "cache control"
+        );
+        assert_eq!(custom.content_encoding(), Some("gzip"), "content encoding");
+
+        custom.set_content_type("image/png").expect("replace");
+        assert_eq!(custom.content_type(), Some("image/png"), "replaced");
+        assert_eq!(custom.count(), 3, "number of entries after replacing");
+    }
+
+    #[test]
+    fn test_custom_http_setters_invalid_value() {
+        let mut custom = Custom::default();
+        for value in [
+            "",
+            " text/html",
+            "text/html\t",
+            "text/html\r\nX: y",
+            "text/hétml",
+        ] {
+            match custom.set_content_type(value) {
+                Err(Error::InvalidArguments(args)) => {
+                    assert_eq!(args.names, "mime", "{:?}: invalid argument name", value)
+                }
+                res => panic!("{:?}: unexpected result: {:?}", value, res),
+            }
+        }
+
+        assert_eq!(custom.count(), 0, "no entries inserted");
+    }
+
+    #[test]
+    fn test_validate_header_value() {
+        for value in ["gzip", "a", "max-age=0, must-revalidate", "a\tb", "~!\"#"] {
+            validate_header_value("value", value).expect(value);
+        }
+    }
+}
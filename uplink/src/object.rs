@@ -1,19 +1,56 @@
 //! Storj DCS Object and related types.
 
+pub mod buffered;
+pub mod checkpoint;
+pub mod filter;
+pub mod head;
+pub mod key;
 pub mod upload;
 
+pub use buffered::BufferedUpload;
+pub use checkpoint::ListingCheckpoint;
+pub use filter::{FilteredIterator, KeyFilter};
+pub use head::HeadObject;
+pub use key::Key;
 pub use upload::Upload;
 
-use crate::error::BoxError;
-use crate::uplink_c::Ensurer;
-use crate::{error, metadata, Error, Result};
+use crate::error::{BoxError, UplinkCode};
+use crate::uplink_c::{ffi_guard, run_with_timeout, OperationFlag, TryEnsure};
+use crate::{error, io_util, metadata, Error, Result};
 
 use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use uplink_sys as ulksys;
 
 /// Contains information about an object.
+///
+/// The objects yielded by the listings only have the metadata that the listing options request,
+/// see [`Self::has_system_metadata`] and [`Self::has_custom_metadata`].
+///
+/// # Migrating from the zero values
+///
+/// The listed objects whose metadata wasn't requested had zeroed metadata (e.g. a `created` time
+/// equal to [`Duration::ZERO`](std::time::Duration::ZERO)) that was indistinguishable from
+/// retrieved metadata with zero values, so the checks of zero values have to check if the metadata
+/// was retrieved instead:
+///
+/// ```no_run
+/// use uplink::Object;
+///
+/// fn created_secs(obj: &Object) -> Option<u64> {
+///     // Before: (obj.metadata_system.created != Duration::ZERO)
+///     //     .then(|| obj.metadata_system.created.as_secs())
+///     obj.has_system_metadata()
+///         .then(|| obj.metadata_system.created.as_secs())
+/// }
+/// ```
+///
+/// Because of the fields that indicate it, this type cannot be constructed outside of this crate.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Object {
     /// The identifier of the object inside of the bucket which it belongs.
     pub key: String,
@@ -23,9 +60,43 @@ pub struct Object {
     pub metadata_system: metadata::System,
     /// The custom metadata associated with the object.
     pub metadata_custom: metadata::Custom,
+    /// Indicates if `metadata_system` was retrieved.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) has_system: bool,
+    /// Indicates if `metadata_custom` was retrieved.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) has_custom: bool,
 }
 
 impl Object {
+    /// Returns true if [`Self::metadata_system`] was retrieved, otherwise it has default values
+    /// that don't reflect the object's system metadata.
+    ///
+    /// The objects yielded by the listings (e.g.
+    /// [`Project::list_objects`](crate::Project::list_objects)) only have it when the listing
+    /// options request it (see
+    /// [`ListObjects::system`](crate::project::options::ListObjects::system)) and the ones
+    /// returned by the rest of operations (e.g.
+    /// [`Project::stat_object`](crate::Project::stat_object)) always have it. The prefixes never
+    /// have it.
+    pub fn has_system_metadata(&self) -> bool {
+        self.has_system
+    }
+
+    /// Returns true if [`Self::metadata_custom`] was retrieved, otherwise it's empty regardless
+    /// of the object's custom metadata.
+    ///
+    /// The objects yielded by the listings (e.g.
+    /// [`Project::list_objects`](crate::Project::list_objects)) only have it when the listing
+    /// options request it (see
+    /// [`ListObjects::custom`](crate::project::options::ListObjects::custom)) and the ones
+    /// returned by the rest of operations (e.g.
+    /// [`Project::stat_object`](crate::Project::stat_object)) always have it. The prefixes never
+    /// have it.
+    pub fn has_custom_metadata(&self) -> bool {
+        self.has_custom
+    }
+
     /// Creates new instance from the FFI representation.
     ///
     /// When no error an `Option` is returned which is `None` when `uc_obj` is `NULL`. This happens
@@ -34,42 +105,86 @@ impl Object {
     /// An [`Error::Internal`](crate::Error::Internal) if `uc_obj`'s key contains invalid UTF-8
     /// characters or [`metadata::Custom::with_ffi_custom_metadata`] return an error.
     fn from_ffi_object(uc_obj: *mut ulksys::UplinkObject) -> Result<Option<Self>> {
+        Self::convert_ffi_object(uc_obj, Self::unchecked_from_ffi_object_ref)
+    }
+
+    /// Returns the key of the object of the FFI representation without converting its metadata.
+    ///
+    /// See [`from_ffi_object`](Self::from_ffi_object) why an `Option` is returned and the
+    /// returned errors.
+    fn key_from_ffi_object(uc_obj: *mut ulksys::UplinkObject) -> Result<Option<String>> {
+        Self::convert_ffi_object(uc_obj, Self::unchecked_ffi_object_key)
+    }
+
+    /// Converts the object of the FFI representation with `convert` and frees it.
+    ///
+    /// It returns `None` when `uc_obj` is `NULL`, the error returned by `convert` and an
+    /// [`Error::Internal`] if `convert` panics.
+    fn convert_ffi_object<T>(
+        uc_obj: *mut ulksys::UplinkObject,
+        convert: unsafe fn(&ulksys::UplinkObject) -> Result<T>,
+    ) -> Result<Option<T>> {
         if uc_obj.is_null() {
             return Ok(None);
         }
 
-        let uc_obj_ptr = uc_obj;
         // SAFETY: We have checked just above that the pointer isn't NULL.
-        let uc_obj = unsafe { *uc_obj_ptr };
-        uc_obj.ensure();
-
-        let key;
-        let is_prefix;
-        let metadata_system: metadata::System;
-        let metadata_custom: metadata::Custom;
-        // SAFETY: we have check that the `uc_obj` doesn't have fields with NULL pointers through
-        // the `ensure` method.
-        unsafe {
-            let cs = CString::from(CStr::from_ptr(uc_obj.key));
-            key = cs.into_string().map_err(|err| {
-                ulksys::uplink_free_object(uc_obj_ptr);
-                Error::new_internal(
-                    "FFI returned an invalid object's key; it contains invalid UTF-8 characters",
-                    BoxError::from(err),
-                )
-            })?;
-            metadata_custom = metadata::Custom::with_ffi_custom_metadata(&uc_obj.custom);
-            metadata_system = metadata::System::with_ffi_system_metadata(&uc_obj.system);
-            is_prefix = uc_obj.is_prefix;
-            ulksys::uplink_free_object(uc_obj_ptr);
-        }
-
-        Ok(Some(Self {
-            key,
-            is_prefix,
-            metadata_system,
-            metadata_custom,
-        }))
+        let uc_obj_val = unsafe { *uc_obj };
+        if let Err(err) = uc_obj_val.try_ensure() {
+            // SAFETY: we trust the FFI is safe freeing the memory of a pointer that it returned.
+            unsafe { ulksys::uplink_free_object(uc_obj) };
+            return Err(err);
+        }
+
+        // SAFETY: we have check that the `uc_obj_val` doesn't have fields with NULL pointers
+        // through the `try_ensure` method. The conversion is guarded for freeing the memory even
+        // if it panics.
+        let res = ffi_guard("FFI returned an invalid object", || unsafe {
+            convert(&uc_obj_val)
+        });
+        // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer and `res` doesn't
+        // reference any memory of it.
+        unsafe { ulksys::uplink_free_object(uc_obj) };
+
+        res.map(Some)
+    }
+
+    /// Creates new instance from a reference to the FFI representation without freeing it. The
+    /// instance indicates that its metadata was retrieved.
+    ///
+    /// An [`Error::Internal`](crate::Error::Internal) if `uc_obj`'s key contains invalid UTF-8
+    /// characters or [`metadata::Custom::with_ffi_custom_metadata`] return an error.
+    ///
+    /// # Safety
+    ///
+    /// `uc_obj` must not have fields with NULL pointers.
+    unsafe fn unchecked_from_ffi_object_ref(uc_obj: &ulksys::UplinkObject) -> Result<Self> {
+        Ok(Self {
+            key: Self::unchecked_ffi_object_key(uc_obj)?,
+            is_prefix: uc_obj.is_prefix,
+            metadata_system: metadata::System::with_ffi_system_metadata(&uc_obj.system),
+            metadata_custom: metadata::Custom::with_ffi_custom_metadata(&uc_obj.custom)?,
+            has_system: true,
+            has_custom: true,
+        })
+    }
+
+    /// Returns the key of a reference to the FFI representation without freeing it.
+    ///
+    /// An [`Error::Internal`](crate::Error::Internal) if `uc_obj`'s key contains invalid UTF-8
+    /// characters.
+    ///
+    /// # Safety
+    ///
+    /// `uc_obj.key` must not be NULL.
+    unsafe fn unchecked_ffi_object_key(uc_obj: &ulksys::UplinkObject) -> Result<String> {
+        let cs = CString::from(CStr::from_ptr(uc_obj.key));
+        cs.into_string().map_err(|err| {
+            Error::new_internal(
+                "FFI returned an invalid object's key; it contains invalid UTF-8 characters",
+                BoxError::from(err),
+            )
+        })
     }
 
     /// Creates a new instance from the FFI representation for an object's result.
@@ -105,12 +220,12 @@ impl Object {
     /// * an [`Error::new_uplink` constructor](crate::Error::new_uplink), if `uc_result` contains a
     ///   non `NULL` pointer in the `error` field.
     /// * an [`Error::Internal`](crate::Error::Internal) if `uc_result.object`'s key contains
-    ///   invalid UTF-8 characters or [`metadata::Custom::with_ffi_custom_metadata`] return an
-    ///   error.
+    ///   invalid UTF-8 characters, [`metadata::Custom::with_ffi_custom_metadata`] return an
+    ///   error or the conversion panics.
     pub(crate) fn from_ffi_commit_upload_result(
         uc_result: ulksys::UplinkCommitUploadResult,
     ) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
@@ -122,8 +237,67 @@ impl Object {
         // the `info` pointer and the `error` pointer is `NULL`, and that's what the free function
         // for the `uc_result` does (i.e. call a free specific function for each pointer returning
         // without doing anything if it's `NULL`).
-        Self::from_ffi_object(uc_result.object)
-            .map(|op| op.expect("successful committed upload must always return an object"))
+        ffi_guard("FFI returned an invalid commit upload result", || {
+            Self::from_ffi_object(uc_result.object)
+                .map(|op| op.expect("successful committed upload must always return an object"))
+        })
+    }
+}
+
+impl std::fmt::Display for Object {
+    /// Formats the key followed by the system metadata as [`metadata::System`] does, when it was
+    /// retrieved (e.g. `dir/file.txt, 1234 bytes, created 2024-05-01T12:00:00Z`), or followed by
+    /// `(prefix)` if it's a prefix.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_prefix {
+            write!(f, "{} (prefix)", self.key)
+        } else if self.has_system {
+            write!(f, "{}, {}", self.key, self.metadata_system)
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+/// The outcome of deleting an object, see
+/// [`Project::delete_object_outcome`](crate::Project::delete_object_outcome).
+///
+/// Which variant is returned depends on the permissions of the access grant used for opening the
+/// project and on how the satellite reports missing objects.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum DeleteOutcome {
+    /// The object existed and it was deleted. It's returned when the access grant has permission
+    /// to delete and to read (i.e. list or download) the object, so the satellite returns the
+    /// information of the deleted object.
+    Deleted(Object),
+    /// The object was deleted but the satellite didn't return its information. It's returned when
+    /// the access grant has permission to delete the object but not to read it (e.g. a write-only
+    /// access grant).
+    ///
+    /// Satellites that delete objects idempotently also return it when the object didn't exist,
+    /// independently of the permissions of the access grant, because they don't report the
+    /// missing object.
+    DeletedNoMetadata,
+    /// The object didn't exist. It's returned when the satellite reports the missing object with
+    /// an [`UplinkCode::ObjectNotFound`] error, which the FFI only does when the access grant
+    /// has permission to read the object.
+    NotFound,
+}
+
+impl DeleteOutcome {
+    /// Creates an outcome from the result of
+    /// [`Project::delete_object`](crate::Project::delete_object), folding the
+    /// [`UplinkCode::ObjectNotFound`] error into [`Self::NotFound`].
+    pub(crate) fn from_delete_object_result(res: Result<Option<Object>>) -> Result<Self> {
+        match res {
+            Ok(Some(o)) => Ok(Self::Deleted(o)),
+            Ok(None) => Ok(Self::DeletedNoMetadata),
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {
+                Ok(Self::NotFound)
+            }
+            Err(err) => Err(err),
+        }
     }
 }
 
@@ -133,39 +307,198 @@ pub struct Iterator {
     /// The object iterator type of the FFI that an instance of this struct represents and guards
     /// its lifetime until the instance drops.
     inner: *mut ulksys::UplinkObjectIterator,
+    /// The key of the last yielded object.
+    last_seen_key: Option<String>,
+    /// The number of objects that the iterator can still yield. `None` when there isn't any
+    /// limit.
+    remaining: Option<usize>,
+    /// The number of yielded objects.
+    yielded_count: usize,
+    /// Indicates that the FFI iterator has finished, successfully or with an error.
+    finished: bool,
+    /// The error that finished the FFI iterator.
+    error: Option<Error>,
+    /// The checkpoint of the start of the listing.
+    start: ListingCheckpoint,
 }
 
 impl Iterator {
     /// Creates a new instance from the type exposed by the FFI.
-    pub(crate) fn from_ffi_object_iterator(uc_iterator: *mut ulksys::UplinkObjectIterator) -> Self {
-        assert!(
-            !uc_iterator.is_null(),
-            "BUG: `uc_iterator` argument cannot be NULL"
-        );
+    ///
+    /// It returns the following errors:
+    /// * an [`Error::Internal`](crate::Error::Internal) if `uc_iterator` is `NULL`.
+    /// * an [`Error::from_ffi_error` constructor](crate::Error::from_ffi_error), if the FFI
+    ///   iterator is in an error state, in which case the FFI iterator is freed.
+    pub(crate) fn from_ffi_object_iterator(
+        uc_iterator: *mut ulksys::UplinkObjectIterator,
+    ) -> Result<Self> {
+        if uc_iterator.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL object iterator",
+                BoxError::from("NULL pointer"),
+            ));
+        }
 
-        Iterator { inner: uc_iterator }
+        // SAFETY: we have checked above that the pointer isn't `NULL` and we trust the FFI
+        // functions don't panic when called with an instance returned by them.
+        let uc_error = unsafe { ulksys::uplink_object_iterator_err(uc_iterator) };
+        if let Some(err) = Error::from_ffi_error(uc_error) {
+            // SAFETY: we trust the FFI is safe freeing the memory of a correct pointer.
+            unsafe { ulksys::uplink_free_object_iterator(uc_iterator) };
+            return Err(err);
+        }
+
+        Ok(Iterator {
+            inner: uc_iterator,
+            last_seen_key: None,
+            remaining: None,
+            yielded_count: 0,
+            finished: false,
+            error: None,
+            start: ListingCheckpoint::default(),
+        })
     }
-}
 
-impl std::iter::Iterator for Iterator {
-    type Item = Result<Object>;
+    /// Sets the maximum number of objects that the iterator yields. `None` means no limit.
+    pub(crate) fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.remaining = limit;
+        self
+    }
+
+    /// Sets the checkpoint of the start of the listing, which [`Self::checkpoint`] advances.
+    pub(crate) fn with_checkpoint(mut self, start: ListingCheckpoint) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Returns a checkpoint for resuming the listing after the last object yielded by this
+    /// iterator with [`Project::resume_listing`](crate::Project::resume_listing), or from where
+    /// this iterator started if it hasn't yielded any yet.
+    ///
+    /// It can be called at any time, for example after every thousand objects, for persisting the
+    /// progress of the listing, see [`checkpoint`].
+    pub fn checkpoint(&self) -> ListingCheckpoint {
+        match &self.last_seen_key {
+            Some(key) => self.start.after(key),
+            None => self.start.clone(),
+        }
+    }
+
+    /// Returns the key of the last object yielded by this iterator or `None` if it hasn't yielded
+    /// any yet.
+    ///
+    /// It can be passed, with the same prefix used for listing, to
+    /// [`ListObjects::with_cursor`](crate::project::options::ListObjects::with_cursor) or
+    /// [`ListObjects::with_prefix_and_cursor`](crate::project::options::ListObjects::with_prefix_and_cursor)
+    /// for resuming the listing after this object with a new iterator.
+    pub fn last_seen_key(&self) -> Option<&str> {
+        self.last_seen_key.as_deref()
+    }
+
+    /// Returns the number of objects yielded by this iterator without counting the yielded
+    /// errors.
+    pub fn yielded_count(&self) -> usize {
+        self.yielded_count
+    }
+
+    /// Returns the error that finished the iterator or `None` if it hasn't finished or it
+    /// finished because there aren't more objects or the limit was reached.
+    ///
+    /// It allows to know if a `for` loop over the iterator ended because of an error without
+    /// collecting the items. The same error is yielded as the last item of the iterator.
+    pub fn finished_with_error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Returns an iterator that only yields the objects whose key matches `filter` and the
+    /// errors.
+    ///
+    /// The filter is applied client-side to the objects retrieved by this iterator. Use
+    /// [`Project::list_objects_filtered`](crate::Project::list_objects_filtered) for also
+    /// narrowing the listing to [`KeyFilter::listing_prefix`] server-side.
+    pub fn filter_keys(self, filter: KeyFilter) -> FilteredIterator {
+        FilteredIterator::new(self, filter, None)
+    }
+
+    /// Retrieves the next item from the FFI iterator converting it with `convert` and records it,
+    /// taking the object's key from the converted item with `key`.
+    fn next_with<T>(
+        &mut self,
+        convert: fn(*mut ulksys::UplinkObject) -> Result<Option<T>>,
+        key: fn(&T) -> &str,
+    ) -> Option<Result<T>> {
+        if self.finished {
+            return None;
+        }
+
+        match self.remaining {
+            Some(0) => return None,
+            Some(n) => self.remaining = Some(n - 1),
+            None => {}
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
         // SAFETY: we trust that the FFI functions don't panic when called with an instance returned
         // by them and they don't return any invalid memory references or `null` if next returns
         // `true`.
         unsafe {
             if !ulksys::uplink_object_iterator_next(self.inner) {
                 let uc_error = ulksys::uplink_object_iterator_err(self.inner);
-                return Error::new_uplink(uc_error).map(Err);
+                return self.track_end(uc_error);
             }
 
-            Some(
-                Object::from_ffi_object(ulksys::uplink_object_iterator_item(self.inner)).map(
-                    |op| op.expect("an iterator that indicated that there is a next element always returns it")
-                ),
-            )
+            // `convert` frees the item before `expect` may panic, so nothing leaks.
+            let uc_obj = ulksys::uplink_object_iterator_item(self.inner);
+            let res = ffi_guard("FFI returned an invalid object iterator item", || {
+                convert(uc_obj).map(|op| {
+                    op.expect(
+                        "an iterator that indicated that there is a next element always returns it",
+                    )
+                })
+            });
+            Some(self.track_item(res, key))
+        }
+    }
+
+    /// Records the result of an item retrieved from the FFI iterator and returns it.
+    fn track_item<T>(&mut self, res: Result<T>, key: fn(&T) -> &str) -> Result<T> {
+        if let Ok(item) = &res {
+            self.last_seen_key = Some(String::from(key(item)));
+            self.yielded_count += 1;
         }
+
+        res
+    }
+
+    /// Records that the FFI iterator has finished with the error `uc_error`, if it isn't `NULL`,
+    /// and returns it. It takes ownership of `uc_error`.
+    fn track_end<T>(&mut self, uc_error: *mut ulksys::UplinkError) -> Option<Result<T>> {
+        self.finished = true;
+        let err = error::Uplink::from_ffi_error(uc_error)?;
+        self.error = Some(Error::Uplink(err.clone()));
+        Some(Err(Error::Uplink(err)))
+    }
+}
+
+impl std::iter::Iterator for Iterator {
+    type Item = Result<Object>;
+
+    /// An object whose key contains invalid UTF-8 characters, which other clients may upload, is
+    /// returned as an [`Error::Internal`](crate::Error::Internal) and the iteration can continue
+    /// with the next objects. It always returns `None` after returning an
+    /// [`Error::Uplink`](crate::Error::Uplink).
+    ///
+    /// The yielded objects only have the metadata that the listing options request and the
+    /// yielded prefixes never have it.
+    fn next(&mut self) -> Option<Self::Item> {
+        let (system, custom) = (self.start.system(), self.start.custom());
+        self.next_with(Object::from_ffi_object, |o| &o.key)
+            .map(|res| {
+                res.map(|mut obj| {
+                    obj.has_system = system && !obj.is_prefix;
+                    obj.has_custom = custom && !obj.is_prefix;
+                    obj
+                })
+            })
     }
 }
 
@@ -179,7 +512,57 @@ impl Drop for Iterator {
     }
 }
 
+/// Iterates over a collection of objects' keys without converting their metadata, see
+/// [`Project::list_objects_keys`](crate::Project::list_objects_keys).
+#[derive(Debug)]
+pub struct KeyIterator {
+    /// The objects' iterator that retrieves the keys.
+    inner: Iterator,
+}
+
+impl KeyIterator {
+    /// Creates a new instance that yields the keys of the objects of `inner`.
+    pub(crate) fn new(inner: Iterator) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the last key yielded by this iterator or `None` if it hasn't yielded any yet.
+    ///
+    /// See [`Iterator::last_seen_key`].
+    pub fn last_seen_key(&self) -> Option<&str> {
+        self.inner.last_seen_key()
+    }
+
+    /// Returns the number of keys yielded by this iterator without counting the yielded errors.
+    pub fn yielded_count(&self) -> usize {
+        self.inner.yielded_count()
+    }
+
+    /// Returns the error that finished the iterator, see [`Iterator::finished_with_error`].
+    pub fn finished_with_error(&self) -> Option<&Error> {
+        self.inner.finished_with_error()
+    }
+}
+
+impl std::iter::Iterator for KeyIterator {
+    type Item = Result<String>;
+
+    /// It always returns `None` after returning an [`Error::Uplink`](crate::Error::Uplink).
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_with(Object::key_from_ffi_object, String::as_str)
+    }
+}
+
 /// Represents a download object operation from Storj DCS network.
+///
+/// Dropping it closes the download without blocking the calling thread for longer than
+/// [`Self::DROP_CLOSE_TIMEOUT`], see [`Self::close`] for closing it synchronously.
+///
+/// A download supports a single operation at a time, even when it's sent to another thread. Its
+/// [`std::io::Read`] and [`std::io::BufRead`] implementations return an error of kind
+/// [`std::io::ErrorKind::WouldBlock`] instead of calling the FFI if they are reentered while
+/// another read is in progress, which can only happen when the borrowing rules are circumvented.
 #[derive(Debug)]
 pub struct Download {
     /// The download type of the FFI than an instance of this struct represents and guards its
@@ -187,9 +570,41 @@ pub struct Download {
     ///
     /// It's an access result
     inner: ulksys::UplinkDownloadResult,
+    /// The buffer of the [`std::io::BufRead`] implementation.
+    buffer: ReadBuffer,
+    /// Indicates that the end of the object's data stream has been reached, so the FFI isn't
+    /// called anymore for reading.
+    eof: bool,
+    /// Indicates that the download has been closed by [`Self::close`], so it isn't closed again
+    /// when it drops.
+    closed: bool,
+    /// The number of bytes of the object's data stream read from this instance, see
+    /// [`Self::bytes_read`].
+    bytes_read: u64,
+    /// Prevents overlapping reads on `inner`.
+    operation: OperationFlag,
 }
 
 impl Download {
+    /// The maximum time that dropping an instance waits for closing the download.
+    ///
+    /// Closing the download tears down its network connections, which can block for a long time
+    /// when a connection has stalled. Dropping an instance that isn't closed closes it in a
+    /// separate thread and stops waiting for it after this time, leaving the thread to finish the
+    /// close and free the download in the background, so a stalled connection doesn't freeze the
+    /// thread that drops it at the cost of spawning a thread per drop. The abandoned closes are
+    /// counted by [`Self::abandoned_closes`].
+    pub const DROP_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Returns the number of downloads whose close has exceeded [`Self::DROP_CLOSE_TIMEOUT`] when
+    /// they were dropped, since the process started.
+    ///
+    /// The threads of the abandoned closes keep running until the FFI returns, so a growing
+    /// number indicates connections that stall when they are closed.
+    pub fn abandoned_closes() -> usize {
+        ABANDONED_DOWNLOAD_CLOSES.load(Ordering::Relaxed)
+    }
+
     /// Creates a new instance from the FFI representation.
     ///
     /// It returns an error, through the
@@ -198,7 +613,7 @@ impl Download {
     pub(crate) fn from_ffi_download_result(
         uc_result: ulksys::UplinkDownloadResult,
     ) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
@@ -206,7 +621,68 @@ impl Download {
             return Err(err);
         }
 
-        Ok(Self { inner: uc_result })
+        Ok(Self {
+            inner: uc_result,
+            buffer: ReadBuffer::new(),
+            eof: false,
+            closed: false,
+            bytes_read: 0,
+            operation: OperationFlag::default(),
+        })
+    }
+
+    /// Returns the raw FFI download of this instance for passing it to other C code that uses the
+    /// uplink-c API.
+    ///
+    /// The pointer is owned by this instance, so the caller must not close nor free it and must
+    /// not use it after this instance is dropped. Reading from it skips the data buffered by the
+    /// [`std::io::BufRead`] implementation of this instance.
+    ///
+    /// ```no_run
+    /// use uplink::access::Grant;
+    /// use uplink::Project;
+    ///
+    /// let grant = Grant::new("my-serialized-access-grant")?;
+    /// let project = Project::open(&grant);
+    /// let download = project.download_object("my-bucket", "hello.txt", None)?;
+    ///
+    /// // SAFETY: the raw download is owned by `download`, which outlives this call, and the FFI
+    /// // doesn't free it.
+    /// let res = unsafe { uplink_sys::uplink_download_info(download.as_raw()) };
+    /// // SAFETY: the result is returned by the FFI and it isn't used after freeing it.
+    /// unsafe { uplink_sys::uplink_free_object_result(res) };
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn as_raw(&self) -> *mut ulksys::UplinkDownload {
+        self.inner.download
+    }
+
+    /// Returns the number of bytes of the object's data stream read so far, which are the bytes
+    /// returned by the [`std::io::Read`] implementation and the bytes consumed through the
+    /// [`std::io::BufRead`] implementation.
+    ///
+    /// The bytes buffered by the [`std::io::BufRead`] implementation aren't counted until they are
+    /// consumed and the bytes read directly from [`Self::as_raw`] aren't counted.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Closes the download in the calling thread and returns the error that closing it may
+    /// produce, which dropping the instance discards.
+    ///
+    /// Contrary to dropping it, it blocks until the download is closed, without any time limit
+    /// (see [`Self::DROP_CLOSE_TIMEOUT`]).
+    pub fn close(mut self) -> Result<()> {
+        self.closed = true;
+        // SAFETY: we trust the FFI is behaving correctly when called with correct value. The
+        // download is closed once because `self.closed` prevents `drop` from closing it again and
+        // `drop` frees its memory when `self` goes out of scope at the end of this method.
+        let uc_err = unsafe { ulksys::uplink_close_download(self.inner.download) };
+
+        match Error::from_ffi_error(uc_err) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 
     /// Returns the last information about the object.
@@ -223,15 +699,59 @@ impl Download {
         Object::from_ffi_object(obj_res.object)
             .map(|op| op.expect("successful download object info must always return an object"))
     }
-}
 
-impl std::io::Read for Download {
-    /// Downloads the object's data stream into `buf` and return the number of downloaded bytes,
-    /// which are at most the `buf` length, when there isn't any error.
+    /// Copies the object's data stream into the Tokio asynchronous `writer` with optional
+    /// options, returning the number of copied bytes.
     ///
-    /// When it returns an error is always a [`std::io::ErrorKind::Other`] and the error payload is
-    /// an [`Error::Uplink`].
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    /// The blocking reads are performed in a Tokio blocking thread (see
+    /// [`tokio::task::spawn_blocking`]), which sends the chunks of data through a bounded channel
+    /// to be written into `writer`. An error on any side stops the other one and it's returned. It
+    /// returns an [`Error::Uplink`] when reading fails and an [`Error::Internal`] when writing
+    /// fails.
+    ///
+    /// It must be called from a Tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn copy_to_tokio<W>(
+        self,
+        writer: &mut W,
+        opts: Option<&crate::project::options::CopyToTokio>,
+    ) -> Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        let default_opts;
+        let opts = match opts {
+            Some(o) => o,
+            None => {
+                default_opts = Default::default();
+                &default_opts
+            }
+        };
+
+        copy_blocking_to_tokio(self, writer, opts.chunk_size, opts.channel_depth).await
+    }
+
+    /// Wraps this download in a [`Throttled`](io_util::Throttled) reader that limits its
+    /// throughput with `throttle`, which can be shared with other transfers for limiting them
+    /// collectively.
+    pub fn throttled(self, throttle: Arc<io_util::Throttle>) -> io_util::Throttled<Self> {
+        io_util::Throttled::new(self, throttle)
+    }
+
+    /// Downloads the object's data stream of `uc_download` into `buf` and return the number of
+    /// downloaded bytes, which are at most the `buf` length, when there isn't any error.
+    ///
+    /// It sets `eof` when the end of the data stream is reached and it returns 0 without calling
+    /// the FFI when `eof` is set.
+    fn read_ffi(
+        uc_download: *mut ulksys::UplinkDownload,
+        eof: &mut bool,
+        buf: &mut [u8],
+    ) -> std::io::Result<usize> {
+        if *eof || buf.is_empty() {
+            return Ok(0);
+        }
+
         // Retry in case that zero bytes are read but no error is returned. We retry 3 times for
         // being safe of not looping infinitely despite 1 retry should always be enough.
         // See Uplink issue: https://github.com/storj/uplink/issues/99.
@@ -240,7 +760,7 @@ impl std::io::Read for Download {
             // SAFETY: we trust the FFI of dealing with a correct `UplinkDownload` instance and an
             // allocated buffer.
             let read_res =
-                unsafe { ulksys::uplink_download_read(self.inner.download, bp.cast(), buf.len()) };
+                unsafe { ulksys::uplink_download_read(uc_download, bp.cast(), buf.len()) };
 
             if let Some(err) = Error::new_uplink(read_res.error) {
                 // According to the Uplink C bindings version that we are targeting v1.7.0 all the
@@ -252,12 +772,12 @@ impl std::io::Read for Download {
                 // Although EOF is usually -1 it's platform-dependent of the C standard library, so
                 // it looks safer an better to compare with 'Unknown' variant than relying in -1
                 // comparison or adding libc as a direct dependency of this crate.
-                if let Error::Uplink(error::Uplink::Unknown(_)) = err {
+                if let Some(UplinkCode::Unknown(_)) = err.uplink_code() {
+                    *eof = true;
                     return Ok(read_res.bytes_read as usize);
                 }
 
-                use std::io::{Error as IOErr, ErrorKind};
-                return Err(IOErr::new(ErrorKind::Other, err));
+                return Err(err.into());
             }
 
             if read_res.bytes_read != 0 {
@@ -265,19 +785,787 @@ impl std::io::Read for Download {
             }
         }
 
+        *eof = true;
         Ok(0)
     }
+
+    /// Reads into `buf` the bytes of `buffer` that aren't consumed or, when there isn't any,
+    /// the bytes that `read` returns, adding the number of read bytes to `bytes_read`.
+    fn read_buffered(
+        buffer: &mut ReadBuffer,
+        bytes_read: &mut u64,
+        buf: &mut [u8],
+        read: impl FnOnce(&mut [u8]) -> std::io::Result<usize>,
+    ) -> std::io::Result<usize> {
+        let n = if !buffer.buffered().is_empty() {
+            buffer.read_into(buf)
+        } else {
+            read(buf)?
+        };
+
+        *bytes_read += n as u64;
+        Ok(n)
+    }
+}
+
+// SAFETY: the FFI download is a handler of a value that it's owned by the Go side and it can be
+// used from any thread as long as it isn't used concurrently, which is guaranteed because this
+// type isn't `Sync` and all its methods that call the FFI take `&self` or `&mut self`. The reads,
+// which are the only long-running calls, are also checked at runtime through `operation`.
+unsafe impl Send for Download {}
+
+impl std::io::Read for Download {
+    /// Downloads the object's data stream into `buf` and return the number of downloaded bytes,
+    /// which are at most the `buf` length, when there isn't any error.
+    ///
+    /// The bytes buffered by the [`std::io::BufRead`] implementation and not consumed yet are
+    /// returned first.
+    ///
+    /// When it returns an error its payload is an [`Error::Uplink`] and its kind is mapped as the
+    /// `From<Error>` implementation of [`std::io::Error`] documents. Use [`Error::from_io_chain`]
+    /// for getting the payload back. It returns an error of kind
+    /// [`std::io::ErrorKind::WouldBlock`], whose payload is an [`Error::Internal`], without
+    /// calling the FFI if another read is in progress.
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let _guard = self.operation.enter("download")?;
+        let uc_download = self.inner.download;
+        let eof = &mut self.eof;
+        Self::read_buffered(&mut self.buffer, &mut self.bytes_read, buf, |buf| {
+            Self::read_ffi(uc_download, eof, buf)
+        })
+    }
+}
+
+impl std::io::BufRead for Download {
+    /// Returns the buffered bytes that aren't consumed yet, downloading the next ones from the
+    /// object's data stream, with reads of up to 64 KiB, when all of them are consumed.
+    ///
+    /// It returns an empty slice once the end of the data stream is reached. When it returns an
+    /// error, the buffer is kept empty, so it can be called again, and the error is as the one
+    /// returned by the [`std::io::Read`] implementation.
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        let _guard = self.operation.enter("download")?;
+        let uc_download = self.inner.download;
+        let eof = &mut self.eof;
+        self.buffer
+            .fill_with(|buf| Self::read_ffi(uc_download, eof, buf))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.bytes_read += self.buffer.consume(amt) as u64;
+    }
+}
+
+/// The buffer of the [`std::io::BufRead`] implementation of [`Download`].
+struct ReadBuffer {
+    /// The buffered data. It's allocated when it's filled for the first time, so downloads that
+    /// aren't used through [`std::io::BufRead`] don't allocate it.
+    data: Box<[u8]>,
+    /// The position in `data` of the first buffered byte that isn't consumed.
+    pos: usize,
+    /// The number of bytes in `data` that are buffered.
+    filled: usize,
+}
+
+impl ReadBuffer {
+    /// The capacity of the buffer, which is the maximum number of bytes filled at once.
+    const CAPACITY: usize = 64 * 1024;
+
+    /// Creates an empty buffer without allocating it.
+    fn new() -> Self {
+        Self {
+            data: Box::default(),
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns the buffered bytes that aren't consumed.
+    fn buffered(&self) -> &[u8] {
+        &self.data[self.pos..self.filled]
+    }
+
+    /// Fills the buffer calling `read`, only when all the buffered bytes are consumed, and returns
+    /// the bytes that aren't consumed.
+    ///
+    /// It returns the error returned by `read` keeping the buffer empty.
+    fn fill_with(
+        &mut self,
+        read: impl FnOnce(&mut [u8]) -> std::io::Result<usize>,
+    ) -> std::io::Result<&[u8]> {
+        if self.pos >= self.filled {
+            if self.data.is_empty() {
+                self.data = vec![0; Self::CAPACITY].into_boxed_slice();
+            }
+
+            self.pos = 0;
+            self.filled = 0;
+            self.filled = read(&mut self.data)?;
+        }
+
+        Ok(self.buffered())
+    }
+
+    /// Marks `amt` buffered bytes as consumed and returns the number of consumed bytes, which is
+    /// capped to the number of buffered bytes.
+    fn consume(&mut self, amt: usize) -> usize {
+        let pos = self.filled.min(self.pos.saturating_add(amt));
+        let consumed = pos - self.pos;
+        self.pos = pos;
+        consumed
+    }
+
+    /// Copies the buffered bytes into `buf` consuming them and returns the number of copied bytes.
+    fn read_into(&mut self, buf: &mut [u8]) -> usize {
+        let buffered = self.buffered();
+        let n = buffered.len().min(buf.len());
+        buf[..n].copy_from_slice(&buffered[..n]);
+        self.consume(n);
+        n
+    }
+}
+
+impl std::fmt::Debug for ReadBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadBuffer")
+            .field("capacity", &self.data.len())
+            .field("pos", &self.pos)
+            .field("filled", &self.filled)
+            .finish()
+    }
+}
+
+/// Copies all the data read from `reader`, which is read in a Tokio blocking thread by chunks of
+/// `chunk_size` bytes, into `writer`, sending the chunks through a channel of `channel_depth`
+/// capacity. It returns the number of copied bytes.
+///
+/// It stops reading when writing fails and stops writing when reading fails, and it doesn't return
+/// until the blocking thread finishes, hence `reader` is always dropped when it returns.
+#[cfg(feature = "tokio")]
+async fn copy_blocking_to_tokio<R, W>(
+    mut reader: R,
+    writer: &mut W,
+    chunk_size: usize,
+    channel_depth: usize,
+) -> Result<u64>
+where
+    R: std::io::Read + Send + 'static,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::AsyncWriteExt;
+
+    if chunk_size == 0 || channel_depth == 0 {
+        return Err(Error::new_invalid_arguments(
+            "opts{chunk_size, channel_depth}",
+            "cannot be zero",
+        ));
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(channel_depth);
+    let reading = tokio::task::spawn_blocking(move || loop {
+        let mut chunk = vec![0; chunk_size];
+        match reader.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(n) => {
+                chunk.truncate(n);
+                // Sending only fails when the receiver is dropped because writing failed.
+                if tx.blocking_send(Ok(chunk)).is_err() {
+                    return;
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                let _ = tx.blocking_send(Err(err));
+                return;
+            }
+        }
+    });
+
+    let mut copied = 0;
+    let writing = async {
+        while let Some(chunk) = rx.recv().await {
+            let chunk = chunk.map_err(Error::from_io_error)?;
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(Error::from_io_error)?;
+            copied += chunk.len() as u64;
+        }
+
+        writer.flush().await.map_err(Error::from_io_error)
+    }
+    .await;
+
+    // Dropping the receiver makes the reader to stop if writing has failed.
+    drop(rx);
+    let reading = reading.await;
+
+    writing?;
+    reading.map_err(|err| {
+        Error::new_internal(
+            "reading the download in a blocking thread",
+            BoxError::from(err),
+        )
+    })?;
+    Ok(copied)
 }
 
 impl Drop for Download {
+    /// Frees the download, closing it first in a separate thread, waiting for it up to
+    /// [`Self::DROP_CLOSE_TIMEOUT`], if it isn't closed.
     fn drop(&mut self) {
-        // SAFETY: we trust that the FFI is doing correct operations when closing and freeing a
-        // correctly created `UplinkDownloadResult` value.
-        unsafe {
-            // At this point we cannot do anything about the error, so discarded.
-            // TODO(https://github.com/storj-thirdparty/uplink-rust/issues/51).
-            let _ = ulksys::uplink_close_download(self.inner.download);
-            ulksys::uplink_free_download_result(self.inner);
+        if self.closed {
+            // SAFETY: we trust that the FFI is doing correct operations when freeing a correctly
+            // created `UplinkDownloadResult` value, which isn't used after this.
+            unsafe { ulksys::uplink_free_download_result(self.inner) };
+            return;
+        }
+
+        let uc_result = SendDownloadResult(self.inner);
+        let close = move || {
+            // Capture the whole wrapper rather than only its fields, which aren't `Send`.
+            let uc_result = uc_result;
+            // SAFETY: we trust that the FFI is doing correct operations when closing and freeing
+            // a correctly created `UplinkDownloadResult` value. It's only used by this closure
+            // because the instance that owned it is dropping.
+            unsafe {
+                // At this point we cannot do anything about the error, so discarded.
+                drop(Error::from_ffi_error(ulksys::uplink_close_download(
+                    uc_result.0.download,
+                )));
+                ulksys::uplink_free_download_result(uc_result.0);
+            }
+        };
+
+        if !close_with_timeout(close, Self::DROP_CLOSE_TIMEOUT) {
+            ABANDONED_DOWNLOAD_CLOSES.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The number of closes abandoned by [`Download`]'s drop, see [`Download::abandoned_closes`].
+static ABANDONED_DOWNLOAD_CLOSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the FFI download result of a dropped [`Download`] for moving it to the thread that
+/// closes it.
+struct SendDownloadResult(ulksys::UplinkDownloadResult);
+
+// SAFETY: the FFI download is a handler of a value that it's owned by the Go side and it can be
+// used from any thread as long as it isn't used concurrently, which is guaranteed because it's
+// only used by the thread that closes it, see `Download`'s `Send` implementation.
+unsafe impl Send for SendDownloadResult {}
+
+/// Runs `close` with [`run_with_timeout`] and returns `false` if it hasn't finished when
+/// `timeout` elapses, in which case it keeps running in the background, or if it cannot run, in
+/// which case the resources that it frees are leaked rather than blocking or panicking in a drop.
+fn close_with_timeout(close: impl FnOnce() + Send + 'static, timeout: Duration) -> bool {
+    run_with_timeout("close download", timeout, move || {
+        close();
+        Ok(())
+    })
+    .is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helpers::test::ffi_error;
+
+    use std::os::raw::c_char;
+    use std::ptr;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[cfg(feature = "tokio")]
+    mod tokio_copy {
+        use super::*;
+
+        use std::io::{self, Read};
+        use std::pin::Pin;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use std::task::{Context, Poll};
+
+        /// Reader that never ends and flags when it drops.
+        struct EndlessReader {
+            dropped: Arc<AtomicBool>,
+        }
+
+        impl Read for EndlessReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                buf.fill(7);
+                Ok(buf.len())
+            }
+        }
+
+        impl Drop for EndlessReader {
+            fn drop(&mut self) {
+                self.dropped.store(true, Ordering::SeqCst);
+            }
+        }
+
+        /// Writer that fails when it has written more than `limit` bytes.
+        struct FailingWriter {
+            written: usize,
+            limit: usize,
+        }
+
+        impl tokio::io::AsyncWrite for FailingWriter {
+            fn poll_write(
+                mut self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                if self.written >= self.limit {
+                    return Poll::Ready(Err(io::Error::other("writer")));
+                }
+
+                self.written += buf.len();
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        #[test]
+        fn test_copy_blocking_to_tokio() {
+            let size = 20 * 1024 * 1024;
+            let reader = io::repeat(7).take(size as u64);
+            let mut writer = Vec::new();
+
+            let copied =
+                tokio_test::block_on(copy_blocking_to_tokio(reader, &mut writer, 64 * 1024, 4))
+                    .expect("copy");
+            assert_eq!(copied, size as u64, "copied bytes");
+            assert_eq!(writer.len(), size, "written bytes");
+            assert!(writer.iter().all(|b| *b == 7), "written data");
+        }
+
+        #[test]
+        fn test_copy_blocking_to_tokio_writer_error() {
+            let dropped = Arc::new(AtomicBool::new(false));
+            let reader = EndlessReader {
+                dropped: dropped.clone(),
+            };
+            let mut writer = FailingWriter {
+                written: 0,
+                limit: 1024 * 1024,
+            };
+
+            match tokio_test::block_on(copy_blocking_to_tokio(reader, &mut writer, 64 * 1024, 4)) {
+                Err(Error::Internal(_)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            assert!(dropped.load(Ordering::SeqCst), "reader is aborted");
+        }
+
+        #[test]
+        fn test_copy_blocking_to_tokio_reader_error() {
+            let reader = io::repeat(7).take(1024).chain(FailingReader);
+            let mut writer = Vec::new();
+
+            match tokio_test::block_on(copy_blocking_to_tokio(reader, &mut writer, 256, 1)) {
+                Err(Error::Uplink(err)) if err.code() == UplinkCode::Internal => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
         }
+
+        #[test]
+        fn test_copy_blocking_to_tokio_invalid_arguments() {
+            let mut writer = Vec::new();
+            match tokio_test::block_on(copy_blocking_to_tokio(io::empty(), &mut writer, 0, 1)) {
+                Err(Error::InvalidArguments(_)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+
+        /// Reader that always fails with an error of this crate.
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(Error::Uplink(error::Uplink::with_code(UplinkCode::Internal, "reader")).into())
+            }
+        }
+    }
+
+    #[test]
+    fn test_object_display() {
+        let mut obj = Object {
+            key: String::from("dir/file.txt"),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: Duration::from_secs(1_714_564_800),
+                expires: None,
+                content_length: Some(1234),
+            },
+            metadata_custom: metadata::Custom::default(),
+            has_system: true,
+            has_custom: true,
+        };
+        assert_eq!(
+            obj.to_string(),
+            "dir/file.txt, 1234 bytes, created 2024-05-01T12:00:00Z",
+            "object without expiration"
+        );
+
+        obj.metadata_system.expires = Some(Duration::from_secs(1_717_243_200));
+        obj.metadata_system.content_length = Some(0);
+        assert_eq!(
+            obj.to_string(),
+            "dir/file.txt, 0 bytes, created 2024-05-01T12:00:00Z, expires 2024-06-01T12:00:00Z",
+            "empty object with expiration"
+        );
+
+        obj.has_system = false;
+        assert!(
+            !obj.has_system_metadata(),
+            "system metadata isn't retrieved"
+        );
+        assert_eq!(
+            obj.to_string(),
+            "dir/file.txt",
+            "object without system metadata"
+        );
+
+        obj.key = String::from("dir/");
+        obj.is_prefix = true;
+        assert_eq!(obj.to_string(), "dir/ (prefix)", "prefix");
+    }
+
+    #[test]
+    fn test_iterator_state_tracking() {
+        // The FFI iterator is never used, so it's `NULL` and the instance must be forgotten.
+        let mut it = Iterator {
+            inner: ptr::null_mut(),
+            last_seen_key: None,
+            remaining: None,
+            yielded_count: 0,
+            finished: false,
+            error: None,
+            start: ListingCheckpoint::new("my-bucket", None, true),
+        };
+        assert_eq!(
+            it.checkpoint(),
+            ListingCheckpoint::new("my-bucket", None, true),
+            "checkpoint before yielding"
+        );
+
+        for key in ["a.txt", "b.txt"] {
+            it.track_item(
+                Ok(Object {
+                    key: String::from(key),
+                    is_prefix: false,
+                    metadata_system: metadata::System {
+                        created: Duration::ZERO,
+                        expires: None,
+                        content_length: Some(0),
+                    },
+                    metadata_custom: metadata::Custom::default(),
+                    has_system: true,
+                    has_custom: true,
+                }),
+                |o| &o.key,
+            )
+            .expect("tracked item");
+        }
+        assert_eq!(it.yielded_count(), 2, "yielded count");
+        assert_eq!(it.last_seen_key(), Some("b.txt"), "last seen key");
+        assert_eq!(it.checkpoint().cursor(), "b.txt", "checkpoint cursor");
+        assert!(it.finished_with_error().is_none(), "not finished");
+
+        match it.track_end::<Object>(ffi_error(ulksys::UPLINK_ERROR_PERMISSION_DENIED)) {
+            Some(Err(Error::Uplink(err))) if err.code() == UplinkCode::PermissionDenied => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match it.finished_with_error() {
+            Some(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+            err => panic!("unexpected finished error: {:?}", err),
+        }
+        assert!(it.next().is_none(), "no more items after the error");
+
+        std::mem::forget(it);
+    }
+
+    #[test]
+    fn test_delete_outcome_from_delete_object_result() {
+        let object = Object {
+            key: String::from("a.txt"),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: Duration::ZERO,
+                expires: None,
+                content_length: Some(0),
+            },
+            metadata_custom: metadata::Custom::default(),
+            has_system: true,
+            has_custom: true,
+        };
+        match DeleteOutcome::from_delete_object_result(Ok(Some(object))) {
+            Ok(DeleteOutcome::Deleted(o)) => assert_eq!(o.key, "a.txt", "deleted object key"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match DeleteOutcome::from_delete_object_result(Ok(None)) {
+            Ok(DeleteOutcome::DeletedNoMetadata) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match DeleteOutcome::from_delete_object_result(Err(Error::Uplink(
+            error::Uplink::with_code(UplinkCode::ObjectNotFound, "not found"),
+        ))) {
+            Ok(DeleteOutcome::NotFound) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match DeleteOutcome::from_delete_object_result(Err(Error::Uplink(
+            error::Uplink::with_code(UplinkCode::PermissionDenied, "denied"),
+        ))) {
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_iterator_from_ffi_object_iterator_null() {
+        match Iterator::from_ffi_object_iterator(ptr::null_mut()).map(|_| ()) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_object_invalid_utf8_key() {
+        let key = CString::new(b"dir/\xffobject".to_vec()).unwrap();
+        let uc_obj = ulksys::UplinkObject {
+            key: key.as_ptr() as *mut c_char,
+            is_prefix: false,
+            system: ulksys::UplinkSystemMetadata {
+                created: 3600,
+                expires: 0,
+                content_length: 10,
+            },
+            custom: ulksys::UplinkCustomMetadata {
+                entries: ptr::null_mut(),
+                count: 0,
+            },
+        };
+
+        // SAFETY: the object doesn't have fields with NULL pointers and the memory that it
+        // references lives until the end of the test.
+        match unsafe { Object::unchecked_from_ffi_object_ref(&uc_obj) } {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        // SAFETY: the object's key isn't NULL and it lives until the end of the test.
+        match unsafe { Object::unchecked_ffi_object_key(&uc_obj) } {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_object_key_conversion_skips_metadata() {
+        const OBJECTS: usize = 10;
+        const METADATA_ENTRIES: usize = 5;
+
+        let mut custom = metadata::Custom::with_capacity(METADATA_ENTRIES);
+        for i in 0..METADATA_ENTRIES {
+            custom.insert(format!("key-{}", i), "v".repeat(64));
+        }
+        let uc_custom_wrapper = custom.to_ffi_custom_metadata();
+        let uc_custom = uc_custom_wrapper.as_ffi_custom_metadata();
+
+        let keys: Vec<CString> = (0..OBJECTS)
+            .map(|i| CString::new(format!("dir/object-{:04}", i)).unwrap())
+            .collect();
+        let uc_objects: Vec<ulksys::UplinkObject> = keys
+            .iter()
+            .map(|k| ulksys::UplinkObject {
+                key: k.as_ptr() as *mut c_char,
+                is_prefix: false,
+                system: ulksys::UplinkSystemMetadata {
+                    created: 3600,
+                    expires: 0,
+                    content_length: 10,
+                },
+                custom: uc_custom,
+            })
+            .collect();
+
+        let objects: Vec<Object> = uc_objects
+            .iter()
+            // SAFETY: the objects don't have fields with NULL pointers and the memory that they
+            // reference lives until the end of the test.
+            .map(|o| unsafe { Object::unchecked_from_ffi_object_ref(o) }.expect("object"))
+            .collect();
+        let keys_only: Vec<String> = uc_objects
+            .iter()
+            // SAFETY: the objects' keys aren't NULL and they live until the end of the test.
+            .map(|o| unsafe { Object::unchecked_ffi_object_key(o) }.expect("key"))
+            .collect();
+
+        assert_eq!(objects.len(), OBJECTS, "converted objects");
+        assert_eq!(
+            objects[OBJECTS - 1].metadata_custom.count(),
+            METADATA_ENTRIES,
+            "custom metadata entries"
+        );
+        assert_eq!(
+            keys_only,
+            objects.into_iter().map(|o| o.key).collect::<Vec<_>>(),
+            "keys"
+        );
+    }
+
+    #[test]
+    fn test_close_with_timeout() {
+        let (tx, rx) = mpsc::channel();
+        assert!(
+            close_with_timeout(move || tx.send(()).expect("send"), Duration::from_secs(60)),
+            "close finished"
+        );
+        rx.try_recv().expect("close ran");
+
+        // Simulates a close blocked by a stalled connection until it's released.
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let finished = close_with_timeout(
+            move || {
+                let _ = release_rx.recv();
+                done_tx.send(()).expect("send");
+            },
+            Duration::ZERO,
+        );
+        assert!(!finished, "stalled close abandoned");
+        assert!(done_rx.try_recv().is_err(), "stalled close still blocked");
+
+        drop(release_tx);
+        done_rx
+            .recv()
+            .expect("abandoned close keeps running in the background");
+    }
+
+    #[test]
+    fn test_read_buffer_fill_and_consume() {
+        let data: Vec<u8> = (0..=255).cycle().take(ReadBuffer::CAPACITY + 10).collect();
+        let mut src = &data[..];
+        let mut read = |buf: &mut [u8]| std::io::Read::read(&mut src, buf);
+
+        let mut buffer = ReadBuffer::new();
+        assert!(buffer.buffered().is_empty(), "new buffer");
+
+        let filled = buffer.fill_with(&mut read).expect("fill").to_vec();
+        assert_eq!(filled, data[..ReadBuffer::CAPACITY], "filled bytes");
+
+        // It doesn't fill again until all the buffered bytes are consumed.
+        buffer.consume(5);
+        let filled = buffer
+            .fill_with(|_| panic!("fill with buffered bytes"))
+            .expect("fill with buffered bytes");
+        assert_eq!(filled, &data[5..ReadBuffer::CAPACITY], "unconsumed bytes");
+
+        let mut buf = [0; 3];
+        assert_eq!(buffer.read_into(&mut buf), 3, "read into");
+        assert_eq!(buf, data[5..8], "read into bytes");
+
+        buffer.consume(usize::MAX);
+        assert!(buffer.buffered().is_empty(), "all consumed");
+        let filled = buffer.fill_with(&mut read).expect("fill").to_vec();
+        assert_eq!(filled, data[ReadBuffer::CAPACITY..], "last filled bytes");
+
+        buffer.consume(filled.len());
+        assert!(
+            buffer
+                .fill_with(&mut read)
+                .expect("fill at the end")
+                .is_empty(),
+            "fill at the end"
+        );
+    }
+
+    #[test]
+    fn test_download_read_buffered_bytes_read() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut src = &data[..];
+        let mut read = |buf: &mut [u8]| std::io::Read::read(&mut src, buf);
+
+        let mut buffer = ReadBuffer::new();
+        let mut bytes_read = 0;
+        let mut buf = [0; 100];
+        for (expected, total) in [(100, 100), (100, 200)] {
+            let n = Download::read_buffered(&mut buffer, &mut bytes_read, &mut buf, &mut read)
+                .expect("read");
+            assert_eq!(n, expected, "read bytes");
+            assert_eq!(bytes_read, total, "bytes read");
+        }
+
+        // The buffered bytes are counted when they are consumed or read.
+        let filled = buffer.fill_with(&mut read).expect("fill").len();
+        assert_eq!(filled, 56, "filled bytes");
+        assert_eq!(bytes_read, 200, "bytes read after filling");
+        bytes_read += buffer.consume(6) as u64;
+        assert_eq!(bytes_read, 206, "bytes read after consuming");
+        let n = Download::read_buffered(&mut buffer, &mut bytes_read, &mut buf, |_| {
+            panic!("read with buffered bytes")
+        })
+        .expect("read buffered");
+        assert_eq!(n, 50, "read buffered bytes");
+        assert_eq!(buf[..n], data[206..], "read buffered data");
+        assert_eq!(bytes_read, 256, "bytes read after reading the buffer");
+        assert_eq!(buffer.consume(10), 0, "consume without buffered bytes");
+
+        // Errors and the end of the data don't change the count.
+        Download::read_buffered(&mut buffer, &mut bytes_read, &mut buf, |_| {
+            Err(Error::Uplink(error::Uplink::with_code(UplinkCode::Internal, "reader")).into())
+        })
+        .expect_err("read error");
+        assert_eq!(
+            Download::read_buffered(&mut buffer, &mut bytes_read, &mut buf, &mut read)
+                .expect("read at the end"),
+            0,
+            "read at the end"
+        );
+        assert_eq!(bytes_read, 256, "bytes read at the end");
+    }
+
+    #[test]
+    fn test_read_buffer_fill_error() {
+        let mut buffer = ReadBuffer::new();
+        buffer
+            .fill_with(|buf| {
+                buf[..4].copy_from_slice(b"abcd");
+                Ok(4)
+            })
+            .expect("fill");
+        buffer.consume(4);
+
+        let err = buffer
+            .fill_with(|buf| {
+                // Partially written data isn't exposed after the error.
+                buf[..2].copy_from_slice(b"xy");
+                Err(Error::Uplink(error::Uplink::with_code(UplinkCode::Internal, "reader")).into())
+            })
+            .expect_err("fill with an error");
+        match Error::from_io_chain(&err) {
+            Some(Error::Uplink(err)) if err.code() == UplinkCode::Internal => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert!(buffer.buffered().is_empty(), "buffer after an error");
+
+        let filled = buffer
+            .fill_with(|buf| {
+                buf[..2].copy_from_slice(b"ef");
+                Ok(2)
+            })
+            .expect("fill after an error");
+        assert_eq!(filled, b"ef", "filled bytes after an error");
     }
 }
@@ -0,0 +1,290 @@
+//! Buffered uploads of the objects' data.
+//!
+//! Each call to [`Upload`]'s `write` crosses the FFI boundary, so writing the data in small
+//! chunks (e.g. 1 KiB) is considerably slower than writing it in large ones. [`BufferedUpload`]
+//! accumulates the small writes in memory and writes them to the upload in chunks of its
+//! capacity.
+
+use super::upload::Upload;
+use crate::{metadata, Error, Object, Result};
+
+use std::io::{self, Write};
+
+/// An [`Upload`] that buffers the written data and writes it to the upload in large chunks.
+///
+/// The buffered data is written when the buffer is full, when it's flushed and when the upload is
+/// committed, see [`Self::commit`]. It's discarded if the instance drops without committing it.
+///
+/// Create it with [`Upload::into_buffered`].
+#[derive(Debug)]
+pub struct BufferedUpload {
+    /// The upload where the buffered data is written.
+    inner: Upload,
+    /// The data which hasn't been written to the upload yet.
+    buf: ChunkBuffer,
+}
+
+impl BufferedUpload {
+    /// The capacity of the buffer that performs best with the uplink-c, which is the size of the
+    /// chunks that makes the cost of the FFI calls negligible compared with the transfer of the
+    /// data. The `upload_chunking` benchmark compares it with smaller chunks.
+    pub const DEFAULT_CAPACITY: usize = 256 * 1024;
+
+    /// Creates a new instance that buffers up to `capacity` bytes before writing them to `inner`.
+    pub(crate) fn new(inner: Upload, capacity: usize) -> Self {
+        Self {
+            inner,
+            buf: ChunkBuffer::new(capacity),
+        }
+    }
+
+    /// Aborts the upload discarding the buffered data.
+    ///
+    /// It returns the same errors than [`Upload::abort`].
+    pub fn abort(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.inner.abort()
+    }
+
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity
+    }
+
+    /// Writes the buffered data to the upload and commits it.
+    ///
+    /// It returns the error of writing the buffered data, keeping the data that wasn't written in
+    /// the buffer, or the same errors than [`Upload::commit`].
+    pub fn commit(&mut self) -> Result<()> {
+        self.buf
+            .flush_to(&mut self.inner)
+            .map_err(Error::from_io_error)?;
+        self.inner.commit()
+    }
+
+    /// Returns a reference to the underlying upload.
+    ///
+    /// The data written directly to it skips the buffered data, so it isn't possible to obtain a
+    /// mutable reference; use [`Self::into_inner`] instead.
+    pub fn get_ref(&self) -> &Upload {
+        &self.inner
+    }
+
+    /// Returns the last information about the uploaded object, see [`Upload::info`].
+    ///
+    /// The buffered data isn't part of the uploaded object until it's written to the upload.
+    pub fn info(&self) -> Result<Object> {
+        self.inner.info()
+    }
+
+    /// Writes the buffered data to the upload and returns the underlying upload.
+    ///
+    /// It returns the error of writing the buffered data and drops the upload in that case.
+    pub fn into_inner(mut self) -> Result<Upload> {
+        self.buf
+            .flush_to(&mut self.inner)
+            .map_err(Error::from_io_error)?;
+        Ok(self.inner)
+    }
+
+    /// Updates the custom metadata to be included with the object, see
+    /// [`Upload::set_custom_metadata`].
+    pub fn set_custom_metadata(&mut self, metadata: &metadata::Custom) -> Result<()> {
+        self.inner.set_custom_metadata(metadata)
+    }
+}
+
+impl Write for BufferedUpload {
+    /// Writes the buffered data to the upload.
+    ///
+    /// It returns the same errors than [`Upload`]'s `write` and the data that wasn't written
+    /// remains in the buffer.
+    fn flush(&mut self) -> io::Result<()> {
+        self.buf.flush_to(&mut self.inner)?;
+        self.inner.flush()
+    }
+
+    /// Buffers the bytes in `buf`, writing the buffered data to the upload before if they don't
+    /// fit in the buffer. The bytes that are more than the capacity of the buffer are written
+    /// directly to the upload.
+    ///
+    /// It returns the same errors than [`Upload`]'s `write`.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.write_through(&mut self.inner, buf)
+    }
+}
+
+/// The buffer of a [`BufferedUpload`], which is independent of the upload for testing it with
+/// any writer.
+#[derive(Debug)]
+struct ChunkBuffer {
+    /// The buffered data.
+    data: Vec<u8>,
+    /// The maximum number of bytes to buffer.
+    capacity: usize,
+}
+
+impl ChunkBuffer {
+    /// Creates an empty buffer of `capacity` bytes.
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Discards the buffered data.
+    fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Writes all the buffered data to `writer`.
+    ///
+    /// When `writer` returns an error, the data that it has already accepted is removed from the
+    /// buffer and the rest remains, so a later call writes it without duplicating or losing data.
+    fn flush_to(&mut self, writer: &mut impl Write) -> io::Result<()> {
+        let mut written = 0;
+        let res = loop {
+            if written == self.data.len() {
+                break Ok(());
+            }
+
+            match writer.write(&self.data[written..]) {
+                Ok(0) => {
+                    break Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ))
+                }
+                Ok(n) => written += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => break Err(err),
+            }
+        };
+
+        self.data.drain(..written);
+        res
+    }
+
+    /// Buffers `data`, writing the buffered data to `writer` before if `data` doesn't fit in the
+    /// buffer, and returns the number of bytes accepted from `data`.
+    ///
+    /// `data` is written directly to `writer` when it's at least as large as the buffer.
+    fn write_through(&mut self, writer: &mut impl Write, data: &[u8]) -> io::Result<usize> {
+        if self.data.len() + data.len() > self.capacity {
+            self.flush_to(writer)?;
+        }
+
+        if data.len() >= self.capacity {
+            writer.write(data)
+        } else {
+            self.data.extend_from_slice(data);
+            Ok(data.len())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Writer that records the size of each write and fails after accepting `fail_after` bytes.
+    #[derive(Default)]
+    struct RecordingWriter {
+        data: Vec<u8>,
+        writes: Vec<usize>,
+        fail_after: Option<usize>,
+    }
+
+    impl Write for RecordingWriter {
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = match self.fail_after {
+                Some(limit) if self.data.len() >= limit => {
+                    return Err(io::Error::other("writer failure"))
+                }
+                Some(limit) => buf.len().min(limit - self.data.len()),
+                None => buf.len(),
+            };
+
+            self.data.extend_from_slice(&buf[..n]);
+            self.writes.push(n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_chunk_buffer_small_writes() {
+        let data: Vec<u8> = (0..2_500u32).map(|i| i as u8).collect();
+        let mut writer = RecordingWriter::default();
+        let mut buf = ChunkBuffer::new(1_000);
+
+        for chunk in data.chunks(100) {
+            assert_eq!(
+                buf.write_through(&mut writer, chunk).expect("write"),
+                chunk.len(),
+                "accepted bytes"
+            );
+        }
+        assert_eq!(writer.writes, vec![1_000, 1_000], "full chunks written");
+        assert_eq!(buf.data.len(), 500, "partially filled buffer");
+
+        buf.flush_to(&mut writer).expect("flush");
+        assert_eq!(
+            writer.writes,
+            vec![1_000, 1_000, 500],
+            "partial chunk written"
+        );
+        assert_eq!(writer.data, data, "written data");
+        assert!(buf.data.is_empty(), "empty buffer");
+
+        buf.flush_to(&mut writer).expect("flush empty buffer");
+        assert_eq!(writer.writes.len(), 3, "no writes for an empty buffer");
+    }
+
+    #[test]
+    fn test_chunk_buffer_large_writes() {
+        let data: Vec<u8> = (0..2_600u32).map(|i| i as u8).collect();
+        let mut writer = RecordingWriter::default();
+        let mut buf = ChunkBuffer::new(1_000);
+
+        buf.write_through(&mut writer, &data[..300])
+            .expect("small write");
+        assert_eq!(
+            buf.write_through(&mut writer, &data[300..])
+                .expect("large write"),
+            2_300,
+            "accepted bytes"
+        );
+        assert_eq!(
+            writer.writes,
+            vec![300, 2_300],
+            "buffer flushed before writing directly"
+        );
+        assert_eq!(writer.data, data, "written data");
+        assert!(buf.data.is_empty(), "empty buffer");
+    }
+
+    #[test]
+    fn test_chunk_buffer_flush_error() {
+        let data: Vec<u8> = (0..800u32).map(|i| i as u8).collect();
+        let mut writer = RecordingWriter {
+            fail_after: Some(300),
+            ..Default::default()
+        };
+        let mut buf = ChunkBuffer::new(1_000);
+
+        buf.write_through(&mut writer, &data).expect("write");
+        buf.flush_to(&mut writer).expect_err("writer failure");
+        assert_eq!(writer.data, &data[..300], "accepted data");
+        assert_eq!(buf.data, &data[300..], "data remaining in the buffer");
+
+        writer.fail_after = None;
+        buf.flush_to(&mut writer).expect("flush after the failure");
+        assert_eq!(writer.data, data, "no data lost or duplicated");
+        assert!(buf.data.is_empty(), "empty buffer");
+    }
+}
@@ -0,0 +1,262 @@
+//! Checkpoints of the objects' listings for resuming them later, even from another process.
+//!
+//! [`Iterator::checkpoint`](super::Iterator::checkpoint) returns a [`ListingCheckpoint`] with the
+//! position of the listing and the options that it uses, and
+//! [`Project::resume_listing`](crate::Project::resume_listing) continues the listing after the
+//! last object yielded before the checkpoint. With the `serde` feature, a checkpoint can be
+//! serialized for persisting the progress of a long crawl periodically.
+//!
+//! ```no_run
+//! use uplink::access::Grant;
+//! use uplink::project::options::ListObjects;
+//! use uplink::Project;
+//!
+//! let grant = Grant::new("my-serialized-access-grant")?;
+//! let project = Project::open(&grant);
+//!
+//! let mut opts = ListObjects::with_prefix("logs/")?;
+//! opts.recursive = true;
+//! let mut objects = project.list_objects("my-bucket", Some(&opts))?;
+//! for _ in objects.by_ref().take(1000) {
+//!     // Process the first 1000 objects.
+//! }
+//!
+//! // Persist `checkpoint` and resume the crawl later.
+//! let checkpoint = objects.checkpoint();
+//! for object in project.resume_listing("my-bucket", &checkpoint)? {
+//!     // Process the rest of the objects.
+//! }
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::project::options;
+use crate::{Error, Result};
+
+/// The position of an objects' listing and the options that it uses, see the
+/// [module documentation](self).
+///
+/// The options are part of the checkpoint because the position is only meaningful for the same
+/// bucket, prefix and recursive flag; the listing resumes with the same options, so a resumed
+/// listing cannot differ from the original one. The limit of the listing isn't part of it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListingCheckpoint {
+    /// The bucket of the listing.
+    bucket: String,
+    /// The prefix of the listing, which is empty when the listing isn't restricted to a prefix.
+    prefix: String,
+    /// The key of the last object yielded before the checkpoint or the cursor of the listing if
+    /// it didn't yield any. Empty when the listing starts from the first object.
+    cursor: String,
+    /// Indicates if the listing iterates the objects without collapsing prefixes.
+    recursive: bool,
+    /// Indicates if the listing includes the system metadata of the objects.
+    system: bool,
+    /// Indicates if the listing includes the custom metadata of the objects.
+    custom: bool,
+}
+
+impl ListingCheckpoint {
+    /// Creates the checkpoint of the start of a listing of `bucket` with optional options,
+    /// retrieving the objects' metadata requested by `opts` only if `metadata` is `true`.
+    pub(crate) fn new(bucket: &str, opts: Option<&options::ListObjects>, metadata: bool) -> Self {
+        match opts {
+            Some(o) => Self {
+                bucket: String::from(bucket),
+                prefix: String::from(o.prefix()),
+                cursor: String::from(o.cursor()),
+                recursive: o.recursive,
+                system: metadata && o.system,
+                custom: metadata && o.custom,
+            },
+            None => Self {
+                bucket: String::from(bucket),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns a copy of the checkpoint positioned after the object with `key`.
+    pub(crate) fn after(&self, key: &str) -> Self {
+        Self {
+            cursor: String::from(key),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the bucket of the listing.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Returns the prefix of the listing, which is empty when the listing isn't restricted to a
+    /// prefix.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the key of the last object yielded before the checkpoint, which is where the
+    /// listing resumes. It's empty when the listing resumes from the first object.
+    pub fn cursor(&self) -> &str {
+        &self.cursor
+    }
+
+    /// Returns `true` if the listing iterates the objects without collapsing prefixes.
+    pub fn recursive(&self) -> bool {
+        self.recursive
+    }
+
+    /// Returns `true` if the listing includes the system metadata of the objects.
+    pub(crate) fn system(&self) -> bool {
+        self.system
+    }
+
+    /// Returns `true` if the listing includes the custom metadata of the objects.
+    pub(crate) fn custom(&self) -> bool {
+        self.custom
+    }
+
+    /// Returns the options for resuming the listing of `bucket` from the checkpoint.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `bucket` isn't the bucket of the checkpoint
+    /// or the checkpoint is invalid, which can only happen when it's deserialized from altered
+    /// data.
+    pub(crate) fn resume_options(&self, bucket: &str) -> Result<options::ListObjects> {
+        if bucket != self.bucket {
+            return Err(Error::new_invalid_arguments(
+                "bucket",
+                &format!(
+                    "must be the bucket of the checkpoint ({:?}), got {:?}",
+                    self.bucket, bucket
+                ),
+            ));
+        }
+
+        if !self.cursor.is_empty() && !self.cursor.starts_with(&self.prefix) {
+            return Err(Error::new_invalid_arguments(
+                "checkpoint",
+                "the cursor must start with the prefix",
+            ));
+        }
+
+        let mut builder = options::ListObjects::builder()
+            .prefix(&self.prefix)
+            .recursive(self.recursive)
+            .system(self.system)
+            .custom(self.custom);
+        if !self.cursor.is_empty() {
+            builder = builder.cursor(&self.cursor);
+        }
+
+        builder.build().map_err(|err| match err {
+            Error::InvalidArguments(args) => Error::new_invalid_arguments(
+                "checkpoint",
+                &format!("invalid {}: {}", args.names, args.msg),
+            ),
+            err => err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_listing_checkpoint_resume_options() {
+        let checkpoint = ListingCheckpoint::new("my-bucket", None, true);
+        let opts = checkpoint
+            .resume_options("my-bucket")
+            .expect("options without position");
+        assert_eq!(opts.prefix(), "", "prefix");
+        assert_eq!(opts.cursor(), "", "cursor");
+        assert!(!opts.recursive, "recursive");
+
+        let mut listed = options::ListObjects::with_prefix("logs/").expect("listing options");
+        listed.recursive = true;
+        listed.system = true;
+        listed.custom = true;
+        listed.limit = Some(10);
+
+        let checkpoint = ListingCheckpoint::new("my-bucket", Some(&listed), false);
+        assert_eq!(checkpoint.bucket(), "my-bucket", "checkpoint bucket");
+        assert_eq!(checkpoint.prefix(), "logs/", "checkpoint prefix");
+        assert_eq!(checkpoint.cursor(), "", "checkpoint cursor");
+        assert!(checkpoint.recursive(), "checkpoint recursive");
+
+        let checkpoint = checkpoint.after("logs/2024/01.log");
+        assert_eq!(checkpoint.cursor(), "logs/2024/01.log", "checkpoint cursor");
+        let opts = checkpoint
+            .resume_options("my-bucket")
+            .expect("options after an object");
+        assert_eq!(opts.prefix(), "logs/", "prefix");
+        assert_eq!(opts.cursor(), "logs/2024/01.log", "cursor");
+        assert!(opts.recursive, "recursive");
+        assert!(!opts.system, "system metadata isn't retrieved");
+        assert!(!opts.custom, "custom metadata isn't retrieved");
+        assert_eq!(opts.limit, None, "limit");
+    }
+
+    #[test]
+    fn test_listing_checkpoint_resume_options_errors() {
+        let listed = options::ListObjects::with_prefix("logs/").expect("listing options");
+        let checkpoint = ListingCheckpoint::new("my-bucket", Some(&listed), true);
+        for (name, checkpoint, bucket, arg) in [
+            ("other bucket", checkpoint.clone(), "other-bucket", "bucket"),
+            (
+                "cursor outside of the prefix",
+                checkpoint.after("other/01.log"),
+                "my-bucket",
+                "checkpoint",
+            ),
+            (
+                "prefix without slash",
+                ListingCheckpoint {
+                    prefix: String::from("logs"),
+                    ..checkpoint.clone()
+                },
+                "my-bucket",
+                "checkpoint",
+            ),
+            (
+                "cursor with NUL",
+                checkpoint.after("logs/0\x001.log"),
+                "my-bucket",
+                "checkpoint",
+            ),
+        ] {
+            match checkpoint.resume_options(bucket) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, arg, "{}: invalid argument name", name)
+                }
+                res => panic!("{}: unexpected result: {:?}", name, res),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_listing_checkpoint_serde() {
+        let listed = options::ListObjects::with_prefix("logs/").expect("listing options");
+        let checkpoint = ListingCheckpoint::new("my-bucket", Some(&listed), true).after("logs/01");
+
+        let json = serde_json::to_value(&checkpoint).expect("serialize checkpoint");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "bucket": "my-bucket",
+                "prefix": "logs/",
+                "cursor": "logs/01",
+                "recursive": false,
+                "system": false,
+                "custom": false,
+            }),
+            "serialized checkpoint"
+        );
+
+        let deserialized: ListingCheckpoint =
+            serde_json::from_value(json).expect("deserialize checkpoint");
+        assert_eq!(deserialized, checkpoint, "deserialized checkpoint");
+    }
+}
@@ -0,0 +1,233 @@
+//! Client-side filtering of the objects' listings by key.
+
+use super::{Iterator, Object};
+use crate::{Error, Result};
+
+/// The predicate of [`KeyFilter::Custom`].
+pub type KeyPredicate = dyn Fn(&str) -> bool + Send;
+
+/// Filter that selects the listed objects whose key matches it.
+///
+/// The keys of the objects are matched in full, including their prefix, and the prefixes yielded
+/// by the non-recursive listings (e.g. `logs/2024/`) are matched as any other key.
+pub enum KeyFilter {
+    /// Matches the keys that end with the string (e.g. `.parquet`).
+    Suffix(String),
+    /// Matches the keys with a glob pattern where `*` matches any sequence of characters,
+    /// including `/` and the empty sequence, and `?` matches any single character. The rest of
+    /// the characters match themselves.
+    Glob(String),
+    /// Matches the keys for which the predicate returns true.
+    Custom(Box<KeyPredicate>),
+}
+
+impl KeyFilter {
+    /// Returns true if `key` matches the filter.
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            Self::Suffix(suffix) => key.ends_with(suffix.as_str()),
+            Self::Glob(pattern) => glob_matches(pattern, key),
+            Self::Custom(predicate) => predicate(key),
+        }
+    }
+
+    /// Returns the prefix that all the keys matched by the filter have and that can be used for
+    /// listing them, so the satellite doesn't return objects that cannot match.
+    ///
+    /// It's the literal part of a glob pattern until its last `/` before the first wildcard (e.g.
+    /// `logs/` for `logs/2024-*`), and it's empty for the rest of filters.
+    pub fn listing_prefix(&self) -> &str {
+        match self {
+            Self::Glob(pattern) => {
+                let literal = pattern
+                    .find(['*', '?'])
+                    .map_or(pattern.as_str(), |i| &pattern[..i]);
+                literal.rfind('/').map_or("", |i| &literal[..=i])
+            }
+            Self::Suffix(_) | Self::Custom(_) => "",
+        }
+    }
+}
+
+impl std::fmt::Debug for KeyFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Suffix(suffix) => f.debug_tuple("Suffix").field(suffix).finish(),
+            Self::Glob(pattern) => f.debug_tuple("Glob").field(pattern).finish(),
+            Self::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Iterates over the objects of an [`Iterator`] whose key matches a [`KeyFilter`], see
+/// [`Iterator::filter_keys`] and
+/// [`Project::list_objects_filtered`](crate::Project::list_objects_filtered).
+#[derive(Debug)]
+pub struct FilteredIterator {
+    /// The iterator of the listed objects.
+    inner: Iterator,
+    /// The filter of the objects' keys.
+    filter: KeyFilter,
+    /// The number of matching objects that the iterator can still yield. `None` when there isn't
+    /// any limit.
+    remaining: Option<usize>,
+}
+
+impl FilteredIterator {
+    /// Creates a new instance that yields the objects of `inner` that match `filter`, up to
+    /// `limit` objects when it isn't `None`.
+    pub(crate) fn new(inner: Iterator, filter: KeyFilter, limit: Option<usize>) -> Self {
+        Self {
+            inner,
+            filter,
+            remaining: limit,
+        }
+    }
+
+    /// Returns the filter of this iterator.
+    pub fn filter(&self) -> &KeyFilter {
+        &self.filter
+    }
+
+    /// Returns the key of the last object listed by this iterator, matching the filter or not, or
+    /// `None` if it hasn't listed any yet.
+    ///
+    /// See [`Iterator::last_seen_key`] about resuming the listing.
+    pub fn last_seen_key(&self) -> Option<&str> {
+        self.inner.last_seen_key()
+    }
+
+    /// Returns the error that finished the iterator, see [`Iterator::finished_with_error`].
+    pub fn finished_with_error(&self) -> Option<&Error> {
+        self.inner.finished_with_error()
+    }
+}
+
+impl std::iter::Iterator for FilteredIterator {
+    type Item = Result<Object>;
+
+    /// It yields the errors of the listing as [`Iterator`] does.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        let item = self.inner.by_ref().find(|res| match res {
+            Ok(obj) => self.filter.matches(&obj.key),
+            Err(_) => true,
+        })?;
+
+        if item.is_ok() {
+            if let Some(n) = self.remaining.as_mut() {
+                *n -= 1;
+            }
+        }
+
+        Some(item)
+    }
+}
+
+/// Returns true if `key` matches the glob `pattern`, see [`KeyFilter::Glob`].
+fn glob_matches(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let (mut p, mut k) = (0, 0);
+    // The position of the last `*` in the pattern and the position in the key where it started
+    // matching, for backtracking when the rest of the pattern doesn't match.
+    let mut star: Option<(usize, usize)> = None;
+
+    while k < key.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, k));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == key[k] => {
+                p += 1;
+                k += 1;
+            }
+            _ => match star {
+                Some((sp, sk)) => {
+                    star = Some((sp, sk + 1));
+                    p = sp + 1;
+                    k = sk + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        for (pattern, key, want) in [
+            ("", "", true),
+            ("", "a", false),
+            ("*", "", true),
+            ("*", "logs/2024/a.txt", true),
+            ("a.txt", "a.txt", true),
+            ("a.txt", "b.txt", false),
+            ("?.txt", "a.txt", true),
+            ("?.txt", "ab.txt", false),
+            ("*.parquet", "data/part-0.parquet", true),
+            ("*.parquet", "data/part-0.parquet.tmp", false),
+            ("logs/2024-*", "logs/2024-05-01.log", true),
+            ("logs/2024-*", "logs/2023-12-31.log", false),
+            ("logs/2024-*", "other/logs/2024-05-01.log", false),
+            ("logs/*/*.log", "logs/2024/05/01.log", true),
+            ("logs/*/*.log", "logs/a.log", false),
+            ("a*b*c", "aXXbYYbZZc", true),
+            ("a*b*c", "aXXbYYcZZ", false),
+            ("**", "a/b", true),
+            ("día-?", "día-ñ", true),
+        ] {
+            assert_eq!(
+                glob_matches(pattern, key),
+                want,
+                "pattern: {:?}, key: {:?}",
+                pattern,
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_filter_matches() {
+        let filter = KeyFilter::Suffix(String::from(".parquet"));
+        assert!(filter.matches("data/a.parquet"), "suffix match");
+        assert!(!filter.matches("data/a.csv"), "suffix mismatch");
+
+        let filter = KeyFilter::Glob(String::from("data/*.csv"));
+        assert!(filter.matches("data/a.csv"), "glob match");
+        assert!(!filter.matches("data/a.parquet"), "glob mismatch");
+
+        let filter = KeyFilter::Custom(Box::new(|key| key.len() == 3));
+        assert!(filter.matches("abc"), "custom match");
+        assert!(!filter.matches("abcd"), "custom mismatch");
+    }
+
+    #[test]
+    fn test_key_filter_listing_prefix() {
+        for (filter, want) in [
+            (KeyFilter::Glob(String::from("logs/2024-*")), "logs/"),
+            (
+                KeyFilter::Glob(String::from("logs/2024/*/a.txt")),
+                "logs/2024/",
+            ),
+            (KeyFilter::Glob(String::from("logs/?/a.txt")), "logs/"),
+            (KeyFilter::Glob(String::from("logs/a.txt")), "logs/"),
+            (KeyFilter::Glob(String::from("*.txt")), ""),
+            (KeyFilter::Glob(String::from("a*/b/")), ""),
+            (KeyFilter::Suffix(String::from("a/b/")), ""),
+            (KeyFilter::Custom(Box::new(|_| true)), ""),
+        ] {
+            assert_eq!(filter.listing_prefix(), want, "{:?}", filter);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! The metadata of an object for responding to HTTP `HEAD` requests.
+
+use crate::metadata::{self, http};
+use crate::Object;
+
+use std::time::Duration;
+
+/// The metadata of an object returned by [`Project::head_object`](crate::Project::head_object),
+/// which has what an HTTP `HEAD` response needs without downloading the object's data.
+#[derive(Debug)]
+pub struct HeadObject {
+    /// The identifier of the object inside of the bucket which it belongs.
+    pub key: String,
+    /// The length of the object's data in bytes. `None` when it's unknown.
+    pub content_length: Option<u64>,
+    /// The creation time of the object since the Unix Epoch time.
+    pub created: Duration,
+    /// The expiration time of the object since the Unix Epoch time. `None` when it never expires.
+    pub expires: Option<Duration>,
+    /// The entity tag of the object, enclosed in double quotes as the `ETag` header requires it,
+    /// taken from the [`http::KEY_ETAG`] custom metadata entry. `None` when the entry is missing
+    /// or its value isn't a valid entity tag.
+    pub etag: Option<String>,
+    /// The custom metadata of the object.
+    pub metadata_custom: metadata::Custom,
+}
+
+impl HeadObject {
+    /// Returns the HTTP headers of the object with their canonical names (e.g. `Content-Type`).
+    ///
+    /// They are the `Content-Type`, `Cache-Control` and `Content-Encoding` headers, taken from the
+    /// custom metadata entries of the [`http`] keys, matching the keys case-insensitively when
+    /// the exact one is missing, and the `ETag` header, taken from [`Self::etag`]. The headers
+    /// whose entries are missing or whose values aren't valid header values (see
+    /// [`http::validate_header_value`]) are omitted.
+    ///
+    /// The `Content-Length` header isn't returned because it has to be set from
+    /// [`Self::content_length`] by the HTTP frameworks that don't derive it from the response.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        http::HEADERS
+            .iter()
+            .filter_map(move |&(key, name)| {
+                let value = self.metadata_custom.get(key).or_else(|| {
+                    self.metadata_custom
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                        .map(|(_, v)| v)
+                })?;
+                http::validate_header_value(name, value).ok()?;
+                Some((name, value.as_str()))
+            })
+            .chain(self.etag.as_deref().map(|etag| ("ETag", etag)))
+    }
+}
+
+impl From<Object> for HeadObject {
+    /// Converts an object with its system and custom metadata (e.g. returned by
+    /// [`Project::stat_object`](crate::Project::stat_object)).
+    fn from(obj: Object) -> Self {
+        let etag = obj
+            .metadata_custom
+            .get(http::KEY_ETAG)
+            .map(String::as_str)
+            .and_then(entity_tag);
+
+        Self {
+            key: obj.key,
+            content_length: obj.metadata_system.content_length,
+            created: obj.metadata_system.created,
+            expires: obj.metadata_system.expires,
+            etag,
+            metadata_custom: obj.metadata_custom,
+        }
+    }
+}
+
+/// Returns `value` as an entity tag enclosed in double quotes, keeping the `W/` prefix of the weak
+/// ones, or `None` if it has characters that an entity tag cannot have.
+///
+/// `value` may be already enclosed in double quotes (e.g. `"0a1b"` or `W/"0a1b"`) or not (e.g.
+/// `0a1b`).
+fn entity_tag(value: &str) -> Option<String> {
+    let (weak, opaque) = match value.strip_prefix("W/\"") {
+        Some(rest) => ("W/", rest.strip_suffix('"')?),
+        None => (
+            "",
+            value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value),
+        ),
+    };
+
+    // The characters of an entity tag are the visible ASCII ones except the double quote.
+    if opaque.is_empty() || !opaque.bytes().all(|b| b.is_ascii_graphic() && b != b'"') {
+        return None;
+    }
+
+    Some(format!("{}\"{}\"", weak, opaque))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns an object with `content_length` and with custom metadata with the `entries`, as
+    /// [`Project::stat_object`](crate::Project::stat_object) would return it.
+    fn object(content_length: Option<u64>, entries: &[(&str, &str)]) -> Object {
+        let mut custom = metadata::Custom::with_capacity(entries.len());
+        for (k, v) in entries {
+            custom.insert(String::from(*k), String::from(*v));
+        }
+
+        Object {
+            key: String::from("index.html"),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: Duration::from_secs(1_714_564_800),
+                expires: Some(Duration::from_secs(1_717_243_200)),
+                content_length,
+            },
+            metadata_custom: custom,
+            has_system: true,
+            has_custom: true,
+        }
+    }
+
+    #[test]
+    fn test_head_object_from_object() {
+        let head = HeadObject::from(object(
+            Some(13),
+            &[
+                ("content-type", "text/html; charset=utf-8"),
+                ("cache-control", "max-age=3600"),
+                ("content-encoding", "gzip"),
+                ("s3:etag", "0a1b2c"),
+                ("owner", "ops"),
+            ],
+        ));
+        assert_eq!(head.key, "index.html", "key");
+        assert_eq!(head.content_length, Some(13), "content length");
+        assert_eq!(head.created, Duration::from_secs(1_714_564_800), "created");
+        assert_eq!(
+            head.expires,
+            Some(Duration::from_secs(1_717_243_200)),
+            "expires"
+        );
+        assert_eq!(head.etag.as_deref(), Some("\"0a1b2c\""), "etag");
+        assert_eq!(head.metadata_custom.count(), 5, "custom metadata");
+        assert_eq!(
+            head.headers().collect::<Vec<_>>(),
+            [
+                ("Content-Type", "text/html; charset=utf-8"),
+                ("Cache-Control", "max-age=3600"),
+                ("Content-Encoding", "gzip"),
+                ("ETag", "\"0a1b2c\""),
+            ],
+            "headers"
+        );
+    }
+
+    #[test]
+    fn test_head_object_missing_metadata() {
+        let head = HeadObject::from(object(None, &[]));
+        assert_eq!(head.content_length, None, "content length");
+        assert_eq!(head.etag, None, "etag");
+        assert_eq!(head.headers().count(), 0, "headers");
+
+        // The entries with other keys or with invalid values are omitted.
+        let head = HeadObject::from(object(
+            Some(0),
+            &[
+                ("content-type", "text/html\r\nSet-Cookie: a=b"),
+                ("cache-control", ""),
+                ("content-language", "en"),
+                ("s3:etag", "0a\"1b"),
+            ],
+        ));
+        assert_eq!(head.content_length, Some(0), "empty content length");
+        assert_eq!(head.etag, None, "invalid etag");
+        assert_eq!(head.headers().count(), 0, "invalid headers");
+    }
+
+    #[test]
+    fn test_head_object_headers_canonicalization() {
+        let head = HeadObject::from(object(
+            Some(1),
+            &[
+                ("Content-Type", "text/plain"),
+                ("CACHE-CONTROL", "no-cache"),
+                ("cache-control", "max-age=60"),
+                ("S3:ETag", "0a1b"),
+            ],
+        ));
+        assert_eq!(
+            head.headers().collect::<Vec<_>>(),
+            [
+                ("Content-Type", "text/plain"),
+                ("Cache-Control", "max-age=60")
+            ],
+            "case-insensitive keys with the exact ones taking precedence"
+        );
+        assert_eq!(head.etag, None, "the etag key is case-sensitive");
+    }
+
+    #[test]
+    fn test_entity_tag() {
+        for (value, want) in [
+            ("0a1b", Some("\"0a1b\"")),
+            ("\"0a1b\"", Some("\"0a1b\"")),
+            ("W/\"0a1b\"", Some("W/\"0a1b\"")),
+            ("0a1b-2", Some("\"0a1b-2\"")),
+            ("", None),
+            ("\"\"", None),
+            ("0a 1b", None),
+            ("0a\"1b", None),
+            ("W/\"0a1b", None),
+            ("0a1bé", None),
+        ] {
+            assert_eq!(entity_tag(value).as_deref(), want, "{:?}", value);
+        }
+    }
+}
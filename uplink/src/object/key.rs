@@ -0,0 +1,135 @@
+//! Object keys built from file system paths.
+
+use crate::{Error, Result};
+
+use std::fmt;
+use std::path::Path;
+
+/// The key of an object built from a file system path with [`Key::from_path`].
+///
+/// The [`Project`](crate::Project) methods accept it as any other key because it implements
+/// `AsRef<str>`, so a key built from a path can be passed where a `&str` or a `String` is.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(String);
+
+impl Key {
+    /// Creates the key of the relative `path`, whose components are separated by `/` regardless
+    /// of the OS, so `dir\file.txt` and `dir/file.txt` are the same key.
+    ///
+    /// The `.` components are removed (e.g. `./dir/file.txt` is `dir/file.txt`).
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `path` isn't valid UTF-8, it contains a NUL
+    /// character, it's absolute, it has empty components (e.g. `dir//file.txt` or `dir/`) or it
+    /// has `..` components, which could reference keys outside of the intended prefix.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let path = path
+            .to_str()
+            .ok_or_else(|| Error::new_invalid_arguments("path", "must be valid UTF-8"))?;
+        if path.contains('\0') {
+            return Err(Error::new_invalid_arguments(
+                "path",
+                "cannot contain NUL characters",
+            ));
+        }
+
+        let mut components = Vec::new();
+        for component in path.split(['/', '\\']) {
+            match component {
+                "" => {
+                    return Err(Error::new_invalid_arguments(
+                        "path",
+                        "cannot be absolute nor have empty components",
+                    ))
+                }
+                "." => {}
+                ".." => {
+                    return Err(Error::new_invalid_arguments(
+                        "path",
+                        "cannot have '..' components",
+                    ))
+                }
+                _ => components.push(component),
+            }
+        }
+
+        if components.is_empty() {
+            return Err(Error::new_invalid_arguments(
+                "path",
+                "must have at least one component different than '.'",
+            ));
+        }
+
+        Ok(Self(components.join("/")))
+    }
+
+    /// Returns the key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the key as a `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_key_from_path() {
+        for (path, want) in [
+            ("file.txt", "file.txt"),
+            ("dir/sub/file.txt", "dir/sub/file.txt"),
+            ("dir\\sub\\file.txt", "dir/sub/file.txt"),
+            ("dir\\sub/file.txt", "dir/sub/file.txt"),
+            (".\\dir\\.\\file.txt", "dir/file.txt"),
+            ("C:\\file.txt", "C:/file.txt"),
+        ] {
+            let key = Key::from_path(Path::new(path)).expect(path);
+            assert_eq!(key.as_str(), want, "{}", path);
+            assert_eq!(key.to_string(), want, "{}: display", path);
+            assert_eq!(key.as_ref(), want, "{}: as ref", path);
+        }
+    }
+
+    #[test]
+    fn test_key_from_path_invalid() {
+        for path in [
+            "",
+            ".",
+            "/dir/file.txt",
+            "\\dir\\file.txt",
+            "dir//file.txt",
+            "dir\\\\file.txt",
+            "dir/",
+            "dir\\",
+            "..",
+            "../file.txt",
+            "dir\\..\\..\\file.txt",
+            "dir/../file.txt",
+            "dir/fi\0le.txt",
+        ] {
+            match Key::from_path(Path::new(path)) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "path", "{:?}: invalid argument name", path)
+                }
+                res => panic!("{:?}: unexpected result: {:?}", path, res),
+            }
+        }
+    }
+}
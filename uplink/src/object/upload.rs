@@ -1,15 +1,24 @@
 //! Contains information and operations for uploading objects.
 
-use crate::uplink_c::Ensurer;
-use crate::{metadata, Error, Object, Result};
+use super::BufferedUpload;
+use crate::error::BoxError;
+use crate::project::options;
+use crate::uplink_c::{ffi_guard, OperationFlag, TryEnsure};
+use crate::{helpers, io_util, metadata, Error, Object, Project, Result};
 
 use std::ffi::{CStr, CString};
+use std::sync::Arc;
 use std::time;
 use std::vec::Vec;
 
 use uplink_sys as ulksys;
 
 /// Allows to upload the object's data to the Storj DCS network.
+///
+/// An upload supports a single operation at a time. Its [`std::io::Write`] implementation returns
+/// an error of kind [`std::io::ErrorKind::WouldBlock`] instead of calling the FFI if it's reentered
+/// while another write is in progress, which can only happen when the borrowing rules are
+/// circumvented.
 #[derive(Debug)]
 pub struct Upload {
     /// The upload type of the FFI that an instance of this struct represents and guards its life
@@ -21,6 +30,16 @@ pub struct Upload {
     /// `inner.error` must be NULL when this instance is created and should usually remain NULL
     /// except for the identified circumstance of the `self.write` method.
     inner: ulksys::UplinkUploadResult,
+    /// The state of the upload, which determines the operations that are delegated to the FFI.
+    state: State,
+    /// The number of bytes of the object's data written to this instance, see
+    /// [`Self::bytes_written`].
+    bytes_written: u64,
+    /// Prevents overlapping writes on `inner`.
+    operation: OperationFlag,
+    /// The limits of the project for validating the custom metadata, see
+    /// [`Config::set_metadata_limits`](crate::Config::set_metadata_limits).
+    metadata_limits: metadata::MetadataLimits,
 }
 
 impl Upload {
@@ -29,52 +48,144 @@ impl Upload {
     /// It returns an error, through the
     /// [`Error::new_uplink` constructor](crate::Error::new_uplink), if `uc_upload` contains a non
     /// `NULL` pointer in the `error` field.
-    pub(crate) fn from_ffi_upload_result(uc_upload: ulksys::UplinkUploadResult) -> Result<Self> {
-        uc_upload.ensure();
+    pub(crate) fn from_ffi_upload_result(
+        uc_upload: ulksys::UplinkUploadResult,
+        metadata_limits: metadata::MetadataLimits,
+    ) -> Result<Self> {
+        uc_upload.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_upload.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a correct value.
             unsafe { ulksys::uplink_free_upload_result(uc_upload) };
             Err(err)
         } else {
-            Ok(Self { inner: uc_upload })
+            Ok(Self {
+                inner: uc_upload,
+                state: State::Open,
+                bytes_written: 0,
+                operation: OperationFlag::default(),
+                metadata_limits,
+            })
         }
     }
 
     /// Aborts a non-finalized upload.
     ///
-    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::Uplink::UploadDone`] if this
-    /// method or [`Self::commit`] was previously called. It may return others [`Error::Uplink`]
-    /// variants in other cases.
+    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::UplinkCode::UploadDone`] code
+    /// if this method or [`Self::commit`] was previously called. It may return [`Error::Uplink`]
+    /// with other codes in other cases.
     pub fn abort(&mut self) -> Result<()> {
         // SAFETY: we trust the FFI when dealing with a correct instance.
         let err = unsafe { ulksys::uplink_upload_abort(self.inner.upload) };
-        if let Some(err) = Error::new_uplink(err) {
-            Err(err)
-        } else {
-            Ok(())
-        }
+        let res = match Error::from_ffi_error(err) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        };
+
+        self.state = self.state.after_abort(res.is_ok());
+        res
+    }
+
+    /// Returns the raw FFI upload of this instance for passing it to other C code that uses the
+    /// uplink-c API.
+    ///
+    /// The pointer is owned by this instance, so the caller must not free it and must not use it
+    /// after this instance is dropped. Committing or aborting it through the FFI isn't tracked by
+    /// this instance, so [`Self::info`] may not detect an aborted upload.
+    ///
+    /// ```no_run
+    /// use uplink::access::Grant;
+    /// use uplink::Project;
+    ///
+    /// let grant = Grant::new("my-serialized-access-grant")?;
+    /// let project = Project::open(&grant);
+    /// let upload = project.upload_object("my-bucket", "hello.txt", None)?;
+    ///
+    /// // SAFETY: the raw upload is owned by `upload`, which outlives this call, and the FFI
+    /// // doesn't free it.
+    /// let res = unsafe { uplink_sys::uplink_upload_info(upload.as_raw()) };
+    /// // SAFETY: the result is returned by the FFI and it isn't used after freeing it.
+    /// unsafe { uplink_sys::uplink_free_object_result(res) };
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn as_raw(&self) -> *mut ulksys::UplinkUpload {
+        self.inner.upload
+    }
+
+    /// Returns the number of bytes of the object's data written so far through the
+    /// [`std::io::Write`] implementation, including the bytes written by a write that has failed
+    /// after writing some of them.
+    ///
+    /// The bytes written directly through [`Self::as_raw`] aren't counted.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
     }
 
     /// Commits the object's data to the store.
     ///
-    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::Uplink::UploadDone`] if this
-    /// method or [`Self::abort`] was previously called. It may return others [`Error::Uplink`]
-    /// variants in other cases.
+    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::UplinkCode::UploadDone`] code
+    /// if this method or [`Self::abort`] was previously called. It may return [`Error::Uplink`]
+    /// with other codes in other cases.
     pub fn commit(&mut self) -> Result<()> {
         // SAFETY: we trust the FFI when dealing with a correct instance.
         let err = unsafe { ulksys::uplink_upload_commit(self.inner.upload) };
-        if let Some(err) = Error::new_uplink(err) {
-            Err(err)
-        } else {
-            Ok(())
+        let res = match Error::from_ffi_error(err) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        };
+
+        self.state = self.state.after_commit(res.is_ok());
+        res
+    }
+
+    /// Writes all the bytes of `data` with consecutive calls to the FFI and commits the upload
+    /// right after, without going through the [`std::io::Write`] implementation.
+    ///
+    /// It returns the error of a previous write that has failed after writing some bytes, the
+    /// errors returned by the FFI when writing, without committing the upload, and the same
+    /// errors than [`Self::commit`].
+    pub(crate) fn write_all_and_commit(&mut self, mut data: &[u8]) -> Result<()> {
+        if let Some(err) = Error::new_uplink(self.inner.error) {
+            return Err(err);
+        }
+
+        while !data.is_empty() {
+            // SAFETY: we trust the FFI when dealing with a correct instance. See the `write`
+            // method of the `std::io::Write` implementation about casting `data` to a mutable
+            // pointer.
+            let uc_res = unsafe {
+                ulksys::uplink_upload_write(
+                    self.inner.upload,
+                    (data.as_ptr() as *mut u8).cast(),
+                    data.len(),
+                )
+            };
+            self.bytes_written += uc_res.bytes_written as u64;
+            if let Some(err) = Error::from_ffi_error(uc_res.error) {
+                return Err(err);
+            }
+
+            if uc_res.bytes_written == 0 {
+                return Err(Error::new_internal(
+                    "cannot write the object's data",
+                    BoxError::from("the upload didn't accept any byte"),
+                ));
+            }
+
+            data = &data[uc_res.bytes_written..];
         }
+
+        self.commit()
     }
 
     /// Returns the last information about the uploaded object.
     ///
-    /// It returns an [`Error::Uplink`] if any of the calls to the FFI returns an error.
+    /// It returns an [`Error::InvalidArguments`] without calling the FFI if the upload was
+    /// aborted or its commit failed, because the FFI doesn't behave consistently in those cases,
+    /// and an [`Error::Uplink`] if any of the calls to the FFI returns an error.
     pub fn info(&self) -> Result<Object> {
+        self.state.check_info()?;
+
         // SAFETY: we trust the FFI when dealing with a correct instance.
         let uc_obj_res = unsafe { ulksys::uplink_upload_info(self.inner.upload) };
 
@@ -82,83 +193,171 @@ impl Upload {
             .map(|op| op.expect("successful upload info must always return an object"))
     }
 
+    /// Wraps this upload in a [`BufferedUpload`] that writes the data in chunks of `capacity`
+    /// bytes, which is considerably faster when the data is written in small chunks.
+    ///
+    /// [`BufferedUpload::DEFAULT_CAPACITY`] is the recommended capacity.
+    pub fn into_buffered(self, capacity: usize) -> BufferedUpload {
+        BufferedUpload::new(self, capacity)
+    }
+
     /// Updates the custom metadata to be included with the object.
-    pub fn set_custom_metadata(&mut self, metadata: &mut metadata::Custom) -> Result<()> {
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `metadata` doesn't satisfy the limits of the
+    /// project, see [`metadata::Custom::validate`].
+    pub fn set_custom_metadata(&mut self, metadata: &metadata::Custom) -> Result<()> {
+        metadata.validate_fn_arg("metadata", &self.metadata_limits)?;
+        let uc_custom = metadata.to_ffi_custom_metadata();
+
         // SAFETY: We are sure that FFI doesn't take ownership of the two parameters.
-        // Metadata FFI type is obtained from a safe wrapper.
+        // Metadata FFI type is obtained from a safe wrapper that lives until the call returns.
         let err = unsafe {
             ulksys::uplink_upload_set_custom_metadata(
                 self.inner.upload,
-                metadata.to_ffi_custom_metadata(),
+                uc_custom.as_ffi_custom_metadata(),
             )
         };
-        if let Some(err) = Error::new_uplink(err) {
+        if let Some(err) = Error::from_ffi_error(err) {
             Err(err)
         } else {
             Ok(())
         }
     }
-}
 
-impl std::io::Write for Upload {
-    /// Flush doesn't do anything, it only exists to fulfill the [`std::io::Write`] trait
-    /// implementation. It always return `Ok(())`.
-    fn flush(&mut self) -> std::io::Result<()> {
-        Ok(())
+    /// Wraps this upload in a [`Throttled`](io_util::Throttled) writer that limits its throughput
+    /// with `throttle`, which can be shared with other transfers for limiting them collectively.
+    pub fn throttled(self, throttle: Arc<io_util::Throttle>) -> io_util::Throttled<Self> {
+        io_util::Throttled::new(self, throttle)
     }
 
-    /// Uploads the bytes in `buf` to the object's data stream. It returns the total number of
-    /// written bytes which are between 0 and the `buf` length or an error.
+    /// Writes `buf` calling `write`, which wraps the FFI write function, and returns the number
+    /// of written bytes, adding them to `bytes_written`.
     ///
-    /// When it returns an error is always a [`std::io::ErrorKind::Other`] and the error payload is
-    /// an [`Error::Uplink`].
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        // When self is created, it ensures that `self.inner.error` is NULL, but in order of being
+    /// `pending_err` is the error of a previous write that has written some bytes before failing,
+    /// which is returned without calling `write`; it's set when `write` returns an error after
+    /// writing some bytes.
+    fn write_ffi(
+        pending_err: &mut *mut ulksys::UplinkError,
+        bytes_written: &mut u64,
+        buf: &[u8],
+        write: impl FnOnce(&[u8]) -> ulksys::UplinkWriteResult,
+    ) -> std::io::Result<usize> {
+        // When the upload is created, it ensures that its error is NULL, but in order of being
         // able to return the written bytes when some of them are written but an error has
-        // happened, we keep the returned FFI error in `self.inner.error` and in the next call to
+        // happened, we keep the returned FFI error in `pending_err` and in the next call to
         // `write` that the caller should to write the rest of the bytes, we return the error
         // returned on the previous call.
-        if !self.inner.error.is_null() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                Error::new_uplink(self.inner.error)
-                    .expect("BUG: missing a non NULL verification previous to this call"),
-            ));
+        if !pending_err.is_null() {
+            return Err(Error::new_uplink(*pending_err)
+                .expect("BUG: missing a non NULL verification previous to this call")
+                .into());
         }
 
-        // SAFETY: we trust the FFI when dealing with a correct instance.
-        //
-        // We cannot use `buf.as_mut_ptr()` because `buf` is not passed as a mutable reference,
-        // hence we have to directly cast it and it should not be a problem because the FFI
-        // function doesn't write in this pointer despite the parameter is a `*mut c_void`.
-        // We believe that the parameter is `mut` because it's what _bindgen_ has unfairly
-        // generated.
-        let uc_res = unsafe {
-            ulksys::uplink_upload_write(
-                self.inner.upload,
-                (buf.as_ptr() as *mut u8).cast(),
-                buf.len(),
-            )
-        };
+        let uc_res = write(buf);
 
         if !uc_res.error.is_null() {
             // There is an error and the operation didn't upload any byte, so we return the error
             // directly.
             if uc_res.bytes_written == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    Error::new_uplink(uc_res.error)
-                        .expect("BUG: missing a non NULL verification previous to this call"),
-                ));
+                return Err(Error::new_uplink(uc_res.error)
+                    .expect("BUG: missing a non NULL verification previous to this call")
+                    .into());
             }
 
             // There is an error but the operation uploaded a few bytes, so keep the error for
             // returning it on the next call `write` and this call returns the amount of uploaded
             // bytes.
-            self.inner.error = uc_res.error;
+            *pending_err = uc_res.error;
         }
 
-        Ok(uc_res.bytes_written as usize)
+        *bytes_written += uc_res.bytes_written as u64;
+        Ok(uc_res.bytes_written)
+    }
+}
+
+/// The states of an [`Upload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Neither committed nor aborted.
+    Open,
+    /// Successfully committed.
+    Committed,
+    /// Successfully aborted.
+    Aborted,
+    /// The commit failed.
+    Failed,
+}
+
+impl State {
+    /// Returns the state after a commit, which succeeded if `ok` is true.
+    ///
+    /// Only the commits of open uploads change the state, the rest fail without any effect.
+    fn after_commit(self, ok: bool) -> Self {
+        match (self, ok) {
+            (Self::Open, true) => Self::Committed,
+            (Self::Open, false) => Self::Failed,
+            (state, _) => state,
+        }
+    }
+
+    /// Returns the state after an abort, which succeeded if `ok` is true.
+    ///
+    /// A failed abort doesn't change the state.
+    fn after_abort(self, ok: bool) -> Self {
+        if ok {
+            Self::Aborted
+        } else {
+            self
+        }
+    }
+
+    /// Returns an [`Error::InvalidArguments`] if the upload information cannot be retrieved in
+    /// this state.
+    fn check_info(self) -> Result<()> {
+        match self {
+            Self::Open | Self::Committed => Ok(()),
+            Self::Aborted => Err(Error::new_invalid_arguments(
+                "upload",
+                "upload already aborted",
+            )),
+            Self::Failed => Err(Error::new_invalid_arguments(
+                "upload",
+                "upload commit failed",
+            )),
+        }
+    }
+}
+
+impl std::io::Write for Upload {
+    /// Flush doesn't do anything, it only exists to fulfill the [`std::io::Write`] trait
+    /// implementation. It always return `Ok(())`.
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Uploads the bytes in `buf` to the object's data stream. It returns the total number of
+    /// written bytes which are between 0 and the `buf` length or an error.
+    ///
+    /// When it returns an error its payload is an [`Error::Uplink`] and its kind is mapped as the
+    /// `From<Error>` implementation of [`std::io::Error`] documents. Use [`Error::from_io_chain`]
+    /// for getting the payload back. It returns an error of kind
+    /// [`std::io::ErrorKind::WouldBlock`], whose payload is an [`Error::Internal`], without
+    /// calling the FFI if another write is in progress.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let _guard = self.operation.enter("upload")?;
+        let uc_upload = self.inner.upload;
+        Self::write_ffi(&mut self.inner.error, &mut self.bytes_written, buf, |buf| {
+            // SAFETY: we trust the FFI when dealing with a correct instance.
+            //
+            // We cannot use `buf.as_mut_ptr()` because `buf` is not passed as a mutable
+            // reference, hence we have to directly cast it and it should not be a problem
+            // because the FFI function doesn't write in this pointer despite the parameter is
+            // a `*mut c_void`. We believe that the parameter is `mut` because it's what
+            // _bindgen_ has unfairly generated.
+            unsafe {
+                ulksys::uplink_upload_write(uc_upload, (buf.as_ptr() as *mut u8).cast(), buf.len())
+            }
+        })
     }
 }
 
@@ -170,22 +369,61 @@ impl Drop for Upload {
 }
 
 /// Iterator over a collection of uncommitted uploads.
+#[derive(Debug)]
 pub struct Iterator {
     /// The upload iterator type of the FFI that an instance of this struct represents and guards
     /// its lifetime until the instance drops.
     inner: *mut ulksys::UplinkUploadIterator,
+    /// Indicates if the listing requested the system metadata of the uploads.
+    system: bool,
+    /// Indicates if the listing requested the custom metadata of the uploads.
+    custom: bool,
 }
 
 impl Iterator {
-    /// Creates a new instance from the FFI representation.
-    pub(crate) fn from_ffi_upload_iterator(uc_iterator: *mut ulksys::UplinkUploadIterator) -> Self {
-        Self { inner: uc_iterator }
+    /// Creates a new instance from the FFI representation of an iterator created with listing
+    /// options that request the system and/or the custom metadata according to `system` and
+    /// `custom`.
+    ///
+    /// It returns the following errors:
+    /// * an [`Error::Internal`](crate::Error::Internal) if `uc_iterator` is `NULL`.
+    /// * an [`Error::from_ffi_error` constructor](crate::Error::from_ffi_error), if the FFI
+    ///   iterator is in an error state, in which case the FFI iterator is freed.
+    pub(crate) fn from_ffi_upload_iterator(
+        uc_iterator: *mut ulksys::UplinkUploadIterator,
+        system: bool,
+        custom: bool,
+    ) -> Result<Self> {
+        if uc_iterator.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL upload iterator",
+                BoxError::from("NULL pointer"),
+            ));
+        }
+
+        // SAFETY: we have checked above that the pointer isn't `NULL` and we trust the FFI
+        // functions don't panic when called with an instance returned by them.
+        let uc_error = unsafe { ulksys::uplink_upload_iterator_err(uc_iterator) };
+        if let Some(err) = Error::from_ffi_error(uc_error) {
+            // SAFETY: we trust the FFI is safe freeing the memory of a correct pointer.
+            unsafe { ulksys::uplink_free_upload_iterator(uc_iterator) };
+            return Err(err);
+        }
+
+        Ok(Self {
+            inner: uc_iterator,
+            system,
+            custom,
+        })
     }
 }
 
 impl std::iter::Iterator for Iterator {
     type Item = Result<Info>;
 
+    /// An upload whose ID or key contains invalid UTF-8 characters or whose custom metadata is
+    /// invalid is returned as an [`Error::Internal`] and the iteration can continue with the next uploads. It always returns
+    /// `None` after returning an [`Error::Uplink`].
     fn next(&mut self) -> Option<Self::Item> {
         // SAFETY: we trust the FFI functions don't panic when called with an instance returned by
         // them and they don't return any invalid memory references or `null` if next returns
@@ -193,12 +431,15 @@ impl std::iter::Iterator for Iterator {
         unsafe {
             if !ulksys::uplink_upload_iterator_next(self.inner) {
                 let uc_error = ulksys::uplink_upload_iterator_err(self.inner);
-                return Error::new_uplink(uc_error).map(Err);
+                return Error::from_ffi_error(uc_error).map(Err);
             }
 
-            Some(Ok(Info::from_ffi_upload_info(
-                ulksys::uplink_upload_iterator_item(self.inner),
-            )))
+            let uc_upload = ulksys::uplink_upload_iterator_item(self.inner);
+            let (system, custom) = (self.system, self.custom);
+            Some(ffi_guard(
+                "FFI returned an invalid upload iterator item",
+                || Info::from_ffi_upload_info(uc_upload, system, custom),
+            ))
         }
     }
 }
@@ -210,7 +451,25 @@ impl Drop for Iterator {
     }
 }
 
+/// The summary of aborting the pending uploads of a bucket, see
+/// [`Project::abort_uploads`](crate::Project::abort_uploads).
+#[derive(Debug, Default)]
+pub struct AbortSummary {
+    /// The number of aborted uploads.
+    pub aborted: usize,
+    /// The uploads that couldn't be aborted with the error of aborting each of them.
+    pub failed: Vec<(Info, Error)>,
+}
+
+impl AbortSummary {
+    /// Returns `true` if all the pending uploads were aborted.
+    pub fn all_aborted(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
 /// Contains information about a multipart upload operation.
+#[derive(Debug)]
 pub struct Info {
     /// The ID associated to the upload.
     pub upload_id: String,
@@ -222,58 +481,133 @@ pub struct Info {
     pub metadata_system: metadata::System,
     /// The custom metadata associated to the upload.
     pub metadata_custom: metadata::Custom,
+    /// Indicates if `metadata_system` was retrieved.
+    has_system: bool,
+    /// Indicates if `metadata_custom` was retrieved.
+    has_custom: bool,
 }
 
 impl Info {
-    /// Creates a new instance from the FFI representation.
-    fn from_ffi_upload_info(uc_upload: *mut ulksys::UplinkUploadInfo) -> Self {
-        assert!(
-            !uc_upload.is_null(),
-            "BUG: `uc_upload` argument cannot be NULL"
-        );
+    /// Returns true if [`Self::metadata_system`] was retrieved, otherwise it has default values
+    /// that don't reflect the upload's system metadata.
+    ///
+    /// The uploads returned by [`Project::list_uploads`](crate::Project::list_uploads) only have
+    /// it when the listing options request it (see
+    /// [`ListUploads::system`](crate::project::options::ListUploads::system)) and the ones
+    /// returned by [`Project::begin_upload`](crate::Project::begin_upload) always have it.
+    pub fn has_system_metadata(&self) -> bool {
+        self.has_system
+    }
+
+    /// Returns true if [`Self::metadata_custom`] was retrieved, otherwise it's empty regardless
+    /// of the upload's custom metadata.
+    ///
+    /// The uploads returned by [`Project::list_uploads`](crate::Project::list_uploads) only have
+    /// it when the listing options request it (see
+    /// [`ListUploads::custom`](crate::project::options::ListUploads::custom)) and the ones
+    /// returned by [`Project::begin_upload`](crate::Project::begin_upload) always have it.
+    pub fn has_custom_metadata(&self) -> bool {
+        self.has_custom
+    }
+
+    /// Creates a new instance from the FFI representation and frees it. `system` and `custom`
+    /// indicate if the system and custom metadata were retrieved; the custom metadata isn't
+    /// converted when it wasn't.
+    ///
+    /// It returns an [`Error::Internal`] if `uc_upload` is `NULL`, its ID or key contains invalid
+    /// UTF-8 characters or its conversion panics.
+    fn from_ffi_upload_info(
+        uc_upload: *mut ulksys::UplinkUploadInfo,
+        system: bool,
+        custom: bool,
+    ) -> Result<Self> {
+        if uc_upload.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL upload info",
+                BoxError::from("NULL pointer"),
+            ));
+        }
         // SAFETY: we just checked above that this pointer isn't NULL.
         let upload = unsafe { *uc_upload };
-        upload.ensure();
-
-        let is_prefix = upload.is_prefix;
-        let upload_id;
-        let key;
-        // SAFETY: We have guarantee that upload fields aren't null through the `ensure` method
-        // call of the `Ensurer` trait. We make a copy of the C string pointers to Rust strings,
-        // and we free the memory of the C strings to not leak memory.
-        unsafe {
-            upload_id = CStr::from_ptr(upload.upload_id)
-                .to_str()
-                .expect("FFI returned an invalid upload's ID; it contains invalid UTF-8 characters")
-                .to_string();
-            key = CStr::from_ptr(upload.key)
-                .to_str()
-                .expect(
-                    "FFI returned an invalid upload's key; it contains invalid UTF-8 characters",
-                )
-                .to_string();
+        if let Err(err) = upload.try_ensure() {
+            // SAFETY: we trust the FFI is safe freeing the memory of a pointer that it returned.
+            unsafe { ulksys::uplink_free_upload_info(uc_upload) };
+            return Err(err);
+        }
 
+        // SAFETY: We have guarantee that upload fields aren't null through the `try_ensure`
+        // method call of the `TryEnsure` trait. The conversion copies the C strings to Rust strings, so we
+        // free the memory of the C strings to not leak memory. The conversion is guarded for
+        // freeing the memory even if it panics.
+        unsafe {
+            let res = ffi_guard("FFI returned an invalid upload info", || {
+                Self::unchecked_from_ffi_upload_info_ref(&upload, system, custom)
+            });
             ulksys::uplink_free_upload_info(uc_upload);
+            res
         }
+    }
 
-        Self {
+    /// Creates a new instance from a reference to the FFI representation without freeing it. See
+    /// [`Self::from_ffi_upload_info`] about `system` and `custom`.
+    ///
+    /// Uploads started by other clients may have keys that aren't valid UTF-8, so it returns an
+    /// [`Error::Internal`] if `uc_upload`'s ID or key contains invalid UTF-8 characters rather
+    /// than panicking, and the errors of [`metadata::Custom::with_ffi_custom_metadata`].
+    ///
+    /// # Safety
+    ///
+    /// `uc_upload` must not have fields with NULL pointers.
+    unsafe fn unchecked_from_ffi_upload_info_ref(
+        uc_upload: &ulksys::UplinkUploadInfo,
+        system: bool,
+        custom: bool,
+    ) -> Result<Self> {
+        let upload_id = CStr::from_ptr(uc_upload.upload_id)
+            .to_str()
+            .map_err(|err| {
+                Error::new_internal(
+                    "FFI returned an invalid upload's ID; it contains invalid UTF-8 characters",
+                    BoxError::from(err),
+                )
+            })?
+            .to_string();
+        let key = CStr::from_ptr(uc_upload.key)
+            .to_str()
+            .map_err(|err| {
+                Error::new_internal(
+                    "FFI returned an invalid upload's key; it contains invalid UTF-8 characters",
+                    BoxError::from(err),
+                )
+            })?
+            .to_string();
+
+        Ok(Self {
             upload_id,
             key,
-            is_prefix,
-            metadata_system: metadata::System::with_ffi_system_metadata(&upload.system),
-            metadata_custom: metadata::Custom::with_ffi_custom_metadata(&upload.custom),
-        }
+            is_prefix: uc_upload.is_prefix,
+            metadata_system: metadata::System::with_ffi_system_metadata(&uc_upload.system),
+            metadata_custom: if custom {
+                metadata::Custom::with_ffi_custom_metadata(&uc_upload.custom)?
+            } else {
+                metadata::Custom::default()
+            },
+            has_system: system,
+            has_custom: custom,
+        })
     }
 
     /// Creates a new instance from the FFI representation for a info's result.
     ///
-    /// It returns an error, through the
-    /// [`Error::new_uplink` constructor](crate::Error::new_uplink), if `uc_result` contains a non
-    /// `NULL` pointer in the `error` field.
+    /// It returns the following errors:
+    /// * an [`Error::new_uplink` constructor](crate::Error::new_uplink), if `uc_result` contains a
+    ///   non `NULL` pointer in the `error` field.
+    /// * an [`Error::Internal`] if `uc_result.info`'s ID or key contains invalid UTF-8
+    ///   characters or its custom metadata is invalid.
     pub(crate) fn from_ffi_upload_info_result(
         uc_result: ulksys::UplinkUploadInfoResult,
     ) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
@@ -285,11 +619,186 @@ impl Info {
         // the `info` pointer and the `error` pointer is `NULL`, and that's what the free function
         // for the `uc_result` does (i.e. call a free specific function for each pointer returning
         // without doing anything if it's `NULL`).
-        Ok(Self::from_ffi_upload_info(uc_result.info))
+        Self::from_ffi_upload_info(uc_result.info, true, true)
     }
 }
 
+impl std::fmt::Display for Info {
+    /// Formats the key and the upload ID followed by the system metadata as
+    /// [`metadata::System`] does, when it was retrieved (e.g.
+    /// `dir/file.txt (upload 1a2b3c), 0 bytes, created 2024-05-01T12:00:00Z`), or followed by
+    /// `(prefix)` if it's a prefix.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_prefix {
+            return write!(f, "{} (prefix)", self.key);
+        }
+
+        write!(f, "{} (upload {})", self.key, self.upload_id)?;
+        if self.has_system {
+            write!(f, ", {}", self.metadata_system)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A multipart upload started with
+/// [`Project::begin_multipart_upload`](crate::Project::begin_multipart_upload) that remembers its
+/// bucket, key and upload ID, so they don't have to be passed to each operation.
+///
+/// It borrows the project that started it and delegates the operations to the [`Project`]
+/// methods that operate on multipart uploads.
+///
+/// It accumulates the custom metadata of the object while the parts are uploaded, through
+/// [`Self::metadata_mut`], which [`Self::commit`] sets when it isn't called with explicit options.
+pub struct MultipartUpload<'a> {
+    /// The project that started the upload.
+    project: &'a Project,
+    /// The bucket where the object is uploaded.
+    bucket: String,
+    /// The information returned when the upload began.
+    info: Info,
+    /// The custom metadata accumulated for committing the upload.
+    metadata: metadata::Custom,
+}
+
+impl<'a> MultipartUpload<'a> {
+    /// Creates a new instance for the upload of `info` started in `project` and `bucket`.
+    pub(crate) fn new(project: &'a Project, bucket: &str, info: Info) -> Self {
+        Self {
+            project,
+            bucket: String::from(bucket),
+            info,
+            metadata: metadata::Custom::default(),
+        }
+    }
+
+    /// Aborts the upload, see [`Project::abort_upload`].
+    pub fn abort(&self) -> Result<()> {
+        self.project
+            .abort_upload(&self.bucket, &self.info.key, &self.info.upload_id)
+    }
+
+    /// Returns the bucket where the object is uploaded.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Commits the upload with optional options, see [`Project::commit_upload`].
+    ///
+    /// When `opts` is `None` and the accumulated custom metadata (see [`Self::metadata_mut`]) has
+    /// entries, it commits the upload with them; explicit options are used as they are, ignoring
+    /// the accumulated custom metadata.
+    ///
+    /// It returns the same errors than [`Project::commit_upload`], which validates the
+    /// accumulated custom metadata.
+    pub fn commit(&self, opts: Option<&options::CommitUpload>) -> Result<Object> {
+        let accumulated = match opts {
+            None if self.metadata.count() > 0 => {
+                Some(options::CommitUpload::new(self.metadata.clone()))
+            }
+            _ => None,
+        };
+
+        self.project.commit_upload(
+            &self.bucket,
+            &self.info.key,
+            &self.info.upload_id,
+            opts.or(accumulated.as_ref()),
+        )
+    }
+
+    /// Returns the information returned when the upload began.
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    /// Returns the key of the uploaded object.
+    pub fn key(&self) -> &str {
+        &self.info.key
+    }
+
+    /// Returns the custom metadata accumulated for committing the upload.
+    pub fn metadata(&self) -> &metadata::Custom {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the custom metadata accumulated for committing the upload,
+    /// for inserting entries as the parts are uploaded.
+    pub fn metadata_mut(&mut self) -> &mut metadata::Custom {
+        &mut self.metadata
+    }
+
+    /// Inserts into the accumulated custom metadata, under the
+    /// [`metadata::KEY_PARTS_MANIFEST`] key, the manifest of the parts uploaded so far, so it
+    /// should be called after uploading all the parts and before committing.
+    ///
+    /// A manifest of many parts may exceed the custom metadata limits, which makes
+    /// [`Self::commit`] fail.
+    ///
+    /// It returns the same errors than [`Self::list_parts`] and the ones of iterating over them.
+    pub fn parts_manifest(&mut self) -> Result<()> {
+        let mut parts = self.list_parts(None)?.collect::<Result<Vec<Part>>>()?;
+        parts.sort_unstable_by_key(|part| part.part_number);
+        self.metadata.insert(
+            String::from(metadata::KEY_PARTS_MANIFEST),
+            parts_manifest_value(&parts),
+        );
+        Ok(())
+    }
+
+    /// Returns an iterator over the parts of the upload with optional options, see
+    /// [`Project::list_upload_parts`].
+    pub fn list_parts(&self, opts: Option<&options::ListUploadParts>) -> Result<PartIterator> {
+        self.project
+            .list_upload_parts(&self.bucket, &self.info.key, &self.info.upload_id, opts)
+    }
+
+    /// Returns the upload ID.
+    pub fn upload_id(&self) -> &str {
+        &self.info.upload_id
+    }
+
+    /// Uploads the part with `part_number`, see [`Project::upload_part`].
+    pub fn upload_part(&self, part_number: u32) -> Result<PartUpload> {
+        self.project.upload_part(
+            &self.bucket,
+            &self.info.key,
+            &self.info.upload_id,
+            part_number,
+        )
+    }
+}
+
+impl std::fmt::Debug for MultipartUpload<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultipartUpload")
+            .field("bucket", &self.bucket)
+            .field("info", &self.info)
+            .field("metadata", &self.metadata)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the value of the [`metadata::KEY_PARTS_MANIFEST`] custom metadata entry for `parts`
+/// in their order.
+fn parts_manifest_value(parts: &[Part]) -> String {
+    let entries: Vec<String> = parts
+        .iter()
+        .map(|part| {
+            format!(
+                r#"{{"part_number":{},"size":{},"etag_hex":"{}"}}"#,
+                part.part_number,
+                part.size,
+                helpers::hex(&part.etag)
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
 /// Metadata associated to an upload part of a multipart upload operation.
+#[derive(Debug)]
 pub struct Part {
     /// The number of the part.
     pub part_number: u32,
@@ -302,9 +811,22 @@ pub struct Part {
 }
 
 impl Part {
-    /// Creates a new instance from the FFI representation.
-    fn from_ffi_part(uc_part: *mut ulksys::UplinkPart) -> Self {
-        assert!(!uc_part.is_null(), "BUG: `uc_part` argument cannot be NULL");
+    /// Returns the modification time as a UTC date time.
+    #[cfg(feature = "chrono")]
+    pub fn modified_datetime(&self) -> chrono::DateTime<chrono::Utc> {
+        crate::helpers::datetime_from_unix_time(self.modified)
+    }
+
+    /// Creates a new instance from the FFI representation and frees it.
+    ///
+    /// It returns an [`Error::Internal`] if `uc_part` is `NULL`.
+    fn from_ffi_part(uc_part: *mut ulksys::UplinkPart) -> Result<Self> {
+        if uc_part.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL part",
+                BoxError::from("NULL pointer"),
+            ));
+        }
 
         // SAFETY: we just checked above that this pointer isn't NULL.
         let part = unsafe { *uc_part };
@@ -328,12 +850,12 @@ impl Part {
             ulksys::uplink_free_part(uc_part);
         }
 
-        Self {
+        Ok(Self {
             part_number,
             size,
             modified: time::Duration::from_secs(modified),
             etag,
-        }
+        })
     }
 
     /// Creates a new instance from the FFI representation for a part's result.
@@ -342,7 +864,7 @@ impl Part {
     /// [`Error::new_uplink` constructor](crate::Error::new_uplink), if `uc_result` contains a non
     /// `NULL` pointer in the `error` field.
     pub(crate) fn from_ffi_part_result(uc_result: ulksys::UplinkPartResult) -> Result<Self> {
-        uc_result.ensure();
+        uc_result.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_result.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid pointer.
@@ -354,12 +876,20 @@ impl Part {
         // the `part` pointer and the `error` pointer is `NULL`, and that's what the free function
         // for the `uc_result` does (i.e. call a free specific function for each pointer returning
         // without doing anything if it's `NULL`).
-        Ok(Self::from_ffi_part(uc_result.part))
+        ffi_guard("FFI returned an invalid part result", || {
+            Self::from_ffi_part(uc_result.part)
+        })
     }
 }
 
 /// Allows to upload partial object's data to the Storj DCS network through a multipart upload
 /// operation.
+///
+/// A part upload supports a single operation at a time, even when it's sent to another thread. Its
+/// [`std::io::Write`] implementation returns an error of kind [`std::io::ErrorKind::WouldBlock`]
+/// instead of calling the FFI if it's reentered while another write is in progress, which can only
+/// happen when the borrowing rules are circumvented.
+#[derive(Debug)]
 pub struct PartUpload {
     /// The upload type of the FFI that an instance of this struct represents and guards its life
     /// time until the instances drops.
@@ -370,6 +900,8 @@ pub struct PartUpload {
     /// `inner.error` must be NULL when this instance is created and should usually remain NULL
     /// except for the identified circumstance of the `self.write` method.
     inner: ulksys::UplinkPartUploadResult,
+    /// Prevents overlapping writes on `inner`.
+    operation: OperationFlag,
 }
 
 impl PartUpload {
@@ -381,27 +913,30 @@ impl PartUpload {
     pub(crate) fn from_ffi_part_upload_result(
         uc_pupload: ulksys::UplinkPartUploadResult,
     ) -> Result<Self> {
-        uc_pupload.ensure();
+        uc_pupload.try_ensure()?;
 
         if let Some(err) = Error::new_uplink(uc_pupload.error) {
             // SAFETY: we trust the FFI is safe freeing the memory of a valid value.
             unsafe { ulksys::uplink_free_part_upload_result(uc_pupload) };
             Err(err)
         } else {
-            Ok(Self { inner: uc_pupload })
+            Ok(Self {
+                inner: uc_pupload,
+                operation: OperationFlag::default(),
+            })
         }
     }
 
     /// Aborts the part upload.
     ///
     ///
-    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::Uplink::UploadDone`] if this
-    /// method or [`Self::commit`] was previously called. It may return others [`Error::Uplink`]
-    /// variants in other cases.
+    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::UplinkCode::UploadDone`] code
+    /// if this method or [`Self::commit`] was previously called. It may return [`Error::Uplink`]
+    /// with other codes in other cases.
     pub fn abort(&mut self) -> Result<()> {
         // SAFETY: we trust the FFI when dealing with a correct instance.
         let err = unsafe { ulksys::uplink_part_upload_abort(self.inner.part_upload) };
-        if let Some(err) = Error::new_uplink(err) {
+        if let Some(err) = Error::from_ffi_error(err) {
             Err(err)
         } else {
             Ok(())
@@ -410,13 +945,13 @@ impl PartUpload {
 
     /// Commits the part upload to the store.
     ///
-    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::Uplink::UploadDone`] if this
-    /// method or [`Self::abort`] was previously called. It may return others [`Error::Uplink`]
-    /// variants in other cases.
+    /// Returns an [`crate::Error::Uplink`] with the [`crate::error::UplinkCode::UploadDone`] code
+    /// if this method or [`Self::abort`] was previously called. It may return [`Error::Uplink`]
+    /// with other codes in other cases.
     pub fn commit(&mut self) -> Result<()> {
         // SAFETY: we trust the FFI when dealing with a correct instance.
         let err = unsafe { ulksys::uplink_part_upload_commit(self.inner.part_upload) };
-        if let Some(err) = Error::new_uplink(err) {
+        if let Some(err) = Error::from_ffi_error(err) {
             Err(err)
         } else {
             Ok(())
@@ -460,7 +995,7 @@ impl PartUpload {
             )
         };
 
-        if let Some(err) = Error::new_uplink(err) {
+        if let Some(err) = Error::from_ffi_error(err) {
             Err(err)
         } else {
             Ok(())
@@ -468,6 +1003,13 @@ impl PartUpload {
     }
 }
 
+// SAFETY: the FFI part upload is a handler of a value that it's owned by the Go side and it can be
+// used from any thread as long as it isn't used concurrently, which is guaranteed because this
+// type isn't `Sync` and the writes are also checked at runtime through `operation`. uplink-c
+// guards the table of handlers with a mutex, so different handlers can be used concurrently from
+// different threads.
+unsafe impl Send for PartUpload {}
+
 impl std::io::Write for PartUpload {
     /// Flush doesn't do anything, it only exists to fulfill the [`std::io::Write`] trait
     /// implementation. It always return `Ok(())`.
@@ -475,18 +1017,23 @@ impl std::io::Write for PartUpload {
         Ok(())
     }
 
+    /// Uploads the bytes in `buf` to the part's data stream. It returns the total number of
+    /// written bytes which are between 0 and the `buf` length or an error.
+    ///
+    /// It returns an error of kind [`std::io::ErrorKind::WouldBlock`], whose payload is an
+    /// [`Error::Internal`], without calling the FFI if another write is in progress.
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let _guard = self.operation.enter("part upload")?;
+
         // When self is created, it ensures that `self.inner.error` is NULL, but in order of being
         // able to return the written bytes when some of them are written but an error has
         // happened, we keep the returned FFI error in `self.inner.error` and in the next call to
         // `write` that the caller should to write the rest of the bytes, we return the error
         // returned on the previous call.
         if !self.inner.error.is_null() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                Error::new_uplink(self.inner.error)
-                    .expect("BUG: missing a non NULL verification previous to this call"),
-            ));
+            return Err(Error::new_uplink(self.inner.error)
+                .expect("BUG: missing a non NULL verification previous to this call")
+                .into());
         }
 
         // SAFETY: we trust the FFI when dealing with a correct instance.
@@ -508,11 +1055,9 @@ impl std::io::Write for PartUpload {
             // There is an error and the operation didn't upload any byte, so we return the error
             // directly.
             if uc_res.bytes_written == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    Error::new_uplink(uc_res.error)
-                        .expect("BUG: missing a non NULL verification previous to this call"),
-                ));
+                return Err(Error::new_uplink(uc_res.error)
+                    .expect("BUG: missing a non NULL verification previous to this call")
+                    .into());
             }
 
             // There is an error but the operation uploaded a few bytes, so keep the error for
@@ -533,6 +1078,7 @@ impl Drop for PartUpload {
 }
 
 /// Iterator over a collection of parts of a multipart upload operation.
+#[derive(Debug)]
 pub struct PartIterator {
     /// The upload iterator type of the FFI that an instance of this struct represents and guards
     /// its lifetime until the instance drops.
@@ -541,13 +1087,31 @@ pub struct PartIterator {
 
 impl PartIterator {
     /// Creates a new instance from the type exposed by the FFI.
-    pub(crate) fn from_ffi_part_iterator(uc_iterator: *mut ulksys::UplinkPartIterator) -> Self {
-        assert!(
-            !uc_iterator.is_null(),
-            "BUG: `uc_iterator` argument cannot be NULL"
-        );
+    ///
+    /// It returns the following errors:
+    /// * an [`Error::Internal`](crate::Error::Internal) if `uc_iterator` is `NULL`.
+    /// * an [`Error::from_ffi_error` constructor](crate::Error::from_ffi_error), if the FFI
+    ///   iterator is in an error state, in which case the FFI iterator is freed.
+    pub(crate) fn from_ffi_part_iterator(
+        uc_iterator: *mut ulksys::UplinkPartIterator,
+    ) -> Result<Self> {
+        if uc_iterator.is_null() {
+            return Err(Error::new_internal(
+                "FFI returned a NULL part iterator",
+                BoxError::from("NULL pointer"),
+            ));
+        }
+
+        // SAFETY: we have checked above that the pointer isn't `NULL` and we trust the FFI
+        // functions don't panic when called with an instance returned by them.
+        let uc_error = unsafe { ulksys::uplink_part_iterator_err(uc_iterator) };
+        if let Some(err) = Error::from_ffi_error(uc_error) {
+            // SAFETY: we trust the FFI is safe freeing the memory of a correct pointer.
+            unsafe { ulksys::uplink_free_part_iterator(uc_iterator) };
+            return Err(err);
+        }
 
-        Self { inner: uc_iterator }
+        Ok(Self { inner: uc_iterator })
     }
 }
 
@@ -561,12 +1125,14 @@ impl std::iter::Iterator for PartIterator {
         unsafe {
             if !ulksys::uplink_part_iterator_next(self.inner) {
                 let uc_error = ulksys::uplink_part_iterator_err(self.inner);
-                return Error::new_uplink(uc_error).map(Err);
+                return Error::from_ffi_error(uc_error).map(Err);
             }
 
-            Some(Ok(Part::from_ffi_part(ulksys::uplink_part_iterator_item(
-                self.inner,
-            ))))
+            let uc_part = ulksys::uplink_part_iterator_item(self.inner);
+            Some(ffi_guard(
+                "FFI returned an invalid part iterator item",
+                || Part::from_ffi_part(uc_part),
+            ))
         }
     }
 }
@@ -577,3 +1143,283 @@ impl Drop for PartIterator {
         unsafe { ulksys::uplink_free_part_iterator(self.inner) };
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::ptr;
+
+    #[test]
+    fn test_info_display() {
+        let mut info = Info {
+            upload_id: String::from("1a2b3c"),
+            key: String::from("dir/file.txt"),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: time::Duration::from_secs(1_714_564_800),
+                expires: None,
+                content_length: Some(0),
+            },
+            metadata_custom: metadata::Custom::default(),
+            has_system: true,
+            has_custom: false,
+        };
+        assert_eq!(
+            info.to_string(),
+            "dir/file.txt (upload 1a2b3c), 0 bytes, created 2024-05-01T12:00:00Z",
+            "with system metadata"
+        );
+
+        info.has_system = false;
+        assert_eq!(
+            info.to_string(),
+            "dir/file.txt (upload 1a2b3c)",
+            "without system metadata"
+        );
+
+        info.key = String::from("dir/");
+        info.is_prefix = true;
+        assert_eq!(info.to_string(), "dir/ (prefix)", "prefix");
+    }
+
+    #[test]
+    fn test_parts_manifest_value() {
+        assert_eq!(parts_manifest_value(&[]), "[]", "no parts");
+
+        let part = |part_number, size, etag: &[u8]| Part {
+            part_number,
+            size,
+            modified: time::Duration::ZERO,
+            etag: etag.to_vec(),
+        };
+        assert_eq!(
+            parts_manifest_value(&[part(1, 5_242_880, &[0x0a, 0x1b]), part(2, 10, &[])]),
+            r#"[{"part_number":1,"size":5242880,"etag_hex":"0a1b"},{"part_number":2,"size":10,"etag_hex":""}]"#,
+            "parts"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_part_modified_datetime() {
+        let mut part = Part {
+            part_number: 1,
+            size: 0,
+            modified: time::Duration::ZERO,
+            etag: Vec::new(),
+        };
+        assert_eq!(part.modified_datetime().timestamp(), 0, "epoch");
+
+        part.modified = time::Duration::from_secs(1_714_564_800);
+        assert_eq!(
+            part.modified_datetime().to_rfc3339(),
+            "2024-05-01T12:00:00+00:00",
+            "modified"
+        );
+    }
+
+    #[test]
+    fn test_upload_write_ffi_bytes_written() {
+        let mut uc_err = ulksys::UplinkError {
+            code: ulksys::UPLINK_ERROR_INTERNAL as i32,
+            message: ptr::null_mut(),
+        };
+        let written = |n: usize, error: *mut ulksys::UplinkError| {
+            move |buf: &[u8]| {
+                assert!(n <= buf.len(), "written bytes fit in the buffer");
+                ulksys::UplinkWriteResult {
+                    bytes_written: n,
+                    error,
+                }
+            }
+        };
+
+        let mut pending_err = ptr::null_mut();
+        let mut bytes_written = 0;
+        let buf = [0; 10];
+        for total in [10, 20, 30] {
+            let n = Upload::write_ffi(
+                &mut pending_err,
+                &mut bytes_written,
+                &buf,
+                written(10, ptr::null_mut()),
+            )
+            .expect("write");
+            assert_eq!(n, 10, "written bytes");
+            assert_eq!(bytes_written, total, "bytes written");
+        }
+
+        // A write that fails without writing any byte doesn't change the count.
+        Upload::write_ffi(
+            &mut pending_err,
+            &mut bytes_written,
+            &buf,
+            written(0, &mut uc_err),
+        )
+        .expect_err("write error");
+        assert!(pending_err.is_null(), "no pending error");
+        assert_eq!(bytes_written, 30, "bytes written after an error");
+
+        // A write that fails after writing some bytes counts them and the next write returns its
+        // error.
+        let n = Upload::write_ffi(
+            &mut pending_err,
+            &mut bytes_written,
+            &buf,
+            written(4, &mut uc_err),
+        )
+        .expect("partial write");
+        assert_eq!(n, 4, "partially written bytes");
+        assert_eq!(bytes_written, 34, "bytes written after a partial write");
+
+        match Upload::write_ffi(&mut pending_err, &mut bytes_written, &buf, |_| {
+            panic!("write with a pending error")
+        }) {
+            Err(err) => assert_eq!(
+                Error::from_io_chain(&err).and_then(Error::uplink_code),
+                Some(crate::error::UplinkCode::Internal),
+                "pending error code"
+            ),
+            Ok(n) => panic!("unexpected written bytes: {}", n),
+        }
+        assert_eq!(bytes_written, 34, "bytes written after the pending error");
+    }
+
+    #[test]
+    fn test_state_commit() {
+        assert_eq!(State::Open.after_commit(true), State::Committed, "open");
+        assert_eq!(State::Open.after_commit(false), State::Failed, "open");
+
+        for state in [State::Committed, State::Aborted, State::Failed] {
+            assert_eq!(state.after_commit(true), state, "{:?}", state);
+            assert_eq!(state.after_commit(false), state, "{:?}", state);
+        }
+    }
+
+    #[test]
+    fn test_state_abort() {
+        for state in [State::Open, State::Committed, State::Aborted, State::Failed] {
+            assert_eq!(state.after_abort(true), State::Aborted, "{:?}", state);
+            assert_eq!(state.after_abort(false), state, "{:?}", state);
+        }
+
+        // A failed commit can be aborted.
+        assert_eq!(
+            State::Open.after_commit(false).after_abort(true),
+            State::Aborted,
+            "failed commit then abort"
+        );
+    }
+
+    #[test]
+    fn test_state_check_info() {
+        State::Open.check_info().expect("open");
+        State::Committed.check_info().expect("committed");
+
+        for (state, msg) in [
+            (State::Aborted, "upload already aborted"),
+            (State::Failed, "upload commit failed"),
+        ] {
+            match state.check_info() {
+                Err(Error::InvalidArguments(args)) => {
+                    assert_eq!(args.names, "upload", "{:?}: names", state);
+                    assert_eq!(args.msg, msg, "{:?}: msg", state);
+                }
+                res => panic!("{:?}: unexpected result: {:?}", state, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_info_invalid_utf8() {
+        let valid = CString::new("dir/object").unwrap();
+        let invalid = CString::new(b"dir/\xffobject".to_vec()).unwrap();
+        let uc_info = |upload_id: &CString, key: &CString| ulksys::UplinkUploadInfo {
+            upload_id: upload_id.as_ptr() as *mut std::os::raw::c_char,
+            key: key.as_ptr() as *mut std::os::raw::c_char,
+            is_prefix: false,
+            system: ulksys::UplinkSystemMetadata {
+                created: 3600,
+                expires: 0,
+                content_length: 0,
+            },
+            custom: ulksys::UplinkCustomMetadata {
+                entries: ptr::null_mut(),
+                count: 0,
+            },
+        };
+
+        for (uc_info, what) in [
+            (uc_info(&valid, &invalid), "key"),
+            (uc_info(&invalid, &valid), "upload ID"),
+        ] {
+            // SAFETY: the info doesn't have fields with NULL pointers and the memory that it
+            // references lives until the end of the test.
+            match unsafe { Info::unchecked_from_ffi_upload_info_ref(&uc_info, true, true) } {
+                Err(Error::Internal(_)) => {}
+                res => panic!(
+                    "invalid {}: unexpected result: {:?}",
+                    what,
+                    res.map(|i| i.key)
+                ),
+            }
+        }
+
+        // SAFETY: the info doesn't have fields with NULL pointers and the memory that it
+        // references lives until the end of the test.
+        let info = unsafe {
+            Info::unchecked_from_ffi_upload_info_ref(&uc_info(&valid, &valid), true, true)
+        }
+        .expect("valid info");
+        assert_eq!(info.upload_id, "dir/object", "upload ID");
+        assert_eq!(info.key, "dir/object", "key");
+        assert!(info.has_system_metadata(), "system metadata retrieved");
+        assert!(info.has_custom_metadata(), "custom metadata retrieved");
+
+        // SAFETY: the info doesn't have fields with NULL pointers and the memory that it
+        // references lives until the end of the test.
+        let info = unsafe {
+            Info::unchecked_from_ffi_upload_info_ref(&uc_info(&valid, &valid), false, false)
+        }
+        .expect("valid info without metadata");
+        assert!(!info.has_system_metadata(), "system metadata not retrieved");
+        assert!(!info.has_custom_metadata(), "custom metadata not retrieved");
+        assert_eq!(info.metadata_custom.count(), 0, "empty custom metadata");
+    }
+
+    #[test]
+    fn test_iterator_from_ffi_upload_iterator_null() {
+        match Iterator::from_ffi_upload_iterator(ptr::null_mut(), false, false).map(|_| ()) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_from_ffi_null() {
+        match Info::from_ffi_upload_info(ptr::null_mut(), true, true) {
+            Err(Error::Internal(internal)) => assert_eq!(
+                internal.ctx_msg, "FFI returned a NULL upload info",
+                "upload info context message"
+            ),
+            res => panic!("upload info: unexpected result: {:?}", res),
+        }
+
+        match Part::from_ffi_part(ptr::null_mut()) {
+            Err(Error::Internal(internal)) => assert_eq!(
+                internal.ctx_msg, "FFI returned a NULL part",
+                "part context message"
+            ),
+            res => panic!("part: unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_part_iterator_from_ffi_part_iterator_null() {
+        match PartIterator::from_ffi_part_iterator(ptr::null_mut()).map(|_| ()) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
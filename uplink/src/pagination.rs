@@ -0,0 +1,163 @@
+//! Pages of listed items for serving the listings by pages (e.g. from an HTTP API).
+//!
+//! [`Project::list_buckets_page`](crate::Project::list_buckets_page) and
+//! [`Project::list_objects_page`](crate::Project::list_objects_page) return a [`Page`] whose
+//! `next_cursor` is passed as the cursor of the options for retrieving the next page. With the
+//! `serde` feature, a page is serialized as `{"items": [...], "next_cursor": ..., "is_truncated":
+//! ...}`.
+
+use crate::{Error, Result};
+
+/// A page of listed items.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Page<T> {
+    /// The items of the page in the listing order.
+    pub items: Vec<T>,
+    /// The cursor for listing the next page, which is the key or the name of the last item. It's
+    /// `None` when it's the last page.
+    pub next_cursor: Option<String>,
+    /// Indicates that there are more items after the ones of this page.
+    pub is_truncated: bool,
+}
+
+impl<T> Page<T> {
+    /// Collects a page of at most `size` items of `iter`, taking the cursor of the last one with
+    /// `cursor` when `iter` has more items.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `size` is zero and the first error of `iter`,
+    /// including the error of the item after the page, which is read for knowing if the page is
+    /// truncated.
+    pub(crate) fn collect<I>(iter: I, size: usize, cursor: fn(&T) -> &str) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<T>>,
+    {
+        if size == 0 {
+            return Err(Error::new_invalid_arguments("page_size", "cannot be zero"));
+        }
+
+        let mut iter = iter.into_iter();
+        let items = iter.by_ref().take(size).collect::<Result<Vec<T>>>()?;
+        let is_truncated = iter.next().transpose()?.is_some();
+        let next_cursor = if is_truncated {
+            items.last().map(|item| String::from(cursor(item)))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            items,
+            next_cursor,
+            is_truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    fn items(names: &[&'static str]) -> Vec<Result<&'static str>> {
+        names.iter().map(|n| Ok(*n)).collect()
+    }
+
+    #[test]
+    fn test_page_collect() {
+        let page = Page::collect(items(&["a", "b", "c"]), 2, |s| s).expect("truncated page");
+        assert_eq!(page.items, ["a", "b"], "truncated page items");
+        assert_eq!(
+            page.next_cursor.as_deref(),
+            Some("b"),
+            "truncated page cursor"
+        );
+        assert!(page.is_truncated, "truncated page");
+
+        for size in [2, 3] {
+            let page = Page::collect(items(&["a", "b"]), size, |s| s).expect("last page");
+            assert_eq!(page.items, ["a", "b"], "{}: last page items", size);
+            assert_eq!(page.next_cursor, None, "{}: last page cursor", size);
+            assert!(!page.is_truncated, "{}: last page", size);
+        }
+
+        let page = Page::collect(items(&[]), 2, |s| s).expect("empty page");
+        assert_eq!(page, Page::default(), "empty page");
+    }
+
+    #[test]
+    fn test_page_collect_errors() {
+        match Page::collect(items(&["a"]), 0, |s| s) {
+            Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                assert_eq!(names, "page_size", "invalid argument name")
+            }
+            res => panic!("zero size: unexpected result: {:?}", res),
+        }
+
+        for (size, what) in [(3, "in the page"), (2, "after the page")] {
+            let mut iter = items(&["a", "b"]);
+            iter.push(Err(Error::new_internal("listing", "failed".into())));
+            match Page::collect(iter, size, |s| s) {
+                Err(Error::Internal(internal)) => {
+                    assert_eq!(internal.ctx_msg, "listing", "{}: error", what)
+                }
+                res => panic!("{}: unexpected result: {:?}", what, res),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_page_serialize() {
+        use crate::{metadata, Object};
+        use std::time::Duration;
+
+        let object = |key: &str| Object {
+            key: String::from(key),
+            is_prefix: false,
+            metadata_system: metadata::System {
+                created: Duration::from_secs(1_714_564_800),
+                expires: None,
+                content_length: Some(5),
+            },
+            metadata_custom: {
+                let mut custom = metadata::Custom::default();
+                custom.insert(String::from("color"), String::from("blue"));
+                custom
+            },
+            has_system: true,
+            has_custom: true,
+        };
+
+        let page = Page {
+            items: vec![object("a.txt")],
+            next_cursor: Some(String::from("a.txt")),
+            is_truncated: true,
+        };
+        let json = serde_json::to_value(&page).expect("serialize a truncated page");
+        assert_eq!(json["items"][0]["key"], "a.txt", "item key");
+        assert_eq!(
+            json["items"][0]["metadata_system"]["content_length"], 5,
+            "item content length"
+        );
+        assert_eq!(
+            json["items"][0]["metadata_custom"],
+            serde_json::json!({"color": "blue"}),
+            "item custom metadata"
+        );
+        assert_eq!(json["next_cursor"], "a.txt", "truncated page cursor");
+        assert_eq!(json["is_truncated"], true, "truncated page");
+
+        let page = Page {
+            items: vec![object("b.txt")],
+            next_cursor: None,
+            is_truncated: false,
+        };
+        let json = serde_json::to_value(&page).expect("serialize the last page");
+        assert_eq!(
+            json["next_cursor"],
+            serde_json::Value::Null,
+            "last page cursor"
+        );
+        assert_eq!(json["is_truncated"], false, "last page");
+    }
+}
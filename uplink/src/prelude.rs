@@ -0,0 +1,45 @@
+//! Re-exports the types that most of the applications use, so they can be imported with a single
+//! glob import.
+//!
+//! It re-exports the [`options`] module instead of its types because their names clash with other
+//! types of this crate (e.g. [`options::Download`] and [`Download`]). It re-exports the
+//! [`metadata`] module too, besides its most frequently used types.
+//!
+//! Note that the glob import shadows the standard [`std::result::Result`] with this crate's
+//! [`Result`].
+//!
+//! ```no_run
+//! use std::io::Write;
+//!
+//! use uplink::prelude::*;
+//!
+//! let grant = Grant::new("my-serialized-access-grant")?;
+//! let shared = grant.share(
+//!     &Permission::full(),
+//!     Some(vec![SharePrefix::full_bucket("my-bucket")?]),
+//! )?;
+//!
+//! let project = Project::open(&shared);
+//! let bucket: Bucket = project.ensure_bucket("my-bucket")?;
+//!
+//! let mut upload: Upload = project.upload_object(&bucket.name, "hello.txt", None)?;
+//! upload.write_all(b"Hello world!")?;
+//! let mut custom = Custom::default();
+//! custom.insert(String::from("greeting"), String::from("true"));
+//! upload.set_custom_metadata(&custom)?;
+//! upload.commit()?;
+//! let obj: Object = upload.info()?;
+//!
+//! let opts = options::Download::new(0, 5);
+//! let _download: Download = project.download_object(&bucket.name, &obj.key, Some(&opts))?;
+//!
+//! let _res: Result<()> = project.delete_object(&bucket.name, &obj.key).map(|_| ());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub use crate::access::{Grant, Permission, SharePrefix};
+pub use crate::metadata::{self, Custom, MetadataPatch, System};
+pub use crate::object::{Download, Upload};
+pub use crate::project::options;
+pub use crate::types::{BucketName, ObjectKey};
+pub use crate::{Bucket, Config, EncryptionKey, Error, Object, Project, Result};
@@ -1,73 +1,297 @@
 //! Storj DCS Project.
 
+pub mod batch;
+pub mod browse;
+mod defaults;
 pub mod options;
-
-use crate::access::Grant;
-use crate::config::Config;
-use crate::object::upload;
-use crate::uplink_c::Ensurer;
-use crate::{bucket, error, helpers, metadata, object, Bucket, Error, Object, Result};
-
+pub mod pacing;
+pub mod quota;
+
+pub use defaults::{Defaults, UploadDefaults};
+
+use crate::access::{AccessHandle, Grant};
+#[cfg(feature = "compression")]
+use crate::compression;
+use crate::config::{self, Config};
+use crate::error::{BoxError, UplinkCode};
+use crate::object::{upload, HeadObject};
+use crate::pagination::Page;
+use crate::types::{BucketName, ObjectKey};
+use crate::{
+    bucket, error, helpers, metadata, object, tuning, uplink_c, Bucket, Error, Object, Result,
+};
+
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::fs;
+use std::io::{self, Read, Seek, Write};
+use std::ops::Deref;
 use std::os::raw::c_char;
+use std::path::Path;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use uplink_sys as ulksys;
 
+/// The context message of the [`Error::Internal`] returned by [`Project::open_from_serialized`]
+/// and [`Project::open_from_serialized_with_config`] when the access grant cannot be parsed.
+pub const OPEN_ERROR_CTX_GRANT: &str = "cannot parse the serialized access grant";
+
+/// The context message of the [`Error::Internal`] returned by [`Project::open_from_serialized`]
+/// and [`Project::open_from_serialized_with_config`] when the project cannot be opened.
+pub const OPEN_ERROR_CTX_PROJECT: &str = "cannot open the project";
+
 /// Provides access to manage buckets and objects.
+///
+/// It's `Send` and `Sync`, so its operations can run concurrently from several threads.
 pub struct Project {
     /// The project type of the FFI that an instance of this struct represents and guards its life
-    /// time until this instance drops.
-    ///
-    /// It's a project result because it's the one that holds the project and allows to free its
+    /// time until this instance and the operations abandoned by a timeout drop it.
+    inner: Arc<ProjectHandle>,
+    /// Indicates if the bucket names are validated before calling the FFI.
+    validate_bucket_names: bool,
+    /// The dial timeout of the configuration that opened it, which is zero, the default of the
+    /// FFI, when it's opened without configuration.
+    dial_timeout: Duration,
+    /// Indicates if the project was closed by [`Self::close`], so dropping it only frees its
     /// memory.
-    inner: ulksys::UplinkProjectResult,
+    closed: bool,
+    /// The default options of the uploads to its buckets.
+    defaults: Defaults,
+    /// The allowance for the skew of the system clock when validating the expiration time of the
+    /// uploads, see [`Config::set_expiration_skew`].
+    expiration_skew: Duration,
+    /// The default timeout of the operations, see [`Config::set_operation_timeout`].
+    operation_timeout: Option<Duration>,
+    /// The size up to which [`Self::put_object`] writes and commits back-to-back, see
+    /// [`Config::set_inline_segment_threshold`].
+    inline_segment_threshold: usize,
+    /// The limits for validating the custom metadata, see [`Config::set_metadata_limits`].
+    metadata_limits: metadata::MetadataLimits,
+    /// The gate that paces its operations, see [`Self::set_rate_gate`].
+    rate_gate: Option<pacing::RateGate>,
 }
 
 impl Project {
     /// Opens a project with the specified access grant.
+    ///
+    /// `grant` can be dropped after opening the project without affecting it.
     pub fn open(grant: &Grant) -> Self {
         // SAFETY: we trust the FFI is behaving correctly when called with correct value.
         // The `grant.as_ffi_access` return a pointer to its FFI representation that only lives as
         // long as `grant` but we don't need to take ownership of `grant` because the FFI access is
         // only a handler, not the actual access value, so `grant` can be dropped without affecting
         // the FFI project instance.
+        // uplink-c resolves the handler to the Go access value and the Go project keeps its own
+        // reference to it, while freeing the FFI access only releases the handler and its C
+        // struct, hence the Go garbage collector doesn't collect the access value meanwhile the
+        // project uses it.
         let inner = unsafe { ulksys::uplink_open_project(grant.as_ffi_access()) };
-        Self { inner }
+        Self {
+            inner: Arc::new(ProjectHandle {
+                result: inner,
+                access: grant.access_handle(),
+            }),
+            validate_bucket_names: true,
+            dial_timeout: Duration::ZERO,
+            closed: false,
+            defaults: Defaults::default(),
+            expiration_skew: Duration::ZERO,
+            operation_timeout: None,
+            inline_segment_threshold: Config::DEFAULT_INLINE_SEGMENT_THRESHOLD,
+            metadata_limits: metadata::MetadataLimits::default(),
+            rate_gate: None,
+        }
     }
 
     /// Opens a project with the specified access grant and configuration.
     pub fn open_with_config(grant: Grant, config: &Config) -> Self {
+        Self::open_with_access_handle(grant.access_handle(), config)
+    }
+
+    /// Opens a project with the FFI access of `access` and `config`.
+    fn open_with_access_handle(access: Arc<AccessHandle>, config: &Config) -> Self {
         // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let inner = unsafe {
-            ulksys::uplink_config_open_project(config.as_ffi_config(), grant.as_ffi_access())
-        };
-        Self { inner }
+        // See `open` about why the access grant can be dropped without affecting the FFI project
+        // instance; the project handle keeps `access` alive anyway.
+        let inner =
+            unsafe { ulksys::uplink_config_open_project(config.as_ffi_config(), access.access) };
+        Self {
+            inner: Arc::new(ProjectHandle {
+                result: inner,
+                access,
+            }),
+            validate_bucket_names: config.validate_bucket_names(),
+            dial_timeout: config.dial_timeout(),
+            closed: false,
+            defaults: Defaults::default(),
+            expiration_skew: config.expiration_skew(),
+            operation_timeout: config.operation_timeout(),
+            inline_segment_threshold: config.inline_segment_threshold(),
+            metadata_limits: config.metadata_limits(),
+            rate_gate: None,
+        }
+    }
+
+    /// Opens a project from the same access grant with `user_agent` and the rest of the settings of
+    /// this project, see [`Self::create_bucket_with_attribution`].
+    fn open_attributed(&self, user_agent: &str) -> Result<Self> {
+        config::validate_user_agent(user_agent)?;
+        let mut attributed = Config::new(user_agent, self.dial_timeout, None)?;
+        attributed.set_validate_bucket_names(self.validate_bucket_names);
+        attributed.set_expiration_skew(self.expiration_skew);
+        attributed.set_operation_timeout(self.operation_timeout);
+        attributed.set_inline_segment_threshold(self.inline_segment_threshold);
+        attributed.set_metadata_limits(self.metadata_limits);
+
+        let mut project =
+            Self::open_with_access_handle(Arc::clone(&self.inner.access), &attributed);
+        if let Some(err) = Error::new_uplink(project.inner.error) {
+            // The FFI project is `NULL` when there is an error, so there is nothing to close and
+            // the error is freed with the project result.
+            project.closed = true;
+            return Err(err);
+        }
+
+        project.rate_gate = self.rate_gate.clone();
+        Ok(project)
+    }
+
+    /// Opens a project with the serialized access grant `access`, parsing it, so the project can
+    /// be opened in one call.
+    ///
+    /// ```no_run
+    /// use uplink::Project;
+    ///
+    /// let project = Project::open_from_serialized("my-serialized-access-grant")?;
+    /// let (bucket, _) = project.create_bucket("my-bucket")?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    ///
+    /// The access grant can be retrieved afterwards with [`Self::grant`].
+    ///
+    /// It returns an [`Error::Internal`] whose context message is [`OPEN_ERROR_CTX_GRANT`] and
+    /// whose inner error is the one of [`Grant::new`] if `access` cannot be parsed, and an
+    /// [`Error::Internal`] whose context message is [`OPEN_ERROR_CTX_PROJECT`] and whose inner
+    /// error is the one returned by the FFI if the project cannot be opened. The inner
+    /// errors can be got with `std::error::Error::source`.
+    pub fn open_from_serialized(access: &str) -> Result<Self> {
+        let grant = Self::parse_serialized_access(access)?;
+        Self::open(&grant).checked_open()
+    }
+
+    /// Opens a project with the serialized access grant `access` and `config`, parsing it, so the
+    /// project can be opened in one call.
+    ///
+    /// ```no_run
+    /// use uplink::{Config, Project};
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let config = Config::new("my-app/1.0.0", Duration::from_secs(10), None)?;
+    /// let project =
+    ///     Project::open_from_serialized_with_config("my-serialized-access-grant", &config)?;
+    /// let (bucket, _) = project.create_bucket("my-bucket")?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    ///
+    /// It returns the same errors than [`Self::open_from_serialized`].
+    pub fn open_from_serialized_with_config(access: &str, config: &Config) -> Result<Self> {
+        let grant = Self::parse_serialized_access(access)?;
+        Self::open_with_config(grant, config).checked_open()
     }
 
     /// Aborts a multipart upload started with [`Self::begin_upload`].
     ///
     /// The `upload_id` is an upload identifier that [`Self::begin_upload`] has returned.
-    pub fn abort_upload(&self, bucket: &str, key: &str, upload_id: &str) -> Result<()> {
+    pub fn abort_upload(&self, bucket: &str, key: impl AsRef<str>, upload_id: &str) -> Result<()> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
         let c_upload_id = helpers::cstring_from_str_fn_arg("upload_id", upload_id)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_err = unsafe {
-            ulksys::uplink_abort_upload(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_upload_id.as_ptr() as *mut c_char,
-            )
-        };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_err = unsafe {
+                ulksys::uplink_abort_upload(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    c_upload_id.as_ptr() as *mut c_char,
+                )
+            };
 
-        if let Some(err) = Error::new_uplink(uc_err) {
-            helpers::drop_uplink_sys_error(uc_err);
-            Err(err)
-        } else {
-            Ok(())
+            if let Some(err) = Error::from_ffi_error(uc_err) {
+                Err(err)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Aborts the pending uploads of `bucket`, all of them or only the ones whose key starts with
+    /// `prefix`, which must end with '/' as the prefix of the listings (see
+    /// [`options::ListUploads::with_prefix`]).
+    ///
+    /// It aborts the listed uploads one by one without stopping at the first failure, so the
+    /// returned summary has the number of aborted uploads and the error of each one that couldn't
+    /// be aborted.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `bucket` or `prefix` are invalid and the
+    /// errors of listing the pending uploads, in which case none is aborted.
+    pub fn abort_uploads(
+        &self,
+        bucket: &str,
+        prefix: Option<&str>,
+    ) -> Result<upload::AbortSummary> {
+        let mut opts = match prefix {
+            Some(prefix) => options::ListUploads::with_prefix(prefix)?,
+            None => options::ListUploads::default(),
+        };
+        opts.recursive = true;
+
+        // Collect the uploads before aborting them, so the listing isn't altered while iterating.
+        let uploads = self
+            .list_uploads(bucket, Some(&opts))?
+            .collect::<Result<Vec<upload::Info>>>()?;
+
+        let mut summary = upload::AbortSummary::default();
+        for info in uploads {
+            match self.abort_upload(bucket, &info.key, &info.upload_id) {
+                Ok(()) => summary.aborted += 1,
+                Err(err) => summary.failed.push((info, err)),
+            }
         }
+
+        Ok(summary)
+    }
+
+    /// Returns the raw FFI project of this instance for passing it to other C code that uses the
+    /// uplink-c API.
+    ///
+    /// The pointer is owned by this instance, so the caller must not close nor free it and must
+    /// not use it after this instance is dropped.
+    ///
+    /// ```no_run
+    /// use uplink::access::Grant;
+    /// use uplink::Project;
+    ///
+    /// let grant = Grant::new("my-serialized-access-grant")?;
+    /// let project = Project::open(&grant);
+    /// let raw = project.as_raw();
+    ///
+    /// // SAFETY: `raw` is a valid project owned by `project`, which outlives this call, and the
+    /// // FFI doesn't free it.
+    /// let it = unsafe { uplink_sys::uplink_list_buckets(raw, std::ptr::null_mut()) };
+    /// // SAFETY: the iterator is returned by the FFI and it isn't used after freeing it.
+    /// unsafe { uplink_sys::uplink_free_bucket_iterator(it) };
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn as_raw(&self) -> *mut ulksys::UplinkProject {
+        self.inner.project
     }
 
     /// Begins a new multipart upload to `bucket` and `key` with optional options.
@@ -77,120 +301,312 @@ impl Project {
     /// * [`Self::commit_upload`] to finish the upload.
     /// * [`Self::abort_upload`] to cancel the upload at any time.
     ///
+    /// [`Self::begin_multipart_upload`] returns a handle that doesn't require to pass the bucket,
+    /// key and upload ID to each of them.
+    ///
     /// For uploading single parts objects use [`Self::upload_object`] because it's more
     /// convenient.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the expiration time of `opts` is in the past,
+    /// see [`Config::set_expiration_skew`].
     pub fn begin_upload(
         &self,
         bucket: &str,
-        key: &str,
+        key: impl AsRef<str>,
         opts: Option<&options::Upload>,
     ) -> Result<upload::Info> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
+        let with_defaults = self.defaults.upload_options(bucket, opts)?;
+        let opts = with_defaults.as_ref().or(opts);
+        if let Some(o) = opts {
+            o.validate_expires(helpers::unix_time_now()?, self.expiration_skew)?;
+        }
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_upload_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_upload_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_begin_upload(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    c_opts,
+                )
+            };
 
-            ulksys::uplink_begin_upload(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+            upload::Info::from_ffi_upload_info_result(uc_res)
+        })
+    }
+
+    /// Begins a new multipart upload to `bucket` and `key` with optional options and returns a
+    /// handle that remembers them and the upload ID for uploading its parts, listing them and
+    /// committing or aborting it.
+    ///
+    /// It returns the same errors than [`Self::begin_upload`].
+    ///
+    /// ```no_run
+    /// use uplink::access::Grant;
+    /// use uplink::Project;
+    ///
+    /// use std::io::Write;
+    ///
+    /// let grant = Grant::new("my-serialized-access-grant")?;
+    /// let project = Project::open(&grant);
+    /// let upload = project.begin_multipart_upload("my-bucket", "data.bin", None)?;
+    ///
+    /// let mut part = upload.upload_part(1)?;
+    /// part.write_all(&[0; 5 * 1024 * 1024])?;
+    /// part.commit()?;
+    /// upload.commit(None)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn begin_multipart_upload(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        opts: Option<&options::Upload>,
+    ) -> Result<upload::MultipartUpload<'_>> {
+        let key = key.as_ref();
+        let info = self.begin_upload(bucket, key, opts)?;
+        Ok(upload::MultipartUpload::new(self, bucket, info))
+    }
 
-        upload::Info::from_ffi_upload_info_result(uc_res)
+    /// Returns the registry of the default options of the uploads to the buckets of the project,
+    /// see [`Self::set_bucket_defaults`].
+    pub fn bucket_defaults(&self) -> &Defaults {
+        &self.defaults
     }
 
-    /// Commits a multipart upload with `upload_id` to `bucket` and `key` with optional options.
+    /// Returns the number of objects inside of `bucket` and their total size, calling `progress`,
+    /// if any, with the usage summed so far after each object.
+    ///
+    /// WARNING: the uplink-c doesn't expose the usage of the buckets, so it's computed by listing
+    /// all the objects of the bucket recursively with their system metadata. It sends a request
+    /// to the satellite for each page of the listing, so it's slow and costly on large buckets;
+    /// `progress` allows to report how it advances. The usage is computed while the objects may
+    /// be uploaded or deleted, so it's an approximation when the bucket is in use.
+    ///
+    /// It returns the same errors than [`Self::list_objects`] and the errors of the listing.
+    pub fn bucket_usage(
+        &self,
+        bucket: &str,
+        progress: Option<&mut dyn FnMut(&bucket::Usage)>,
+    ) -> Result<bucket::Usage> {
+        let opts = options::ListObjects::builder()
+            .recursive(true)
+            .system(true)
+            .build()?;
+        let it = self.list_objects(bucket, Some(&opts))?;
+
+        match progress {
+            Some(progress) => bucket::Usage::sum(it, progress),
+            None => bucket::Usage::sum(it, |_| {}),
+        }
+    }
+
+    /// Checks, before uploading `planned_bytes` to `bucket`, if the limits of the project's
+    /// account are exceeded.
+    ///
+    /// It compares `planned_bytes` with the [limits and usage of the project](Self::limits) when
+    /// they are available. Otherwise, which is always the case with the bundled uplink-c version,
+    /// it applies the following heuristic: it begins a multipart upload whose key starts with
+    /// [`quota::PROBE_KEY_PREFIX`], which the satellite rejects if the storage or the segments
+    /// limit is exceeded, and aborts it. The heuristic doesn't detect if `planned_bytes` would
+    /// exceed the storage limit, only if it's already exceeded, and it requires the permission to
+    /// upload to `bucket`.
+    ///
+    /// It returns the same errors than [`Self::begin_upload`] and [`Self::abort_upload`] except
+    /// the ones of the exceeded limits.
+    pub fn check_quota(&self, bucket: &str, planned_bytes: u64) -> Result<quota::QuotaCheck> {
+        match self.limits() {
+            Ok(limits) => return Ok(quota::QuotaCheck::from_limits(&limits, planned_bytes)),
+            Err(Error::Unsupported(_)) => {}
+            Err(err) => return Err(err),
+        }
+
+        let key = format!(
+            "{}{}",
+            quota::PROBE_KEY_PREFIX,
+            helpers::unix_time_now()?.as_nanos()
+        );
+        match self.begin_upload(bucket, &key, None) {
+            Ok(info) => {
+                self.abort_upload(bucket, &key, &info.upload_id)?;
+                Ok(quota::QuotaCheck::Available)
+            }
+            Err(err) => quota::QuotaCheck::from_probe_error(err),
+        }
+    }
+
+    /// Closes the project and returns the error that closing it may produce, which dropping the
+    /// project discards. Servers and long running applications should use it for knowing that the
+    /// project's connections were released.
     ///
-    /// `opts` wraps a mutable reference because the [`options::CommitUpload`] requires a mutable
-    /// reference to obtain its FFI representation.
+    /// It consumes the project, so it cannot be used nor closed again after closing it:
+    ///
+    /// ```compile_fail
+    /// use uplink::{access::Grant, Project};
+    ///
+    /// let project = Project::open(&Grant::new("my-serialized-access-grant")?);
+    /// project.close()?;
+    /// project.close()?;
+    /// # Ok::<(), uplink::Error>(())
+    /// ```
+    pub fn close(mut self) -> Result<()> {
+        self.closed = true;
+        // SAFETY: we trust the FFI is behaving correctly when called with correct value. The
+        // project is closed once because `self.closed` prevents `drop` from closing it again and
+        // `drop` frees its memory when `self` goes out of scope at the end of this method.
+        let uc_err = unsafe { ulksys::uplink_close_project(self.inner.project) };
+
+        match Error::from_ffi_error(uc_err) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Commits a multipart upload with `upload_id` to `bucket` and `key` with optional options.
     ///
     /// The `upload_id` is an upload identifier that [`Self::begin_upload`] has returned.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the custom metadata of `opts`, with the
+    /// bucket's default entries, doesn't satisfy the limits of the project, see
+    /// [`metadata::Custom::validate`].
     pub fn commit_upload(
         &self,
         bucket: &str,
-        key: &str,
+        key: impl AsRef<str>,
         upload_id: &str,
-        opts: Option<&mut options::CommitUpload>,
+        opts: Option<&options::CommitUpload>,
     ) -> Result<Object> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
         let c_upload_id = helpers::cstring_from_str_fn_arg("upload_id", upload_id)?;
-
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.to_ffi_commit_upload_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
-
-            ulksys::uplink_commit_upload(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_upload_id.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
-
-        Object::from_ffi_commit_upload_result(uc_res)
+        let with_defaults = self.defaults.commit_upload_options(bucket, opts);
+        let opts = with_defaults.as_ref().or(opts);
+        if let Some(o) = opts {
+            o.custom_metadata()
+                .validate_fn_arg("opts{custom_metadata}", &self.metadata_limits)?;
+        }
+        // The operation may run in a separate thread that outlives `opts`, so it owns a copy.
+        let custom = opts.map(|o| o.custom_metadata().clone());
+
+        self.run_bounded(
+            "commit upload",
+            opts.and_then(|o| o.timeout()),
+            move |project| {
+                let uc_custom = custom.as_ref().map(|c| c.to_ffi_custom_metadata());
+
+                // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+                // mutable reference to it but we use the reference only inside of the scope, hence we
+                // are always referencing it during its lifetime that the scope establishes.
+                // For the rest, we trust the FFI is behaving correctly when called with correct value.
+                let uc_res = unsafe {
+                    let mut c_opts = ptr::null_mut();
+                    let mut uc_opts;
+                    if let Some(uc_custom) = &uc_custom {
+                        uc_opts = options::CommitUpload::as_ffi_commit_upload_options(uc_custom);
+                        c_opts = ptr::addr_of_mut!(uc_opts);
+                    }
+
+                    ulksys::uplink_commit_upload(
+                        project,
+                        c_bucket.as_ptr() as *mut c_char,
+                        c_key.as_ptr() as *mut c_char,
+                        c_upload_id.as_ptr() as *mut c_char,
+                        c_opts,
+                    )
+                };
+
+                Object::from_ffi_commit_upload_result(uc_res)
+            },
+        )
     }
 
     /// Atomically copies an object to a different bucket or/and key without downloading and
     /// uploading it.
+    ///
+    /// When `opts` has custom metadata, the metadata of the copied object is replaced after
+    /// copying it, hence the whole operation isn't atomic; if replacing the metadata fails, the
+    /// error is returned and the copied object retains the custom metadata of the original one.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the custom metadata of `opts` doesn't satisfy
+    /// the limits of the project, see [`metadata::Custom::validate`].
     pub fn copy_object(
         &self,
         current_bucket: &str,
-        current_key: &str,
+        current_key: impl AsRef<str>,
         new_bucket: &str,
-        new_key: &str,
+        new_key: impl AsRef<str>,
         opts: Option<&options::CopyObject>,
     ) -> Result<Object> {
+        let current_key = current_key.as_ref();
+        let new_key = new_key.as_ref();
         let c_cur_bucket = helpers::cstring_from_str_fn_arg("current_bucket", current_bucket)?;
         let c_cur_key = helpers::cstring_from_str_fn_arg("current_key", current_key)?;
         let c_new_bucket = helpers::cstring_from_str_fn_arg("new_bucket", new_bucket)?;
         let c_new_key = helpers::cstring_from_str_fn_arg("new_key", new_key)?;
+        let custom_metadata = opts.and_then(|o| o.custom_metadata.as_ref());
+        if let Some(md) = custom_metadata {
+            md.validate_fn_arg("opts{custom_metadata}", &self.metadata_limits)?;
+        }
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_copy_object_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
+        let mut object = self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_copy_object_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_copy_object(
+                    project,
+                    c_cur_bucket.as_ptr() as *mut c_char,
+                    c_cur_key.as_ptr() as *mut c_char,
+                    c_new_bucket.as_ptr() as *mut c_char,
+                    c_new_key.as_ptr() as *mut c_char,
+                    c_opts,
+                )
+            };
 
-            ulksys::uplink_copy_object(
-                self.inner.project,
-                c_cur_bucket.as_ptr() as *mut c_char,
-                c_cur_key.as_ptr() as *mut c_char,
-                c_new_bucket.as_ptr() as *mut c_char,
-                c_new_key.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+            Object::from_ffi_object_result(uc_res)
+                .map(|op| op.expect("successful copying an object must always return an object"))
+        })?;
+
+        if let Some(md) = custom_metadata {
+            self.update_object_metadata(new_bucket, new_key, md, None)?;
+            object.metadata_custom = md.clone();
+        }
 
-        Object::from_ffi_object_result(uc_res)
-            .map(|op| op.expect("successful copying an object must always return an object"))
+        Ok(object)
+    }
+
+    /// Copies the object of the `(bucket, key)` pair `current` to the `(bucket, key)` pair `new`,
+    /// which cannot be transposed, see [`Self::copy_object`].
+    pub fn copy_object_typed(
+        &self,
+        current: (&BucketName, &ObjectKey),
+        new: (&BucketName, &ObjectKey),
+        opts: Option<&options::CopyObject>,
+    ) -> Result<Object> {
+        self.copy_object(current.0, current.1, new.0, new.1, opts)
     }
 
     /// Creates a new bucket.
@@ -198,370 +614,1101 @@ impl Project {
     /// It returns the bucket information and `true` when it's created or `false` if it already
     /// existed.
     pub fn create_bucket(&self, bucket: &str) -> Result<(Bucket, bool)> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_bucket = self.bucket_name_fn_arg(bucket)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_create_bucket(self.inner.project, c_bucket.as_ptr() as *mut c_char)
-        };
-        uc_res.ensure();
+        let res = self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res =
+                unsafe { ulksys::uplink_create_bucket(project, c_bucket.as_ptr() as *mut c_char) };
 
-        let created = if let Some(err) = Error::new_uplink(uc_res.error) {
-            if let Error::Uplink(error::Uplink::BucketAlreadyExists(_)) = &err {
-                false
-            } else {
-                // SAFETY: the `Error` constructor doesn't take ownership of the FFI error pointer
-                // so it's still allocated at this point and we trust the FFI of freeing memory of
-                // pointers allocated by itself.
-                unsafe { ulksys::uplink_free_bucket_result(uc_res) };
-                return Err(err);
-            }
-        } else {
-            true
-        };
+            Bucket::from_ffi_create_bucket_result(uc_res)
+        })?;
 
-        let bucket = Bucket::from_ffi_bucket(uc_res.bucket)?;
-        Ok((bucket, created))
+        match res {
+            (Some(b), created) => Ok((b, created)),
+            // The bucket already existed but the FFI didn't return it, which happens when another
+            // client created it concurrently, so we retrieve it.
+            (None, _) => self.stat_bucket(bucket).map(|b| (b, false)),
+        }
+    }
+
+    /// Creates a new bucket attributed to `user_agent` instead of the user agent of the project's
+    /// configuration, for services that create buckets on behalf of several partners.
+    ///
+    /// The satellites attribute a bucket, and its usage, to the partner identified by the user
+    /// agent sent when the bucket is created, so the user agent of the rest of the operations
+    /// doesn't matter for the attribution and they don't have an override.
+    ///
+    /// The FFI doesn't allow to set the user agent of an operation, so it opens a short-lived
+    /// project from the same access grant, with a configuration whose user agent is
+    /// `user_agent` and with the rest of the settings of this project (e.g. the operation timeout
+    /// and the rate gate), and it closes it after creating the bucket.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `user_agent` isn't a valid user agent (e.g.
+    /// `partner/1.0 (comment)`), an [`Error::Uplink`] if the short-lived project cannot be opened
+    /// and the same errors than [`Self::create_bucket`].
+    pub fn create_bucket_with_attribution(
+        &self,
+        bucket: &str,
+        user_agent: &str,
+    ) -> Result<(Bucket, bool)> {
+        self.open_attributed(user_agent)?.create_bucket(bucket)
     }
 
     /// Deletes a bucket.
     ///
-    /// It returns an [`crate::Error::Uplink`] error with [`crate::error::Uplink::BucketNotEmpty`]
-    /// variant if `bucket` isn't empty.
+    /// It returns an [`crate::Error::Uplink`] error with the
+    /// [`crate::error::UplinkCode::BucketNotEmpty`] code if `bucket` isn't empty.
     pub fn delete_bucket(&self, bucket: &str) -> Result<Bucket> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_bucket = self.bucket_name_fn_arg(bucket)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_delete_bucket(self.inner.project, c_bucket.as_ptr() as *mut c_char)
-        };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res =
+                unsafe { ulksys::uplink_delete_bucket(project, c_bucket.as_ptr() as *mut c_char) };
 
-        Bucket::from_ffi_bucket_result(uc_res)
+            Bucket::from_ffi_bucket_result(uc_res)
+        })
     }
 
     /// Deletes a bucket and all its objects.
     pub fn delete_bucket_with_objects(&self, bucket: &str) -> Result<Bucket> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_bucket = self.bucket_name_fn_arg(bucket)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_delete_bucket_with_objects(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-            )
-        };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                ulksys::uplink_delete_bucket_with_objects(project, c_bucket.as_ptr() as *mut c_char)
+            };
 
-        Bucket::from_ffi_bucket_result(uc_res)
+            Bucket::from_ffi_bucket_result(uc_res)
+        })
     }
 
     /// Deletes the object inside of `bucket` and referenced with `key`.
-    pub fn delete_object(&self, bucket: &str, key: &str) -> Result<Option<Object>> {
+    ///
+    /// It returns the deleted object or `None` when the satellite doesn't return it. See
+    /// [`Self::delete_object_outcome`] for a method that distinguishes the possible outcomes.
+    pub fn delete_object(&self, bucket: &str, key: impl AsRef<str>) -> Result<Option<Object>> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_delete_object(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-            )
-        };
+        self.run_bounded("delete object", None, move |project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                ulksys::uplink_delete_object(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                )
+            };
 
-        Object::from_ffi_object_result(uc_res)
+            Object::from_ffi_object_result(uc_res)
+        })
     }
 
-    /// Starts a download of the object inside of `bucket` and referenced with `key` with optional
-    /// options.
-    pub fn download_object(
+    /// Deletes the object inside of `bucket` and referenced with `key` only if the value of its
+    /// custom metadata `metadata_key` is `expected_value`, and returns true if it was deleted or
+    /// false if the value didn't match or the key wasn't in its custom metadata.
+    ///
+    /// The satellite doesn't support conditional deletes, so it's a best-effort operation that
+    /// gets the object's metadata and then deletes it. The object can be replaced between both
+    /// operations by another client, in which case the new object is deleted without comparing
+    /// its metadata.
+    ///
+    /// It returns the same errors than [`Self::stat_object`] and [`Self::delete_object`].
+    pub fn delete_object_if_unchanged(
         &self,
         bucket: &str,
-        key: &str,
-        opts: Option<&options::Download>,
-    ) -> Result<object::Download> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
-        let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
-
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_download_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
-
-            ulksys::uplink_download_object(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+        key: impl AsRef<str>,
+        metadata_key: &str,
+        expected_value: &str,
+    ) -> Result<bool> {
+        let key = key.as_ref();
+        let obj = self.stat_object(bucket, key)?;
+        if obj.metadata_custom.get(metadata_key).map(String::as_str) != Some(expected_value) {
+            return Ok(false);
+        }
 
-        object::Download::from_ffi_download_result(uc_res)
+        self.delete_object(bucket, key)?;
+        Ok(true)
     }
 
-    /// Returns the bucket if it exists otherwise it creates it.
-    pub fn ensure_bucket(&self, bucket: &str) -> Result<Bucket> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
-
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_ensure_bucket(self.inner.project, c_bucket.as_ptr() as *mut c_char)
-        };
-
-        Bucket::from_ffi_bucket_result(uc_res)
+    /// Deletes the object inside of `bucket` and referenced with `key` and returns the outcome.
+    ///
+    /// It's the same than [`Self::delete_object`] but it folds its three possible results
+    /// (deleted object, no object and object not found error) into an
+    /// [`object::DeleteOutcome`], whose documentation details which access grants produce each
+    /// variant. It returns the same errors than [`Self::delete_object`] except the
+    /// [`crate::error::UplinkCode::ObjectNotFound`] one.
+    pub fn delete_object_outcome(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+    ) -> Result<object::DeleteOutcome> {
+        let key = key.as_ref();
+        object::DeleteOutcome::from_delete_object_result(self.delete_object(bucket, key))
     }
 
-    /// Returns an iterator over the list of existing buckets with optional options.
-    pub fn list_buckets(&self, opts: Option<&options::ListBuckets>) -> bucket::Iterator {
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_it = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_list_buckets_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            };
+    /// Downloads the object inside of `bucket` and referenced by `key` into the file at `path`
+    /// with optional options and returns the number of downloaded bytes.
+    ///
+    /// It streams the data through a large buffer, so the memory usage doesn't depend on the
+    /// object's size. When the object has the modification time recorded by
+    /// [`Self::upload_file`] (see [`metadata::KEY_MTIME`]), it's restored on the file.
+    ///
+    /// The file is only created after the download starts, so it isn't created when the object
+    /// doesn't exist, and it's removed if the download fails after creating it.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the file exists and `opts` doesn't allow to
+    /// overwrite it, an [`Error::Internal`] wrapping the I/O error, with the path in its context
+    /// message, if any filesystem operation fails, and the same errors than
+    /// [`Self::download_object`] and [`object::Download::info`] and the errors returned by
+    /// reading from [`object::Download`].
+    pub fn download_file(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        path: &Path,
+        opts: Option<&options::DownloadFile>,
+    ) -> Result<u64> {
+        let key = key.as_ref();
+        let overwrite = opts.is_some_and(|o| o.overwrite);
+        // The file is created without overwriting it below, but checking it in advance avoids
+        // starting a download that is discarded.
+        if !overwrite && path.exists() {
+            return Err(file_exists_error(path));
+        }
+
+        let mut download = self.ffi_download_object(bucket, key, None, false)?;
+        let mtime = download
+            .info()?
+            .metadata_custom
+            .get(metadata::KEY_MTIME)
+            .and_then(|value| metadata::mtime_from_value(value));
+
+        if opts.is_some_and(|o| o.create_dirs) {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).map_err(|err| {
+                    fs_error("creating the parent directories of the file", path, err)
+                })?;
+            }
+        }
 
-            ulksys::uplink_list_buckets(self.inner.project, c_opts)
+        let mut file_opts = fs::OpenOptions::new();
+        file_opts.write(true);
+        if overwrite {
+            file_opts.create(true).truncate(true);
+        } else {
+            file_opts.create_new(true);
+        }
+        let mut file = match file_opts.open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                return Err(file_exists_error(path))
+            }
+            Err(err) => return Err(fs_error("creating the file", path, err)),
         };
 
-        bucket::Iterator::from_ffi_bucket_iterator(uc_it)
+        let res = copy_buffered(&mut download, &mut file, Error::from_io_error, |err| {
+            fs_error("writing the file", path, err)
+        })
+        .and_then(|size| {
+            if let Some(mtime) = mtime {
+                file.set_modified(mtime).map_err(|err| {
+                    fs_error("setting the modification time of the file", path, err)
+                })?;
+            }
+            Ok(size)
+        });
+        helpers::undo_on_error(res, || {
+            drop(file);
+            fs::remove_file(path)
+        })
     }
 
-    /// Returns an iterator over the list of existing object inside of `bucket` with optional
+    /// Starts a download of the object inside of `bucket` and referenced with `key` with optional
     /// options.
-    pub fn list_objects(
+    ///
+    /// It retrieves the object's information, see [`object::Download::info`], before returning
+    /// the download for returning an [`Error::Uplink`] with the [`UplinkCode::ObjectNotFound`]
+    /// code when the object doesn't exist, rather than from the first read, which may cost an
+    /// extra round trip to the satellite. Set [`options::Download::lazy_open`] for skipping it.
+    ///
+    /// Starting the download, including retrieving the object's information, is bounded by
+    /// [`options::Download::timeout`] or by the project's operation timeout, see
+    /// [`Config::set_operation_timeout`]; reading the data isn't bounded.
+    pub fn download_object(
         &self,
         bucket: &str,
-        opts: Option<&options::ListObjects>,
-    ) -> Result<object::Iterator> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
-
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_it = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_list_objects_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            };
-
-            ulksys::uplink_list_objects(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
-
-        Ok(object::Iterator::from_ffi_object_iterator(uc_it))
+        key: impl AsRef<str>,
+        opts: Option<&options::Download>,
+    ) -> Result<object::Download> {
+        let check = !opts.is_some_and(|o| o.lazy_open);
+        self.ffi_download_object(bucket, key.as_ref(), opts, check)
     }
 
-    /// Returns an iterator over the parts of a multipart upload started with [`Self::begin_upload`]
-    /// with optional options.
-    pub fn list_upload_parts(
+    /// Downloads the object inside of `bucket` and referenced by `key` into `writer` with
+    /// `parallelism` concurrent ranged downloads of `chunk_size` bytes, and returns the number of
+    /// downloaded bytes.
+    ///
+    /// Each chunk is downloaded from its own thread into a buffer and written from the current
+    /// thread at its offset from the start of `writer`, so the memory usage is at most
+    /// `parallelism * chunk_size` bytes. It's faster than [`Self::download_object`] for big
+    /// objects because the chunks are downloaded from different segments at the same time.
+    ///
+    /// The object's size is retrieved before downloading it, so `writer` may end up with data of
+    /// different objects if it's replaced during the download.
+    ///
+    /// It stops at the first error, cancelling the chunks which are being downloaded at that
+    /// moment, and returns an [`Error::InvalidArguments`] if `parallelism` or `chunk_size` is zero,
+    /// an [`Error::Internal`] if the object's length is unknown, so it cannot be split in chunks,
+    /// and the same errors than [`Self::stat_object`], [`Self::download_object`] and the errors
+    /// returned by reading the data and by writing it to `writer`.
+    pub fn download_object_parallel(
         &self,
         bucket: &str,
-        key: &str,
-        upload_id: &str,
-        opts: Option<&options::ListUploadParts>,
-    ) -> Result<upload::PartIterator> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
-        let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
-        let c_upload_id = helpers::cstring_from_str_fn_arg("upload_id", upload_id)?;
+        key: impl AsRef<str>,
+        writer: &mut (impl Write + Seek),
+        parallelism: usize,
+        chunk_size: u64,
+    ) -> Result<u64> {
+        let key = key.as_ref();
+        if parallelism == 0 {
+            return Err(Error::new_invalid_arguments(
+                "parallelism",
+                "cannot be zero",
+            ));
+        }
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_it = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_list_upload_parts_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            };
+        if chunk_size == 0 {
+            return Err(Error::new_invalid_arguments("chunk_size", "cannot be zero"));
+        }
 
-            ulksys::uplink_list_upload_parts(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_upload_id.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+        let size = self
+            .stat_object(bucket, key)?
+            .metadata_system
+            .content_length
+            .ok_or_else(|| {
+                Error::new_internal(
+                    "cannot split the object in chunks",
+                    BoxError::from("unknown object's length"),
+                )
+            })?;
+        let cancelled = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let mut workers = VecDeque::with_capacity(parallelism);
+            let res = (|| {
+                let mut offset = 0;
+                while offset < size {
+                    if workers.len() == parallelism {
+                        write_chunk(writer, workers.pop_front())?;
+                    }
+
+                    let length = chunk_size.min(size - offset);
+                    let download = self.ffi_download_object(
+                        bucket,
+                        key,
+                        Some(&options::Download::new(offset as i64, length as i64)),
+                        false,
+                    )?;
+                    let cancelled = &cancelled;
+                    workers.push_back(scope.spawn(move || {
+                        read_chunk(download, length, cancelled).map(|data| (offset, data))
+                    }));
+                    offset += length;
+                }
+
+                while !workers.is_empty() {
+                    write_chunk(writer, workers.pop_front())?;
+                }
+
+                Ok(size)
+            })();
+
+            if res.is_err() {
+                // The chunks which are still being downloaded are discarded, so they stop at their
+                // next read and the scope joins them.
+                cancelled.store(true, Ordering::Relaxed);
+            }
 
-        Ok(upload::PartIterator::from_ffi_part_iterator(uc_it))
+            res
+        })
     }
 
-    /// Returns an iterator over the uncommitted uploads in `bucket` with optional options.
-    pub fn list_uploads(
+    /// Starts a download of the object inside of `bucket` and referenced with `key` that
+    /// decompresses its data with `compression` on the fly.
+    ///
+    /// It's meant for the objects uploaded with [`Self::upload_object_compressed`], whose
+    /// algorithm can be obtained from their custom metadata with
+    /// [`compression::Compression::from_metadata`].
+    ///
+    /// It returns the same errors than [`Self::download_object`] and an [`Error::Internal`] if the
+    /// decoder cannot be created.
+    #[cfg(feature = "compression")]
+    pub fn download_object_decompressed(
         &self,
         bucket: &str,
-        opts: Option<&options::ListUploads>,
-    ) -> Result<upload::Iterator> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        key: impl AsRef<str>,
+        compression: compression::Compression,
+    ) -> Result<compression::DecompressedDownload> {
+        let key = key.as_ref();
+        let download = self.download_object(bucket, key, None)?;
+        compression::DecompressedDownload::new(download, compression)
+    }
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_it = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_list_uploads_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
+    /// Returns the bucket if it exists otherwise it creates it.
+    pub fn ensure_bucket(&self, bucket: &str) -> Result<Bucket> {
+        let c_bucket = self.bucket_name_fn_arg(bucket)?;
 
-            ulksys::uplink_list_uploads(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res =
+                unsafe { ulksys::uplink_ensure_bucket(project, c_bucket.as_ptr() as *mut c_char) };
 
-        Ok(upload::Iterator::from_ffi_upload_iterator(uc_it))
+            Bucket::from_ffi_bucket_result(uc_res)
+        })
     }
 
-    /// Moves an object to a different bucket or/and key with optional options.
-    pub fn move_object(
+    /// Downloads the whole data of the object inside of `bucket` and referenced by `key` into
+    /// memory with optional options.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the object's data is bigger than the maximum
+    /// size of the options, which is [`options::GetObject::DEFAULT_MAX_SIZE`] when `None`, and the
+    /// same errors than [`Self::download_object`] and [`object::Download::info`].
+    pub fn get_object(
         &self,
-        current_bucket: &str,
-        current_key: &str,
-        new_bucket: &str,
-        new_key: &str,
-        opts: Option<&options::MoveObject>,
-    ) -> Result<()> {
-        let c_cur_bucket = helpers::cstring_from_str_fn_arg("current_bucket", current_bucket)?;
-        let c_cur_key = helpers::cstring_from_str_fn_arg("current_key", current_key)?;
-        let c_new_bucket = helpers::cstring_from_str_fn_arg("new_bucket", new_bucket)?;
-        let c_new_key = helpers::cstring_from_str_fn_arg("new_key", new_key)?;
-
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_err = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_move_object_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
-
-            ulksys::uplink_move_object(
-                self.inner.project,
-                c_cur_bucket.as_ptr() as *mut c_char,
-                c_cur_key.as_ptr() as *mut c_char,
-                c_new_bucket.as_ptr() as *mut c_char,
-                c_new_key.as_ptr() as *mut c_char,
-                c_opts,
+        bucket: &str,
+        key: impl AsRef<str>,
+        opts: Option<&options::GetObject>,
+    ) -> Result<Vec<u8>> {
+        let key = key.as_ref();
+        let max_size = opts.map_or(options::GetObject::DEFAULT_MAX_SIZE, |o| o.max_size);
+        let too_big = || {
+            Error::new_invalid_arguments(
+                "opts{max_size}",
+                &format!("object's data is bigger than {} bytes", max_size),
             )
         };
 
-        if let Some(err) = Error::from_ffi_error(uc_err) {
-            Err(err)
-        } else {
-            Ok(())
+        let mut download = self.ffi_download_object(bucket, key, None, false)?;
+        let size = download.info()?.metadata_system.content_length;
+        if size.is_some_and(|s| s > max_size as u64) {
+            return Err(too_big());
+        }
+
+        let mut data = Vec::with_capacity(size.unwrap_or(0) as usize);
+        // Read one byte more than the maximum size for detecting objects whose size has changed
+        // since we got their information.
+        download
+            .by_ref()
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut data)
+            .map_err(Error::from_io_error)?;
+        if data.len() > max_size {
+            return Err(too_big());
         }
+
+        Ok(data)
     }
 
-    /// Revokes the API key embedded in `access`.
+    /// Returns the access grant that opened the project, for sharing or revoking it.
     ///
-    /// When an access grant is revoked, the rest of the further-restricted access grants (via the
-    /// [`crate::access:Grant.share`]) are revoked.
+    /// It shares the FFI access with the project, so overriding its encryption key (see
+    /// [`Grant::override_encryption_key`]) doesn't affect the project.
+    pub fn grant(&self) -> Grant {
+        Grant::with_handle(Arc::clone(&self.inner.access))
+    }
+
+    /// Returns the metadata of the object inside of `bucket` and referenced by `key` for
+    /// responding to an HTTP `HEAD` request, without downloading its data.
     ///
-    /// An access grant is authorized to revoke any of its further-restricted access grants. It
+    /// It makes the same single request than [`Self::stat_object`] and it returns the same
+    /// errors.
+    pub fn head_object(&self, bucket: &str, key: impl AsRef<str>) -> Result<HeadObject> {
+        self.stat_object(bucket, key).map(HeadObject::from)
+    }
+
+    /// Returns the limits of the project's account and their usage.
+    ///
+    /// It always returns an [`Error::Unsupported`] because the bundled uplink-c version doesn't
+    /// expose them; [`Self::check_quota`] detects the exceeded limits without them.
+    pub fn limits(&self) -> Result<quota::ProjectLimits> {
+        Err(Error::new_unsupported("project limits"))
+    }
+
+    /// Returns an iterator over the list of existing buckets with optional options.
+    ///
+    /// It returns an error if the FFI iterator is in an error state right after its creation.
+    pub fn list_buckets(&self, opts: Option<&options::ListBuckets>) -> Result<bucket::Iterator> {
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_it = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_list_buckets_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                };
+
+                ulksys::uplink_list_buckets(project, c_opts)
+            };
+
+            bucket::Iterator::from_ffi_bucket_iterator(uc_it)
+        })
+    }
+
+    /// Lists all the existing buckets with optional options and collects them.
+    ///
+    /// Contrary to collecting the items of [`Self::list_buckets`] into a `Result<Vec<Bucket>>`,
+    /// the returned error contains the buckets listed before the error happened, so the listing
+    /// can be resumed after the last one with [`options::ListBuckets::with_cursor`].
+    pub fn list_buckets_all(
+        &self,
+        opts: Option<&options::ListBuckets>,
+    ) -> std::result::Result<Vec<Bucket>, error::ListError<Bucket>> {
+        match self.list_buckets(opts) {
+            Ok(it) => error::ListError::collect(it),
+            Err(error) => Err(error::ListError {
+                items: Vec::new(),
+                error,
+            }),
+        }
+    }
+
+    /// Lists a page of at most `page_size` existing buckets with optional options.
+    ///
+    /// The next page is listed passing the page's `next_cursor` to
+    /// [`options::ListBuckets::with_cursor`]. Knowing if the page is truncated requires retrieving
+    /// the bucket after it, which is discarded.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `page_size` is zero and the same errors than
+    /// [`Self::list_buckets`] and its iterator.
+    pub fn list_buckets_page(
+        &self,
+        opts: Option<&options::ListBuckets>,
+        page_size: usize,
+    ) -> Result<Page<Bucket>> {
+        Page::collect(self.list_buckets(opts)?, page_size, |b| &b.name)
+    }
+
+    /// Lists all the existing buckets and returns them sorted in `order`.
+    ///
+    /// The satellite only lists the buckets in ascending order of their names and it doesn't
+    /// allow to paginate them backwards, so all of them are collected in memory before sorting
+    /// them, hence the memory usage grows with the number of buckets of the project.
+    ///
+    /// It returns the same errors than [`Self::list_buckets`] and its iterator.
+    pub fn list_buckets_sorted(&self, order: bucket::SortOrder) -> Result<Vec<Bucket>> {
+        let mut buckets = self.list_buckets(None)?.collect::<Result<Vec<_>>>()?;
+        order.sort(&mut buckets);
+        Ok(buckets)
+    }
+
+    /// Returns an iterator over the list of existing object inside of `bucket` with optional
+    /// options.
+    ///
+    /// It returns an error if the FFI iterator is in an error state right after its creation.
+    pub fn list_objects(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListObjects>,
+    ) -> Result<object::Iterator> {
+        self.ffi_list_objects(bucket, opts, true)
+    }
+
+    /// Lists all the existing objects inside of `bucket` with optional options and collects them.
+    ///
+    /// Contrary to collecting the items of [`Self::list_objects`] into a `Result<Vec<Object>>`,
+    /// the returned error contains the objects listed before the error happened, so the listing
+    /// can be resumed after the last one with [`options::ListObjects::with_cursor`].
+    pub fn list_objects_all(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListObjects>,
+    ) -> std::result::Result<Vec<Object>, error::ListError<Object>> {
+        match self.list_objects(bucket, opts) {
+            Ok(it) => error::ListError::collect(it),
+            Err(error) => Err(error::ListError {
+                items: Vec::new(),
+                error,
+            }),
+        }
+    }
+
+    /// Returns an iterator over the existing objects inside of `bucket` whose key matches
+    /// `filter`, with optional options.
+    ///
+    /// The listing is narrowed server-side to [`object::KeyFilter::listing_prefix`] when it's
+    /// longer than the prefix of `opts` and starts with it, and the keys are filtered client-side.
+    /// In non-recursive listings, the narrowed prefix is the one whose objects and prefixes are
+    /// listed (e.g. `logs/2024-*` lists the content of `logs/`), as a shell glob does. The limit
+    /// of `opts` is applied to the matching objects.
+    ///
+    /// It returns the same errors than [`Self::list_objects`].
+    pub fn list_objects_filtered(
+        &self,
+        bucket: &str,
+        filter: object::KeyFilter,
+        opts: Option<&options::ListObjects>,
+    ) -> Result<object::FilteredIterator> {
+        let narrowed = options::ListObjects::narrowed_to_prefix(opts, filter.listing_prefix())?;
+        let it = self.list_objects(bucket, Some(&narrowed))?;
+        Ok(object::FilteredIterator::new(
+            it,
+            filter,
+            opts.and_then(|o| o.limit),
+        ))
+    }
+
+    /// Returns an iterator over the keys of the existing objects inside of `bucket` with optional
+    /// options.
+    ///
+    /// It's faster than [`Self::list_objects`] when only the keys are needed because it doesn't
+    /// retrieve nor convert the objects' metadata, ignoring the `system` and `custom` fields of
+    /// `opts`. The `list_keys` benchmark compares them.
+    ///
+    /// It returns an error if the FFI iterator is in an error state right after its creation.
+    pub fn list_objects_keys(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListObjects>,
+    ) -> Result<object::KeyIterator> {
+        self.ffi_list_objects(bucket, opts, false)
+            .map(object::KeyIterator::new)
+    }
+
+    /// Lists a page of at most `page_size` existing objects inside of `bucket` with optional
+    /// options, ignoring their `limit`.
+    ///
+    /// The next page is listed passing the page's `next_cursor`, with the same prefix, to
+    /// [`options::ListObjects::with_cursor`] or [`options::ListObjects::with_prefix_and_cursor`].
+    /// Knowing if the page is truncated requires retrieving the object after it, which is
+    /// discarded.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `page_size` is zero and the same errors than
+    /// [`Self::list_objects`] and its iterator.
+    pub fn list_objects_page(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListObjects>,
+        page_size: usize,
+    ) -> Result<Page<Object>> {
+        let it = self.list_objects(bucket, opts)?.with_limit(None);
+        Page::collect(it, page_size, |o| &o.key)
+    }
+
+    /// Returns an iterator over the parts of a multipart upload started with [`Self::begin_upload`]
+    /// with optional options.
+    ///
+    /// It returns an error if the FFI iterator is in an error state right after its creation.
+    pub fn list_upload_parts(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        upload_id: &str,
+        opts: Option<&options::ListUploadParts>,
+    ) -> Result<upload::PartIterator> {
+        let key = key.as_ref();
+        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
+        let c_upload_id = helpers::cstring_from_str_fn_arg("upload_id", upload_id)?;
+
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_it = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_list_upload_parts_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                };
+
+                ulksys::uplink_list_upload_parts(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    c_upload_id.as_ptr() as *mut c_char,
+                    c_opts,
+                )
+            };
+
+            upload::PartIterator::from_ffi_part_iterator(uc_it)
+        })
+    }
+
+    /// Returns an iterator over the uncommitted uploads in `bucket` with optional options.
+    ///
+    /// It returns an error if the FFI iterator is in an error state right after its creation.
+    pub fn list_uploads(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListUploads>,
+    ) -> Result<upload::Iterator> {
+        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_it = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_list_uploads_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_list_uploads(project, c_bucket.as_ptr() as *mut c_char, c_opts)
+            };
+
+            upload::Iterator::from_ffi_upload_iterator(
+                uc_it,
+                opts.is_some_and(|o| o.system),
+                opts.is_some_and(|o| o.custom),
+            )
+        })
+    }
+
+    /// Moves an object to a different bucket or/and key with optional options.
+    ///
+    /// It returns an [`UplinkCode::UploadPending`] error if the object doesn't exist because it
+    /// only has a pending multipart upload, which is only looked up when the FFI returns an error.
+    pub fn move_object(
+        &self,
+        current_bucket: &str,
+        current_key: impl AsRef<str>,
+        new_bucket: &str,
+        new_key: impl AsRef<str>,
+        opts: Option<&options::MoveObject>,
+    ) -> Result<()> {
+        let current_key = current_key.as_ref();
+        let new_key = new_key.as_ref();
+        let c_cur_bucket = helpers::cstring_from_str_fn_arg("current_bucket", current_bucket)?;
+        let c_cur_key = helpers::cstring_from_str_fn_arg("current_key", current_key)?;
+        let c_new_bucket = helpers::cstring_from_str_fn_arg("new_bucket", new_bucket)?;
+        let c_new_key = helpers::cstring_from_str_fn_arg("new_key", new_key)?;
+
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_err = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_move_object_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_move_object(
+                    project,
+                    c_cur_bucket.as_ptr() as *mut c_char,
+                    c_cur_key.as_ptr() as *mut c_char,
+                    c_new_bucket.as_ptr() as *mut c_char,
+                    c_new_key.as_ptr() as *mut c_char,
+                    c_opts,
+                )
+            };
+
+            if let Some(err) = Error::from_ffi_error(uc_err) {
+                Err(err)
+            } else {
+                Ok(())
+            }
+        })
+        .map_err(|err| self.upload_pending_or(current_bucket, current_key, err))
+    }
+
+    /// Moves the object of the `(bucket, key)` pair `current` to the `(bucket, key)` pair `new`,
+    /// which cannot be transposed, see [`Self::move_object`].
+    pub fn move_object_typed(
+        &self,
+        current: (&BucketName, &ObjectKey),
+        new: (&BucketName, &ObjectKey),
+        opts: Option<&options::MoveObject>,
+    ) -> Result<()> {
+        self.move_object(current.0, current.1, new.0, new.1, opts)
+    }
+
+    /// Uploads `data` as the object inside of `bucket` and referenced by `key` with optional
+    /// options, committing it, and returns the uploaded object.
+    ///
+    /// When `data` isn't bigger than the [inline segment threshold of the
+    /// configuration](Config::set_inline_segment_threshold), it's written and committed
+    /// back-to-back, which saves the overhead of the [`std::io::Write`] implementation of
+    /// [`object::Upload`] for the tiny objects.
+    ///
+    /// It aborts the upload if any of the operations fails and it returns the same errors than
+    /// [`Self::upload_object`], [`object::Upload::commit`] and [`object::Upload::info`] and the
+    /// errors returned by writing the data to [`object::Upload`].
+    pub fn put_object(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        data: &[u8],
+        opts: Option<&options::Upload>,
+    ) -> Result<Object> {
+        let key = key.as_ref();
+        let mut upload = self.upload_object(bucket, key, opts)?;
+
+        let res = if data.len() <= self.inline_segment_threshold {
+            upload.write_all_and_commit(data)
+        } else {
+            upload
+                .write_all(data)
+                .map_err(Error::from_io_error)
+                .and_then(|_| upload.commit())
+        };
+        helpers::undo_on_error(res, || upload.abort())?;
+
+        upload.info()
+    }
+
+    /// Returns the gate that paces the operations of the project, if any, see
+    /// [`Self::set_rate_gate`].
+    pub fn rate_gate(&self) -> Option<&pacing::RateGate> {
+        self.rate_gate.as_ref()
+    }
+
+    /// Renames the object inside of `bucket` referenced by `from_key` to `to_key`.
+    ///
+    /// It's a convenient method for [moving an object](Self::move_object) inside of the same
+    /// bucket.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `from_key` and `to_key` are equal.
+    pub fn rename_object(
+        &self,
+        bucket: &str,
+        from_key: impl AsRef<str>,
+        to_key: impl AsRef<str>,
+    ) -> Result<()> {
+        let from_key = from_key.as_ref();
+        let to_key = to_key.as_ref();
+        if from_key == to_key {
+            return Err(Error::new_invalid_arguments(
+                "(from_key,to_key)",
+                "keys cannot be equal",
+            ));
+        }
+
+        self.move_object(bucket, from_key, bucket, to_key, None)
+    }
+
+    /// Resumes the listing of the objects inside of `bucket` from `checkpoint`, which is returned
+    /// by [`object::Iterator::checkpoint`], yielding the objects after the last one yielded
+    /// before the checkpoint with the same options than the original listing, except the limit.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `bucket` isn't the bucket of the listing of
+    /// `checkpoint` or if `checkpoint` is invalid, and the same errors than
+    /// [`Self::list_objects`].
+    pub fn resume_listing(
+        &self,
+        bucket: &str,
+        checkpoint: &object::ListingCheckpoint,
+    ) -> Result<object::Iterator> {
+        let opts = checkpoint.resume_options(bucket)?;
+        self.list_objects(bucket, Some(&opts))
+    }
+
+    /// Revokes the API key embedded in `access`.
+    ///
+    /// When an access grant is revoked, the rest of the further-restricted access grants (via the
+    /// [`crate::access:Grant.share`]) are revoked.
+    ///
+    /// An access grant is authorized to revoke any of its further-restricted access grants. It
     /// cannot revoke itself. Revoking an access grant which is not one of its further-restricted
     /// access grants will return an error.
     ///
     /// A successful revocation request may not actually apply the revocation immediately because
     /// of the satellite's access caching policies.
     pub fn revoke_access(&self, access: &Grant) -> Result<()> {
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_err =
-            unsafe { ulksys::uplink_revoke_access(self.inner.project, access.as_ffi_access()) };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_err = unsafe { ulksys::uplink_revoke_access(project, access.as_ffi_access()) };
 
-        if let Some(err) = Error::from_ffi_error(uc_err) {
-            Err(err)
-        } else {
-            Ok(())
-        }
+            if let Some(err) = Error::from_ffi_error(uc_err) {
+                Err(err)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Sets the default options of the uploads to `bucket` and returns the previous ones, if any.
+    ///
+    /// [`Self::upload_object`], [`Self::begin_upload`], [`Self::commit_upload`] and the methods
+    /// that use them (e.g. [`Self::put_object`], [`Self::upload_file`]) apply them to all the
+    /// uploads to `bucket`, merging them with the options passed to them, see [`UploadDefaults`].
+    pub fn set_bucket_defaults(
+        &self,
+        bucket: &str,
+        defaults: UploadDefaults,
+    ) -> Option<UploadDefaults> {
+        self.defaults.set(bucket, defaults)
+    }
+
+    /// Sets the gate that paces the operations of the project when the satellite rate limits them
+    /// and returns the previous one, if any. `None` removes it, so the operations aren't paced.
+    ///
+    /// The gate observes the errors of all the operations of the project (e.g.
+    /// [`Self::stat_object`], [`Self::upload_object`]), but not the errors returned later by the
+    /// types that they return (e.g. the iterators, the uploads and the downloads), which can be
+    /// reported with [`pacing::RateGate::observe`]. See the [`pacing`] module.
+    pub fn set_rate_gate(&mut self, gate: Option<pacing::RateGate>) -> Option<pacing::RateGate> {
+        std::mem::replace(&mut self.rate_gate, gate)
     }
 
     /// Returns the bucket's information.
     pub fn stat_bucket(&self, bucket: &str) -> Result<Bucket> {
-        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_bucket = self.bucket_name_fn_arg(bucket)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_stat_bucket(self.inner.project, c_bucket.as_ptr() as *mut c_char)
-        };
+        self.run_bounded("stat bucket", None, move |project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res =
+                unsafe { ulksys::uplink_stat_bucket(project, c_bucket.as_ptr() as *mut c_char) };
 
-        Bucket::from_ffi_bucket_result(uc_res)
+            Bucket::from_ffi_bucket_result(uc_res)
+        })
     }
 
     /// Returns the object's information inside of `bucket` and reference by `key`.
-    pub fn stat_object(&self, bucket: &str, key: &str) -> Result<Object> {
+    pub fn stat_object(&self, bucket: &str, key: impl AsRef<str>) -> Result<Object> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_stat_object(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-            )
-        };
+        self.run_bounded("stat object", None, move |project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                ulksys::uplink_stat_object(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                )
+            };
 
-        Object::from_ffi_object_result(uc_res)
-            .map(|op| op.expect("successful stat object must always return an object"))
+            Object::from_ffi_object_result(uc_res)
+                .map(|op| op.expect("successful stat object must always return an object"))
+        })
+    }
+
+    /// Uploads the file at `path` as the object inside of `bucket` and referenced by `key` with
+    /// optional options, committing it, and returns the uploaded object.
+    ///
+    /// It streams the data through a large buffer, so the memory usage doesn't depend on the
+    /// file's size. The modification time of the file is recorded in the object's custom metadata
+    /// under the [`metadata::KEY_MTIME`] key, so [`Self::download_file`] can restore it.
+    ///
+    /// It aborts the upload if any of the operations fails and it returns an [`Error::Internal`]
+    /// wrapping the I/O error, with the path in its context message, if any filesystem operation
+    /// fails, and the same errors than [`Self::put_object`].
+    pub fn upload_file(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        path: &Path,
+        opts: Option<&options::UploadFile>,
+    ) -> Result<Object> {
+        let key = key.as_ref();
+        let mut file =
+            fs::File::open(path).map_err(|err| fs_error("opening the file", path, err))?;
+        let file_metadata = file
+            .metadata()
+            .map_err(|err| fs_error("reading the metadata of the file", path, err))?;
+        // Not all the platforms support the modification time, so it's only recorded when it's
+        // available.
+        let mtime = file_metadata
+            .modified()
+            .ok()
+            .and_then(metadata::mtime_to_value);
+
+        let upload_opts = options::Upload {
+            expires: opts.and_then(|o| o.expires),
+            custom_metadata: mtime.map(|mtime| {
+                let mut custom = metadata::Custom::with_capacity(1);
+                custom.insert(String::from(metadata::KEY_MTIME), mtime);
+                custom
+            }),
+        };
+        let mut upload = self.upload_object(bucket, key, Some(&upload_opts))?;
+
+        let res = copy_buffered(
+            &mut file,
+            &mut upload,
+            |err| fs_error("reading the file", path, err),
+            Error::from_io_error,
+        )
+        .and_then(|_| upload.commit());
+        helpers::undo_on_error(res, || upload.abort())?;
+
+        upload.info()
     }
 
     /// Starts an object upload into `bucket` with the specified `key` and optional options.
+    ///
+    /// It sets the custom metadata of `opts`, if any, on the started upload, aborting it and
+    /// returning the error of [`object::Upload::set_custom_metadata`] if it fails.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if the expiration time of `opts` is in the past,
+    /// see [`Config::set_expiration_skew`].
     pub fn upload_object(
         &self,
         bucket: &str,
-        key: &str,
+        key: impl AsRef<str>,
         opts: Option<&options::Upload>,
     ) -> Result<object::Upload> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
+        let with_defaults = self.defaults.upload_options(bucket, opts)?;
+        let opts = with_defaults.as_ref().or(opts);
+        if let Some(o) = opts {
+            o.validate_expires(helpers::unix_time_now()?, self.expiration_skew)?;
+        }
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_upload_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
-            }
+        let mut upload = self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_upload_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_upload_object(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    c_opts,
+                )
+            };
 
-            ulksys::uplink_upload_object(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_opts,
-            )
-        };
+            object::Upload::from_ffi_upload_result(uc_res, self.metadata_limits)
+        })?;
+        if let Some(custom) = opts.and_then(|o| o.custom_metadata.as_ref()) {
+            let res = upload.set_custom_metadata(custom);
+            helpers::undo_on_error(res, || upload.abort())?;
+        }
 
-        object::Upload::from_ffi_upload_result(uc_res)
+        Ok(upload)
+    }
+
+    /// Uploads the data read from `reader` compressed with `compression` as the object inside of
+    /// `bucket` and referenced by `key`, committing it, and returns the uploaded object.
+    ///
+    /// It compresses the data on the fly, so the memory usage doesn't depend on its size. The
+    /// algorithm and the uncompressed size are recorded in the object's custom metadata under the
+    /// [`compression::METADATA_KEY_ALGORITHM`] and
+    /// [`compression::METADATA_KEY_UNCOMPRESSED_SIZE`] keys. Use
+    /// [`Self::download_object_decompressed`] for getting the original data back.
+    ///
+    /// It aborts the upload if any of the operations fails and it returns the same errors than
+    /// [`Self::put_object`] and the errors returned by reading from `reader`.
+    #[cfg(feature = "compression")]
+    pub fn upload_object_compressed(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        mut reader: impl Read,
+        compression: compression::Compression,
+    ) -> Result<Object> {
+        let key = key.as_ref();
+        let mut upload = self.upload_object(bucket, key, None)?;
+
+        let res = compression::compress(&mut reader, &mut upload, compression)
+            .map_err(Error::from_io_error)
+            .and_then(|size| {
+                // It replaces the custom metadata set when the upload started, so the defaults of
+                // the bucket have to be set again.
+                let mut custom = compression::metadata(compression, size);
+                self.defaults.fill_custom_metadata(bucket, &mut custom);
+                upload.set_custom_metadata(&custom)
+            })
+            .and_then(|_| upload.commit());
+        helpers::undo_on_error(res, || upload.abort())?;
+
+        upload.info()
+    }
+
+    /// Uploads the data read from `reader` as the object inside of `bucket` and referenced by
+    /// `key` with a multipart upload tuned by `tuning`, and returns the uploaded object.
+    ///
+    /// The parts are read sequentially from `reader` and uploaded concurrently, so it's faster
+    /// than [`Self::upload_object`] for big objects when `tuning` allows several concurrent parts.
+    /// See [`MultipartTuning`](tuning::MultipartTuning) about its memory usage.
+    ///
+    /// It aborts the upload if any of the operations fails and it returns an
+    /// [`Error::InvalidArguments`] if `tuning` is invalid and the same errors than
+    /// [`Self::begin_upload`], [`Self::upload_part`], [`Self::commit_upload`] and the errors
+    /// returned by reading from `reader`.
+    pub fn upload_object_from_reader(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        mut reader: impl Read,
+        opts: Option<&options::Upload>,
+        tuning: &tuning::MultipartTuning,
+    ) -> Result<Object> {
+        let key = key.as_ref();
+        tuning.validate()?;
+        let info = self.begin_upload(bucket, key, opts)?;
+
+        let custom = opts.and_then(|o| o.custom_metadata.clone());
+        let res = tuning::upload_parts(self, bucket, key, &info.upload_id, &mut reader, tuning)
+            .and_then(|_| match custom {
+                Some(custom) => {
+                    let commit_opts = options::CommitUpload::new(custom);
+                    self.commit_upload(bucket, key, &info.upload_id, Some(&commit_opts))
+                }
+                None => self.commit_upload(bucket, key, &info.upload_id, None),
+            });
+        helpers::undo_on_error(res, || self.abort_upload(bucket, key, &info.upload_id))
+    }
+
+    /// Uploads `data` as the object inside of `bucket` and referenced by `key` only if the object
+    /// doesn't exist, and returns the uploaded object.
+    ///
+    /// It returns an [`UplinkCode::ObjectAlreadyExists`] error if the object exists.
+    ///
+    /// The satellite doesn't support conditional uploads, so it's a best-effort operation that
+    /// checks that the object doesn't exist and then uploads it. Another client can upload the
+    /// object between both operations, in which case the last committed upload replaces the other
+    /// object.
+    ///
+    /// It returns the same errors than [`Self::stat_object`], except the
+    /// [`UplinkCode::ObjectNotFound`] one, and [`Self::put_object`].
+    pub fn upload_object_if_absent(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        data: &[u8],
+        opts: Option<&options::Upload>,
+    ) -> Result<Object> {
+        let key = key.as_ref();
+        match self.stat_object(bucket, key) {
+            Ok(_) => Err(Error::Uplink(error::Uplink::with_code(
+                UplinkCode::ObjectAlreadyExists,
+                &format!("object {:?} already exists in bucket {:?}", key, bucket),
+            ))),
+            Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {
+                self.put_object(bucket, key, data, opts)
+            }
+            Err(err) => Err(err),
+        }
     }
 
     /// Uploads a part with `part_number` to a multipart upload started with
@@ -569,81 +1716,862 @@ impl Project {
     pub fn upload_part(
         &self,
         bucket: &str,
-        key: &str,
+        key: impl AsRef<str>,
         upload_id: &str,
         part_number: u32,
     ) -> Result<upload::PartUpload> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
         let c_upload_id = helpers::cstring_from_str_fn_arg("upload_id", upload_id)?;
 
-        // SAFETY: we trust the FFI is behaving correctly when called with correct value.
-        let uc_res = unsafe {
-            ulksys::uplink_upload_part(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                c_upload_id.as_ptr() as *mut c_char,
-                part_number,
-            )
-        };
+        self.call_ffi(|project| {
+            // SAFETY: we trust the FFI is behaving correctly when called with correct value.
+            let uc_res = unsafe {
+                ulksys::uplink_upload_part(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    c_upload_id.as_ptr() as *mut c_char,
+                    part_number,
+                )
+            };
 
-        upload::PartUpload::from_ffi_part_upload_result(uc_res)
+            upload::PartUpload::from_ffi_part_upload_result(uc_res)
+        })
     }
 
     /// Replaces the custom metadata for the object inside of `bucket` and referenced by `key` with
     /// the new specified metadata and with optional options. Any existing custom metadata is
     /// deleted.
     ///
-    /// `metadata` is mutable because converting to a Uplink-C representation requires it.
+    /// It returns an [`Error::InvalidArguments`] if `metadata` doesn't satisfy the limits of the
+    /// project, see [`metadata::Custom::validate`].
     pub fn update_object_metadata(
         &self,
         bucket: &str,
-        key: &str,
-        metadata: &mut metadata::Custom,
+        key: impl AsRef<str>,
+        metadata: &metadata::Custom,
         opts: Option<&options::UploadObjectMetadata>,
     ) -> Result<()> {
+        let key = key.as_ref();
         let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
         let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
+        metadata.validate_fn_arg("metadata", &self.metadata_limits)?;
+        let uc_custom = metadata.to_ffi_custom_metadata();
+
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_err = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_upload_object_metadata_options();
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                }
+
+                ulksys::uplink_update_object_metadata(
+                    project,
+                    c_bucket.as_ptr() as *mut c_char,
+                    c_key.as_ptr() as *mut c_char,
+                    uc_custom.as_ffi_custom_metadata(),
+                    c_opts,
+                )
+            };
 
-        // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
-        // mutable reference to it but we use the reference only inside of the scope, hence we are
-        // always referencing it during its lifetime that the scope establishes.
-        // For the rest, we trust the FFI is behaving correctly when called with correct value.
-        let uc_err = unsafe {
-            let mut c_opts = ptr::null_mut();
-            let mut uc_opts;
-            if let Some(o) = opts {
-                uc_opts = o.as_ffi_upload_object_metadata_options();
-                c_opts = ptr::addr_of_mut!(uc_opts);
+            if let Some(err) = Error::from_ffi_error(uc_err) {
+                Err(err)
+            } else {
+                Ok(())
             }
+        })
+    }
 
-            ulksys::uplink_update_object_metadata(
-                self.inner.project,
-                c_bucket.as_ptr() as *mut c_char,
-                c_key.as_ptr() as *mut c_char,
-                metadata.to_ffi_custom_metadata(),
-                c_opts,
-            )
-        };
+    /// Applies `changes` to the custom metadata of the object inside of `bucket` and referenced by
+    /// `key`, keeping the entries that `changes` doesn't touch, and returns the resulting custom
+    /// metadata.
+    ///
+    /// It retrieves the current custom metadata, merges `changes` into it and replaces it through
+    /// [`Self::update_object_metadata`], so it's a read-modify-write operation that isn't atomic:
+    /// any change made to the custom metadata of the object by another client between the
+    /// retrieval and the replacement is lost.
+    ///
+    /// It returns the same errors than [`Self::stat_object`] and [`Self::update_object_metadata`].
+    pub fn patch_object_metadata(
+        &self,
+        bucket: &str,
+        key: impl AsRef<str>,
+        changes: metadata::MetadataPatch,
+    ) -> Result<metadata::Custom> {
+        let key = key.as_ref();
+        let object = self.stat_object(bucket, key)?;
+        let mut custom = object.metadata_custom;
+        if changes.is_empty() {
+            return Ok(custom);
+        }
 
-        if let Some(err) = Error::from_ffi_error(uc_err) {
-            Err(err)
-        } else {
-            Ok(())
+        changes.apply(&mut custom);
+        // TODO: replace it with a conditional update, that only succeeds if the metadata hasn't
+        // changed since it was retrieved, when uplink-c exposes one.
+        self.update_object_metadata(bucket, key, &custom, None)?;
+        Ok(custom)
+    }
+
+    /// Returns the C representation of the `bucket` argument of the bucket operations, validating
+    /// it with [`bucket::validate_name`] first, unless that validation is disabled.
+    fn bucket_name_fn_arg(&self, bucket: &str) -> Result<CString> {
+        if self.validate_bucket_names {
+            bucket::validate_name(bucket)?;
+        }
+
+        helpers::cstring_from_str_fn_arg("bucket", bucket)
+    }
+
+    /// Starts a download of the object inside of `bucket` and referenced with `key` with optional
+    /// options, checking that the object exists by retrieving its information when `check` is
+    /// `true`. It's bounded like [`Self::download_object`].
+    fn ffi_download_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        opts: Option<&options::Download>,
+        check: bool,
+    ) -> Result<object::Download> {
+        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+        let c_key = helpers::cstring_from_str_fn_arg("key", key)?;
+        // The operation may run in a separate thread that outlives `opts`, so it owns a copy.
+        let ffi_opts = opts.map(options::Download::as_ffi_download_options);
+
+        self.run_bounded(
+            "download object",
+            opts.and_then(|o| o.timeout),
+            move |project| {
+                // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+                // mutable reference to it but we use the reference only inside of the scope, hence we
+                // are always referencing it during its lifetime that the scope establishes.
+                // For the rest, we trust the FFI is behaving correctly when called with correct value.
+                let uc_res = unsafe {
+                    let mut c_opts = ptr::null_mut();
+                    let mut uc_opts;
+                    if let Some(o) = ffi_opts {
+                        uc_opts = o;
+                        c_opts = ptr::addr_of_mut!(uc_opts);
+                    }
+
+                    ulksys::uplink_download_object(
+                        project,
+                        c_bucket.as_ptr() as *mut c_char,
+                        c_key.as_ptr() as *mut c_char,
+                        c_opts,
+                    )
+                };
+
+                let download = object::Download::from_ffi_download_result(uc_res)?;
+                if check {
+                    download.info()?;
+                }
+
+                Ok(download)
+            },
+        )
+    }
+
+    /// Parses the serialized access grant `access` for opening a project, returning the error
+    /// described by [`Self::open_from_serialized`] if it's invalid.
+    fn parse_serialized_access(access: &str) -> Result<Grant> {
+        Grant::new(access)
+            .map_err(|err| Error::new_internal(OPEN_ERROR_CTX_GRANT, BoxError::from(err)))
+    }
+
+    /// Returns the project or the error described by [`Self::open_from_serialized`] if the FFI
+    /// couldn't open it.
+    fn checked_open(mut self) -> Result<Self> {
+        if let Some(err) = Error::new_uplink(self.inner.error) {
+            // The FFI project is `NULL` when there is an error, so there is nothing to close and
+            // the error is freed with the project result.
+            self.closed = true;
+            return Err(Error::new_internal(
+                OPEN_ERROR_CTX_PROJECT,
+                BoxError::from(err),
+            ));
+        }
+
+        Ok(self)
+    }
+
+    /// Returns an iterator over the existing objects inside of `bucket` with optional options,
+    /// retrieving the objects' metadata requested by `opts` only if `metadata` is `true`.
+    fn ffi_list_objects(
+        &self,
+        bucket: &str,
+        opts: Option<&options::ListObjects>,
+        metadata: bool,
+    ) -> Result<object::Iterator> {
+        let c_bucket = helpers::cstring_from_str_fn_arg("bucket", bucket)?;
+
+        self.call_ffi(|project| {
+            // SAFETY: we get the FFI representation of the opts if it isn't `None` then we get a
+            // mutable reference to it but we use the reference only inside of the scope, hence we
+            // are always referencing it during its lifetime that the scope establishes.
+            // For the rest, we trust the FFI is behaving correctly when called with correct value.
+            let uc_it = unsafe {
+                let mut c_opts = ptr::null_mut();
+                let mut uc_opts;
+                if let Some(o) = opts {
+                    uc_opts = o.as_ffi_list_objects_options();
+                    if !metadata {
+                        uc_opts.system = false;
+                        uc_opts.custom = false;
+                    }
+                    c_opts = ptr::addr_of_mut!(uc_opts);
+                };
+
+                ulksys::uplink_list_objects(project, c_bucket.as_ptr() as *mut c_char, c_opts)
+            };
+
+            object::Iterator::from_ffi_object_iterator(uc_it).map(|it| {
+                it.with_limit(opts.and_then(|o| o.limit))
+                    .with_checkpoint(object::ListingCheckpoint::new(bucket, opts, metadata))
+            })
+        })
+    }
+
+    /// Returns an [`UplinkCode::UploadPending`] error if the object inside of `bucket` and
+    /// referenced by `key` doesn't exist but it has a pending multipart upload, otherwise `err`,
+    /// which is the error of an operation on the object.
+    ///
+    /// The errors of the lookups are discarded because `err` is the relevant one.
+    fn upload_pending_or(&self, bucket: &str, key: &str, err: Error) -> Error {
+        match self.stat_object(bucket, key) {
+            Err(Error::Uplink(stat_err)) if stat_err.code() == UplinkCode::ObjectNotFound => {}
+            _ => return err,
+        }
+
+        // The listing prefix must end with a slash, so the uploads are listed from the key's
+        // parent "directory" or from the root when the key doesn't have one.
+        let opts = match key.rfind('/') {
+            Some(i) => options::ListUploads::with_prefix(&key[..=i]),
+            None => Ok(options::ListUploads::default()),
+        };
+        let pending = opts
+            .and_then(|opts| self.list_uploads(bucket, Some(&opts)))
+            .map(|mut it| it.any(|info| info.is_ok_and(|info| info.key == key)))
+            .unwrap_or(false);
+        if !pending {
+            return err;
         }
+
+        Error::Uplink(error::Uplink::with_code(
+            UplinkCode::UploadPending,
+            &format!(
+                "object {:?} in bucket {:?} isn't committed yet; it has a pending multipart upload",
+                key, bucket
+            ),
+        ))
+    }
+
+    /// Calls `op` with the FFI project, paced by the project's rate gate, if any, see
+    /// [`pacing::paced`].
+    ///
+    /// All the operations call the FFI through it, so the rate gate observes all their results.
+    /// `op` must return the error converted from the FFI result without looking up anything else
+    /// with the project, so the gate observes the error of the FFI call.
+    fn call_ffi<T>(&self, op: impl FnOnce(*mut ulksys::UplinkProject) -> Result<T>) -> Result<T> {
+        pacing::paced(self.rate_gate.as_ref(), || op(self.inner.project))
+    }
+
+    /// Runs `op` with the FFI project through [`Self::call_ffi`], bounded by `timeout` or, if it's
+    /// `None`, by the project's operation timeout, see [`Config::set_operation_timeout`]. It runs
+    /// `op` in the current thread when there isn't any timeout.
+    ///
+    /// It returns the errors of `op` and the same errors than [`uplink_c::run_with_timeout`].
+    fn run_bounded<T: Send + 'static>(
+        &self,
+        ctx: &str,
+        timeout: Option<Duration>,
+        op: impl FnOnce(*mut ulksys::UplinkProject) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        self.call_ffi(|project| match timeout.or(self.operation_timeout) {
+            Some(timeout) => {
+                let handle = Arc::clone(&self.inner);
+                uplink_c::run_with_timeout(ctx, timeout, move || op(handle.project))
+            }
+            None => op(project),
+        })
     }
 }
 
 impl Drop for Project {
+    /// Closes the project, if it isn't closed. Its memory is freed when the operations abandoned
+    /// by a timeout finish, if any, see `ProjectHandle`.
     fn drop(&mut self) {
-        // SAFETY: we trust that the FFI is doing correct operations when closing and freeing a
-        // correctly created `UplinkProjectResult` value.
-        unsafe {
-            // At this point we cannot do anything about the error, so discarded.
-            // TODO(https://github.com/storj-thirdparty/uplink-rust/issues/51).
-            let _ = ulksys::uplink_close_project(self.inner.project);
-            ulksys::uplink_free_project_result(self.inner);
+        if !self.closed {
+            // SAFETY: we trust that the FFI is doing correct operations when closing a correctly
+            // created project, even if an abandoned operation is using it.
+            // At this point we cannot do anything about the error, so it's discarded. Use
+            // `Project::close` for getting it.
+            drop(Error::from_ffi_error(unsafe {
+                ulksys::uplink_close_project(self.inner.project)
+            }));
         }
     }
 }
+
+/// Owns the FFI project result and frees it when it drops.
+///
+/// It's shared with the operations that run in a separate thread for bounding them with a
+/// timeout, so the project's memory isn't freed while an abandoned operation uses it.
+///
+/// It holds a reference to the FFI access of the access grant that opened the project, so the
+/// access is freed after the project regardless of when the access grant drops.
+struct ProjectHandle {
+    /// The FFI project result.
+    result: ulksys::UplinkProjectResult,
+    /// The FFI access of the access grant that opened the project.
+    access: Arc<AccessHandle>,
+}
+
+impl Deref for ProjectHandle {
+    type Target = ulksys::UplinkProjectResult;
+
+    fn deref(&self) -> &Self::Target {
+        &self.result
+    }
+}
+
+impl Drop for ProjectHandle {
+    fn drop(&mut self) {
+        // SAFETY: we trust that the FFI is doing correct operations when freeing a correctly
+        // created `UplinkProjectResult` value, which isn't used anymore because it's the last
+        // reference to it.
+        unsafe { ulksys::uplink_free_project_result(self.result) };
+    }
+}
+
+// SAFETY: the FFI project is a handler that uplink-c resolves to the Go project through a table
+// guarded by a mutex, and the Go project is safe for concurrent use, including closing it while
+// other operations use it, so it can be used and freed from any thread.
+// The Go project references the Go access of the FFI access that it holds, which is only mutated
+// by `Grant::override_encryption_key` when no other instance references the FFI access (see the
+// `Sync` implementation of `AccessHandle`). Because this instance references it, the access grant
+// overrides a copy instead, so the Go access isn't mutated while the project uses it.
+unsafe impl Send for ProjectHandle {}
+
+// SAFETY: see the `Send` implementation; the C struct of the handler is never mutated, it's only
+// passed by value to the FFI, so it can be shared between threads.
+unsafe impl Sync for ProjectHandle {}
+
+/// The size of the buffer used by [`Project::upload_file`] and [`Project::download_file`].
+const FILE_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Writes all the data read from `reader` into `writer` through a buffer of
+/// [`FILE_BUFFER_SIZE`] and returns the number of copied bytes.
+///
+/// It maps the reading errors with `read_err` and the writing errors with `write_err`.
+fn copy_buffered(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    read_err: impl Fn(io::Error) -> Error,
+    write_err: impl Fn(io::Error) -> Error,
+) -> Result<u64> {
+    let mut buf = vec![0; FILE_BUFFER_SIZE];
+    let mut copied = 0;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return Ok(copied),
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(read_err(err)),
+        };
+
+        writer.write_all(&buf[..n]).map_err(&write_err)?;
+        copied += n as u64;
+    }
+}
+
+/// A chunk of [`Project::download_object_parallel`] with its offset and its data.
+type Chunk = (u64, Vec<u8>);
+
+/// Reads the `length` bytes of a chunk from `download`.
+///
+/// It returns an [`Error::Internal`] if `cancelled` is set before reading all the bytes or if the
+/// data ends before.
+fn read_chunk(
+    mut download: object::Download,
+    length: u64,
+    cancelled: &AtomicBool,
+) -> Result<Vec<u8>> {
+    let mut data = vec![0; length as usize];
+    let mut filled = 0;
+    while filled < data.len() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(Error::new_internal(
+                "downloading a chunk",
+                BoxError::from("cancelled because another chunk failed"),
+            ));
+        }
+
+        match download.read(&mut data[filled..]) {
+            Ok(0) => {
+                return Err(Error::new_internal(
+                    "downloading a chunk",
+                    BoxError::from("the object's data ended before the end of the chunk"),
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(Error::from_io_error(err)),
+        }
+    }
+
+    Ok(data)
+}
+
+/// Waits for the download of a chunk to finish and writes it into `writer` at its offset,
+/// propagating its panic if it panicked.
+fn write_chunk(
+    writer: &mut (impl Write + Seek),
+    worker: Option<thread::ScopedJoinHandle<'_, Result<Chunk>>>,
+) -> Result<()> {
+    let (offset, data) = match worker {
+        Some(worker) => worker
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?,
+        None => return Ok(()),
+    };
+
+    writer
+        .seek(io::SeekFrom::Start(offset))
+        .and_then(|_| writer.write_all(&data))
+        .map_err(Error::from_io_error)
+}
+
+/// Returns an [`Error::Internal`] wrapping `err` returned by the filesystem when performing
+/// `action` on `path`.
+fn fs_error(action: &str, path: &Path, err: io::Error) -> Error {
+    Error::new_internal(
+        &format!("{} {}", action, path.display()),
+        BoxError::from(err),
+    )
+}
+
+/// Returns the error of downloading into the existing file at `path` without allowing to
+/// overwrite it.
+fn file_exists_error(path: &Path) -> Error {
+    Error::new_invalid_arguments(
+        "path",
+        &format!(
+            "file {} already exists; set opts{{overwrite}} for overwriting it",
+            path.display()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reader that returns `data` in chunks of at most `chunk_size` bytes and fails at the end if
+    /// `fail` is true.
+    struct ChunkedReader {
+        data: Vec<u8>,
+        chunk_size: usize,
+        fail: bool,
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                if self.fail {
+                    return Err(io::Error::other("reader"));
+                }
+                return Ok(0);
+            }
+
+            let n = buf.len().min(self.chunk_size).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_copy_buffered() {
+        let data: Vec<u8> = (0..3 * FILE_BUFFER_SIZE as u32).map(|i| i as u8).collect();
+        let read_err = |err| Error::new_internal("read", BoxError::from(err));
+        let write_err = |err| Error::new_invalid_arguments("writer", &format!("{}", err));
+
+        let mut writer = Vec::new();
+        let copied = copy_buffered(
+            &mut ChunkedReader {
+                data: data.clone(),
+                chunk_size: 1000,
+                fail: false,
+            },
+            &mut writer,
+            read_err,
+            write_err,
+        )
+        .expect("copy");
+        assert_eq!(copied, data.len() as u64, "copied bytes");
+        assert_eq!(writer, data, "copied data");
+
+        match copy_buffered(
+            &mut ChunkedReader {
+                data: data.clone(),
+                chunk_size: 1000,
+                fail: true,
+            },
+            &mut Vec::new(),
+            read_err,
+            write_err,
+        ) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match copy_buffered(&mut &data[..], &mut &mut [0u8; 10][..], read_err, write_err) {
+            Err(Error::InvalidArguments(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_fs_errors() {
+        let path = Path::new("/tmp/file.txt");
+
+        let err = fs_error(
+            "opening the file",
+            path,
+            io::Error::new(io::ErrorKind::NotFound, "not found"),
+        );
+        match &err {
+            Error::Internal(internal) => {
+                assert_eq!(
+                    internal.ctx_msg, "opening the file /tmp/file.txt",
+                    "context"
+                )
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        match file_exists_error(path) {
+            Error::InvalidArguments(args) => assert_eq!(args.names, "path", "names"),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_download_object_parallel_invalid_arguments() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let project = Project::open(&grant);
+
+        for (parallelism, chunk_size, names) in [(0, 1024, "parallelism"), (4, 0, "chunk_size")] {
+            match project.download_object_parallel(
+                "bucket",
+                "key",
+                &mut io::Cursor::new(Vec::new()),
+                parallelism,
+                chunk_size,
+            ) {
+                Err(Error::InvalidArguments(args)) => assert_eq!(args.names, names, "names"),
+                res => panic!("{}: unexpected result: {:?}", names, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_chunk() {
+        let mut writer = io::Cursor::new(Vec::new());
+        thread::scope(|scope| {
+            // Chunks written out of order.
+            for (offset, data) in [(3, b"def"), (0, b"abc")] {
+                let worker = scope.spawn(move || Ok((offset, data.to_vec())));
+                write_chunk(&mut writer, Some(worker)).expect("write chunk");
+            }
+
+            let worker = scope.spawn(|| Err(Error::new_invalid_arguments("chunk", "failed")));
+            match write_chunk(&mut writer, Some(worker)) {
+                Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "chunk", "names"),
+                res => panic!("unexpected result: {:?}", res),
+            }
+        });
+
+        assert_eq!(writer.into_inner(), b"abcdef", "written data");
+    }
+
+    #[test]
+    fn test_project_as_raw() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let project = Project::open(&grant);
+
+        assert!(!project.as_raw().is_null(), "raw project is NULL");
+        assert_eq!(project.as_raw(), project.inner.project, "raw project");
+    }
+
+    #[test]
+    fn test_project_close() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+
+        // `close` consumes the project, so closing it twice or using it after closing it doesn't
+        // compile, and its `drop` only frees the memory of the closed project.
+        Project::open(&grant).close().expect("close project");
+
+        // Dropping a project that isn't explicitly closed closes it.
+        drop(Project::open(&grant));
+    }
+
+    #[test]
+    fn test_project_errors_free_ffi_errors_once() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let project = Project::open(&grant);
+
+        // These operations fail without connecting to the satellite and the FFI returns their
+        // errors without a result, so they own them.
+        let ops: [(&str, &dyn Fn() -> Result<()>); 3] = [
+            ("abort_upload", &|| {
+                project.abort_upload("bucket", "key", "invalid-upload-id")
+            }),
+            ("move_object", &|| {
+                project.move_object("", "key", "bucket", "key", None)
+            }),
+            ("update_object_metadata", &|| {
+                project.update_object_metadata("", "key", &metadata::Custom::default(), None)
+            }),
+        ];
+        for (name, op) in ops {
+            let freed = error::ffi_errors_freed();
+            match op() {
+                Err(Error::Uplink(_)) => {}
+                res => panic!("{}: unexpected result: {:?}", name, res),
+            }
+            assert_eq!(
+                error::ffi_errors_freed(),
+                freed + 1,
+                "{}: FFI errors freed",
+                name
+            );
+        }
+
+        let freed = error::ffi_errors_freed();
+        project.close().expect("close project");
+        assert_eq!(error::ffi_errors_freed(), freed, "close without error");
+    }
+
+    #[test]
+    fn test_project_open_attributed() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let mut config =
+            Config::new("app/1.0", Duration::from_secs(7), None).expect("valid config");
+        config.set_validate_bucket_names(false);
+        config.set_expiration_skew(Duration::from_secs(30));
+        config.set_operation_timeout(Some(Duration::from_secs(5)));
+        config.set_inline_segment_threshold(1024);
+        let limits = metadata::MetadataLimits {
+            max_total_size: 10,
+            max_key_length: 4,
+        };
+        config.set_metadata_limits(limits);
+        let mut project = Project::open_with_config(grant, &config);
+        project.set_rate_gate(Some(pacing::RateGate::default()));
+
+        let attributed = project
+            .open_attributed("partner/1.0")
+            .expect("open attributed project");
+        assert_eq!(
+            attributed.dial_timeout,
+            Duration::from_secs(7),
+            "dial timeout"
+        );
+        assert!(!attributed.validate_bucket_names, "validate bucket names");
+        assert_eq!(
+            attributed.expiration_skew,
+            Duration::from_secs(30),
+            "expiration skew"
+        );
+        assert_eq!(
+            attributed.operation_timeout,
+            Some(Duration::from_secs(5)),
+            "operation timeout"
+        );
+        assert_eq!(
+            attributed.inline_segment_threshold, 1024,
+            "inline segment threshold"
+        );
+        assert_eq!(attributed.metadata_limits, limits, "metadata limits");
+        assert!(attributed.rate_gate().is_some(), "rate gate");
+
+        match project.open_attributed("partner/1.0 (unclosed") {
+            Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                assert_eq!(names, "user_agent", "invalid argument name")
+            }
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+
+        attributed.close().expect("close attributed project");
+        project.close().expect("close project");
+    }
+
+    #[test]
+    fn test_project_rate_gate() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let mut project = Project::open(&grant);
+        assert!(project.rate_gate().is_none(), "no gate by default");
+
+        let pause = Duration::from_millis(50);
+        let gate =
+            pacing::RateGate::new(pause, pause, Duration::from_secs(60)).expect("valid rate gate");
+        assert!(
+            project.set_rate_gate(Some(gate.clone())).is_none(),
+            "previous gate"
+        );
+
+        // The gate is shared, so an error injected through a clone engages the project's gate.
+        gate.observe(&Error::Uplink(error::Uplink::with_code(
+            UplinkCode::TooManyRequests,
+            "injected error",
+        )));
+        assert_eq!(
+            project.rate_gate().map(pacing::RateGate::pause),
+            Some(pause),
+            "engaged gate"
+        );
+
+        // This operation fails without connecting to the satellite after waiting the pause.
+        let start = std::time::Instant::now();
+        project
+            .abort_upload("bucket", "key", "invalid-upload-id")
+            .expect_err("abort invalid upload");
+        assert!(start.elapsed() >= pause, "operation not delayed");
+
+        assert!(project.set_rate_gate(None).is_some(), "removed gate");
+        project.close().expect("close project");
+    }
+
+    #[test]
+    fn test_project_metadata_limits() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let mut config =
+            Config::new("rust-uplink", Duration::from_secs(1), None).expect("valid config");
+        config.set_metadata_limits(metadata::MetadataLimits {
+            max_total_size: 10,
+            max_key_length: 4,
+        });
+        let project = Project::open_with_config(grant, &config);
+
+        let mut custom = metadata::Custom::default();
+        custom.insert(String::from("color"), String::from("blue"));
+
+        // These operations fail before calling the FFI because the key exceeds the limits of the
+        // project, although it satisfies the default ones.
+        match project.update_object_metadata("bucket", "key", &custom, None) {
+            Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "metadata", "names"),
+            res => panic!("update object metadata: unexpected result: {:?}", res),
+        }
+        let commit = options::CommitUpload::new(custom.clone());
+        match project.commit_upload("bucket", "key", "upload-id", Some(&commit)) {
+            Err(Error::InvalidArguments(args)) => {
+                assert_eq!(args.names, "opts{custom_metadata}", "names")
+            }
+            res => panic!("commit upload: unexpected result: {:?}", res),
+        }
+        let copy = options::CopyObject {
+            custom_metadata: Some(custom),
+        };
+        match project.copy_object("bucket", "key", "bucket", "copy", Some(&copy)) {
+            Err(Error::InvalidArguments(args)) => {
+                assert_eq!(args.names, "opts{custom_metadata}", "names")
+            }
+            res => panic!("copy object: unexpected result: {:?}", res),
+        }
+
+        project.close().expect("close project");
+    }
+
+    #[test]
+    fn test_project_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Project>();
+
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let mut grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let project = Project::open(&grant);
+
+        // The FFI access that the project may use from other threads isn't mutated.
+        let enc_key =
+            crate::EncryptionKey::derive("Rust test", &[0]).expect("derive encryption key");
+        grant
+            .override_encryption_key("a-bucket", "prefix/", &enc_key)
+            .expect("override encryption key");
+        assert!(
+            !Arc::ptr_eq(&project.inner.access, &grant.access_handle()),
+            "the access grant overrides a copy of the FFI access"
+        );
+
+        project.close().expect("close project");
+    }
+
+    #[test]
+    fn test_project_quota() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let project = Project::open(&grant);
+
+        match project.limits() {
+            Err(Error::Unsupported(details)) => {
+                assert_eq!(details.capability, "project limits", "capability")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match project.check_quota("my-bu\0cket", 1024) {
+            Err(Error::InvalidArguments(args)) => assert_eq!(args.names, "bucket", "names"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_project_open_from_serialized() {
+        // This access grant is invalidated so it isn't leaking any valid access grant.
+        let access = "15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu";
+
+        let project = Project::open_from_serialized(access).expect("open project");
+        let grant = project.grant();
+        assert_eq!(
+            grant.serialize().expect("serialize grant"),
+            access,
+            "serialized grant"
+        );
+        project.close().expect("close project");
+
+        let config = Config::new("uplink-rust-test", Duration::from_secs(1), None)
+            .expect("valid configuration");
+        let project =
+            Project::open_from_serialized_with_config(access, &config).expect("open project");
+        assert_eq!(
+            project.grant().serialize().expect("serialize grant"),
+            access,
+            "serialized grant with configuration"
+        );
+
+        let grant = Grant::new(access).expect("valid serialized access grant");
+        let project = Project::open(&grant);
+        drop(grant);
+        assert_eq!(
+            project.grant().serialize().expect("serialize grant"),
+            access,
+            "serialized grant opened with a grant"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Batches of operations on several buckets and objects that report the result of each operation.
+//!
+//! [`BatchContext`] records the operations and runs them concurrently against a [`Project`]
+//! without stopping at the first failure, so the [`BatchReport`] tells which ones were applied and
+//! which ones failed. The operations aren't atomic; the ones that succeeded remain applied when
+//! others fail.
+//!
+//! ```no_run
+//! use uplink::access::Grant;
+//! use uplink::project::batch::BatchContext;
+//! use uplink::Project;
+//!
+//! let grant = Grant::new("my-serialized-access-grant")?;
+//! let project = Project::open(&grant);
+//!
+//! let mut batch = BatchContext::new();
+//! for bucket in ["tenant-a", "tenant-b", "tenant-c"] {
+//!     batch
+//!         .ensure_bucket(bucket)
+//!         .put_object(bucket, "manifest.json", b"{}".to_vec(), None);
+//! }
+//!
+//! let report = batch.run(&project, 4);
+//! for (op, err) in report.errors() {
+//!     eprintln!("{:?} failed: {}", batch.operations()[op], err);
+//! }
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use super::options;
+use crate::{metadata, Error, Project, Result};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// An operation of a batch.
+#[derive(Clone, Debug)]
+pub enum Operation {
+    /// Creates the bucket if it doesn't exist, see [`Project::ensure_bucket`].
+    EnsureBucket {
+        /// The name of the bucket.
+        bucket: String,
+    },
+    /// Uploads an object, see [`Project::put_object`].
+    PutObject {
+        /// The name of the bucket.
+        bucket: String,
+        /// The key of the object.
+        key: String,
+        /// The data of the object.
+        data: Vec<u8>,
+        /// The options of the upload.
+        opts: Option<options::Upload>,
+    },
+    /// Deletes an object, see [`Project::delete_object`]. Deleting an object that doesn't exist
+    /// succeeds.
+    DeleteObject {
+        /// The name of the bucket.
+        bucket: String,
+        /// The key of the object.
+        key: String,
+    },
+    /// Replaces the custom metadata of an object, see [`Project::update_object_metadata`].
+    SetMetadata {
+        /// The name of the bucket.
+        bucket: String,
+        /// The key of the object.
+        key: String,
+        /// The custom metadata that replaces the object's one.
+        metadata: metadata::Custom,
+    },
+}
+
+impl Operation {
+    /// Applies the operation to `project`.
+    fn apply(&self, project: &Project) -> Result<()> {
+        match self {
+            Self::EnsureBucket { bucket } => project.ensure_bucket(bucket).map(|_| ()),
+            Self::PutObject {
+                bucket,
+                key,
+                data,
+                opts,
+            } => project
+                .put_object(bucket, key, data, opts.as_ref())
+                .map(|_| ()),
+            Self::DeleteObject { bucket, key } => project.delete_object(bucket, key).map(|_| ()),
+            Self::SetMetadata {
+                bucket,
+                key,
+                metadata,
+            } => project.update_object_metadata(bucket, key, metadata, None),
+        }
+    }
+}
+
+/// Records a list of operations and runs them, see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct BatchContext {
+    /// The recorded operations in the order that they were recorded.
+    operations: Vec<Operation>,
+}
+
+impl BatchContext {
+    /// Creates a batch without operations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the creation of `bucket` if it doesn't exist.
+    pub fn ensure_bucket(&mut self, bucket: &str) -> &mut Self {
+        self.push(Operation::EnsureBucket {
+            bucket: String::from(bucket),
+        })
+    }
+
+    /// Records the upload of `data` as the object inside of `bucket` and referenced by `key`
+    /// with optional options.
+    pub fn put_object(
+        &mut self,
+        bucket: &str,
+        key: &str,
+        data: Vec<u8>,
+        opts: Option<options::Upload>,
+    ) -> &mut Self {
+        self.push(Operation::PutObject {
+            bucket: String::from(bucket),
+            key: String::from(key),
+            data,
+            opts,
+        })
+    }
+
+    /// Records the deletion of the object inside of `bucket` and referenced by `key`.
+    pub fn delete_object(&mut self, bucket: &str, key: &str) -> &mut Self {
+        self.push(Operation::DeleteObject {
+            bucket: String::from(bucket),
+            key: String::from(key),
+        })
+    }
+
+    /// Records the replacement of the custom metadata of the object inside of `bucket` and
+    /// referenced by `key` with `metadata`.
+    pub fn set_metadata(
+        &mut self,
+        bucket: &str,
+        key: &str,
+        metadata: metadata::Custom,
+    ) -> &mut Self {
+        self.push(Operation::SetMetadata {
+            bucket: String::from(bucket),
+            key: String::from(key),
+            metadata,
+        })
+    }
+
+    /// Records `op`.
+    pub fn push(&mut self, op: Operation) -> &mut Self {
+        self.operations.push(op);
+        self
+    }
+
+    /// Returns the recorded operations in the order that they were recorded, which is the order
+    /// of the results of the [`BatchReport`].
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Runs the recorded operations against `project` with up to `parallelism` operations at the
+    /// same time, running one at a time when it's zero, and returns the result of each of them.
+    ///
+    /// The [`Operation::EnsureBucket`] operations run before the rest, so the buckets exist when
+    /// the objects are uploaded to them. Apart from that, the operations run in any order, so the
+    /// operations on the same object must be in different batches.
+    pub fn run(&self, project: &Project, parallelism: usize) -> BatchReport {
+        self.execute(parallelism, |op| op.apply(project))
+    }
+
+    /// Runs the recorded operations with `apply` as [`Self::run`] does.
+    fn execute(
+        &self,
+        parallelism: usize,
+        apply: impl Fn(&Operation) -> Result<()> + Sync,
+    ) -> BatchReport {
+        let mut results: Vec<Option<Result<()>>> = Vec::new();
+        results.resize_with(self.operations.len(), || None);
+
+        let (buckets, objects): (Vec<usize>, Vec<usize>) = (0..self.operations.len())
+            .partition(|&i| matches!(self.operations[i], Operation::EnsureBucket { .. }));
+        for phase in [buckets, objects] {
+            let next = AtomicUsize::new(0);
+            let workers = parallelism.clamp(1, phase.len().max(1));
+            let phase_results: Vec<(usize, Result<()>)> = thread::scope(|scope| {
+                let handles: Vec<_> = (0..workers)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut done = Vec::new();
+                            while let Some(&i) = phase.get(next.fetch_add(1, Ordering::Relaxed)) {
+                                done.push((i, apply(&self.operations[i])));
+                            }
+                            done
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| match handle.join() {
+                        Ok(done) => done,
+                        Err(payload) => std::panic::resume_unwind(payload),
+                    })
+                    .collect()
+            });
+
+            for (i, res) in phase_results {
+                results[i] = Some(res);
+            }
+        }
+
+        BatchReport {
+            results: results
+                .into_iter()
+                .map(|res| res.expect("BUG: every operation of the batch runs"))
+                .collect(),
+        }
+    }
+}
+
+/// The results of the operations of a batch, see [`BatchContext::run`].
+#[derive(Debug)]
+pub struct BatchReport {
+    /// The result of each operation in the order of [`BatchContext::operations`].
+    pub results: Vec<Result<()>>,
+}
+
+impl BatchReport {
+    /// Returns `true` if all the operations succeeded.
+    pub fn all_succeeded(&self) -> bool {
+        self.results.iter().all(Result::is_ok)
+    }
+
+    /// Returns the index in [`BatchContext::operations`] and the error of the operations that
+    /// failed.
+    pub fn errors(&self) -> impl std::iter::Iterator<Item = (usize, &Error)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, res)| res.as_ref().err().map(|err| (i, err)))
+    }
+
+    /// Returns `Ok` if all the operations succeeded, otherwise the index and the error of the
+    /// first operation that failed, which is convenient for propagating the failure of a batch
+    /// that must fully succeed with `?`.
+    pub fn into_result(self) -> std::result::Result<(), (usize, Error)> {
+        match self
+            .results
+            .into_iter()
+            .enumerate()
+            .find_map(|(i, res)| res.err().map(|err| (i, err)))
+        {
+            Some(failed) => Err(failed),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bucket, error};
+
+    use std::sync::Mutex;
+
+    fn batch() -> BatchContext {
+        let mut batch = BatchContext::new();
+        batch
+            .put_object("bucket-a", "manifest.json", b"{}".to_vec(), None)
+            .ensure_bucket("bucket-a")
+            .ensure_bucket("Invalid_Bucket")
+            .ensure_bucket("bucket-b")
+            .put_object("bucket-b", "manifest.json", b"{}".to_vec(), None)
+            .delete_object("bucket-a", "old.json")
+            .set_metadata("bucket-b", "old.json", metadata::Custom::default());
+        batch
+    }
+
+    /// Runs `batch` with `parallelism`, failing the bucket operations with an invalid name, and
+    /// returns the report and the indexes of the applied operations in the order they were applied.
+    fn run(batch: &BatchContext, parallelism: usize) -> (BatchReport, Vec<usize>) {
+        let applied = Mutex::new(Vec::new());
+        let report = batch.execute(parallelism, |op| {
+            if let Operation::EnsureBucket { bucket } = op {
+                bucket::validate_name(bucket)?;
+            }
+
+            let i = batch
+                .operations()
+                .iter()
+                .position(|o| std::ptr::eq(o, op))
+                .expect("operation of the batch");
+            applied.lock().expect("applied lock").push(i);
+            Ok(())
+        });
+
+        (report, applied.into_inner().expect("applied lock"))
+    }
+
+    #[test]
+    fn test_batch_execute() {
+        for parallelism in [0, 1, 3, 20] {
+            let batch = batch();
+            let (report, applied) = run(&batch, parallelism);
+
+            assert_eq!(
+                report.results.len(),
+                batch.operations().len(),
+                "{}: results",
+                parallelism
+            );
+            assert!(!report.all_succeeded(), "{}: failed", parallelism);
+            let errors: Vec<_> = report.errors().collect();
+            assert_eq!(errors.len(), 1, "{}: errors", parallelism);
+            assert_eq!(errors[0].0, 2, "{}: failed operation", parallelism);
+            assert!(
+                matches!(errors[0].1, Error::InvalidArguments(error::Args { names, .. }) if names == "bucket"),
+                "{}: invalid bucket name error: {:?}",
+                parallelism,
+                errors[0].1
+            );
+
+            let mut sorted = applied.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, [0, 1, 3, 4, 5, 6], "{}: applied", parallelism);
+            let last_bucket = applied.iter().rposition(|&i| i == 1 || i == 3);
+            let first_object = applied.iter().position(|&i| i != 1 && i != 3);
+            assert!(
+                last_bucket < first_object,
+                "{}: buckets before objects: {:?}",
+                parallelism,
+                applied
+            );
+
+            match report.into_result() {
+                Err((2, Error::InvalidArguments(_))) => {}
+                res => panic!("{}: unexpected result: {:?}", parallelism, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_execute_success() {
+        let (report, applied) = run(&BatchContext::new(), 4);
+        assert!(report.results.is_empty(), "empty batch");
+        assert!(applied.is_empty(), "empty batch");
+        report.into_result().expect("empty batch");
+
+        let mut batch = BatchContext::new();
+        batch.ensure_bucket("bucket-a").ensure_bucket("bucket-b");
+        let (report, _) = run(&batch, 4);
+        assert!(report.all_succeeded(), "all succeeded");
+        assert_eq!(report.errors().count(), 0, "no errors");
+        report.into_result().expect("all succeeded");
+    }
+}
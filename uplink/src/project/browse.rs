@@ -0,0 +1,407 @@
+//! Browsing the objects of a bucket as a hierarchy of folders and files.
+//!
+//! The keys of the objects are flat, but the non-recursive listings collapse the keys that have a
+//! `/` after the listed prefix into a prefix, which is presented as a folder. [`Browser`] lists a
+//! folder when it's opened and caches its entries until it's refreshed, so going back to an
+//! already opened folder doesn't list it again.
+//!
+//! ```no_run
+//! use uplink::access::Grant;
+//! use uplink::project::browse::{self, Browser, Entry};
+//! use uplink::Project;
+//!
+//! let grant = Grant::new("my-serialized-access-grant")?;
+//! let project = Project::open(&grant);
+//! let mut browser = Browser::new(&project, "my-bucket");
+//!
+//! for entry in browser.open("photos/2024")? {
+//!     match entry {
+//!         Entry::Folder(prefix) => println!("{}/ -> {}", entry.name(), prefix),
+//!         Entry::File(obj) => println!("{} {}", entry.name(), obj.metadata_system),
+//!     }
+//! }
+//!
+//! for (name, prefix) in browse::breadcrumbs("photos/2024/") {
+//!     println!("{} -> {}", name, prefix);
+//! }
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::api::ObjectStore;
+use crate::{Object, Project, Result};
+
+use std::collections::{hash_map, HashMap};
+
+/// An entry of a folder.
+#[derive(Debug)]
+pub enum Entry {
+    /// A folder, which contains the objects whose key starts with its prefix. The prefix is the
+    /// full path of the folder and ends with `/` (e.g. `photos/2024/`).
+    Folder(String),
+    /// A file, which is the listed object. It has the metadata that the store returns when
+    /// listing; [`Project`] returns the system and custom metadata.
+    File(Object),
+}
+
+impl Entry {
+    /// Returns the full path of the entry, which is the prefix of a folder or the key of a file.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Folder(prefix) => prefix,
+            Self::File(obj) => &obj.key,
+        }
+    }
+
+    /// Returns the name of the entry inside of its parent folder, without the trailing `/` of the
+    /// folders (e.g. `2024` for the `photos/2024/` folder and `a.jpg` for the `photos/a.jpg`
+    /// file).
+    pub fn name(&self) -> &str {
+        let path = self.path();
+        let path = match self {
+            Self::Folder(_) => path.strip_suffix('/').unwrap_or(path),
+            Self::File(_) => path,
+        };
+
+        path.rfind('/').map_or(path, |i| &path[i + 1..])
+    }
+
+    /// Returns true if the entry is a folder.
+    pub fn is_folder(&self) -> bool {
+        matches!(self, Self::Folder(_))
+    }
+}
+
+/// Browses the objects of a bucket as a hierarchy of folders and files, caching the entries of
+/// each opened folder.
+///
+/// It lists the folders through an [`ObjectStore`], which is a [`Project`] by default.
+pub struct Browser<'a, S: ObjectStore + ?Sized = Project> {
+    /// The store where the bucket is.
+    store: &'a S,
+    /// The browsed bucket.
+    bucket: String,
+    /// The entries of the opened folders indexed by their prefix, which is empty for the root.
+    cache: HashMap<String, Vec<Entry>>,
+}
+
+impl<'a, S: ObjectStore + ?Sized> Browser<'a, S> {
+    /// Creates a browser of `bucket` without any cached folder.
+    pub fn new(store: &'a S, bucket: &str) -> Self {
+        Self {
+            store,
+            bucket: String::from(bucket),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Returns the browsed bucket.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Returns the entries of the root folder of the bucket, see [`Self::open`].
+    pub fn root(&mut self) -> Result<&[Entry]> {
+        self.open("")
+    }
+
+    /// Returns the entries of the folder with `prefix`, listing it if it isn't cached.
+    ///
+    /// The `/` that separates the folder from its entries is optional, so `photos/2024` and
+    /// `photos/2024/` open the same folder, and an empty `prefix` opens the root. The folders
+    /// are returned before the files and both are sorted by path.
+    ///
+    /// It returns the same errors than [`ObjectStore::list`].
+    pub fn open(&mut self, prefix: &str) -> Result<&[Entry]> {
+        let entries = match self.cache.entry(folder_prefix(prefix)) {
+            hash_map::Entry::Occupied(cached) => cached.into_mut(),
+            hash_map::Entry::Vacant(vacant) => {
+                let entries = list_folder(self.store, &self.bucket, vacant.key())?;
+                vacant.insert(entries)
+            }
+        };
+
+        Ok(entries)
+    }
+
+    /// Discards the cached entries of the folder with `prefix`, which follows the same rules
+    /// than in [`Self::open`], so the next time it's opened it's listed again.
+    ///
+    /// The cached entries of its subfolders are kept.
+    pub fn refresh(&mut self, prefix: &str) {
+        self.cache.remove(&folder_prefix(prefix));
+    }
+
+    /// Discards the cached entries of all the folders.
+    pub fn refresh_all(&mut self) {
+        self.cache.clear();
+    }
+}
+
+impl<S: ObjectStore + ?Sized> std::fmt::Debug for Browser<'_, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Browser")
+            .field("bucket", &self.bucket)
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the name and the prefix of each folder from the root to the folder with `prefix`,
+/// excluding the root (e.g. `[("photos", "photos/"), ("2024", "photos/2024/")]` for
+/// `photos/2024/`).
+///
+/// The trailing `/` of `prefix` is optional as in [`Browser::open`].
+pub fn breadcrumbs(prefix: &str) -> Vec<(String, String)> {
+    let prefix = folder_prefix(prefix);
+    let mut crumbs = Vec::new();
+    let mut start = 0;
+    for (i, _) in prefix.match_indices('/') {
+        crumbs.push((String::from(&prefix[start..i]), String::from(&prefix[..=i])));
+        start = i + 1;
+    }
+
+    crumbs
+}
+
+/// Returns the prefix of the parent folder of the folder with `prefix`, which is empty for the
+/// folders in the root, or `None` if `prefix` is the root.
+///
+/// The trailing `/` of `prefix` is optional as in [`Browser::open`].
+pub fn parent(prefix: &str) -> Option<&str> {
+    if prefix.is_empty() {
+        return None;
+    }
+
+    let path = prefix.strip_suffix('/').unwrap_or(prefix);
+    Some(path.rfind('/').map_or("", |i| &path[..=i]))
+}
+
+/// Lists the entries of the folder with `prefix`, which is empty or ends with `/`, in `bucket`.
+fn list_folder<S: ObjectStore + ?Sized>(
+    store: &S,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = store
+        .list(bucket, prefix, false)?
+        .into_iter()
+        .map(|obj| {
+            if obj.is_prefix {
+                Entry::Folder(obj.key)
+            } else {
+                Entry::File(obj)
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        b.is_folder()
+            .cmp(&a.is_folder())
+            .then_with(|| a.path().cmp(b.path()))
+    });
+    Ok(entries)
+}
+
+/// Returns the prefix of the folder with `prefix` ending with `/`, unless it's the root.
+fn folder_prefix(prefix: &str) -> String {
+    let mut prefix = String::from(prefix);
+    if !prefix.is_empty() && !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+
+    prefix
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry_name() {
+        for (path, want) in [
+            ("photos/", "photos"),
+            ("photos/2024/", "2024"),
+            ("photos//", ""),
+        ] {
+            assert_eq!(Entry::Folder(String::from(path)).name(), want, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_breadcrumbs() {
+        assert!(breadcrumbs("").is_empty(), "root");
+
+        let want = vec![
+            (String::from("photos"), String::from("photos/")),
+            (String::from("2024"), String::from("photos/2024/")),
+        ];
+        assert_eq!(breadcrumbs("photos/2024/"), want, "with trailing slash");
+        assert_eq!(breadcrumbs("photos/2024"), want, "without trailing slash");
+    }
+
+    #[test]
+    fn test_parent() {
+        for (prefix, want) in [
+            ("", None),
+            ("photos/", Some("")),
+            ("photos", Some("")),
+            ("photos/2024/", Some("photos/")),
+            ("photos/2024/jan", Some("photos/2024/")),
+        ] {
+            assert_eq!(parent(prefix), want, "{:?}", prefix);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    mod browser {
+        use super::super::*;
+        use crate::api::MemoryStore;
+        use crate::error::UplinkCode;
+        use crate::Error;
+
+        /// Returns the paths of the entries and if they are folders.
+        fn paths(entries: &[Entry]) -> Vec<(&str, bool)> {
+            entries.iter().map(|e| (e.path(), e.is_folder())).collect()
+        }
+
+        /// Returns a store with a bucket with a nested hierarchy of objects.
+        fn fixture() -> MemoryStore {
+            let store = MemoryStore::new();
+            store.make_bucket("bucket").expect("make bucket");
+            for key in [
+                "z.txt",
+                "a.txt",
+                "docs/readme.md",
+                "docs/guides/intro.md",
+                "docs/guides/advanced/tuning.md",
+                "photos/2024/jan/1.jpg",
+                "photos/2024/jan/2.jpg",
+                "photos/2024/feb.jpg",
+            ] {
+                store
+                    .put("bucket", key, key.as_bytes())
+                    .expect("put object");
+            }
+
+            store
+        }
+
+        #[test]
+        fn test_browser_open() {
+            let store = fixture();
+            let mut browser = Browser::new(&store, "bucket");
+            assert_eq!(browser.bucket(), "bucket", "bucket");
+
+            assert_eq!(
+                paths(browser.root().expect("root")),
+                vec![
+                    ("docs/", true),
+                    ("photos/", true),
+                    ("a.txt", false),
+                    ("z.txt", false)
+                ],
+                "root entries"
+            );
+            assert_eq!(
+                paths(browser.open("docs").expect("open docs")),
+                vec![("docs/guides/", true), ("docs/readme.md", false)],
+                "docs entries"
+            );
+            assert_eq!(
+                paths(browser.open("photos/2024/").expect("open photos/2024/")),
+                vec![("photos/2024/jan/", true), ("photos/2024/feb.jpg", false)],
+                "photos/2024 entries"
+            );
+            assert!(
+                browser.open("empty/").expect("open empty").is_empty(),
+                "folder without objects"
+            );
+
+            let entries = browser
+                .open("photos/2024/jan")
+                .expect("open photos/2024/jan");
+            assert_eq!(entries[0].name(), "1.jpg", "file name");
+            match &entries[1] {
+                Entry::File(obj) => {
+                    assert_eq!(obj.key, "photos/2024/jan/2.jpg", "file key");
+                    assert_eq!(
+                        obj.metadata_system.content_length,
+                        Some("photos/2024/jan/2.jpg".len() as u64),
+                        "file metadata"
+                    );
+                }
+                entry => panic!("unexpected entry: {:?}", entry),
+            }
+        }
+
+        #[test]
+        fn test_browser_refresh() {
+            let store = fixture();
+            let mut browser = Browser::new(&store, "bucket");
+            browser.root().expect("root");
+            browser.open("docs/").expect("open docs");
+
+            store
+                .put("bucket", "docs/new.md", b"new")
+                .expect("put object in docs");
+            store
+                .put("bucket", "music/song.mp3", b"song")
+                .expect("put object in a new folder");
+            assert_eq!(
+                browser.open("docs/").expect("open cached docs").len(),
+                2,
+                "cached docs entries"
+            );
+            assert_eq!(
+                browser.root().expect("cached root").len(),
+                4,
+                "cached root entries"
+            );
+
+            browser.refresh("docs");
+            assert_eq!(
+                paths(browser.open("docs/").expect("open refreshed docs")),
+                vec![
+                    ("docs/guides/", true),
+                    ("docs/new.md", false),
+                    ("docs/readme.md", false)
+                ],
+                "refreshed docs entries"
+            );
+            assert_eq!(
+                browser.root().expect("root not refreshed").len(),
+                4,
+                "root entries not refreshed"
+            );
+
+            browser.refresh_all();
+            assert_eq!(
+                paths(browser.root().expect("refreshed root")),
+                vec![
+                    ("docs/", true),
+                    ("music/", true),
+                    ("photos/", true),
+                    ("a.txt", false),
+                    ("z.txt", false)
+                ],
+                "refreshed root entries"
+            );
+        }
+
+        #[test]
+        fn test_browser_open_error() {
+            let store = fixture();
+            let mut browser = Browser::new(&store, "missing");
+            match browser.root() {
+                Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+
+            store.make_bucket("missing").expect("make bucket");
+            assert!(
+                browser.root().expect("root after the error").is_empty(),
+                "errors aren't cached"
+            );
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Default options of the uploads to the buckets of a project.
+//!
+//! [`Project::set_bucket_defaults`](crate::Project::set_bucket_defaults) registers the
+//! [`UploadDefaults`] of a bucket, which the project applies to all the objects uploaded to it,
+//! so an application doesn't have to pass them on each upload.
+
+use super::options;
+use crate::{helpers, metadata, Result};
+
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+
+/// The default options of the uploads to a bucket.
+///
+/// The options explicitly passed to an upload take precedence field by field: the explicit
+/// expiration time replaces the default one and the explicit custom metadata entries replace the
+/// default entries with the same key.
+#[derive(Clone, Debug, Default)]
+pub struct UploadDefaults {
+    /// The time to live of the uploaded objects, which is counted from when the upload starts.
+    /// `None` means that the objects don't expire unless the upload options set it.
+    pub expires: Option<Duration>,
+    /// The custom metadata entries of the uploaded objects.
+    pub metadata: metadata::Custom,
+}
+
+impl UploadDefaults {
+    /// Returns a copy of `opts` with the defaults applied to an upload that starts at `now`, which
+    /// is the time since the Unix Epoch.
+    fn apply(&self, opts: Option<&options::Upload>, now: Duration) -> options::Upload {
+        let mut opts = opts.cloned().unwrap_or_default();
+        if opts.expires.is_none() {
+            opts.expires = self.expires.map(|ttl| now + ttl);
+        }
+
+        match &mut opts.custom_metadata {
+            Some(custom) => self.fill_custom_metadata(custom),
+            None if self.metadata.count() > 0 => opts.custom_metadata = Some(self.metadata.clone()),
+            None => {}
+        }
+
+        opts
+    }
+
+    /// Inserts the default custom metadata entries whose keys aren't in `custom`.
+    fn fill_custom_metadata(&self, custom: &mut metadata::Custom) {
+        for (key, value) in self.metadata.iter() {
+            if custom.get(key).is_none() {
+                custom.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// The registry of the [`UploadDefaults`] of the buckets of a project.
+///
+/// It's shared by all the operations of the project, so it's guarded by a lock.
+#[derive(Debug, Default)]
+pub struct Defaults {
+    /// The defaults indexed by bucket name.
+    buckets: RwLock<HashMap<String, UploadDefaults>>,
+}
+
+impl Defaults {
+    /// Sets the defaults of the uploads to `bucket` and returns the previous ones, if any.
+    pub fn set(&self, bucket: &str, defaults: UploadDefaults) -> Option<UploadDefaults> {
+        self.buckets
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(String::from(bucket), defaults)
+    }
+
+    /// Removes the defaults of the uploads to `bucket` and returns them, if any.
+    pub fn remove(&self, bucket: &str) -> Option<UploadDefaults> {
+        self.buckets
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(bucket)
+    }
+
+    /// Returns a copy of the defaults of the uploads to `bucket`, if any.
+    pub fn get(&self, bucket: &str) -> Option<UploadDefaults> {
+        self.buckets
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(bucket)
+            .cloned()
+    }
+
+    /// Returns `opts` with the defaults of `bucket` applied to an upload that starts now or
+    /// `None` if `bucket` doesn't have defaults, so `opts` is used as it is.
+    ///
+    /// It returns the same errors than [`helpers::unix_time_now`].
+    pub(crate) fn upload_options(
+        &self,
+        bucket: &str,
+        opts: Option<&options::Upload>,
+    ) -> Result<Option<options::Upload>> {
+        let defaults = match self.get(bucket) {
+            Some(defaults) => defaults,
+            None => return Ok(None),
+        };
+
+        Ok(Some(defaults.apply(opts, helpers::unix_time_now()?)))
+    }
+
+    /// Returns `opts` with the default custom metadata entries of `bucket` or `None` if `bucket`
+    /// doesn't have default custom metadata, so `opts` is used as it is.
+    pub(crate) fn commit_upload_options(
+        &self,
+        bucket: &str,
+        opts: Option<&options::CommitUpload>,
+    ) -> Option<options::CommitUpload> {
+        let defaults = match self.get(bucket) {
+            Some(defaults) if defaults.metadata.count() > 0 => defaults,
+            _ => return None,
+        };
+
+        let mut custom = opts
+            .map(|o| o.custom_metadata().clone())
+            .unwrap_or_default();
+        defaults.fill_custom_metadata(&mut custom);
+        let mut commit = options::CommitUpload::new(custom);
+        commit.set_timeout(opts.and_then(|o| o.timeout()));
+        Some(commit)
+    }
+
+    /// Inserts the default custom metadata entries of `bucket` whose keys aren't in `custom`.
+    #[cfg(any(test, feature = "compression"))]
+    pub(crate) fn fill_custom_metadata(&self, bucket: &str, custom: &mut metadata::Custom) {
+        if let Some(defaults) = self.get(bucket) {
+            defaults.fill_custom_metadata(custom);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn custom_metadata(entries: &[(&str, &str)]) -> metadata::Custom {
+        let mut custom = metadata::Custom::default();
+        for (key, value) in entries {
+            custom.insert(String::from(*key), String::from(*value));
+        }
+        custom
+    }
+
+    fn defaults() -> UploadDefaults {
+        UploadDefaults {
+            expires: Some(Duration::from_secs(60)),
+            metadata: custom_metadata(&[("app-version", "1.2.3"), ("tenant", "acme")]),
+        }
+    }
+
+    #[test]
+    fn test_upload_defaults_apply() {
+        let now = Duration::from_secs(1_000);
+
+        let opts = defaults().apply(None, now);
+        assert_eq!(
+            opts.expires,
+            Some(Duration::from_secs(1_060)),
+            "default TTL"
+        );
+        let custom = opts.custom_metadata.expect("default custom metadata");
+        assert_eq!(custom.count(), 2, "default entries");
+        assert_eq!(
+            custom.get("tenant").map(String::as_str),
+            Some("acme"),
+            "default entry"
+        );
+
+        let explicit = options::Upload {
+            expires: Some(Duration::from_secs(5_000)),
+            custom_metadata: Some(custom_metadata(&[("tenant", "other"), ("color", "blue")])),
+        };
+        let opts = defaults().apply(Some(&explicit), now);
+        assert_eq!(
+            opts.expires,
+            Some(Duration::from_secs(5_000)),
+            "explicit expiration"
+        );
+        let custom = opts.custom_metadata.expect("merged custom metadata");
+        assert_eq!(custom.count(), 3, "merged entries");
+        assert_eq!(
+            custom.get("tenant").map(String::as_str),
+            Some("other"),
+            "explicit entry"
+        );
+        assert_eq!(
+            custom.get("app-version").map(String::as_str),
+            Some("1.2.3"),
+            "default entry"
+        );
+        assert_eq!(
+            custom.get("color").map(String::as_str),
+            Some("blue"),
+            "explicit only entry"
+        );
+
+        let opts = UploadDefaults::default().apply(None, now);
+        assert_eq!(opts.expires, None, "no default TTL");
+        assert!(opts.custom_metadata.is_none(), "no default custom metadata");
+    }
+
+    #[test]
+    fn test_defaults_registry() {
+        let registry = Defaults::default();
+        registry.set("my-bucket", defaults());
+
+        let explicit = options::Upload {
+            expires: Some(Duration::from_secs(5_000)),
+            custom_metadata: None,
+        };
+        let opts = registry
+            .upload_options("other-bucket", Some(&explicit))
+            .expect("upload options");
+        assert!(opts.is_none(), "bucket without defaults");
+        let opts = registry.commit_upload_options("other-bucket", None);
+        assert!(opts.is_none(), "bucket without defaults");
+        let mut other = custom_metadata(&[("color", "blue")]);
+        registry.fill_custom_metadata("other-bucket", &mut other);
+        assert_eq!(other.count(), 1, "bucket without defaults");
+
+        let opts = registry
+            .upload_options("my-bucket", Some(&explicit))
+            .expect("upload options")
+            .expect("bucket with defaults");
+        assert_eq!(opts.expires, explicit.expires, "explicit expiration");
+        assert_eq!(
+            opts.custom_metadata.map(|c| c.count()),
+            Some(2),
+            "default entries"
+        );
+
+        let commit = options::CommitUpload::new(custom_metadata(&[("tenant", "other")]));
+        let opts = registry
+            .commit_upload_options("my-bucket", Some(&commit))
+            .expect("bucket with defaults");
+        assert_eq!(opts.custom_metadata().count(), 2, "merged entries");
+        assert_eq!(
+            opts.custom_metadata().get("tenant").map(String::as_str),
+            Some("other"),
+            "explicit entry"
+        );
+
+        assert!(registry.remove("my-bucket").is_some(), "removed defaults");
+        assert!(registry.get("my-bucket").is_none(), "no defaults");
+        let opts = registry
+            .upload_options("my-bucket", None)
+            .expect("upload options");
+        assert!(opts.is_none(), "removed defaults");
+    }
+}
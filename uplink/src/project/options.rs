@@ -1,6 +1,7 @@
 //! All the Storj DCS options types related to a Project.
 
-use crate::{helpers, metadata::Custom, Error, Result};
+use crate::metadata::{Custom, UplinkCustomMetadataWrapper};
+use crate::{helpers, Error, Result};
 
 use std::ffi::CString;
 use std::time::Duration;
@@ -8,45 +9,105 @@ use std::time::Duration;
 use uplink_sys as ulksys;
 
 /// Options for committing a multipart upload.
-pub struct CommitUpload<'a> {
+///
+/// The options own their custom metadata, so they can be created ahead and reused for committing
+/// several multipart uploads.
+#[derive(Clone, Debug, Default)]
+pub struct CommitUpload {
     /// Custom metadata to assign to a multipart upload.
-    custom_metadata: &'a mut Custom,
+    custom_metadata: Custom,
+    /// The timeout of the commit, see [`Self::set_timeout`].
+    timeout: Option<Duration>,
 }
 
-impl<'a> CommitUpload<'a> {
+impl CommitUpload {
     /// Creates an instance of commit upload options.
     ///
-    /// It's mutable because converting to a Uplink-C representation requires it.
-    pub fn new(custom_metadata: &'a mut Custom) -> Self {
-        Self { custom_metadata }
+    /// `custom_metadata` is validated against the limits of the project when committing the
+    /// upload, see [`crate::Project::commit_upload`].
+    pub fn new(custom_metadata: Custom) -> Self {
+        Self {
+            custom_metadata,
+            timeout: None,
+        }
     }
 
-    /// Returns the FFI representation of the options.
-    ///
-    /// It takes a mutable reference because [`metadata::Custom.to_ffi_c`] requires a mutable
-    /// reference.
-    #[allow(clippy::wrong_self_convention)]
-    pub(crate) fn to_ffi_commit_upload_options(&mut self) -> ulksys::UplinkCommitUploadOptions {
+    /// Returns the custom metadata to assign to the multipart upload.
+    pub fn custom_metadata(&self) -> &Custom {
+        &self.custom_metadata
+    }
+
+    /// Returns the timeout of the commit, see [`Self::set_timeout`].
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Sets the timeout of the commit, which overrides the project's operation timeout when it
+    /// isn't `None`, see [`Config::set_operation_timeout`](crate::Config::set_operation_timeout)
+    /// about how the timeouts apply and their cost.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the FFI representation of the options with the FFI representation of their custom
+    /// metadata, see [`Self::custom_metadata`], which must be kept alive meanwhile the returned
+    /// value is used.
+    pub(crate) fn as_ffi_commit_upload_options(
+        uc_custom: &UplinkCustomMetadataWrapper,
+    ) -> ulksys::UplinkCommitUploadOptions {
         ulksys::UplinkCommitUploadOptions {
-            custom_metadata: self.custom_metadata.to_ffi_custom_metadata(),
+            custom_metadata: uc_custom.as_ffi_custom_metadata(),
         }
     }
 }
 
 /// Options for copying objects to a different bucket or/and key without downloading and uploading
 /// it.
-#[derive(Default)]
-pub struct CopyObject {}
+#[derive(Clone, Debug, Default)]
+pub struct CopyObject {
+    /// Custom metadata that replaces the custom metadata of the copied object. When it's `None`,
+    /// the copied object retains the custom metadata of the original object.
+    ///
+    /// The pinned uplink-c version doesn't support it, so it's applied by updating the metadata of
+    /// the copied object just after copying it. See [`crate::Project::copy_object`].
+    pub custom_metadata: Option<Custom>,
+}
 
 impl CopyObject {
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_copy_object_options(&self) -> ulksys::UplinkCopyObjectOptions {
-        ulksys::UplinkCopyObjectOptions {}
+        // SAFETY: the options don't have any field, but the `_address` byte that the stub bindings
+        // add to the empty structs, so the all-zero value is valid for both bindings.
+        unsafe { std::mem::zeroed::<ulksys::UplinkCopyObjectOptions>() }
+    }
+}
+
+/// Options for copying a downloaded object's data into a Tokio asynchronous writer.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug)]
+pub struct CopyToTokio {
+    /// The maximum size, in bytes, of each chunk of data read from the download.
+    pub chunk_size: usize,
+    /// The maximum number of chunks read from the download which are waiting to be written to the
+    /// writer.
+    pub channel_depth: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl Default for CopyToTokio {
+    fn default() -> Self {
+        Self {
+            chunk_size: 64 * 1024,
+            channel_depth: 4,
+        }
     }
 }
 
 /// Options for downloading an object.
-#[derive(Default)]
+///
+/// [`Download::default()`] has a zero `length`; use [`Download::new`] for setting the range to
+/// download.
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Download {
     /// The initial point of the object's blob to download.
     /// If it's negative, it will start at the suffix of the blob but it's isn't supported to be
@@ -55,9 +116,30 @@ pub struct Download {
     /// The length of the blob starting from `offset` to download.
     /// If it's negative, it will read until the end of the blob.
     pub length: i64,
+    /// Don't check that the object exists before returning the download, so
+    /// [`Project::download_object`](crate::Project::download_object) doesn't retrieve the object's
+    /// information and a missing object may only be reported by the first read.
+    pub lazy_open: bool,
+    /// The timeout of starting the download, which overrides the project's operation timeout when
+    /// it isn't `None`; reading the data isn't bounded. See
+    /// [`Config::set_operation_timeout`](crate::Config::set_operation_timeout) about how the
+    /// timeouts apply.
+    pub timeout: Option<Duration>,
 }
 
 impl Download {
+    /// Creates options for downloading `length` bytes of the object's blob starting at `offset`.
+    ///
+    /// See [`Self::offset`] and [`Self::length`] about the negative values.
+    pub fn new(offset: i64, length: i64) -> Self {
+        Self {
+            offset,
+            length,
+            lazy_open: false,
+            timeout: None,
+        }
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_download_options(&self) -> ulksys::UplinkDownloadOptions {
         ulksys::UplinkDownloadOptions {
@@ -67,8 +149,58 @@ impl Download {
     }
 }
 
+/// Options for downloading an object into a file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DownloadFile {
+    /// Overwrite the file when it already exists. When it's `false`, downloading into an existing
+    /// file returns an error without downloading.
+    pub overwrite: bool,
+    /// Create the missing parent directories of the file.
+    pub create_dirs: bool,
+}
+
+/// Options for getting an object's data into memory.
+#[derive(Clone, Copy, Debug)]
+pub struct GetObject {
+    /// The maximum size, in bytes, of the object's data to get. Getting an object bigger than it
+    /// returns an error.
+    ///
+    /// It protects from running out of memory by getting, accidentally, a huge object.
+    pub max_size: usize,
+}
+
+impl GetObject {
+    /// The default maximum size of the object's data to get; it's 64 MiB.
+    pub const DEFAULT_MAX_SIZE: usize = 64 * 1024 * 1024;
+}
+
+impl Default for GetObject {
+    fn default() -> Self {
+        Self {
+            max_size: Self::DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
 /// Options for listing buckets.
-#[derive(Debug, Default)]
+///
+/// [`ListBuckets::default()`] lists all the buckets from the first one, which is the same than
+/// not passing any options, and [`ListBuckets::with_cursor`] lists the buckets after the specified
+/// one.
+///
+/// For resuming a listing, pass the value returned by
+/// [`bucket::Iterator::last_seen`](crate::bucket::Iterator::last_seen) as a cursor:
+///
+/// ```ignore
+/// let mut it = project.list_buckets(Some(&ListBuckets::default()))?;
+/// // Consume some buckets.
+/// it.next();
+///
+/// if let Some(cursor) = it.last_seen() {
+///     let it = project.list_buckets(Some(&ListBuckets::with_cursor(cursor)?))?;
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
 pub struct ListBuckets {
     /// C representation of `cursor` for providing it to the FFI and guards its lifetime until
     /// `self` gets dropped.
@@ -77,12 +209,22 @@ pub struct ListBuckets {
 
 impl ListBuckets {
     /// Creates options for listing buckets with the specified cursor value.
+    ///
+    /// The cursor is the name of a bucket and the listing starts with the bucket after it.
+    ///
     /// It returns an error if `cursor` contains any null byte (0 byte).
     pub fn with_cursor(cursor: &str) -> Result<Self> {
         let inner_cursor = helpers::cstring_from_str_fn_arg("cursor", cursor)?;
         Ok(Self { inner_cursor })
     }
 
+    /// Returns the cursor, which is empty when the listing starts from the first bucket.
+    pub fn cursor(&self) -> &str {
+        self.inner_cursor
+            .to_str()
+            .expect("BUG: the cursor is always created from a valid UTF-8 string")
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_list_buckets_options(&self) -> ulksys::UplinkListBucketsOptions {
         ulksys::UplinkListBucketsOptions {
@@ -92,7 +234,7 @@ impl ListBuckets {
 }
 
 /// Options for listing objects.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct ListObjects {
     /// Only list objects with this key prefix. When not empty, it must ends with slash.
     ///
@@ -113,9 +255,19 @@ pub struct ListObjects {
     pub system: bool,
     /// Include the "custom metadata" associated with the objects.
     pub custom: bool,
+    /// The maximum number of objects that the iterator returns. When `None` it returns all of
+    /// them.
+    ///
+    /// It's enforced by the iterator, which stops after returning this number of items.
+    pub limit: Option<usize>,
 }
 
 impl ListObjects {
+    /// Returns a builder for creating listing objects options combining any of them.
+    pub fn builder() -> ListObjectsBuilder {
+        ListObjectsBuilder::default()
+    }
+
     /// Creates options of listing objects options with the specified prefix.
     ///
     /// `prefix` must:
@@ -184,6 +336,45 @@ impl ListObjects {
         })
     }
 
+    /// Returns the prefix, which is empty when the listing isn't restricted to a prefix.
+    pub fn prefix(&self) -> &str {
+        self.inner_prefix
+            .to_str()
+            .expect("BUG: the prefix is always created from a valid UTF-8 string")
+    }
+
+    /// Returns the cursor, which is empty when the listing starts from the first item.
+    pub fn cursor(&self) -> &str {
+        self.inner_cursor
+            .to_str()
+            .expect("BUG: the cursor is always created from a valid UTF-8 string")
+    }
+
+    /// Returns a copy of `opts`, or of the default options if it's `None`, without limit and whose
+    /// prefix is `prefix` when it's longer than the prefix of `opts` and it starts with it.
+    ///
+    /// It's used for narrowing the listing to the objects that a key filter can match.
+    pub(crate) fn narrowed_to_prefix(opts: Option<&Self>, prefix: &str) -> Result<Self> {
+        let (cur_prefix, cursor) = match opts {
+            Some(o) => (o.prefix(), o.cursor()),
+            None => ("", ""),
+        };
+
+        let prefix = if prefix.len() > cur_prefix.len() && prefix.starts_with(cur_prefix) {
+            prefix
+        } else {
+            cur_prefix
+        };
+
+        let mut narrowed = Self::new(prefix, cursor)?;
+        if let Some(o) = opts {
+            narrowed.recursive = o.recursive;
+            narrowed.system = o.system;
+            narrowed.custom = o.custom;
+        }
+        Ok(narrowed)
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_list_objects_options(&self) -> ulksys::UplinkListObjectsOptions {
         ulksys::UplinkListObjectsOptions {
@@ -196,8 +387,90 @@ impl ListObjects {
     }
 }
 
+/// Builder of [`ListObjects`] which allows to combine any of the options.
+///
+/// The options that aren't set have the same values than [`ListObjects::default`].
+#[derive(Clone, Debug, Default)]
+pub struct ListObjectsBuilder {
+    /// The prefix.
+    prefix: Option<String>,
+    /// The cursor.
+    cursor: Option<String>,
+    /// Iterate the objects without collapsing prefixes.
+    recursive: bool,
+    /// Include the "system metadata" associated with the objects.
+    system: bool,
+    /// Include the "custom metadata" associated with the objects.
+    custom: bool,
+    /// The maximum number of objects to return.
+    limit: Option<usize>,
+}
+
+impl ListObjectsBuilder {
+    /// Sets the prefix, which must end with '/' and not contain any null byte (0 byte).
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(String::from(prefix));
+        self
+    }
+
+    /// Sets the cursor, which must not be empty and not contain any null byte (0 byte).
+    pub fn cursor(mut self, cursor: &str) -> Self {
+        self.cursor = Some(String::from(cursor));
+        self
+    }
+
+    /// Sets if the objects are iterated without collapsing prefixes.
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Sets if the "system metadata" associated with the objects is included.
+    pub fn system(mut self, system: bool) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Sets if the "custom metadata" associated with the objects is included.
+    pub fn custom(mut self, custom: bool) -> Self {
+        self.custom = custom;
+        self
+    }
+
+    /// Sets the maximum number of objects that the iterator returns.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Builds the listing objects options.
+    ///
+    /// It returns the same errors than [`ListObjects::with_prefix_and_cursor`] when the prefix or
+    /// the cursor are set and they are invalid.
+    pub fn build(self) -> Result<ListObjects> {
+        let prefix = self.prefix.unwrap_or_default();
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            return Err(Error::new_invalid_arguments(
+                "prefix",
+                "cannot be empty and must end with '/'",
+            ));
+        }
+
+        if self.cursor.as_deref() == Some("") {
+            return Err(Error::new_invalid_arguments("cursor", "cannot be empty"));
+        }
+
+        let mut opts = ListObjects::new(&prefix, self.cursor.as_deref().unwrap_or_default())?;
+        opts.recursive = self.recursive;
+        opts.system = self.system;
+        opts.custom = self.custom;
+        opts.limit = self.limit;
+        Ok(opts)
+    }
+}
+
 /// Options for listing uncommitted uploads.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct ListUploads {
     /// Only list uncommitted uploads with this key prefix. When not empty, it must ends with slash.
     ///
@@ -289,6 +562,20 @@ impl ListUploads {
         })
     }
 
+    /// Returns the prefix, which is empty when the listing isn't restricted to a prefix.
+    pub fn prefix(&self) -> &str {
+        self.inner_prefix
+            .to_str()
+            .expect("BUG: the prefix is always created from a valid UTF-8 string")
+    }
+
+    /// Returns the cursor, which is empty when the listing starts from the first item.
+    pub fn cursor(&self) -> &str {
+        self.inner_cursor
+            .to_str()
+            .expect("BUG: the cursor is always created from a valid UTF-8 string")
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_list_uploads_options(&self) -> ulksys::UplinkListUploadsOptions {
         ulksys::UplinkListUploadsOptions {
@@ -302,7 +589,7 @@ impl ListUploads {
 }
 
 /// Options for listing uploads parts.
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct ListUploadParts {
     /// Specifies the starting position of the iterator by offsetting from the first object of the
     /// list.
@@ -313,6 +600,11 @@ pub struct ListUploadParts {
 }
 
 impl ListUploadParts {
+    /// Creates options for listing upload parts after the part number `cursor`.
+    pub fn with_cursor(cursor: u32) -> Self {
+        Self { cursor }
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_list_upload_parts_options(&self) -> ulksys::UplinkListUploadPartsOptions {
         ulksys::UplinkListUploadPartsOptions {
@@ -322,27 +614,86 @@ impl ListUploadParts {
 }
 
 /// Options for moving objects to a different bucket or/and key.
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct MoveObject {}
 
 impl MoveObject {
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_move_object_options(&self) -> ulksys::UplinkMoveObjectOptions {
-        ulksys::UplinkMoveObjectOptions {}
+        // SAFETY: see `CopyObject::as_ffi_copy_object_options`.
+        unsafe { std::mem::zeroed::<ulksys::UplinkMoveObjectOptions>() }
     }
 }
 
 /// Options for uploading objects.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Upload {
     /// Determine when the object expires.
     ///
     /// The time is measured with the number of seconds since the Unix Epoch time. 0 is never and
     /// it's the same as `None`.
     pub expires: Option<Duration>,
+    /// Custom metadata to assign to the object.
+    ///
+    /// [`Project::upload_object`](crate::Project::upload_object) sets it when the upload starts,
+    /// so calling [`object::Upload::set_custom_metadata`](crate::object::Upload::set_custom_metadata)
+    /// replaces it, and
+    /// [`Project::upload_object_from_reader`](crate::Project::upload_object_from_reader) sets it
+    /// when committing the upload. [`Project::begin_upload`](crate::Project::begin_upload)
+    /// ignores it because the custom metadata of a multipart upload is set when committing it.
+    pub custom_metadata: Option<Custom>,
 }
 
 impl Upload {
+    /// Sets the MIME type of the object's content in the custom metadata, creating it if it's
+    /// `None`, see [`Custom::set_content_type`].
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `mime` isn't a valid header value.
+    pub fn content_type(&mut self, mime: &str) -> Result<()> {
+        self.custom_metadata
+            .get_or_insert_with(Custom::default)
+            .set_content_type(mime)
+    }
+
+    /// Sets the expiration time from a UTC date time.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `expires` isn't after the Unix Epoch time
+    /// because such times cannot be represented by [`Self::expires`].
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(&mut self, expires: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        match helpers::unix_time_from_datetime(expires) {
+            Some(since_epoch) if !since_epoch.is_zero() => {
+                self.expires = Some(since_epoch);
+                Ok(())
+            }
+            _ => Err(Error::new_invalid_arguments(
+                "expires",
+                "must be after the Unix Epoch time",
+            )),
+        }
+    }
+
+    /// Validates that [`Self::expires`] isn't before `now` minus the `skew` allowance of the
+    /// system clock, measured with the duration since the Unix Epoch time, because the satellite
+    /// rejects the commit of such uploads with an opaque error after all the data is uploaded.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `expires` is before `now - skew`.
+    pub(crate) fn validate_expires(&self, now: Duration, skew: Duration) -> Result<()> {
+        match self.expires {
+            Some(expires) if !expires.is_zero() && expires.saturating_add(skew) < now => {
+                Err(Error::new_invalid_arguments(
+                    "opts.expires",
+                    &format!(
+                        "cannot be in the past; it's {} and now is {}",
+                        helpers::format_unix_time(expires),
+                        helpers::format_unix_time(now)
+                    ),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Returns the FFI representation of the options.
     pub(crate) fn as_ffi_upload_options(&self) -> ulksys::UplinkUploadOptions {
         let expires = self.expires.unwrap_or(Duration::ZERO);
@@ -353,10 +704,17 @@ impl Upload {
     }
 }
 
+/// Options for uploading a file as an object.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UploadFile {
+    /// Determine when the object expires. See [`Upload::expires`].
+    pub expires: Option<Duration>,
+}
+
 /// Options for updating object's metadata.
 ///
 /// Reserved for future use.
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct UploadObjectMetadata {}
 
 impl UploadObjectMetadata {
@@ -364,7 +722,8 @@ impl UploadObjectMetadata {
     pub(crate) fn as_ffi_upload_object_metadata_options(
         &self,
     ) -> ulksys::UplinkUploadObjectMetadataOptions {
-        ulksys::UplinkUploadObjectMetadataOptions {}
+        // SAFETY: see `CopyObject::as_ffi_copy_object_options`.
+        unsafe { std::mem::zeroed::<ulksys::UplinkUploadObjectMetadataOptions>() }
     }
 }
 
@@ -524,6 +883,110 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_list_objects_builder() {
+        {
+            // OK: no options.
+            let lo = ListObjects::builder()
+                .build()
+                .expect("no error without options");
+            assert_eq!("", lo.inner_prefix.to_str().unwrap(), "prefix value");
+            assert_eq!("", lo.inner_cursor.to_str().unwrap(), "cursor value");
+            assert!(!lo.recursive, "recursive");
+            assert!(!lo.system, "system");
+            assert!(!lo.custom, "custom");
+            assert_eq!(lo.limit, None, "limit");
+        }
+        {
+            // OK: flags and limit without prefix.
+            let lo = ListObjects::builder()
+                .recursive(true)
+                .system(true)
+                .custom(true)
+                .limit(5)
+                .build()
+                .expect("no error with flags and limit");
+            assert_eq!("", lo.inner_prefix.to_str().unwrap(), "prefix value");
+            assert!(lo.recursive, "recursive");
+            assert!(lo.system, "system");
+            assert!(lo.custom, "custom");
+            assert_eq!(lo.limit, Some(5), "limit");
+        }
+        {
+            // OK: empty prefix with cursor.
+            let lo = ListObjects::builder()
+                .prefix("")
+                .cursor("cursor-id")
+                .build()
+                .expect("no error with an empty prefix and a cursor");
+            assert_eq!("", lo.inner_prefix.to_str().unwrap(), "prefix value");
+            assert_eq!(
+                "cursor-id",
+                lo.inner_cursor.to_str().unwrap(),
+                "cursor value"
+            );
+        }
+        {
+            // OK: prefix and cursor.
+            let lo = ListObjects::builder()
+                .prefix("a/b/")
+                .cursor("cursor-id")
+                .build()
+                .expect("no error with a valid prefix and cursor");
+            assert_eq!("a/b/", lo.inner_prefix.to_str().unwrap(), "prefix value");
+            assert_eq!(
+                "cursor-id",
+                lo.inner_cursor.to_str().unwrap(),
+                "cursor value"
+            );
+        }
+        {
+            // Error: prefix doesn't end with `/`.
+            if let Error::InvalidArguments(error::Args { names, msg }) = ListObjects::builder()
+                .prefix("a/b")
+                .build()
+                .expect_err("when passing a prefix value without ending with '/'")
+            {
+                assert_eq!(names, "prefix", "invalid error argument name");
+                assert_eq!(
+                    msg, "cannot be empty and must end with '/'",
+                    "invalid error argument message"
+                );
+            } else {
+                panic!("expected an invalid argument error");
+            }
+        }
+        {
+            // Error: empty cursor.
+            if let Error::InvalidArguments(error::Args { names, msg }) = ListObjects::builder()
+                .cursor("")
+                .build()
+                .expect_err("when passing an empty cursor")
+            {
+                assert_eq!(names, "cursor", "invalid error argument name");
+                assert_eq!(msg, "cannot be empty", "invalid error argument message");
+            } else {
+                panic!("expected an invalid argument error");
+            }
+        }
+        {
+            // Error: invalid cursor value.
+            if let Error::InvalidArguments(error::Args { names, msg }) = ListObjects::builder()
+                .cursor("cursor\0id")
+                .build()
+                .expect_err("when passing a cursor value with NULL bytes")
+            {
+                assert_eq!(names, "cursor", "invalid error argument name");
+                assert_eq!(
+                    msg, "cannot contains null bytes (0 byte). Null byte found at 6",
+                    "invalid error argument message"
+                );
+            } else {
+                panic!("expected an invalid argument error");
+            }
+        }
+    }
+
     #[test]
     fn test_list_uploads_with_prefix() {
         {
@@ -648,4 +1111,143 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_list_getters() {
+        let lb = ListBuckets::with_cursor("bucket").expect("valid cursor");
+        assert_eq!(lb.cursor(), "bucket", "list buckets cursor");
+        assert_eq!(
+            ListBuckets::default().cursor(),
+            "",
+            "default list buckets cursor"
+        );
+
+        let lo = ListObjects::with_prefix_and_cursor("a/b/", "a/b/c").expect("valid options");
+        assert_eq!(lo.prefix(), "a/b/", "list objects prefix");
+        assert_eq!(lo.cursor(), "a/b/c", "list objects cursor");
+
+        let lu = ListUploads::with_prefix_and_cursor("a/", "a/b").expect("valid options");
+        assert_eq!(lu.prefix(), "a/", "list uploads prefix");
+        assert_eq!(lu.cursor(), "a/b", "list uploads cursor");
+
+        assert_eq!(
+            ListUploadParts::with_cursor(3).cursor,
+            3,
+            "list upload parts cursor"
+        );
+    }
+
+    #[test]
+    fn test_commit_upload_new() {
+        let mut custom = Custom::with_capacity(1);
+        custom.insert(String::from("key"), String::from("value"));
+        let opts = CommitUpload::new(custom);
+        let reused = opts.clone();
+        assert_eq!(
+            reused.custom_metadata().get("key").map(String::as_str),
+            Some("value"),
+            "custom metadata"
+        );
+        assert_eq!(
+            CommitUpload::default().custom_metadata().count(),
+            0,
+            "default custom metadata"
+        );
+    }
+
+    #[test]
+    fn test_upload_content_type() {
+        let mut opts = Upload::default();
+        opts.content_type("image/png").expect("valid MIME type");
+        assert_eq!(
+            opts.custom_metadata
+                .as_ref()
+                .and_then(|c| c.get("content-type"))
+                .map(String::as_str),
+            Some("image/png"),
+            "content type entry"
+        );
+
+        opts.content_type("bad\nvalue")
+            .expect_err("invalid MIME type");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_upload_validate_expires() {
+        let now = Duration::from_secs(1_000);
+        let upload = |expires| Upload {
+            expires,
+            custom_metadata: None,
+        };
+
+        for (expires, skew, what) in [
+            (None, Duration::ZERO, "no expiration"),
+            (Some(Duration::ZERO), Duration::ZERO, "never expires"),
+            (Some(Duration::from_secs(1_000)), Duration::ZERO, "now"),
+            (Some(Duration::from_secs(1_060)), Duration::ZERO, "future"),
+            (
+                Some(Duration::from_secs(990)),
+                Duration::from_secs(10),
+                "skew boundary",
+            ),
+            (
+                Some(Duration::from_secs(995)),
+                Duration::from_secs(10),
+                "within skew",
+            ),
+        ] {
+            upload(expires)
+                .validate_expires(now, skew)
+                .unwrap_or_else(|err| panic!("{}: unexpected error: {:?}", what, err));
+        }
+
+        for (expires, skew, what) in [
+            (Duration::from_secs(999), Duration::ZERO, "past"),
+            (
+                Duration::new(999, 999_999_999),
+                Duration::ZERO,
+                "past boundary",
+            ),
+            (
+                Duration::from_secs(989),
+                Duration::from_secs(10),
+                "past the skew",
+            ),
+            (Duration::from_secs(1), Duration::ZERO, "long ago"),
+        ] {
+            match upload(Some(expires)).validate_expires(now, skew) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "opts.expires", "{}: invalid argument name", what)
+                }
+                res => panic!("{}: unexpected result: {:?}", what, res),
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_upload_expires_at() {
+        use chrono::DateTime;
+
+        let mut opts = Upload::default();
+        opts.expires_at(DateTime::from_timestamp(1_714_564_800, 0).unwrap())
+            .expect("time after the epoch");
+        assert_eq!(
+            opts.expires,
+            Some(Duration::from_secs(1_714_564_800)),
+            "expires"
+        );
+
+        for secs in [0, -1] {
+            let mut opts = Upload::default();
+            match opts.expires_at(DateTime::from_timestamp(secs, 0).unwrap()) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "expires", "invalid argument name")
+                }
+                res => panic!("{}: unexpected result: {:?}", secs, res),
+            }
+            assert_eq!(opts.expires, None, "{}: expires unchanged", secs);
+        }
+    }
 }
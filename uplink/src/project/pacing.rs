@@ -0,0 +1,333 @@
+//! Pacing of the operations of the projects when the satellite rate limits them.
+//!
+//! When the satellite returns an [`UplinkCode::TooManyRequests`] error, retrying or sending other
+//! requests right away only prolongs the rate limiting, so the requests of the whole application
+//! should back off, not only the rejected one. A [`RateGate`] set to a project with
+//! [`Project::set_rate_gate`](crate::Project::set_rate_gate) observes the results of all its
+//! operations and, after observing that error, delays the subsequent ones with a pause that grows
+//! exponentially while the satellite keeps rate limiting them and decays when it stops.
+//!
+//! ```no_run
+//! use uplink::project::pacing::RateGate;
+//! use uplink::Project;
+//!
+//! use std::time::Duration;
+//!
+//! let gate = RateGate::new(
+//!     Duration::from_millis(200),
+//!     Duration::from_secs(10),
+//!     Duration::from_secs(5),
+//! )?;
+//! let mut project = Project::open_from_serialized("my-serialized-access-grant")?;
+//! // The clones share the gate, so the projects back off together.
+//! let mut other = Project::open_from_serialized("my-other-serialized-access-grant")?;
+//! project.set_rate_gate(Some(gate.clone()));
+//! other.set_rate_gate(Some(gate));
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::error::UplinkCode;
+use crate::{Error, Result};
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Delays the operations after observing [`UplinkCode::TooManyRequests`] errors.
+///
+/// The first error engages the gate with the initial pause, each subsequent one doubles it up to
+/// the maximum pause, and the pause halves for each decay period elapsed without observing
+/// another one, disengaging the gate when it falls below the initial pause. While it's engaged,
+/// each operation waits the current pause before calling the FFI.
+///
+/// The clones share the state, so a gate can pace several projects and threads.
+#[derive(Clone, Debug)]
+pub struct RateGate {
+    /// The pause after the first observed error.
+    initial_pause: Duration,
+    /// The maximum pause.
+    max_pause: Duration,
+    /// The period without observed errors that halves the pause.
+    decay: Duration,
+    /// The state shared by the clones.
+    state: Arc<Mutex<State>>,
+}
+
+/// The state of a [`RateGate`].
+#[derive(Debug, Default)]
+struct State {
+    /// The current pause, which is zero when the gate isn't engaged.
+    pause: Duration,
+    /// When the pause was last changed, which is when the decay periods start counting.
+    changed: Option<Instant>,
+}
+
+impl RateGate {
+    /// The initial pause of the default gate.
+    pub const DEFAULT_INITIAL_PAUSE: Duration = Duration::from_millis(100);
+    /// The maximum pause of the default gate.
+    pub const DEFAULT_MAX_PAUSE: Duration = Duration::from_secs(30);
+    /// The decay period of the default gate.
+    pub const DEFAULT_DECAY: Duration = Duration::from_secs(10);
+
+    /// Creates a disengaged gate with the `initial_pause`, the `max_pause` and the `decay`
+    /// period described in the [type documentation](Self).
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `initial_pause` or `decay` are zero or
+    /// `max_pause` is less than `initial_pause`.
+    pub fn new(initial_pause: Duration, max_pause: Duration, decay: Duration) -> Result<Self> {
+        if initial_pause.is_zero() {
+            return Err(Error::new_invalid_arguments(
+                "initial_pause",
+                "cannot be zero",
+            ));
+        }
+
+        if max_pause < initial_pause {
+            return Err(Error::new_invalid_arguments(
+                "max_pause",
+                "cannot be less than the initial pause",
+            ));
+        }
+
+        if decay.is_zero() {
+            return Err(Error::new_invalid_arguments("decay", "cannot be zero"));
+        }
+
+        Ok(Self {
+            initial_pause,
+            max_pause,
+            decay,
+            state: Arc::default(),
+        })
+    }
+
+    /// Returns the pause that the operations currently wait, which is zero when the gate isn't
+    /// engaged.
+    pub fn pause(&self) -> Duration {
+        self.pause_at(Instant::now())
+    }
+
+    /// Observes the error of an operation, engaging the gate or growing its pause if it's an
+    /// [`UplinkCode::TooManyRequests`] error.
+    ///
+    /// The projects that have the gate observe the errors of their operations, so it's only needed
+    /// for the errors returned by other types (e.g. the iterators, the uploads and the downloads)
+    /// for pacing the projects with them too.
+    pub fn observe(&self, err: &Error) {
+        self.observe_at(err, Instant::now());
+    }
+
+    /// Blocks the calling thread for the current pause.
+    pub(crate) fn wait(&self) {
+        let pause = self.pause();
+        if !pause.is_zero() {
+            thread::sleep(pause);
+        }
+    }
+
+    /// Returns the pause at `now`.
+    fn pause_at(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.decay_at(&mut state, now);
+        state.pause
+    }
+
+    /// Observes `err` at `now`, see [`Self::observe`].
+    fn observe_at(&self, err: &Error, now: Instant) {
+        if err.uplink_code() != Some(UplinkCode::TooManyRequests) {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.decay_at(&mut state, now);
+        state.pause = if state.pause.is_zero() {
+            self.initial_pause
+        } else {
+            state.pause.saturating_mul(2).min(self.max_pause)
+        };
+        state.changed = Some(now);
+    }
+
+    /// Halves the pause of `state` for each decay period elapsed from its last change until
+    /// `now`, disengaging the gate when it falls below the initial pause.
+    fn decay_at(&self, state: &mut State, now: Instant) {
+        let changed = match state.changed {
+            Some(changed) if !state.pause.is_zero() => changed,
+            _ => return,
+        };
+
+        let periods = now.saturating_duration_since(changed).as_nanos() / self.decay.as_nanos();
+        if periods == 0 {
+            return;
+        }
+
+        // Any pause is zero after halving it 128 times.
+        for _ in 0..periods.min(128) {
+            state.pause /= 2;
+        }
+        if state.pause < self.initial_pause {
+            *state = State::default();
+            return;
+        }
+
+        // The periods are less than 128 when the pause doesn't fall below the initial one.
+        state.changed = Some(changed + self.decay * periods as u32);
+    }
+}
+
+impl Default for RateGate {
+    /// Returns a disengaged gate with [`Self::DEFAULT_INITIAL_PAUSE`],
+    /// [`Self::DEFAULT_MAX_PAUSE`] and [`Self::DEFAULT_DECAY`].
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_INITIAL_PAUSE,
+            Self::DEFAULT_MAX_PAUSE,
+            Self::DEFAULT_DECAY,
+        )
+        .expect("BUG: the default rate gate options are invalid")
+    }
+}
+
+/// Calls `op`, which is an operation that calls the FFI, paced by `gate`, if any: it waits the
+/// gate's pause before calling `op` and the gate observes the error that `op` returns.
+pub(crate) fn paced<T>(gate: Option<&RateGate>, op: impl FnOnce() -> Result<T>) -> Result<T> {
+    let gate = match gate {
+        Some(gate) => gate,
+        None => return op(),
+    };
+
+    gate.wait();
+    let res = op();
+    if let Err(err) = &res {
+        gate.observe(err);
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    /// Returns an uplink error with `code`, as the FFI would return it.
+    fn uplink_error(code: UplinkCode) -> Error {
+        Error::Uplink(error::Uplink::with_code(code, "injected error"))
+    }
+
+    #[test]
+    fn test_rate_gate_new() {
+        let second = Duration::from_secs(1);
+        RateGate::new(second, second, second).expect("valid options");
+        RateGate::default();
+
+        for (case, (initial_pause, max_pause, decay), want) in [
+            (
+                "zero initial pause",
+                (Duration::ZERO, second, second),
+                "initial_pause",
+            ),
+            (
+                "max pause less than the initial",
+                (second * 2, second, second),
+                "max_pause",
+            ),
+            ("zero decay", (second, second, Duration::ZERO), "decay"),
+        ] {
+            match RateGate::new(initial_pause, max_pause, decay) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, want, "{}: invalid argument name", case)
+                }
+                res => panic!("{}: unexpected result: {:?}", case, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate_gate_engage_and_decay() {
+        let ms = Duration::from_millis;
+        let gate = RateGate::new(ms(100), ms(350), ms(1000)).expect("valid options");
+        let start = Instant::now();
+        let at = |millis| start + ms(millis);
+
+        assert_eq!(gate.pause_at(at(0)), Duration::ZERO, "disengaged");
+
+        // The errors of other kinds are ignored.
+        gate.observe_at(&uplink_error(UplinkCode::BucketNotFound), at(0));
+        gate.observe_at(&Error::new_invalid_arguments("bucket", "invalid"), at(0));
+        assert_eq!(gate.pause_at(at(0)), Duration::ZERO, "other errors");
+
+        // The pause grows exponentially up to the maximum.
+        for (millis, want) in [(0, 100), (10, 200), (20, 350), (30, 350)] {
+            gate.observe_at(&uplink_error(UplinkCode::TooManyRequests), at(millis));
+            assert_eq!(
+                gate.pause_at(at(millis)),
+                ms(want),
+                "engaged at {}ms",
+                millis
+            );
+        }
+
+        // The pause halves for each decay period without errors until it disengages.
+        for (millis, want) in [(1029, 350), (1030, 175), (2030, 0), (9000, 0)] {
+            assert_eq!(
+                gate.pause_at(at(millis)),
+                ms(want),
+                "decayed at {}ms",
+                millis
+            );
+        }
+
+        // It engages again with the initial pause and the clones share the state.
+        gate.clone()
+            .observe_at(&uplink_error(UplinkCode::TooManyRequests), at(9000));
+        assert_eq!(gate.pause_at(at(9000)), ms(100), "engaged again");
+        assert_eq!(gate.pause_at(at(10_000)), Duration::ZERO, "decayed again");
+    }
+
+    #[test]
+    fn test_paced() {
+        let pause = Duration::from_millis(50);
+        let gate = RateGate::new(pause, pause * 4, Duration::from_secs(60)).expect("valid options");
+
+        // Without a gate or with a disengaged one the operations aren't delayed.
+        assert_eq!(paced(None, || Ok(1)).expect("without gate"), 1);
+        let start = Instant::now();
+        paced(Some(&gate), || Ok(())).expect("disengaged gate");
+        assert!(
+            start.elapsed() < pause,
+            "disengaged gate delayed the operation"
+        );
+
+        // An injected rate limiting error engages the gate, delaying the next operations.
+        match paced(Some(&gate), || -> Result<()> {
+            Err(uplink_error(UplinkCode::TooManyRequests))
+        }) {
+            Err(err) => assert_eq!(
+                err.uplink_code(),
+                Some(UplinkCode::TooManyRequests),
+                "injected error returned"
+            ),
+            Ok(()) => panic!("injected error swallowed"),
+        }
+        assert_eq!(gate.pause(), pause, "engaged gate pause");
+
+        let start = Instant::now();
+        paced(Some(&gate), || Ok(())).expect("engaged gate");
+        assert!(
+            start.elapsed() >= pause,
+            "engaged gate didn't delay the operation"
+        );
+        assert_eq!(
+            gate.pause(),
+            pause,
+            "successful operations don't change the pause"
+        );
+
+        paced(Some(&gate), || -> Result<()> {
+            Err(uplink_error(UplinkCode::TooManyRequests))
+        })
+        .expect_err("injected error");
+        assert_eq!(gate.pause(), pause * 2, "grown pause");
+    }
+}
@@ -0,0 +1,146 @@
+//! Limits and usage of the project's account and early detection of the exceeded ones.
+//!
+//! The bundled uplink-c version doesn't expose the limits and the usage of the project, so
+//! [`Project::limits`](crate::Project::limits) returns an [`Error::Unsupported`] and
+//! [`Project::check_quota`](crate::Project::check_quota) detects the exceeded limits with a probe
+//! upload, see its documentation for the details of the heuristic.
+
+use crate::error::UplinkCode;
+use crate::{Error, Result};
+
+/// The prefix of the keys of the probe uploads of
+/// [`Project::check_quota`](crate::Project::check_quota).
+pub const PROBE_KEY_PREFIX: &str = ".uplink-rust-quota-probe/";
+
+/// The limits of the project's account and their usage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProjectLimits {
+    /// The used storage in bytes.
+    pub storage_used: u64,
+    /// The storage limit in bytes.
+    pub storage_limit: u64,
+    /// The used bandwidth of the current billing period in bytes.
+    pub bandwidth_used: u64,
+    /// The bandwidth limit of a billing period in bytes.
+    pub bandwidth_limit: u64,
+    /// The number of used segments.
+    pub segments_used: u64,
+    /// The segments limit.
+    pub segments_limit: u64,
+}
+
+/// The result of [`Project::check_quota`](crate::Project::check_quota).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaCheck {
+    /// No limit was exceeded when checking it.
+    Available,
+    /// The limit identified by the code ([`UplinkCode::StorageLimitExceeded`] or
+    /// [`UplinkCode::SegmentsLimitExceeded`]) is exceeded or it would be by the planned bytes.
+    Exceeded(UplinkCode),
+}
+
+impl QuotaCheck {
+    /// Returns `true` if no limit was exceeded when checking it.
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Available)
+    }
+
+    /// Returns the check of uploading `planned_bytes`, which need at least one segment, with
+    /// `limits`.
+    pub(crate) fn from_limits(limits: &ProjectLimits, planned_bytes: u64) -> Self {
+        if limits.storage_used.saturating_add(planned_bytes) > limits.storage_limit {
+            return Self::Exceeded(UplinkCode::StorageLimitExceeded);
+        }
+
+        if limits.segments_used >= limits.segments_limit {
+            return Self::Exceeded(UplinkCode::SegmentsLimitExceeded);
+        }
+
+        Self::Available
+    }
+
+    /// Returns the check of the error of a probe upload or the error if it isn't about an
+    /// exceeded limit.
+    pub(crate) fn from_probe_error(err: Error) -> Result<Self> {
+        match err.uplink_code() {
+            Some(code @ (UplinkCode::StorageLimitExceeded | UplinkCode::SegmentsLimitExceeded)) => {
+                Ok(Self::Exceeded(code))
+            }
+            _ => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_quota_check_from_limits() {
+        let limits = ProjectLimits {
+            storage_used: 900,
+            storage_limit: 1_000,
+            segments_used: 9,
+            segments_limit: 10,
+            ..Default::default()
+        };
+
+        for (planned_bytes, limits, want) in [
+            (100, limits, QuotaCheck::Available),
+            (
+                101,
+                limits,
+                QuotaCheck::Exceeded(UplinkCode::StorageLimitExceeded),
+            ),
+            (
+                u64::MAX,
+                limits,
+                QuotaCheck::Exceeded(UplinkCode::StorageLimitExceeded),
+            ),
+            (
+                0,
+                ProjectLimits {
+                    segments_used: 10,
+                    ..limits
+                },
+                QuotaCheck::Exceeded(UplinkCode::SegmentsLimitExceeded),
+            ),
+        ] {
+            assert_eq!(
+                QuotaCheck::from_limits(&limits, planned_bytes),
+                want,
+                "planned bytes: {}, limits: {:?}",
+                planned_bytes,
+                limits
+            );
+        }
+    }
+
+    #[test]
+    fn test_quota_check_from_probe_error() {
+        for code in [
+            UplinkCode::StorageLimitExceeded,
+            UplinkCode::SegmentsLimitExceeded,
+        ] {
+            let check = QuotaCheck::from_probe_error(Error::Uplink(error::Uplink::with_code(
+                code, "exceeded",
+            )))
+            .expect("exceeded limit");
+            assert_eq!(check, QuotaCheck::Exceeded(code), "{:?}", code);
+            assert!(!check.is_available(), "{:?}: available", code);
+        }
+
+        match QuotaCheck::from_probe_error(Error::Uplink(error::Uplink::with_code(
+            UplinkCode::BucketNotFound,
+            "not found",
+        ))) {
+            Err(Error::Uplink(err)) => {
+                assert_eq!(err.code(), UplinkCode::BucketNotFound, "error code")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        assert!(QuotaCheck::Available.is_available(), "available");
+    }
+}
@@ -0,0 +1,234 @@
+//! Public links of objects for downloading them without an access grant.
+//!
+//! [`create_public_link`] shares an access grant restricted to download a single object, registers
+//! it in the Auth service as public and joins the linksharing URL of the object, which are the
+//! operations of [`Grant::share`], [`edge::Config::register_gateway_access`] and
+//! [`edge::linksharing::share_url`].
+//!
+//! ```no_run
+//! use uplink::access::Grant;
+//! use uplink::{edge, share};
+//!
+//! use std::time::Duration;
+//!
+//! let grant = Grant::new("my-serialized-access-grant")?;
+//! let edge_config = edge::Config::new("auth.storjshare.io:7777")?;
+//! let link = share::create_public_link(
+//!     &grant,
+//!     "my-bucket",
+//!     "reports/2024.pdf",
+//!     Some(Duration::from_secs(24 * 3600)),
+//!     &edge_config,
+//! )?;
+//!
+//! println!("{} (access key ID: {})", link.url, link.access_key_id);
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::access::{Grant, Permission, SharePrefix};
+use crate::edge::{self, config::OptionsRegisterAccess};
+use crate::error::BoxError;
+use crate::{Error, Result};
+
+use std::time::{Duration, SystemTime};
+
+/// The base URL of the Storj-hosted linksharing service used by [`create_public_link`].
+pub const DEFAULT_LINKSHARING_URL: &str = "https://link.storjshare.io";
+
+/// The context message of the [`Error::Internal`] returned by [`create_public_link`] when the
+/// access grant cannot be shared.
+pub const PUBLIC_LINK_ERROR_CTX_SHARE: &str = "cannot share the access grant";
+
+/// The context message of the [`Error::Internal`] returned by [`create_public_link`] when the
+/// shared access grant cannot be registered in the Auth service.
+pub const PUBLIC_LINK_ERROR_CTX_REGISTER: &str = "cannot register the shared access grant";
+
+/// The context message of the [`Error::Internal`] returned by [`create_public_link`] when the
+/// linksharing URL cannot be joined.
+pub const PUBLIC_LINK_ERROR_CTX_URL: &str = "cannot join the linksharing URL";
+
+/// A public link of an object returned by [`create_public_link`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicLink {
+    /// The linksharing URL of the object.
+    pub url: String,
+    /// The access key ID registered in the Auth service, which identifies the link for revoking
+    /// it later.
+    pub access_key_id: String,
+    /// The time since the Unix Epoch when the link expires, rounded down to the second. `None`
+    /// when it doesn't expire.
+    pub expires_at: Option<Duration>,
+}
+
+/// Creates a public link of the object with `key` in `bucket` served by the
+/// [Storj-hosted linksharing service](DEFAULT_LINKSHARING_URL).
+///
+/// See [`create_public_link_with_base_url`] for the details and the errors.
+pub fn create_public_link(
+    project_grant: &Grant,
+    bucket: &str,
+    key: &str,
+    expires_in: Option<Duration>,
+    edge_config: &edge::Config,
+) -> Result<PublicLink> {
+    create_public_link_with_base_url(
+        project_grant,
+        bucket,
+        key,
+        expires_in,
+        edge_config,
+        DEFAULT_LINKSHARING_URL,
+    )
+}
+
+/// Creates a public link of the object with `key` in `bucket` served by the linksharing service
+/// at `base_url`.
+///
+/// It shares `project_grant` with a read-only permission restricted to `key`, which expires when
+/// `expires_in` elapses if it's `Some`, registers it in the Auth service of `edge_config` with
+/// public visibility and joins the URL of the object.
+///
+/// It returns an [`Error::InvalidArguments`] if `bucket` or `key` is empty, `key` ends with `/`
+/// (i.e. it's a prefix rather than an object) or `expires_in` is less than 1 second, so the link
+/// would be expired when it's created.
+///
+/// It returns an [`Error::Internal`] whose context message indicates the operation that failed:
+/// [`PUBLIC_LINK_ERROR_CTX_SHARE`], [`PUBLIC_LINK_ERROR_CTX_REGISTER`] or
+/// [`PUBLIC_LINK_ERROR_CTX_URL`]. Its inner error is the one returned by the operation and it can
+/// be got with `std::error::Error::source`.
+pub fn create_public_link_with_base_url(
+    project_grant: &Grant,
+    bucket: &str,
+    key: &str,
+    expires_in: Option<Duration>,
+    edge_config: &edge::Config,
+    base_url: &str,
+) -> Result<PublicLink> {
+    validate_object(bucket, key)?;
+    let expires_at = match expires_in {
+        Some(ttl) => Some(expiration_time(ttl)?),
+        None => None,
+    };
+
+    let mut permission = Permission::read_only();
+    permission
+        .set_not_after(expires_at)
+        .map_err(|err| stage_error(PUBLIC_LINK_ERROR_CTX_SHARE, err))?;
+    let shared = SharePrefix::new(bucket, key)
+        .and_then(|prefix| project_grant.share(&permission, Some(vec![prefix])))
+        .map_err(|err| stage_error(PUBLIC_LINK_ERROR_CTX_SHARE, err))?;
+
+    let credentials = edge_config
+        .register_gateway_access(&shared, Some(&OptionsRegisterAccess { public: true }))
+        .map_err(|err| stage_error(PUBLIC_LINK_ERROR_CTX_REGISTER, err))?;
+
+    let url = edge::linksharing::share_url(base_url, &credentials.access_key_id, bucket, key, None)
+        .map_err(|err| stage_error(PUBLIC_LINK_ERROR_CTX_URL, err))?;
+
+    Ok(PublicLink {
+        url,
+        access_key_id: credentials.access_key_id,
+        expires_at: expires_at.map(|t| Duration::from_secs(t.as_secs())),
+    })
+}
+
+/// Validates that `bucket` and `key` identify an object.
+fn validate_object(bucket: &str, key: &str) -> Result<()> {
+    if bucket.is_empty() {
+        return Err(Error::new_invalid_arguments("bucket", "cannot be empty"));
+    }
+
+    if key.is_empty() {
+        return Err(Error::new_invalid_arguments("key", "cannot be empty"));
+    }
+
+    if key.ends_with('/') {
+        return Err(Error::new_invalid_arguments(
+            "key",
+            "cannot end with '/' because it must be an object key rather than a prefix",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the time since the Unix Epoch when `expires_in` elapses from now.
+fn expiration_time(expires_in: Duration) -> Result<Duration> {
+    if expires_in.as_secs() == 0 {
+        return Err(Error::new_invalid_arguments(
+            "expires_in",
+            "cannot be less than 1 second because the link would be already expired",
+        ));
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|err| Error::new_internal("system time before Unix Epoch", BoxError::from(err)))?;
+    now.checked_add(expires_in)
+        .ok_or_else(|| Error::new_invalid_arguments("expires_in", "is too large"))
+}
+
+/// Wraps `err` in an [`Error::Internal`] whose context message is `ctx` for indicating the
+/// operation that failed.
+fn stage_error(ctx: &str, err: Error) -> Error {
+    Error::new_internal(ctx, BoxError::from(err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_create_public_link_invalid_arguments() {
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let edge_config = edge::Config::new("auth.storjshare.io:7777").expect("edge config");
+
+        for (name, bucket, key, expires_in, arg) in [
+            ("empty bucket", "", "file.txt", None, "bucket"),
+            ("empty key", "my-bucket", "", None, "key"),
+            ("prefix key", "my-bucket", "reports/", None, "key"),
+            (
+                "expired",
+                "my-bucket",
+                "file.txt",
+                Some(Duration::ZERO),
+                "expires_in",
+            ),
+            (
+                "expired within a second",
+                "my-bucket",
+                "file.txt",
+                Some(Duration::from_millis(999)),
+                "expires_in",
+            ),
+            (
+                "too large expiration",
+                "my-bucket",
+                "file.txt",
+                Some(Duration::MAX),
+                "expires_in",
+            ),
+        ] {
+            match create_public_link(&grant, bucket, key, expires_in, &edge_config) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, arg, "{}: invalid argument name", name)
+                }
+                res => panic!("{}: unexpected result: {:?}", name, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_create_public_link_share_error() {
+        let grant = Grant::new("15kvZYL7aMhTXFU6vne8iedGfvvZdcbaDLAZ9SiN1yChpAYupdDw3SMfyHqA7pETdFjhe8SnjLox4tnq5hYbZWfCm443kv3fWV8ZWNkKwaq1mbrmyz3pPd1WSxiJn2g5tYKWoPpzvG1ygjDaB4yEq9zdpYSaH5DiVHrbaWmq6mCwRrnEF1ANdVcA2gXNbFpmSKp2i59fA14RRdZYVTrvY6rWKyG35p35eenp3ePyjwoXNSe9Cs8KvMRteVcozNiMwwuYCm4ExwP8os5Eqydqwjpx8ic8hnirkn7ThBbLLAtJBLtu").expect("valid serialized access grant");
+        let edge_config = edge::Config::new("auth.storjshare.io:7777").expect("edge config");
+
+        match create_public_link(&grant, "my-bucket", "fi\0le.txt", None, &edge_config) {
+            Err(Error::Internal(error::Internal { ctx_msg, .. })) => {
+                assert_eq!(ctx_msg, PUBLIC_LINK_ERROR_CTX_SHARE, "context message")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Helpers for integration testing code against a satellite.
+//!
+//! [`TestProject`] opens a project with the configuration of the environment and deletes the
+//! buckets created through it, with all their objects, when it drops, so the tests don't leave
+//! buckets behind even when they panic.
+//!
+//! ```no_run
+//! use uplink::testing::TestProject;
+//!
+//! let tp = TestProject::from_env()?;
+//! let bucket = tp.create_bucket("my-test")?;
+//! tp.project().put_object(&bucket, "key", b"data", None)?;
+//! // The bucket and its objects are deleted when `tp` drops.
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::access::Grant;
+use crate::error::UplinkCode;
+use crate::{Error, Project, Result};
+
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// The address of the satellite of a local [storj-up](https://github.com/storj/up) instance, which
+/// is the one started by `make integration-tests-env-up`.
+pub const STORJ_UP_SATELLITE_ADDR: &str =
+    "12whfK1EDvHJtajBiAUeajQLYcWqxcQmdYQU5zX5cCf6bAxfgu4@localhost:7777";
+
+/// A project for integration tests that deletes the buckets registered in it when it drops.
+pub struct TestProject {
+    /// The access grant used for opening the project.
+    grant: Grant,
+    /// The opened project.
+    project: Project,
+    /// The names of the buckets to delete when it drops.
+    buckets: Mutex<Vec<String>>,
+}
+
+impl TestProject {
+    /// Opens a project with the access grant of the environment.
+    ///
+    /// It uses the serialized access grant of the `STORJ_ACCESS` environment variable when it's
+    /// defined, otherwise it requests an access grant with the API key and the passphrase of the
+    /// `STORJ_API_KEY` and `STORJ_ENCRYPTION_SECRET` environment variables to the satellite of
+    /// the `STORJ_SATELLITE` environment variable, which defaults to
+    /// [`STORJ_UP_SATELLITE_ADDR`].
+    ///
+    /// It returns an [`Error::InvalidArguments`] if none of those environment variables are
+    /// defined and the same errors than [`Grant::new`] and
+    /// [`Grant::request_access_with_passphrase`].
+    pub fn from_env() -> Result<Self> {
+        if let Ok(access) = env::var("STORJ_ACCESS") {
+            return Ok(Self::new(Grant::new(&access)?));
+        }
+
+        match (
+            env::var("STORJ_API_KEY"),
+            env::var("STORJ_ENCRYPTION_SECRET"),
+        ) {
+            (Ok(api_key), Ok(passphrase)) => {
+                let satellite = env::var("STORJ_SATELLITE")
+                    .unwrap_or_else(|_| String::from(STORJ_UP_SATELLITE_ADDR));
+                Ok(Self::new(Grant::request_access_with_passphrase(
+                    &satellite,
+                    &api_key,
+                    &passphrase,
+                )?))
+            }
+            _ => Err(Error::new_invalid_arguments(
+                "env{STORJ_ACCESS}",
+                "must be defined when STORJ_API_KEY and STORJ_ENCRYPTION_SECRET aren't",
+            )),
+        }
+    }
+
+    /// Opens a project with `grant`.
+    pub fn new(grant: Grant) -> Self {
+        let project = Project::open(&grant);
+        Self {
+            grant,
+            project,
+            buckets: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the access grant used for opening the project.
+    pub fn grant(&self) -> &Grant {
+        &self.grant
+    }
+
+    /// Returns the opened project.
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// Creates a bucket with a unique name generated from `ctx`, see [`generate_name`], registers
+    /// it for deleting it when this instance drops and returns its name.
+    ///
+    /// It returns the same errors than [`Project::create_bucket`].
+    pub fn create_bucket(&self, ctx: &str) -> Result<String> {
+        let name = generate_name(ctx);
+        self.project.create_bucket(&name)?;
+        self.register_bucket(&name);
+        Ok(name)
+    }
+
+    /// Registers the bucket for deleting it, with all its objects, when this instance drops.
+    ///
+    /// The bucket may not exist yet or may be deleted by the test, the buckets that don't exist
+    /// when this instance drops are ignored.
+    pub fn register_bucket(&self, name: &str) {
+        self.buckets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(String::from(name));
+    }
+}
+
+impl Drop for TestProject {
+    /// Deletes the registered buckets with all their objects.
+    ///
+    /// It doesn't panic because it may be dropped while the test panics, so it prints the errors,
+    /// except the ones of the buckets that don't exist, to the standard error.
+    fn drop(&mut self) {
+        let buckets = self
+            .buckets
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for name in buckets.drain(..) {
+            match self.project.delete_bucket_with_objects(&name) {
+                Ok(_) => {}
+                Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+                Err(err) => eprintln!("cleaning up the test bucket {:?} failed: {}", name, err),
+            }
+        }
+    }
+}
+
+/// Returns a unique name, valid for a bucket if `ctx` is, with the format
+/// `uplink-rust-{ctx}-{nanoseconds since the Unix Epoch}-{counter}`.
+///
+/// `ctx` should be short because the bucket names cannot be longer than 63 characters.
+pub fn generate_name(ctx: &str) -> String {
+    /// Counter that makes the names unique when they are generated at the same nanosecond.
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let d = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("system time since Unix epoch failed");
+    format!(
+        "uplink-rust-{}-{}-{}",
+        ctx,
+        d.as_nanos(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Returns the number of errors returned by the FFI without a result that have been freed in the
+/// current thread, which allows to assert that an operation frees its FFI error exactly once.
+///
+/// The operations that run in a separate thread, like the ones bounded by a timeout, don't count.
+pub fn ffi_errors_freed() -> usize {
+    crate::error::ffi_errors_freed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate_name() {
+        let first = generate_name("ctx");
+        let second = generate_name("ctx");
+        assert!(first.starts_with("uplink-rust-ctx-"), "prefix: {}", first);
+        assert_ne!(first, second, "unique names");
+        crate::bucket::validate_name(&first).expect("valid bucket name");
+    }
+}
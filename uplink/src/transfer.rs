@@ -0,0 +1,400 @@
+//! Transfers objects between projects.
+//!
+//! The satellites cannot copy objects between different projects, so the functions of this module
+//! download the data from the source project and upload it to the destination project, streaming
+//! it through a bounded buffer. Use [`Project::copy_object`] for copying objects inside of the
+//! same project because it doesn't transfer the data.
+
+use crate::object::Download;
+use crate::project::options;
+use crate::tuning::MultipartTuning;
+use crate::{error, helpers, metadata, Error, Object, Project, Result};
+
+use std::io::{BufRead, Read, Write};
+
+/// Options for transferring objects between projects.
+pub struct TransferOptions {
+    /// The size, in bytes, of the buffer used for transferring the data. It's the maximum amount
+    /// of data read from the source before writing it to the destination.
+    pub buffer_size: usize,
+    /// The objects whose size, in bytes, is equal or greater than this value are uploaded to the
+    /// destination with a multipart upload. The objects whose size is unknown aren't.
+    pub multipart_threshold: u64,
+    /// The size, in bytes, of the parts of the multipart uploads. The last part may be smaller.
+    ///
+    /// It must be at least [`Self::MIN_PART_SIZE`].
+    pub part_size: u64,
+    /// Callback that receives the key of the object being transferred and the cumulative number
+    /// of its transferred bytes after each write to the destination.
+    pub progress: Option<Box<Progress>>,
+}
+
+/// The progress callback of [`TransferOptions`].
+pub type Progress = dyn FnMut(&str, u64) + Send;
+
+impl TransferOptions {
+    /// The minimum size of the parts of a multipart upload accepted by the satellites, except for
+    /// the last one.
+    pub const MIN_PART_SIZE: u64 = MultipartTuning::MIN_PART_SIZE;
+
+    /// Returns an [`Error::InvalidArguments`] if any of the options has an invalid value.
+    fn validate(&self) -> Result<()> {
+        if self.buffer_size == 0 {
+            return Err(Error::new_invalid_arguments(
+                "opts{buffer_size}",
+                "cannot be zero",
+            ));
+        }
+
+        if self.part_size < Self::MIN_PART_SIZE {
+            return Err(Error::new_invalid_arguments(
+                "opts{part_size}",
+                &format!("cannot be less than {} bytes", Self::MIN_PART_SIZE),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Calls the progress callback, if any, with `key` and `transferred`.
+    fn report(&mut self, key: &str, transferred: u64) {
+        if let Some(progress) = self.progress.as_mut() {
+            progress(key, transferred);
+        }
+    }
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        Self {
+            buffer_size: 64 * 1024,
+            multipart_threshold: 64 * 1024 * 1024,
+            part_size: 64 * 1024 * 1024,
+            progress: None,
+        }
+    }
+}
+
+/// Copies the object inside of `src_bucket` and referenced by `src_key` of the `src` project to
+/// `dst_bucket` and `dst_key` of the `dst` project, and returns the copied object.
+///
+/// It streams the data, so the memory usage doesn't depend on the object's size, and it preserves
+/// the custom metadata and the expiration time of the object. The objects whose size is equal or
+/// greater than the multipart threshold of `opts` are uploaded with a multipart upload.
+///
+/// It aborts the upload if any of the operations fails and it returns an
+/// [`Error::InvalidArguments`] if any of the options is invalid and the same errors than
+/// [`Project::download_object`], [`Project::upload_object`], [`Project::begin_upload`],
+/// [`Project::upload_part`], [`Project::commit_upload`] and the errors returned by reading and
+/// writing the data.
+pub fn copy_object(
+    src: &Project,
+    src_bucket: &str,
+    src_key: &str,
+    dst: &Project,
+    dst_bucket: &str,
+    dst_key: &str,
+    mut opts: TransferOptions,
+) -> Result<Object> {
+    opts.validate()?;
+    copy(
+        src, src_bucket, src_key, dst, dst_bucket, dst_key, &mut opts,
+    )
+}
+
+/// Copies all the objects inside of `src_bucket` whose key starts with `prefix` of the `src`
+/// project to `dst_bucket` of the `dst` project, preserving their keys, and returns the copied
+/// objects.
+///
+/// `prefix` must be empty, for copying all the objects of the bucket, or end with '/'. The objects
+/// are listed recursively and each one is copied as [`copy_object`] does.
+///
+/// It stops at the first error and returns it with the objects copied until then. The listing is
+/// ordered by key, so the copy can be resumed after the last copied object.
+pub fn copy_prefix(
+    src: &Project,
+    src_bucket: &str,
+    prefix: &str,
+    dst: &Project,
+    dst_bucket: &str,
+    mut opts: TransferOptions,
+) -> std::result::Result<Vec<Object>, error::ListError<Object>> {
+    let into_list_error = |error| error::ListError {
+        items: Vec::new(),
+        error,
+    };
+
+    opts.validate().map_err(into_list_error)?;
+    let mut list_opts = if prefix.is_empty() {
+        options::ListObjects::default()
+    } else {
+        options::ListObjects::with_prefix(prefix).map_err(into_list_error)?
+    };
+    list_opts.recursive = true;
+
+    let keys = src
+        .list_objects_keys(src_bucket, Some(&list_opts))
+        .map_err(into_list_error)?;
+    let mut items = Vec::new();
+    for key in keys {
+        let res = key.and_then(|key| copy(src, src_bucket, &key, dst, dst_bucket, &key, &mut opts));
+        match res {
+            Ok(obj) => items.push(obj),
+            Err(error) => return Err(error::ListError { items, error }),
+        }
+    }
+
+    Ok(items)
+}
+
+/// Copies the object as [`copy_object`] does, using `opts` without validating them.
+fn copy(
+    src: &Project,
+    src_bucket: &str,
+    src_key: &str,
+    dst: &Project,
+    dst_bucket: &str,
+    dst_key: &str,
+    opts: &mut TransferOptions,
+) -> Result<Object> {
+    let mut download = src.download_object(src_bucket, src_key, None)?;
+    let src_obj = download.info()?;
+    let upload_opts = options::Upload {
+        expires: src_obj.metadata_system.expires,
+        custom_metadata: None,
+    };
+    let custom = src_obj.metadata_custom;
+
+    if src_obj
+        .metadata_system
+        .content_length
+        .is_some_and(|l| l >= opts.multipart_threshold)
+    {
+        copy_multipart(
+            &mut download,
+            dst,
+            dst_bucket,
+            dst_key,
+            &upload_opts,
+            custom,
+            opts,
+        )
+    } else {
+        copy_single(
+            &mut download,
+            dst,
+            dst_bucket,
+            dst_key,
+            &upload_opts,
+            &custom,
+            opts,
+        )
+    }
+}
+
+/// Uploads the data of `download` with a single upload.
+fn copy_single(
+    download: &mut Download,
+    dst: &Project,
+    dst_bucket: &str,
+    dst_key: &str,
+    upload_opts: &options::Upload,
+    custom: &metadata::Custom,
+    opts: &mut TransferOptions,
+) -> Result<Object> {
+    let mut upload = dst.upload_object(dst_bucket, dst_key, Some(upload_opts))?;
+    let mut buf = vec![0; opts.buffer_size];
+    let mut transferred = 0;
+
+    let res = transfer(
+        download,
+        &mut upload,
+        &mut buf,
+        dst_key,
+        &mut transferred,
+        opts,
+    )
+    .and_then(|_| upload.set_custom_metadata(custom))
+    .and_then(|_| upload.commit());
+    helpers::undo_on_error(res, || upload.abort())?;
+
+    upload.info()
+}
+
+/// Uploads the data of `download` with a multipart upload.
+fn copy_multipart(
+    download: &mut Download,
+    dst: &Project,
+    dst_bucket: &str,
+    dst_key: &str,
+    upload_opts: &options::Upload,
+    custom: metadata::Custom,
+    opts: &mut TransferOptions,
+) -> Result<Object> {
+    let info = dst.begin_upload(dst_bucket, dst_key, Some(upload_opts))?;
+    let mut buf = vec![0; opts.buffer_size];
+    let mut transferred = 0;
+
+    let mut upload_parts = || -> Result<()> {
+        let mut part_number = 1;
+        while !download
+            .fill_buf()
+            .map_err(Error::from_io_error)?
+            .is_empty()
+        {
+            let mut part = dst.upload_part(dst_bucket, dst_key, &info.upload_id, part_number)?;
+            let res = transfer(
+                &mut download.by_ref().take(opts.part_size),
+                &mut part,
+                &mut buf,
+                dst_key,
+                &mut transferred,
+                opts,
+            )
+            .and_then(|_| part.commit());
+            helpers::undo_on_error(res, || part.abort())?;
+
+            part_number += 1;
+        }
+
+        Ok(())
+    };
+
+    let res = upload_parts().and_then(|_| {
+        let commit_opts = options::CommitUpload::new(custom);
+        dst.commit_upload(dst_bucket, dst_key, &info.upload_id, Some(&commit_opts))
+    });
+    helpers::undo_on_error(res, || {
+        dst.abort_upload(dst_bucket, dst_key, &info.upload_id)
+    })
+}
+
+/// Writes all the data read from `reader` into `writer` through `buf`, adding the number of
+/// written bytes to `transferred` and reporting it to the progress callback of `opts` after each
+/// write.
+fn transfer(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    buf: &mut [u8],
+    key: &str,
+    transferred: &mut u64,
+    opts: &mut TransferOptions,
+) -> Result<()> {
+    loop {
+        let n = match reader.read(buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(Error::from_io_error(err)),
+        };
+
+        writer.write_all(&buf[..n]).map_err(Error::from_io_error)?;
+        *transferred += n as u64;
+        opts.report(key, *transferred);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_transfer_options_validate() {
+        TransferOptions::default()
+            .validate()
+            .expect("default options");
+
+        for (opts, names) in [
+            (
+                TransferOptions {
+                    buffer_size: 0,
+                    ..Default::default()
+                },
+                "opts{buffer_size}",
+            ),
+            (
+                TransferOptions {
+                    part_size: TransferOptions::MIN_PART_SIZE - 1,
+                    ..Default::default()
+                },
+                "opts{part_size}",
+            ),
+        ] {
+            match opts.validate() {
+                Err(Error::InvalidArguments(args)) => assert_eq!(args.names, names, "names"),
+                res => panic!("{}: unexpected result: {:?}", names, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_transfer() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let mut opts = TransferOptions {
+            progress: Some(Box::new({
+                let reports = Arc::clone(&reports);
+                move |key: &str, n| reports.lock().unwrap().push((String::from(key), n))
+            })),
+            ..Default::default()
+        };
+
+        let mut dst = Vec::new();
+        let mut buf = vec![0; 4096];
+        let mut transferred = 100;
+        transfer(
+            &mut &data[..],
+            &mut dst,
+            &mut buf,
+            "key",
+            &mut transferred,
+            &mut opts,
+        )
+        .expect("transfer");
+
+        assert_eq!(dst, data, "transferred data");
+        assert_eq!(transferred, 10_100, "transferred bytes");
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![
+                (String::from("key"), 4196),
+                (String::from("key"), 8292),
+                (String::from("key"), 10_100),
+            ],
+            "progress reports"
+        );
+    }
+
+    #[test]
+    fn test_transfer_write_error() {
+        /// Writer that always fails.
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("writer"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut transferred = 0;
+        match transfer(
+            &mut &b"data"[..],
+            &mut FailingWriter,
+            &mut [0; 2],
+            "key",
+            &mut transferred,
+            &mut TransferOptions::default(),
+        ) {
+            Err(Error::Internal(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(transferred, 0, "transferred bytes");
+    }
+}
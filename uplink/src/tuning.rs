@@ -0,0 +1,201 @@
+//! Tuning of the multipart uploads performed by the high-level helpers of this crate.
+//!
+//! The upload throughput depends heavily on the size of the parts and on how many of them are
+//! uploaded concurrently. The configuration of the uplink-c version that this crate binds (see
+//! [`Config`](crate::Config)) doesn't expose any of those knobs, so they are implemented on the
+//! Rust side with [`MultipartTuning`] and consumed by
+//! [`Project::upload_object_from_reader`](crate::Project::upload_object_from_reader).
+
+use crate::error::BoxError;
+use crate::object::upload::PartUpload;
+use crate::{helpers, Error, Project, Result};
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::thread;
+
+/// Tuning of the multipart uploads.
+///
+/// The memory usage of an upload is at most `part_size * max_concurrent_parts` bytes because each
+/// part is buffered in memory until it's uploaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MultipartTuning {
+    /// The size, in bytes, of the parts. The last part may be smaller.
+    ///
+    /// It must be at least [`Self::MIN_PART_SIZE`]. Sizes that are multiple of the segment size of
+    /// the satellite (64 MiB by default) use the storage more efficiently.
+    pub part_size: u64,
+    /// The maximum number of parts uploaded concurrently, each one from its own thread.
+    ///
+    /// It must be at least 1, which uploads the parts sequentially.
+    pub max_concurrent_parts: usize,
+}
+
+impl MultipartTuning {
+    /// The minimum size of the parts of a multipart upload accepted by the satellites, except for
+    /// the last one.
+    pub const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+    /// Returns an [`Error::InvalidArguments`] if any of the fields has an invalid value.
+    pub fn validate(&self) -> Result<()> {
+        if self.part_size < Self::MIN_PART_SIZE {
+            return Err(Error::new_invalid_arguments(
+                "tuning{part_size}",
+                &format!("cannot be less than {} bytes", Self::MIN_PART_SIZE),
+            ));
+        }
+
+        if self.max_concurrent_parts == 0 {
+            return Err(Error::new_invalid_arguments(
+                "tuning{max_concurrent_parts}",
+                "cannot be zero",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MultipartTuning {
+    /// Returns a tuning with parts of 64 MiB uploaded sequentially.
+    fn default() -> Self {
+        Self {
+            part_size: 64 * 1024 * 1024,
+            max_concurrent_parts: 1,
+        }
+    }
+}
+
+/// Uploads the data read from `reader` as the parts of the multipart upload `upload_id`, started
+/// with [`Project::begin_upload`], according to `tuning` without validating it.
+///
+/// It stops at the first error and returns it. The parts which are being uploaded at that moment
+/// are finished before returning.
+pub(crate) fn upload_parts(
+    project: &Project,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    reader: &mut impl Read,
+    tuning: &MultipartTuning,
+) -> Result<()> {
+    thread::scope(|scope| {
+        let mut workers = VecDeque::with_capacity(tuning.max_concurrent_parts);
+        let mut part_number = 1;
+        loop {
+            // The oldest part is finished before reading the next one, so at most
+            // `max_concurrent_parts` parts are held in memory.
+            if workers.len() == tuning.max_concurrent_parts {
+                join(workers.pop_front())?;
+            }
+
+            let data = read_part(reader, tuning.part_size)?;
+            if data.is_empty() {
+                break;
+            }
+
+            let part = project.upload_part(bucket, key, upload_id, part_number)?;
+            workers.push_back(scope.spawn(move || upload_part(part, &data)));
+            part_number += 1;
+        }
+
+        while !workers.is_empty() {
+            join(workers.pop_front())?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Reads from `reader` until filling a part of `part_size` bytes or reaching the end of the data
+/// and returns the read bytes, which are empty when there isn't more data.
+fn read_part(reader: &mut impl Read, part_size: u64) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader
+        .take(part_size)
+        .read_to_end(&mut data)
+        .map_err(|err| Error::new_internal("reading the data of a part", BoxError::from(err)))?;
+    Ok(data)
+}
+
+/// Writes `data` into `part` and commits it, aborting it if any of the operations fails.
+fn upload_part(mut part: PartUpload, data: &[u8]) -> Result<()> {
+    let res = part
+        .write_all(data)
+        .map_err(Error::from_io_error)
+        .and_then(|_| part.commit());
+    helpers::undo_on_error(res, || part.abort())
+}
+
+/// Waits for the upload of a part to finish and returns its result, propagating its panic if it
+/// panicked.
+fn join(worker: Option<thread::ScopedJoinHandle<'_, Result<()>>>) -> Result<()> {
+    match worker {
+        Some(worker) => worker
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_multipart_tuning_validate() {
+        MultipartTuning::default()
+            .validate()
+            .expect("default tuning");
+
+        for (tuning, names) in [
+            (
+                MultipartTuning {
+                    part_size: MultipartTuning::MIN_PART_SIZE - 1,
+                    ..Default::default()
+                },
+                "tuning{part_size}",
+            ),
+            (
+                MultipartTuning {
+                    max_concurrent_parts: 0,
+                    ..Default::default()
+                },
+                "tuning{max_concurrent_parts}",
+            ),
+        ] {
+            match tuning.validate() {
+                Err(Error::InvalidArguments(args)) => assert_eq!(args.names, names, "names"),
+                res => panic!("{}: unexpected result: {:?}", names, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_part() {
+        let data: Vec<u8> = (0..2_500u32).map(|i| i as u8).collect();
+        let mut reader = &data[..];
+
+        assert_eq!(
+            read_part(&mut reader, 1_000).expect("first part"),
+            &data[..1_000],
+            "first part"
+        );
+        assert_eq!(
+            read_part(&mut reader, 1_000).expect("second part"),
+            &data[1_000..2_000],
+            "second part"
+        );
+        assert_eq!(
+            read_part(&mut reader, 1_000).expect("last part"),
+            &data[2_000..],
+            "last part"
+        );
+        assert!(
+            read_part(&mut reader, 1_000)
+                .expect("no more data")
+                .is_empty(),
+            "no more data"
+        );
+    }
+}
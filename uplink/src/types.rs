@@ -0,0 +1,243 @@
+//! Typed bucket names and object keys, validated when they are constructed.
+//!
+//! [`BucketName`] and [`ObjectKey`] dereference to `str`, so they can be passed to any method of
+//! [`Project`](crate::Project) that takes a bucket name or an object key. The `_typed` methods
+//! (e.g. [`Project::copy_object_typed`](crate::Project::copy_object_typed)) take them in
+//! `(bucket, key)` pairs, so a bucket name and a key cannot be transposed.
+//!
+//! ```no_run
+//! use uplink::types::{BucketName, ObjectKey};
+//! use uplink::Project;
+//!
+//! let project = Project::open_from_serialized("my-serialized-access-grant")?;
+//! let bucket = BucketName::new("my-bucket")?;
+//! let key = ObjectKey::new("reports/2024.pdf")?;
+//! let backup = ObjectKey::new("backups/reports/2024.pdf")?;
+//!
+//! project.stat_object(&bucket, &key)?;
+//! project.copy_object_typed((&bucket, &key), (&bucket, &backup), None)?;
+//! # Ok::<(), uplink::Error>(())
+//! ```
+
+use crate::{bucket, helpers, Error, Result};
+
+use std::fmt;
+use std::ops::Deref;
+
+/// A bucket name that follows the naming rules of the satellites, see [`bucket::validate_name`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub struct BucketName(String);
+
+impl BucketName {
+    /// Creates a bucket name from `name`.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `name` contains null bytes or it doesn't
+    /// follow the naming rules, even when
+    /// [`Config::set_validate_bucket_names`](crate::Config::set_validate_bucket_names) disables
+    /// their validation; pass the names that don't follow them as `&str`.
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        helpers::cstring_from_str_fn_arg("bucket", &name)?;
+        bucket::validate_name(&name)?;
+        Ok(Self(name))
+    }
+
+    /// Returns the bucket name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the bucket name and returns its string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for BucketName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for BucketName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BucketName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for BucketName {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self> {
+        Self::new(name)
+    }
+}
+
+impl TryFrom<&str> for BucketName {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self> {
+        Self::new(name)
+    }
+}
+
+/// An object key without null bytes.
+///
+/// The satellites accept any other sequence of bytes as a key, so no other validation is
+/// performed.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub struct ObjectKey(String);
+
+impl ObjectKey {
+    /// Creates an object key from `key`.
+    ///
+    /// It returns an [`Error::InvalidArguments`] if `key` contains null bytes.
+    pub fn new(key: impl Into<String>) -> Result<Self> {
+        let key = key.into();
+        helpers::cstring_from_str_fn_arg("key", &key)?;
+        Ok(Self(key))
+    }
+
+    /// Returns the object key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the object key and returns its string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for ObjectKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for ObjectKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ObjectKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for ObjectKey {
+    type Error = Error;
+
+    fn try_from(key: String) -> Result<Self> {
+        Self::new(key)
+    }
+}
+
+impl TryFrom<&str> for ObjectKey {
+    type Error = Error;
+
+    fn try_from(key: &str) -> Result<Self> {
+        Self::new(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_bucket_name_new() {
+        let name = BucketName::new("my-bucket").expect("valid bucket name");
+        assert_eq!(name.as_str(), "my-bucket", "as str");
+        assert_eq!(&*name, "my-bucket", "deref");
+        assert_eq!(name.to_string(), "my-bucket", "display");
+        assert_eq!(
+            BucketName::try_from("my-bucket").expect("try from &str"),
+            name,
+            "try from &str"
+        );
+        assert_eq!(name.into_string(), "my-bucket", "into string");
+
+        for (case, name) in [
+            ("null byte", "my\0bucket"),
+            ("too short", "ab"),
+            ("uppercase", "My-Bucket"),
+            ("IP address", "192.168.5.4"),
+        ] {
+            match BucketName::new(name) {
+                Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                    assert_eq!(names, "bucket", "{}: invalid argument name", case)
+                }
+                res => panic!("{}: unexpected result: {:?}", case, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_object_key_new() {
+        let key = ObjectKey::new(String::from("Reports/2024 Q1.pdf")).expect("valid key");
+        assert_eq!(key.as_str(), "Reports/2024 Q1.pdf", "as str");
+        assert_eq!(&*key, "Reports/2024 Q1.pdf", "deref");
+        assert_eq!(key.to_string(), "Reports/2024 Q1.pdf", "display");
+        assert_eq!(
+            ObjectKey::try_from("Reports/2024 Q1.pdf").expect("try from &str"),
+            key,
+            "try from &str"
+        );
+
+        match ObjectKey::new("file\0.txt") {
+            Err(Error::InvalidArguments(error::Args { names, .. })) => {
+                assert_eq!(names, "key", "invalid argument name")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_types_serde() {
+        let name = BucketName::new("my-bucket").expect("valid bucket name");
+        let json = serde_json::to_value(&name).expect("serialize bucket name");
+        assert_eq!(
+            json,
+            serde_json::json!("my-bucket"),
+            "serialized bucket name"
+        );
+        let deserialized: BucketName =
+            serde_json::from_value(json).expect("deserialize bucket name");
+        assert_eq!(deserialized, name, "deserialized bucket name");
+        serde_json::from_value::<BucketName>(serde_json::json!("My_Bucket"))
+            .expect_err("deserialize invalid bucket name");
+
+        let key = ObjectKey::new("dir/file.txt").expect("valid key");
+        let json = serde_json::to_value(&key).expect("serialize key");
+        assert_eq!(json, serde_json::json!("dir/file.txt"), "serialized key");
+        let deserialized: ObjectKey = serde_json::from_value(json).expect("deserialize key");
+        assert_eq!(deserialized, key, "deserialized key");
+        serde_json::from_value::<ObjectKey>(serde_json::json!("file\0.txt"))
+            .expect_err("deserialize key with a null byte");
+    }
+}
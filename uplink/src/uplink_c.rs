@@ -1,8 +1,15 @@
 //! Convenient methods implemented for the original `uplink-sys` types.
 
+use crate::error::{self, BoxError, UplinkCode};
 use crate::{Error, Result};
 
+use std::any::Any;
 use std::ffi::CStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
 use uplink_sys as ulksys;
 
@@ -12,186 +19,381 @@ use uplink_sys as ulksys;
 /// For example a UplinkAccessResult struct has 2 fields which are 2 pointers,
 /// one is the access and the other is an error, always one and only one can be
 /// NULL.
-pub(crate) trait Ensurer {
+pub(crate) trait TryEnsure {
     /// Does a shallow check to ensure that the instance is correct according its own rules and it
-    /// returns itself, otherwise it panics.
+    /// returns itself, otherwise it returns an [`Error::Internal`] which indicates the type and
+    /// its fields that are NULL.
+    fn try_ensure(&self) -> Result<&Self>;
+}
+
+/// The panicking counterpart of [`TryEnsure`], which the tests use for asserting the messages of
+/// the errors.
+#[cfg(test)]
+pub(crate) trait Ensurer {
+    /// Does the same check than [`TryEnsure::try_ensure`] but it panics with the error's message
+    /// when the instance isn't correct.
     fn ensure(&self) -> &Self;
 }
 
-impl Ensurer for ulksys::UplinkAccessResult {
+#[cfg(test)]
+impl<T: TryEnsure> Ensurer for T {
     fn ensure(&self) -> &Self {
-        assert!(
-            !self.access.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkAccessResult; access and error fields are both NULL"
-        );
-        self
+        match self.try_ensure() {
+            Ok(v) => v,
+            Err(err) => panic!("{}", err),
+        }
     }
 }
 
-impl Ensurer for ulksys::UplinkBucket {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.name.is_null(),
-            "FFI returned an invalid UplinkBucket; name field is NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkAccessResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkAccessResult",
+            "access",
+            self.access.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkBucketResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.bucket.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkBucketResult; bucket and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkBucket {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_not_null(self, "UplinkBucket", &[("name", self.name.is_null())])
     }
 }
 
-impl Ensurer for ulksys::UplinkCommitUploadResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.object.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkCommitUploadResult; object and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkBucketResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkBucketResult",
+            "bucket",
+            self.bucket.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkDownloadResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.download.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkDownloadResult; download and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkCommitUploadResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkCommitUploadResult",
+            "object",
+            self.object.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkEncryptionKeyResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.encryption_key.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkEncryptionKeyResult; encryption_key and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkDownloadResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkDownloadResult",
+            "download",
+            self.download.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkObject {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.key.is_null(),
-            "FFI returned an invalid UplinkObject; key field is NULL",
-        );
+impl TryEnsure for ulksys::UplinkEncryptionKeyResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkEncryptionKeyResult",
+            "encryption_key",
+            self.encryption_key.is_null(),
+            self.error.is_null(),
+        )
+    }
+}
 
-        self
+impl TryEnsure for ulksys::UplinkObject {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_not_null(self, "UplinkObject", &[("key", self.key.is_null())])
     }
 }
 
-impl Ensurer for ulksys::UplinkPartResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.part.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkPartResult; part and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkPartResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkPartResult",
+            "part",
+            self.part.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkPartUploadResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.part_upload.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkPartUploadResult; part_upload and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkPartUploadResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkPartUploadResult",
+            "part_upload",
+            self.part_upload.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkStringResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.string.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkStringResult; string and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkStringResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkStringResult",
+            "string",
+            self.string.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkUploadInfo {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.upload_id.is_null(),
-            "FFI returned an invalid UplinkUploadInfo; upload_id field is NULL"
-        );
-        assert!(
-            !self.key.is_null(),
-            "FFI returned an invalid UplinkUploadInfo; key field is NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkUploadInfo {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_not_null(
+            self,
+            "UplinkUploadInfo",
+            &[
+                ("upload_id", self.upload_id.is_null()),
+                ("key", self.key.is_null()),
+            ],
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkUploadInfoResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.info.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkUploadInfoResult; info and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkUploadInfoResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkUploadInfoResult",
+            "info",
+            self.info.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::UplinkUploadResult {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.upload.is_null() || !self.error.is_null(),
-            "FFI returned an invalid UplinkUploadResult; upload and error fields are both NULL"
-        );
-        self
+impl TryEnsure for ulksys::UplinkUploadResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "UplinkUploadResult",
+            "upload",
+            self.upload.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::EdgeCredentials {
-    fn ensure(&self) -> &Self {
-        assert!(
-            !self.access_key_id.is_null(),
-            "FFI returned an invalid EdgeCredentials; access_key_id field is NULL"
-        );
-        assert!(
-            !self.secret_key.is_null(),
-            "FFI returned an invalid EdgeCredentials; secret_key field is NULL"
-        );
-        assert!(
-            !self.endpoint.is_null(),
-            "FFI returned an invalid EdgeCredentials; endpoint field is NULL"
-        );
+impl TryEnsure for ulksys::EdgeCredentials {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_not_null(
+            self,
+            "EdgeCredentials",
+            &[
+                ("access_key_id", self.access_key_id.is_null()),
+                ("secret_key", self.secret_key.is_null()),
+                ("endpoint", self.endpoint.is_null()),
+            ],
+        )
+    }
+}
 
-        self
+impl TryEnsure for ulksys::EdgeCredentialsResult {
+    fn try_ensure(&self) -> Result<&Self> {
+        ensure_result(
+            self,
+            "EdgeCredentialsResult",
+            "credentials",
+            self.credentials.is_null(),
+            self.error.is_null(),
+        )
     }
 }
 
-impl Ensurer for ulksys::EdgeCredentialsResult {
-    fn ensure(&self) -> &Self {
-        assert!(!self.credentials.is_null() || !self.error.is_null(),
-        "FFI returned an invalid EdgeCredentialsResult; credentials and error fields are both NULL",
-        );
+/// Returns `value` if the `fields` of the FFI type `type_name`, which are pairs of the field's
+/// name and whether it's NULL, aren't NULL, otherwise an [`Error::Internal`] that indicates the
+/// NULL fields.
+fn ensure_not_null<'a, T>(value: &'a T, type_name: &str, fields: &[(&str, bool)]) -> Result<&'a T> {
+    let null_fields: Vec<&str> = fields
+        .iter()
+        .filter(|(_, is_null)| *is_null)
+        .map(|(name, _)| *name)
+        .collect();
+    if null_fields.is_empty() {
+        return Ok(value);
+    }
+
+    Err(null_fields_error(type_name, &null_fields))
+}
+
+/// Returns `value`, which is a result of the FFI type `type_name`, if its `field` or its error
+/// isn't NULL, otherwise an [`Error::Internal`] that indicates that both are NULL.
+fn ensure_result<'a, T>(
+    value: &'a T,
+    type_name: &str,
+    field: &str,
+    field_is_null: bool,
+    error_is_null: bool,
+) -> Result<&'a T> {
+    if field_is_null && error_is_null {
+        return Err(null_fields_error(type_name, &[field, "error"]));
+    }
+
+    Ok(value)
+}
+
+/// Returns an [`Error::Internal`] that indicates that the FFI returned an invalid `type_name`
+/// value because its `null_fields` are NULL.
+fn null_fields_error(type_name: &str, null_fields: &[&str]) -> Error {
+    let fields = match null_fields {
+        [field] => format!("{} field is NULL", field),
+        [first, second] => format!("{} and {} fields are both NULL", first, second),
+        [init @ .., last] => format!("{} and {} fields are NULL", init.join(", "), last),
+        [] => unreachable!("BUG: an error requires at least one NULL field"),
+    };
+
+    Error::new_internal(
+        &format!("FFI returned an invalid {}; {}", type_name, fields),
+        BoxError::from("the FFI returned a value that violates its invariants"),
+    )
+}
 
-        self
+/// Calls `f`, which converts values returned by the FFI, and returns its result or an
+/// [`Error::Internal`] with `ctx` and the panic's message if it panics.
+///
+/// It stops the panics caused by values that violate the FFI invariants from unwinding through the
+/// callers, which abort the process when they are FFI callbacks. `f` must free the FFI values that
+/// it owns before calling anything that may panic, otherwise they leak when the panic is caught.
+pub(crate) fn ffi_guard<T>(ctx: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    // The values that `f` captures aren't used after a panic, so they cannot be observed in a
+    // broken state.
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(Error::new_internal(
+            ctx,
+            BoxError::from(panic_message(payload.as_ref())),
+        ))
+    })
+}
+
+/// Returns the message of a panic's `payload`, which is a `&str` or a `String` when the panic was
+/// raised with a message.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        String::from(*msg)
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        String::from("panic without message")
+    }
+}
+
+/// Runs `op`, which calls the FFI, in a separate thread and waits for it up to `timeout`.
+///
+/// It always spawns a new OS thread, even if `op` returns immediately.
+///
+/// The FFI calls cannot be cancelled, so when `timeout` elapses, it returns an
+/// [`UplinkCode::Canceled`] error with `ctx` in its message and `op` keeps running in the
+/// background until the FFI returns, discarding its result. `op` must own everything that the FFI
+/// uses for not freeing it meanwhile.
+///
+/// It returns an [`Error::Internal`] if the thread cannot be spawned or `op` panics.
+pub(crate) fn run_with_timeout<T: Send + 'static>(
+    ctx: &str,
+    timeout: Duration,
+    op: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let (tx, rx) = mpsc::channel();
+    let panic_ctx = format!("{} panicked", ctx);
+    thread::Builder::new()
+        .name(String::from("uplink-op"))
+        .spawn(move || {
+            // The receiver is gone when the operation has been abandoned.
+            let _ = tx.send(ffi_guard(&panic_ctx, op));
+        })
+        .map_err(|err| {
+            Error::new_internal(
+                &format!("{}: cannot spawn the operation thread", ctx),
+                BoxError::from(err),
+            )
+        })?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(res) => res,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::Uplink(error::Uplink::with_code(
+            UplinkCode::Canceled,
+            &format!("{} timed out after {:?}", ctx, timeout),
+        ))),
+        // The sender only drops without sending if the thread is killed.
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(Error::new_internal(
+            &format!("{}: the operation thread ended without a result", ctx),
+            BoxError::from("the operation thread disconnected"),
+        )),
+    }
+}
+
+/// Tracks whether an operation on an FFI handle (e.g. an upload or a download) is in progress, so
+/// the types that own a handle never issue overlapping FFI calls on it.
+///
+/// The handles support a single operation at a time and the types that own them only allow it
+/// through `&mut self` and not being `Sync`, but the FFI calls on the same handle would race
+/// inside the Go side if those guarantees are circumvented (e.g. through `unsafe` code), so the
+/// operations are also checked at runtime without blocking.
+#[derive(Debug, Default)]
+pub(crate) struct OperationFlag(AtomicBool);
+
+impl OperationFlag {
+    /// Marks an operation on the `handle` as in progress until the returned guard drops.
+    ///
+    /// It returns an [`std::io::Error`] of kind [`std::io::ErrorKind::WouldBlock`], whose payload
+    /// is an [`Error::Internal`] with `handle` in its message, if another operation is in
+    /// progress.
+    pub(crate) fn enter(&self, handle: &str) -> std::io::Result<OperationGuard<'_>> {
+        if self
+            .0
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                Error::new_internal(
+                    &format!("{}: another operation is in progress", handle),
+                    BoxError::from("the FFI handles don't allow concurrent operations"),
+                ),
+            ));
+        }
+
+        Ok(OperationGuard(&self.0))
+    }
+}
+
+/// The guard of an operation in progress returned by [`OperationFlag::enter`], which marks the
+/// operation as finished when it drops.
+#[derive(Debug)]
+pub(crate) struct OperationGuard<'a>(&'a AtomicBool);
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
     }
 }
 
 /// Returns a string from an FFI string result or an
 ///
 /// * [Uplink error](crate::error::Uplink) if `ffi_result` contains an error.
-/// * [Internal error](crate::error::Internal) if the string contains invalid UTF-8 characters.
+/// * [Internal error](crate::error::Internal) if `ffi_result` is invalid (see [`TryEnsure`]) or the
+///   string contains invalid UTF-8 characters.
 ///
 /// It takes ownership of `ffi_result`, hence this function frees `ffi_result` before returning.
 pub(crate) fn string_from_ffi_string_result(
     ffi_result: ulksys::UplinkStringResult,
 ) -> Result<String> {
-    ffi_result.ensure();
+    ffi_result.try_ensure()?;
 
     if let Some(e) = Error::new_uplink(ffi_result.error) {
         // SAFETY: the FFI release result memory of those fields that they aren't `NULL` otherwise
@@ -225,6 +427,7 @@ mod test {
     use super::*;
 
     use std::ffi::CString;
+    use std::os::raw::c_char;
     use std::ptr;
 
     #[test]
@@ -1037,4 +1240,369 @@ mod test {
 
         creds_res.ensure();
     }
+
+    /// Asserts that `res` is an [`Error::Internal`] with the `want` context message.
+    fn assert_internal_error<T: std::fmt::Debug>(res: Result<T>, want: &str) {
+        match res {
+            Err(Error::Internal(internal)) => assert_eq!(internal.ctx_msg, want, "context message"),
+            res => panic!("{}: unexpected result: {:?}", want, res),
+        }
+    }
+
+    #[test]
+    fn test_try_ensure_results_both_null() {
+        assert_internal_error(
+            ulksys::UplinkAccessResult {
+                access: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkAccessResult; access and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkBucketResult {
+                bucket: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkBucketResult; bucket and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkCommitUploadResult {
+                object: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkCommitUploadResult; object and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkDownloadResult {
+                download: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkDownloadResult; download and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkEncryptionKeyResult {
+                encryption_key: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkEncryptionKeyResult; encryption_key and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkPartResult {
+                part: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkPartResult; part and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkPartUploadResult {
+                part_upload: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkPartUploadResult; part_upload and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkStringResult {
+                string: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkStringResult; string and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkUploadInfoResult {
+                info: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkUploadInfoResult; info and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::UplinkUploadResult {
+                upload: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid UplinkUploadResult; upload and error fields are both NULL",
+        );
+        assert_internal_error(
+            ulksys::EdgeCredentialsResult {
+                credentials: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }
+            .try_ensure()
+            .map(|_| ()),
+            "FFI returned an invalid EdgeCredentialsResult; credentials and error fields are both NULL",
+        );
+    }
+
+    #[test]
+    fn test_try_ensure_results_valid() {
+        let mut error = ulksys::UplinkError {
+            code: 0,
+            message: ptr::null_mut(),
+        };
+
+        ulksys::UplinkAccessResult {
+            access: ptr::null_mut(),
+            error: &mut error,
+        }
+        .try_ensure()
+        .expect("access result with an error");
+        ulksys::UplinkDownloadResult {
+            download: &mut ulksys::UplinkDownload { _handle: 0 },
+            error: ptr::null_mut(),
+        }
+        .try_ensure()
+        .expect("download result with a download");
+        ulksys::UplinkUploadResult {
+            upload: &mut ulksys::UplinkUpload { _handle: 0 },
+            error: &mut error,
+        }
+        .try_ensure()
+        .expect("upload result with an upload and an error");
+    }
+
+    #[test]
+    fn test_try_ensure_null_fields() {
+        let bucket = ulksys::UplinkBucket {
+            name: ptr::null_mut(),
+            created: 0,
+        };
+        assert_internal_error(
+            bucket.try_ensure().map(|_| ()),
+            "FFI returned an invalid UplinkBucket; name field is NULL",
+        );
+
+        let obj = ulksys::UplinkObject {
+            key: ptr::null_mut(),
+            is_prefix: false,
+            system: ulksys::UplinkSystemMetadata {
+                created: 0,
+                expires: 0,
+                content_length: 0,
+            },
+            custom: ulksys::UplinkCustomMetadata {
+                entries: ptr::null_mut(),
+                count: 0,
+            },
+        };
+        assert_internal_error(
+            obj.try_ensure().map(|_| ()),
+            "FFI returned an invalid UplinkObject; key field is NULL",
+        );
+
+        let key = CString::new("key").unwrap();
+        let mut info = ulksys::UplinkUploadInfo {
+            upload_id: ptr::null_mut(),
+            key: key.as_ptr() as *mut c_char,
+            is_prefix: false,
+            system: ulksys::UplinkSystemMetadata {
+                created: 0,
+                expires: 0,
+                content_length: 0,
+            },
+            custom: ulksys::UplinkCustomMetadata {
+                entries: ptr::null_mut(),
+                count: 0,
+            },
+        };
+        assert_internal_error(
+            info.try_ensure().map(|_| ()),
+            "FFI returned an invalid UplinkUploadInfo; upload_id field is NULL",
+        );
+        info.key = ptr::null_mut();
+        assert_internal_error(
+            info.try_ensure().map(|_| ()),
+            "FFI returned an invalid UplinkUploadInfo; upload_id and key fields are both NULL",
+        );
+        info.upload_id = key.as_ptr() as *mut c_char;
+        assert_internal_error(
+            info.try_ensure().map(|_| ()),
+            "FFI returned an invalid UplinkUploadInfo; key field is NULL",
+        );
+        info.key = key.as_ptr() as *mut c_char;
+        info.try_ensure().expect("valid upload info");
+
+        let mut creds = ulksys::EdgeCredentials {
+            access_key_id: ptr::null_mut(),
+            secret_key: ptr::null_mut(),
+            endpoint: ptr::null_mut(),
+        };
+        assert_internal_error(
+            creds.try_ensure().map(|_| ()),
+            "FFI returned an invalid EdgeCredentials; access_key_id, secret_key and endpoint fields are NULL",
+        );
+        creds.access_key_id = key.as_ptr() as *mut c_char;
+        creds.endpoint = key.as_ptr() as *mut c_char;
+        assert_internal_error(
+            creds.try_ensure().map(|_| ()),
+            "FFI returned an invalid EdgeCredentials; secret_key field is NULL",
+        );
+        creds.secret_key = key.as_ptr() as *mut c_char;
+        creds.try_ensure().expect("valid edge credentials");
+    }
+
+    #[test]
+    fn test_ffi_guard() {
+        assert_eq!(ffi_guard("no panic", || Ok(5)).expect("no panic"), 5);
+        assert_internal_error(
+            ffi_guard("error", || -> Result<()> {
+                Err(Error::new_internal("inner error", BoxError::from("error")))
+            }),
+            "inner error",
+        );
+
+        let msg = String::from("formatted message");
+        for (res, want) in [
+            (
+                ffi_guard("str panic", || -> Result<()> { panic!("static message") }),
+                "static message",
+            ),
+            (
+                ffi_guard("string panic", || -> Result<()> { panic!("{}", msg) }),
+                "formatted message",
+            ),
+            (
+                ffi_guard("any panic", || -> Result<()> { std::panic::panic_any(5) }),
+                "panic without message",
+            ),
+        ] {
+            match res {
+                Err(Error::Internal(internal)) => assert_eq!(
+                    std::error::Error::source(&internal).map(|e| e.to_string()),
+                    Some(String::from(want)),
+                    "{}: panic message",
+                    internal.ctx_msg
+                ),
+                res => panic!("{}: unexpected result: {:?}", want, res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        assert_eq!(
+            run_with_timeout("fast", Duration::from_secs(5), || Ok(5)).expect("fast operation"),
+            5
+        );
+        assert_internal_error(
+            run_with_timeout("panic", Duration::from_secs(5), || -> Result<()> {
+                panic!("operation")
+            }),
+            "panic panicked",
+        );
+
+        let timeout = Duration::from_millis(100);
+        let start = std::time::Instant::now();
+        let res = run_with_timeout("slow", timeout, || {
+            std::thread::sleep(Duration::from_secs(5));
+            Ok(())
+        });
+        let elapsed = start.elapsed();
+        match res {
+            Err(Error::Uplink(err)) => {
+                assert_eq!(err.code(), UplinkCode::Canceled, "code");
+                assert!(err.message().starts_with("slow timed out"), "message");
+            }
+            res => panic!("slow operation: unexpected result: {:?}", res),
+        }
+        assert!(
+            elapsed >= timeout && elapsed < Duration::from_secs(2),
+            "returned when the timeout elapsed: {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_operation_flag() {
+        let flag = OperationFlag::default();
+        let guard = flag.enter("handle").expect("first operation");
+        match flag.enter("handle") {
+            Err(err) => {
+                assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock, "error kind");
+                match Error::from_io_chain(&err) {
+                    Some(Error::Internal(internal)) => assert_eq!(
+                        internal.ctx_msg, "handle: another operation is in progress",
+                        "error message"
+                    ),
+                    payload => panic!("unexpected error payload: {:?}", payload),
+                }
+            }
+            Ok(_) => panic!("overlapping operation entered"),
+        }
+        drop(guard);
+        flag.enter("handle")
+            .expect("operation after the previous one finished");
+    }
+
+    #[test]
+    fn test_operation_flag_threads() {
+        use std::sync::atomic::AtomicUsize;
+
+        // Each thread hammers the flag calling a stub of an FFI call that detects overlapping
+        // calls, which must never happen, while the rejected operations get the typed error.
+        let flag = OperationFlag::default();
+        let active = AtomicBool::new(false);
+        let (entered, rejected) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    for _ in 0..2_000 {
+                        match flag.enter("upload") {
+                            Ok(_guard) => {
+                                assert!(!active.swap(true, Ordering::SeqCst), "overlapping call");
+                                thread::yield_now();
+                                active.store(false, Ordering::SeqCst);
+                                entered.fetch_add(1, Ordering::Relaxed);
+                            }
+                            Err(err) => {
+                                assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock, "kind");
+                                assert!(
+                                    matches!(Error::from_io_chain(&err), Some(Error::Internal(_))),
+                                    "error payload: {:?}",
+                                    err
+                                );
+                                rejected.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(
+            entered.load(Ordering::Relaxed) + rejected.load(Ordering::Relaxed),
+            4_000,
+            "operations"
+        );
+        assert!(entered.load(Ordering::Relaxed) > 0, "entered operations");
+    }
+
+    #[test]
+    fn test_string_from_ffi_string_result_invalid() {
+        assert_internal_error(
+            string_from_ffi_string_result(ulksys::UplinkStringResult {
+                string: ptr::null_mut(),
+                error: ptr::null_mut(),
+            }),
+            "FFI returned an invalid UplinkStringResult; string and error fields are both NULL",
+        );
+    }
 }
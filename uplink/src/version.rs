@@ -0,0 +1,101 @@
+//! Versions of this crate and of the libraries that it's built with.
+
+use std::fmt;
+
+use uplink_sys as ulksys;
+
+/// Returns the versions of this crate and of the uplink-c library, and the Go runtime bundled
+/// with it, that this crate is built with.
+///
+/// It's useful for reporting issues because the behavior of this crate depends on the bundled
+/// uplink-c revision.
+pub fn version() -> Version {
+    Version {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        uplink_c: ulksys::UPLINK_C_VERSION,
+        go: ulksys::UPLINK_C_GO_VERSION,
+    }
+}
+
+/// The versions returned by [`version`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    /// The version of this crate (e.g. `0.10.1`).
+    pub crate_version: &'static str,
+    /// The git tag of the bundled uplink-c (e.g. `v1.9.0`), or its `git describe` output when it's
+    /// built from a commit that isn't tagged.
+    pub uplink_c: &'static str,
+    /// The version of the Go toolchain that built the bundled uplink-c (e.g. `go1.19.13`), or
+    /// `unknown` when it isn't available (e.g. the documentation built by docs.rs).
+    pub go: &'static str,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "uplink-rust {} (uplink-c {}, {})",
+            self.crate_version, self.uplink_c, self.go
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        let v = version();
+
+        assert_eq!(v.crate_version, env!("CARGO_PKG_VERSION"), "crate version");
+
+        assert!(!v.uplink_c.is_empty(), "uplink-c version is empty");
+        assert!(
+            is_version_tag(v.uplink_c) || is_commit_hash(v.uplink_c),
+            "uplink-c version isn't a version tag nor a commit hash: {}",
+            v.uplink_c
+        );
+
+        assert!(
+            v.go == "unknown"
+                || v.go
+                    .strip_prefix("go")
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit())),
+            "Go version isn't well-formed: {}",
+            v.go
+        );
+
+        assert_eq!(
+            v.to_string(),
+            format!(
+                "uplink-rust {} (uplink-c {}, {})",
+                v.crate_version, v.uplink_c, v.go
+            ),
+            "display"
+        );
+    }
+
+    /// Returns true if `s` starts with a version tag (e.g. `v1.9.0` or `v1.9.0-3-gabcdef1`).
+    fn is_version_tag(s: &str) -> bool {
+        let version = match s.strip_prefix('v') {
+            Some(rest) => rest.split('-').next().unwrap_or_default(),
+            None => return false,
+        };
+
+        let mut parts = 0;
+        for part in version.split('.') {
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                return false;
+            }
+            parts += 1;
+        }
+
+        parts == 3
+    }
+
+    /// Returns true if `s` is an abbreviated git commit hash.
+    fn is_commit_hash(s: &str) -> bool {
+        s.len() >= 7 && s.chars().all(|c| c.is_ascii_hexdigit())
+    }
+}
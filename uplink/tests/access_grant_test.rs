@@ -1,8 +1,11 @@
-use uplink::access::{Grant, Permission, SharePrefix};
+use uplink::access::{Grant, Issuer, Permission, SharePrefix};
+use uplink::error::UplinkCode;
 use uplink::Result as UlResult;
-use uplink::{error, Bucket, Error, Object, Project};
+use uplink::{Bucket, Error, Object, Project};
 
 use std::io::{Read, Write};
+use std::thread;
+use std::time::Duration;
 
 mod common;
 
@@ -22,6 +25,38 @@ fn integration_grant_new() {
         grant.satellite_address().expect("satellite address"),
         "satellite address"
     );
+    assert_eq!(
+        common::SATELLITE_ADDR,
+        grant
+            .satellite()
+            .expect("parsed satellite address")
+            .to_string(),
+        "parsed satellite address"
+    );
+}
+
+#[test]
+fn integration_grant_strings_outlive_grant() {
+    let env = common::Environment::load();
+    let (serialized, satellite) = {
+        let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+        (
+            grant.serialize().expect("serialize valid access grant"),
+            grant.satellite_address().expect("satellite address"),
+        )
+    };
+
+    // Churn the C and Rust allocators, so the freed FFI strings are likely reused.
+    for _ in 0..100 {
+        let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+        let _ = grant.serialize().expect("serialize valid access grant");
+        let _ = grant.satellite_address().expect("satellite address");
+        drop(vec![0xffu8; serialized.len()]);
+    }
+
+    assert_eq!(env.access_grant, serialized, "serialize");
+    assert_eq!(common::SATELLITE_ADDR, satellite, "satellite address");
+    Grant::new(&serialized).expect("parse the serialized access grant");
 }
 
 #[test]
@@ -48,7 +83,7 @@ fn integration_grant_override_encryption_key() {
     let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
 
     // Create an access grant for the user and restrict it to its bucket.
-    let grant_user = grant_root
+    let mut grant_user = grant_root
         .share(
             &Permission::full(),
             Some(vec![
@@ -140,7 +175,9 @@ fn integration_grant_share() {
 
         // Listing buckets with this restricted access grant.
         let proj_restricted = &mut Project::open(&grant);
-        let it = proj_restricted.list_buckets(None);
+        let it = proj_restricted
+            .list_buckets(None)
+            .expect("list buckets with restricted access grant");
         assert_eq!(
             1,
             it.count(),
@@ -184,7 +221,7 @@ fn integration_grant_share() {
             "write-only access grant returns an error when listing objects",
         );
         match res.unwrap_err() {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when listing objects with a write-only restricted access grant", err),
         };
 
@@ -195,12 +232,12 @@ fn integration_grant_share() {
             "write-only access grant returns an error when deleting buckets",
         );
         match res.unwrap_err() {
-            Error::Uplink(error::Uplink::Internal(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::Internal => {},
             err => panic!("{} is an unexpected error when deleting buckets with a write-only restricted access grant", err),
         };
 
         // Deleting objects with the write-only access grant.
-        let res = proj_restricted.delete_object(&bucket1_name, &object_key_writeonly);
+        let res = proj_restricted.delete_object(&bucket1_name, object_key_writeonly);
         assert!(
             res.is_ok(),
             "write-only access grant returns no error when deleting objects",
@@ -253,7 +290,7 @@ fn integration_grant_share() {
             .expect("commit an object upload to another prefix");
 
         let upload = &mut proj_upload
-            .upload_object(&bucket1_name, &format!("{}2", object_key), None)
+            .upload_object(&bucket1_name, format!("{}2", object_key), None)
             .expect("upload object");
         upload
             .write_all(object_data.as_bytes())
@@ -266,7 +303,7 @@ fn integration_grant_share() {
         let res = proj_upload.upload_object(&bucket1_name, "/pair-2/data.txt", None);
 
         match res.unwrap_err() {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {}
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {}
             err => panic!(
                 "{} is an unexpected error when uploading data to a unauthorized prefix",
                 err
@@ -300,10 +337,10 @@ fn integration_grant_share() {
 
         // Downloading from another prefix.
         match &mut proj_download
-            .download_object(&bucket1_name, &format!("{}2", object_key), None)
+            .download_object(&bucket1_name, format!("{}2", object_key), None)
             .expect_err("download object")
         {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {}
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {}
             err => panic!(
                 "{} is an unexpected error when downloading an object from another prefix",
                 err
@@ -315,7 +352,7 @@ fn integration_grant_share() {
             .list_objects(&bucket1_name, None)
             .expect("list objects without list permissions");
         match it.collect::<UlResult<Vec<Object>>>().expect_err("list objects iterator without list permissions") {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when listing objects with an upload restricted access grant", err),
         };
 
@@ -323,7 +360,7 @@ fn integration_grant_share() {
             .list_objects(&bucket1_name, None)
             .expect("list objects without list permissions");
         match it.collect::<UlResult<Vec<Object>>>().expect_err("list objects iterator without list permissions") {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when listing objects with a download restricted access grant", err),
         };
 
@@ -332,7 +369,7 @@ fn integration_grant_share() {
             .download_object(&bucket1_name, object_key, None)
             .expect_err("download object");
         match err {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when downloading the object with an upload restricted access grant", err),
         };
 
@@ -344,7 +381,7 @@ fn integration_grant_share() {
             .write_all(object_data.as_bytes())
             .expect("upload object write data");
         match upload.commit().expect_err("commit an object upload with a download restricted access grant") {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when uploading an object with a download restricted access grant", err),
         };
 
@@ -353,7 +390,7 @@ fn integration_grant_share() {
             .delete_object(&bucket1_name, object_key)
             .expect_err("list objects without delete permissions");
         match err {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when deleting the object with an upload restricted access grant", err),
         };
 
@@ -361,7 +398,7 @@ fn integration_grant_share() {
             .delete_object(&bucket1_name, object_key)
             .expect_err("list objects without delete permissions");
         match err {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {},
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {},
             err => panic!("{} is an unexpected error when deleting the object with a download restricted access grant", err),
         };
     }
@@ -384,9 +421,11 @@ fn integration_grant_share() {
         let grant = grant_root.share(&perm, None).expect("shared grant");
 
         let project = &mut Project::open(&grant);
-        let it = project.list_buckets(None);
-        match it.collect::<UlResult<Vec<Bucket>>>().expect_err("listing buckets with a grant that cannot be used before a future date") {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {}
+        let res = project
+            .list_buckets(None)
+            .and_then(|it| it.collect::<UlResult<Vec<Bucket>>>());
+        match res.expect_err("listing buckets with a grant that cannot be used before a future date") {
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {}
             err => panic!(
                 "{} is an unexpected error when listing buckets with a grant that cannot be used before a future date",
                 err
@@ -394,16 +433,18 @@ fn integration_grant_share() {
         };
 
         thread::sleep(Duration::from_secs(1));
-        let it = project.list_buckets(None);
-        let buckets = it
-            .collect::<UlResult<Vec<Bucket>>>()
+        let buckets = project
+            .list_buckets(None)
+            .and_then(|it| it.collect::<UlResult<Vec<Bucket>>>())
             .expect("listing buckets with a grant that has a not before in the past");
         assert_eq!(2, buckets.len(), "number of buckets");
 
         thread::sleep(Duration::from_secs(2));
-        let it = project.list_buckets(None);
-        match it.collect::<UlResult<Vec<Bucket>>>().expect_err("listing buckets with a grant that cannot be used after a past date") {
-            Error::Uplink(error::Uplink::PermissionDenied(_)) => {}
+        let res = project
+            .list_buckets(None)
+            .and_then(|it| it.collect::<UlResult<Vec<Bucket>>>());
+        match res.expect_err("listing buckets with a grant that cannot be used after a past date") {
+            Error::Uplink(err) if err.code() == UplinkCode::PermissionDenied => {}
             err => panic!(
                 "{} is an unexpected error when listing buckets with a grant that cannot be used after a past date",
                 err
@@ -418,3 +459,162 @@ fn integration_grant_share() {
         .delete_bucket_with_objects(&bucket2_name)
         .expect("clean up delete bucket with objects");
 }
+
+#[test]
+fn integration_grant_inspect() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+
+    let root = grant_root.inspect().expect("inspect root grant");
+    assert_eq!(
+        root.satellite,
+        grant_root.satellite().expect("satellite"),
+        "root satellite"
+    );
+    assert!(root.permission.allow_download, "root allow download");
+    assert!(root.permission.allow_upload, "root allow upload");
+    assert!(root.permission.allow_list, "root allow list");
+    assert!(root.permission.allow_delete, "root allow delete");
+    assert!(root.prefixes.is_empty(), "root prefixes");
+
+    let bucket_name = common::generate_name("grant-inspect");
+    let grant_shared = grant_root
+        .share(
+            &Permission::read_only(),
+            Some(vec![
+                SharePrefix::new(&bucket_name, "logs/").expect("share prefix")
+            ]),
+        )
+        .expect("share read only grant");
+
+    let shared = grant_shared.inspect().expect("inspect shared grant");
+    assert_eq!(root.satellite, shared.satellite, "shared satellite");
+    assert!(shared.permission.allow_download, "shared allow download");
+    assert!(!shared.permission.allow_upload, "shared allow upload");
+    assert!(shared.permission.allow_list, "shared allow list");
+    assert!(!shared.permission.allow_delete, "shared allow delete");
+    assert_eq!(1, shared.prefixes.len(), "number of shared prefixes");
+    assert_eq!(
+        bucket_name, shared.prefixes[0].bucket,
+        "shared prefix bucket"
+    );
+    assert_eq!(
+        Some("logs/"),
+        shared.prefixes[0].prefix.as_deref(),
+        "shared prefix"
+    );
+    assert!(
+        shared.has_encryption_overrides,
+        "shared grant has the encryption key of the prefix"
+    );
+}
+
+#[test]
+fn integration_issuer_issue_expires() {
+    let env = common::Environment::load();
+    let issuer = Issuer::new(Grant::new(&env.access_grant).expect("access grant parsing"));
+
+    let bucket_name = common::generate_name("issuer-expires");
+    issuer
+        .project()
+        .create_bucket(&bucket_name)
+        .expect("create bucket");
+    issuer
+        .project()
+        .put_object(&bucket_name, "data.txt", b"issued", None)
+        .expect("put object");
+
+    let issued = issuer
+        .issue(
+            &Permission::read_only(),
+            Some(vec![
+                SharePrefix::full_bucket(&bucket_name).expect("share prefix")
+            ]),
+            Duration::from_secs(2),
+        )
+        .expect("issue grant");
+    assert!(!issued.id().is_empty(), "issued grant identifier");
+    common::assert_epoch_timestamp_from_now(
+        issued.expires_at() - Duration::from_secs(2),
+        Duration::from_secs(5),
+        "issued grant expiration",
+    );
+
+    let grant = Grant::new(issued.serialized()).expect("issued grant parsing");
+    let info = grant.inspect().expect("inspect issued grant");
+    assert!(!info.permission.allow_upload, "issued grant allow upload");
+    assert!(!info.permission.allow_delete, "issued grant allow delete");
+
+    let project = Project::open(&grant);
+    project
+        .stat_object(&bucket_name, "data.txt")
+        .expect("stat object with the issued grant");
+
+    thread::sleep(Duration::from_secs(3));
+    match project.stat_object(&bucket_name, "data.txt") {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+        res => panic!("expected a permission denied error, got: {:?}", res),
+    }
+
+    issuer
+        .project()
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("delete bucket");
+}
+
+#[test]
+fn integration_issuer_revoke() {
+    let env = common::Environment::load();
+    let issuer = Issuer::new(Grant::new(&env.access_grant).expect("access grant parsing"));
+
+    let bucket_name = common::generate_name("issuer-revoke");
+    issuer
+        .project()
+        .create_bucket(&bucket_name)
+        .expect("create bucket");
+    issuer
+        .project()
+        .put_object(&bucket_name, "data.txt", b"issued", None)
+        .expect("put object");
+
+    let prefixes = || {
+        Some(vec![
+            SharePrefix::full_bucket(&bucket_name).expect("share prefix")
+        ])
+    };
+    let kept = issuer
+        .issue(
+            &Permission::read_only(),
+            prefixes(),
+            Duration::from_secs(60),
+        )
+        .expect("issue kept grant");
+    let revoked = issuer
+        .issue(
+            &Permission::read_only(),
+            prefixes(),
+            Duration::from_secs(60),
+        )
+        .expect("issue revoked grant");
+    assert_ne!(kept.id(), revoked.id(), "unique identifiers");
+
+    // The revoked grant isn't used before revoking it because the satellite may cache that it
+    // isn't revoked.
+    issuer.revoke(&revoked).expect("revoke issued grant");
+
+    let project = Project::open(&Grant::new(revoked.serialized()).expect("revoked grant parsing"));
+    match project.stat_object(&bucket_name, "data.txt") {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+        res => panic!("expected a permission denied error, got: {:?}", res),
+    }
+
+    let project = Project::open(&Grant::new(kept.serialized()).expect("kept grant parsing"));
+    project
+        .stat_object(&bucket_name, "data.txt")
+        .expect("stat object with the grant that isn't revoked");
+
+    issuer
+        .project()
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("delete bucket");
+}
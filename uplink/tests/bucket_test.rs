@@ -5,8 +5,13 @@
 //
 // Other integration tests may check some bucket operations that are also tested in this test file.
 
-use uplink::access::Grant;
-use uplink::{error, Error, Project};
+use uplink::access::{Grant, Permission};
+use uplink::error::{self, UplinkCode};
+use uplink::project::options;
+use uplink::{bucket, Config, Error, Project};
+
+use std::thread;
+use std::time::Duration;
 
 mod common;
 
@@ -49,7 +54,7 @@ fn integration_bucket_operations() {
     // Stat an non-existing bucket returns an error.
     let res = project.stat_bucket("does-not-exist");
     match res.expect_err("stat an non-existing bucket should return error") {
-        Error::Uplink(error::Uplink::BucketNotFound(_)) => {}
+        Error::Uplink(err) if err.code() == UplinkCode::BucketNotFound => {}
         err => panic!(
             "{} is an unexpected error when stating an non-existing bucket",
             err
@@ -57,7 +62,7 @@ fn integration_bucket_operations() {
     }
 
     // List buckets.
-    let mut it = project.list_buckets(None);
+    let mut it = project.list_buckets(None).expect("list buckets");
     let res = it
         .next()
         .expect("listing buckets has the crated bucket as the first element");
@@ -78,3 +83,316 @@ fn integration_bucket_operations() {
         .delete_bucket_with_objects(&bucket_name)
         .expect("clean up: delete bucket with objects");
 }
+
+#[test]
+fn integration_bucket_create_twice() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    let bucket_name = common::generate_name("bucket-create-twice");
+
+    let (bucket_1, created) = project
+        .create_bucket(&bucket_name)
+        .expect("create bucket not to fail");
+    assert!(created, "create bucket that doesn't exist");
+
+    let (bucket_2, created) = project
+        .create_bucket(&bucket_name)
+        .expect("create bucket that already exists not to fail");
+    assert!(!created, "create bucket that already exists");
+    assert_eq!(bucket_1.name, bucket_2.name, "created bucket names");
+    assert_eq!(
+        bucket_1.created_at, bucket_2.created_at,
+        "created bucket creation times"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_bucket_create_with_attribution() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    // The satellite doesn't expose the attribution of a bucket through the uplink, so this only
+    // verifies that the buckets are created with different user agents without errors.
+    let bucket_names = [
+        common::generate_name("bucket-attribution-a"),
+        common::generate_name("bucket-attribution-b"),
+    ];
+    for (name, user_agent) in bucket_names
+        .iter()
+        .zip(["partner-a/1.0", "partner-b/2.1 (tenant 42)"])
+    {
+        let (bucket, created) = project
+            .create_bucket_with_attribution(name, user_agent)
+            .expect("create bucket with attribution");
+        assert!(created, "{}: bucket created", user_agent);
+        assert_eq!(&bucket.name, name, "{}: bucket name", user_agent);
+        project.stat_bucket(name).expect("stat attributed bucket");
+    }
+
+    match project.create_bucket_with_attribution(
+        &common::generate_name("bucket-attribution-invalid"),
+        "partner/1.0 (unclosed",
+    ) {
+        Err(Error::InvalidArguments(error::Args { names, .. })) => {
+            assert_eq!(names, "user_agent", "invalid argument name")
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // Clean up.
+    for name in &bucket_names {
+        project
+            .delete_bucket(name)
+            .expect("clean up: delete bucket");
+    }
+}
+
+#[test]
+fn integration_bucket_usage() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    let bucket_name = common::generate_name("bucket-usage");
+    project
+        .create_bucket(&bucket_name)
+        .expect("create bucket not to fail");
+
+    let usage = project
+        .bucket_usage(&bucket_name, None)
+        .expect("usage of an empty bucket");
+    assert_eq!(usage, bucket::Usage::default(), "empty bucket usage");
+
+    for (key, size) in [
+        ("a.txt", 10),
+        ("dir/b.txt", 1_000),
+        ("dir/sub/c.bin", 25_000),
+    ] {
+        project
+            .put_object(&bucket_name, key, &vec![7; size], None)
+            .expect("put object");
+    }
+    project
+        .put_object(&bucket_name, "empty", &[], None)
+        .expect("put empty object");
+
+    let mut reports = 0;
+    let usage = project
+        .bucket_usage(&bucket_name, Some(&mut |_| reports += 1))
+        .expect("bucket usage");
+    assert_eq!(
+        usage,
+        bucket::Usage {
+            objects: 4,
+            bytes: 26_010,
+        },
+        "bucket usage"
+    );
+    assert_eq!(reports, 4, "progress reports");
+
+    match project.bucket_usage("does-not-exist", None) {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+        res => panic!("unexpected result of a non-existing bucket: {:?}", res),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_bucket_list_resume() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    let prefix = common::generate_name("bucket-list-resume");
+    let bucket_names: Vec<String> = ["a", "b", "c"]
+        .iter()
+        .map(|s| format!("{}-{}", prefix, s))
+        .collect();
+    for name in &bucket_names {
+        project.create_bucket(name).expect("create bucket");
+    }
+
+    // List until the second bucket is yielded and remember the last seen.
+    let mut it = project.list_buckets(None).expect("list buckets");
+    assert!(it.last_seen().is_none(), "last seen before iterating");
+    let cursor;
+    loop {
+        let bucket = it
+            .next()
+            .expect("listing buckets has the second bucket")
+            .expect("list bucket item isn't an error");
+        assert_eq!(Some(bucket.name.as_str()), it.last_seen(), "last seen");
+        if bucket.name == bucket_names[1] {
+            cursor = String::from(it.last_seen().unwrap());
+            break;
+        }
+    }
+
+    // Resume the listing from the remembered cursor.
+    let it = project
+        .list_buckets(Some(
+            &options::ListBuckets::with_cursor(&cursor).expect("list buckets options"),
+        ))
+        .expect("list buckets with cursor");
+    let resumed: Vec<String> = it
+        .map(|res| res.expect("list bucket item isn't an error").name)
+        .filter(|name| name.starts_with(&prefix))
+        .collect();
+    assert_eq!(resumed, vec![bucket_names[2].clone()], "resumed listing");
+
+    // Clean up.
+    for name in &bucket_names {
+        project
+            .delete_bucket(name)
+            .expect("clean up: delete bucket");
+    }
+}
+
+#[test]
+fn integration_bucket_list_sorted() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    // The buckets are created in a different order than their names, spaced more than a second
+    // because the creation time is measured in seconds.
+    let prefix = common::generate_name("bucket-list-sorted");
+    let bucket_names: Vec<String> = ["b", "c", "a"]
+        .iter()
+        .map(|s| format!("{}-{}", prefix, s))
+        .collect();
+    for (i, name) in bucket_names.iter().enumerate() {
+        if i > 0 {
+            thread::sleep(Duration::from_millis(1100));
+        }
+        project.create_bucket(name).expect("create bucket");
+    }
+
+    let (b, c, a) = (
+        &bucket_names[0][..],
+        &bucket_names[1][..],
+        &bucket_names[2][..],
+    );
+    for (order, want) in [
+        (bucket::SortOrder::NameAsc, [a, b, c]),
+        (bucket::SortOrder::NameDesc, [c, b, a]),
+        (bucket::SortOrder::CreatedAsc, [b, c, a]),
+        (bucket::SortOrder::CreatedDesc, [a, c, b]),
+    ] {
+        let sorted: Vec<String> = project
+            .list_buckets_sorted(order)
+            .expect("list sorted buckets")
+            .into_iter()
+            .map(|b| b.name)
+            .filter(|name| name.starts_with(&prefix))
+            .collect();
+        assert_eq!(sorted, want, "{:?}", order);
+    }
+
+    // Clean up.
+    for name in &bucket_names {
+        project
+            .delete_bucket(name)
+            .expect("clean up: delete bucket");
+    }
+}
+
+#[test]
+fn integration_bucket_list_finished_with_error() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    let bucket_name = common::generate_name("bucket-list-finished");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    // A listing that finishes cleanly.
+    let mut it = project.list_buckets(None).expect("list buckets");
+    let mut count = 0;
+    for res in &mut it {
+        res.expect("list bucket item isn't an error");
+        count += 1;
+    }
+    assert_eq!(count, it.yielded_count(), "yielded count");
+    assert!(
+        it.finished_with_error().is_none(),
+        "listing finished without error"
+    );
+
+    // A listing with an access grant that expires before iterating.
+    //
+    // NOTE it isn't feasible to revoke a grant in the middle of a listing because the satellite
+    // caches the revocations, so the grant expires before the first page is fetched.
+    let mut perm = Permission::full();
+    perm.set_not_after(Some(Duration::from_secs(
+        common::seconds_since_unix_epoch() + 2,
+    )))
+    .expect("setting not after to sharing permissions");
+    let grant = grant_root.share(&perm, None).expect("shared grant");
+    let project_expired = &mut Project::open(&grant);
+    let mut it = project_expired
+        .list_buckets(None)
+        .expect("list buckets with a grant that expires");
+
+    thread::sleep(Duration::from_secs(3));
+    let mut errors = 0;
+    for res in &mut it {
+        if res.is_err() {
+            errors += 1;
+        }
+    }
+    assert_eq!(1, errors, "number of yielded errors");
+    assert_eq!(0, it.yielded_count(), "yielded count");
+    match it.finished_with_error() {
+        Some(Error::Uplink(err)) if err.code() == UplinkCode::PermissionDenied => {}
+        err => panic!("unexpected finished error: {:?}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_bucket_name_validation() {
+    let env = common::Environment::load();
+    let grant_root = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&grant_root);
+
+    // Invalid names are rejected without sending the request to the satellite.
+    match project
+        .create_bucket("Invalid_Bucket")
+        .expect_err("create bucket with an invalid name")
+    {
+        Error::InvalidArguments(error::Args { names, .. }) => {
+            assert_eq!(names, "bucket", "invalid error argument name")
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Disabling the validation lets the satellite reject them.
+    let mut config =
+        Config::new("uplink-rust-test", Duration::from_secs(10), None).expect("new config");
+    config.set_validate_bucket_names(false);
+    let project = &mut Project::open_with_config(grant_root, &config);
+    match project
+        .create_bucket("Invalid_Bucket")
+        .expect_err("create bucket with an invalid name without validation")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::BucketNameInvalid => {}
+        err => panic!("unexpected error: {}", err),
+    }
+}
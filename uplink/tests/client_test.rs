@@ -0,0 +1,125 @@
+// client_test test the following storage operations, checking that they behave as the project
+// operations that they delegate to:
+//
+// * Make a bucket.
+// * Upload an object.
+// * Download an object.
+// * List objects.
+// * Delete an object.
+
+use uplink::client::Storage;
+use uplink::error::UplinkCode;
+use uplink::Error;
+
+use std::io::Read;
+
+mod common;
+
+#[test]
+fn integration_client_operations() {
+    let env = common::Environment::load();
+    let storage = Storage::connect(&env.access_grant).expect("storage connect");
+
+    let bucket_name = common::generate_name("client-ops");
+
+    // Make a bucket twice returns the same bucket.
+    let bucket_1 = storage.make_bucket(&bucket_name).expect("make bucket");
+    let bucket_2 = storage
+        .make_bucket(&bucket_name)
+        .expect("make an existing bucket");
+    assert_eq!(bucket_1.name, bucket_2.name, "bucket names");
+    assert_eq!(
+        bucket_1.created_at, bucket_2.created_at,
+        "bucket creation times"
+    );
+
+    // Upload objects.
+    let data = "Hello world!";
+    let object = storage
+        .upload(&bucket_name, "hello.txt", data.as_bytes())
+        .expect("upload object");
+    assert_eq!("hello.txt", object.key, "uploaded object key");
+    assert_eq!(
+        Some(data.len() as u64),
+        object.metadata_system.content_length,
+        "uploaded object content length"
+    );
+    storage
+        .upload(&bucket_name, "dir/nested.txt", data.as_bytes())
+        .expect("upload object with prefix");
+
+    // Download an object.
+    let mut downloaded = String::new();
+    storage
+        .download(&bucket_name, "hello.txt")
+        .expect("download object")
+        .read_to_string(&mut downloaded)
+        .expect("read downloaded object");
+    assert_eq!(data, downloaded, "downloaded data");
+
+    // Download a non-existing object.
+    match storage
+        .download(&bucket_name, "does-not-exist")
+        .map(|_| ())
+        .expect_err("download a non-existing object")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // List objects without prefix isn't recursive and includes metadata.
+    let objects = storage.list(&bucket_name, "").expect("list objects");
+    assert_eq!(2, objects.len(), "number of listed objects");
+    for o in &objects {
+        match o.key.as_str() {
+            "hello.txt" => {
+                assert!(!o.is_prefix, "object isn't a prefix");
+                assert_eq!(
+                    Some(data.len() as u64),
+                    o.metadata_system.content_length,
+                    "listed object content length"
+                );
+            }
+            "dir/" => assert!(o.is_prefix, "object is a prefix"),
+            key => panic!("unexpected listed object: {}", key),
+        }
+    }
+
+    // List objects with prefix.
+    let objects = storage.list(&bucket_name, "dir/").expect("list objects");
+    assert_eq!(1, objects.len(), "number of listed objects with prefix");
+    assert_eq!("dir/nested.txt", objects[0].key, "listed object key");
+
+    // List objects with an invalid prefix.
+    match storage
+        .list(&bucket_name, "dir")
+        .expect_err("list objects with an invalid prefix")
+    {
+        Error::InvalidArguments(args) => assert_eq!("prefix", args.names, "invalid argument"),
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Delete objects, including a non-existing one.
+    storage
+        .delete(&bucket_name, "hello.txt")
+        .expect("delete object");
+    storage
+        .delete(&bucket_name, "dir/nested.txt")
+        .expect("delete object with prefix");
+    storage
+        .delete(&bucket_name, "does-not-exist")
+        .expect("delete a non-existing object");
+    assert!(
+        storage
+            .list(&bucket_name, "")
+            .expect("list objects")
+            .is_empty(),
+        "bucket is empty after deleting all the objects"
+    );
+
+    // Clean up.
+    storage
+        .project()
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+}
@@ -1,5 +1,8 @@
 use uplink::access::Grant;
-use uplink::edge;
+use uplink::{edge, share, Project};
+
+use std::io::Write;
+use std::time::Duration;
 
 mod common;
 
@@ -261,3 +264,63 @@ fn integration_join_share_url() {
         .expect_err("object key required when raw it's set");
     }
 }
+
+// TODO: this test fails for the same reason than `integration_config_register_access` because
+// it registers the shared access grant in the Auth service.
+#[test]
+#[ignore]
+fn integration_share_create_public_link() {
+    const BASE_URL: &str = "https://link.us1.storjshare.io";
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("share-public-link");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    let object_key = "reports/2024.txt";
+    let mut upload = project
+        .upload_object(&bucket_name, object_key, None)
+        .expect("upload object");
+    upload
+        .write_all(b"Uplink Rust public link")
+        .expect("upload object write data");
+    upload.commit().expect("upload object commit");
+
+    let config =
+        edge::Config::new_insecure(AUTH_SERVICE_URL).expect("Edge config from AUTH service URL");
+    let link = share::create_public_link_with_base_url(
+        &access_grant,
+        &bucket_name,
+        object_key,
+        Some(Duration::from_secs(3600)),
+        &config,
+        BASE_URL,
+    )
+    .expect("create public link");
+
+    assert!(
+        link.url.starts_with(BASE_URL),
+        "must start with '{}', got '{}'",
+        BASE_URL,
+        link.url
+    );
+    assert!(!link.access_key_id.is_empty(), "not empty access key ID");
+    assert!(
+        link.url.contains(&link.access_key_id),
+        "must contain '{}', got '{}'",
+        link.access_key_id,
+        link.url
+    );
+    assert!(
+        link.url.contains(&bucket_name),
+        "must contain '{}', got '{}'",
+        bucket_name,
+        link.url
+    );
+    assert!(link.expires_at.is_some(), "link expiration time");
+
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up delete bucket with objects");
+}
@@ -0,0 +1,126 @@
+use uplink::access::Grant;
+use uplink::export::{self, InventoryFormat, InventoryOptions};
+use uplink::project::options;
+use uplink::{metadata, Project};
+
+use std::time::Duration;
+
+mod common;
+
+/// The placeholder of the golden inventories for the creation times, which aren't deterministic.
+const CREATED: &str = "{created}";
+
+/// Asserts that `inventory` matches `golden`, where each [`CREATED`] placeholder matches an RFC
+/// 3339 UTC timestamp with seconds precision (e.g. `2024-05-01T12:00:00Z`).
+fn assert_golden(inventory: &str, golden: &str, format: InventoryFormat) {
+    let mut rest = inventory;
+    for (i, piece) in golden.split(CREATED).enumerate() {
+        if i > 0 {
+            let (created, tail) = rest.split_at(rest.len().min(20));
+            let valid = created.len() == 20
+                && created.bytes().enumerate().all(|(i, b)| match i {
+                    4 | 7 => b == b'-',
+                    10 => b == b'T',
+                    13 | 16 => b == b':',
+                    19 => b == b'Z',
+                    _ => b.is_ascii_digit(),
+                });
+            assert!(
+                valid,
+                "{:?}: invalid creation time {:?} in:\n{}",
+                format, created, inventory
+            );
+            rest = tail;
+        }
+
+        assert!(
+            rest.starts_with(piece),
+            "{:?}: inventory doesn't match the golden one:\n{}\nwant:\n{}",
+            format,
+            inventory,
+            golden
+        );
+        rest = &rest[piece.len()..];
+    }
+
+    assert!(rest.is_empty(), "{:?}: unexpected trailing data", format);
+}
+
+#[test]
+fn integration_export_write_inventory() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("export-inventory");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    // 2100-01-01T00:00:00Z, so the expiration time is deterministic.
+    let expires = Duration::from_secs(4_102_444_800);
+    let mut custom = metadata::Custom::with_capacity(2);
+    custom.insert(String::from("owner"), String::from("ops, \"infra\""));
+    custom.insert(String::from("content-type"), String::from("text/csv"));
+    for (key, data, opts) in [
+        (
+            "reports/2024,Q1 \"final\".csv",
+            &b"a,b\n1,2\n"[..],
+            options::Upload {
+                expires: Some(expires),
+                custom_metadata: Some(custom),
+            },
+        ),
+        ("a.txt", &b"hello"[..], options::Upload::default()),
+        ("dir/empty", &b""[..], options::Upload::default()),
+    ] {
+        project
+            .put_object(&bucket_name, key, data, Some(&opts))
+            .expect("put fixture object");
+    }
+
+    for (format, golden) in [
+        (
+            InventoryFormat::Csv,
+            concat!(
+                "key,size,created,expires,owner,content-type\n",
+                "a.txt,5,{created},,,\n",
+                "dir/empty,0,{created},,,\n",
+                "\"reports/2024,Q1 \"\"final\"\".csv\",8,{created},2100-01-01T00:00:00Z,",
+                "\"ops, \"\"infra\"\"\",text/csv\n",
+            ),
+        ),
+        (
+            InventoryFormat::Ndjson,
+            concat!(
+                r#"{"key":"a.txt","size":5,"created":"{created}","expires":null,"#,
+                r#""metadata":{"owner":null,"content-type":null}}"#,
+                "\n",
+                r#"{"key":"dir/empty","size":0,"created":"{created}","expires":null,"#,
+                r#""metadata":{"owner":null,"content-type":null}}"#,
+                "\n",
+                r#"{"key":"reports/2024,Q1 \"final\".csv","size":8,"created":"{created}","#,
+                r#""expires":"2100-01-01T00:00:00Z","#,
+                r#""metadata":{"owner":"ops, \"infra\"","content-type":"text/csv"}}"#,
+                "\n",
+            ),
+        ),
+    ] {
+        let opts = InventoryOptions {
+            format,
+            metadata_keys: vec![String::from("owner"), String::from("content-type")],
+        };
+        let mut out = Vec::new();
+        let exported = export::write_inventory(&project, &bucket_name, &opts, &mut out)
+            .expect("write inventory");
+        assert_eq!(exported, 3, "{:?}: exported objects", format);
+        assert_golden(
+            &String::from_utf8(out).expect("UTF-8 inventory"),
+            golden,
+            format,
+        );
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
@@ -0,0 +1,129 @@
+use uplink::access::Grant;
+use uplink::project::options;
+use uplink::{error, metadata, Error, Project};
+
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use rand::{self, RngCore};
+
+mod common;
+
+#[test]
+fn integration_file_upload_and_download() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("file");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let dir = std::env::temp_dir().join(common::generate_name("file"));
+    fs::create_dir_all(&dir).expect("create temporary directory");
+
+    // A file bigger than the buffer with a modification time in the past.
+    let mut data = vec![0u8; 3 * 1024 * 1024 + 7];
+    rand::thread_rng().fill_bytes(&mut data);
+    let src_path = dir.join("source.bin");
+    fs::write(&src_path, &data).expect("write source file");
+    let mtime = SystemTime::UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789);
+    fs::File::options()
+        .write(true)
+        .open(&src_path)
+        .and_then(|f| f.set_modified(mtime))
+        .expect("set source file modification time");
+
+    let obj = project
+        .upload_file(&bucket_name, "file.bin", &src_path, None)
+        .expect("upload file");
+    assert_eq!(
+        Some(data.len() as u64),
+        obj.metadata_system.content_length,
+        "uploaded object size"
+    );
+    assert_eq!(
+        Some(&String::from("1600000000.123456789")),
+        obj.metadata_custom.get(metadata::KEY_MTIME),
+        "recorded modification time"
+    );
+
+    // Download into a new file creating its parent directories.
+    let dst_path = dir.join("nested").join("dirs").join("downloaded.bin");
+    let size = project
+        .download_file(
+            &bucket_name,
+            "file.bin",
+            &dst_path,
+            Some(&options::DownloadFile {
+                create_dirs: true,
+                ..Default::default()
+            }),
+        )
+        .expect("download file");
+    assert_eq!(data.len() as u64, size, "downloaded bytes");
+    assert_eq!(data, fs::read(&dst_path).expect("read downloaded file"));
+    assert_eq!(
+        mtime,
+        fs::metadata(&dst_path)
+            .and_then(|m| m.modified())
+            .expect("downloaded file modification time"),
+        "restored modification time"
+    );
+
+    // Downloading into an existing file is refused unless overwriting is allowed.
+    fs::write(&dst_path, b"existing").expect("write existing file");
+    match project
+        .download_file(&bucket_name, "file.bin", &dst_path, None)
+        .expect_err("download into an existing file")
+    {
+        Error::InvalidArguments(error::Args { names, .. }) => {
+            assert_eq!("path", names, "invalid argument")
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(
+        b"existing".to_vec(),
+        fs::read(&dst_path).expect("read existing file"),
+        "existing file isn't modified"
+    );
+
+    let size = project
+        .download_file(
+            &bucket_name,
+            "file.bin",
+            &dst_path,
+            Some(&options::DownloadFile {
+                overwrite: true,
+                ..Default::default()
+            }),
+        )
+        .expect("download file overwriting it");
+    assert_eq!(data.len() as u64, size, "downloaded bytes overwriting");
+    assert_eq!(data, fs::read(&dst_path).expect("read overwritten file"));
+
+    // Downloading a missing object doesn't create the file.
+    let missing_path = dir.join("missing.bin");
+    project
+        .download_file(&bucket_name, "missing.bin", &missing_path, None)
+        .expect_err("download a missing object");
+    assert!(!missing_path.exists(), "file of a missing object exists");
+
+    // Uploading a missing file returns an internal error with the path.
+    match project
+        .upload_file(&bucket_name, "missing.bin", &missing_path, None)
+        .expect_err("upload a missing file")
+    {
+        Error::Internal(error::Internal { ctx_msg, .. }) => assert!(
+            ctx_msg.contains(&missing_path.display().to_string()),
+            "error context doesn't contain the path: {}",
+            ctx_msg
+        ),
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    // Clean up.
+    fs::remove_dir_all(&dir).expect("clean up: remove temporary directory");
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
@@ -27,6 +27,7 @@ fn integration_create_upload_list_download_delete() {
     assert!(
         project
             .list_buckets(None)
+            .expect("list buckets")
             .find(|res| {
                 match res {
                     Ok(b) => b.name == bucket_name,
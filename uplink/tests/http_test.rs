@@ -0,0 +1,182 @@
+use uplink::access::Grant;
+use uplink::http::DownloadBody;
+use uplink::{Error, Project};
+
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use rand::{self, RngCore};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+/// The state shared by the handlers of the test server.
+struct Server {
+    project: Project,
+    bucket: String,
+}
+
+/// Serves the object with `key` of the server's bucket honoring the `Range` header.
+async fn serve_object(
+    State(server): State<Arc<Server>>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let res = tokio::task::spawn_blocking(move || {
+        DownloadBody::from_object(
+            &server.project,
+            &server.bucket,
+            &key,
+            range.as_deref(),
+            4096,
+        )
+    })
+    .await
+    .expect("download body task");
+
+    match res {
+        Ok((body, None)) => Response::new(Body::new(body)),
+        Ok((body, Some(range))) => Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_RANGE, range.content_range())
+            .body(Body::new(body))
+            .expect("partial content response"),
+        Err(Error::InvalidArguments(_)) => StatusCode::RANGE_NOT_SATISFIABLE.into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+/// Sends a GET request of `path` to `addr` with an optional `Range` header and returns the status
+/// code, the headers, with lowercase names, and the body of the response.
+async fn http_get(
+    addr: SocketAddr,
+    path: &str,
+    range: Option<&str>,
+) -> (u16, Vec<(String, String)>, Vec<u8>) {
+    let mut stream = TcpStream::connect(addr).await.expect("connect to server");
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        path, addr
+    );
+    if let Some(range) = range {
+        request.push_str(&format!("Range: {}\r\n", range));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .expect("send request");
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .expect("read response");
+
+    let head_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .expect("response head");
+    let head = String::from_utf8(response[..head_end].to_vec()).expect("UTF-8 response head");
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|l| l.split(' ').nth(1))
+        .and_then(|s| s.parse().ok())
+        .expect("status code");
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+
+    (status, headers, response[head_end + 4..].to_vec())
+}
+
+/// Returns the value of the header `name` of `headers`.
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn integration_http_download_body() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("http-download-body");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut data = vec![0u8; 100 * 1024 + 3];
+    rand::thread_rng().fill_bytes(&mut data);
+    let mut upload = project
+        .upload_object(&bucket_name, "data.bin", None)
+        .expect("upload object");
+    upload.write_all(&data).expect("upload object data");
+    upload.commit().expect("commit upload");
+
+    let server = Arc::new(Server {
+        project,
+        bucket: bucket_name.clone(),
+    });
+    let app = Router::new()
+        .route("/:key", get(serve_object))
+        .with_state(Arc::clone(&server));
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind server");
+    let addr = listener.local_addr().expect("server address");
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    // The whole object.
+    let (status, headers, body) = http_get(addr, "/data.bin", None).await;
+    assert_eq!(status, 200, "status code");
+    assert_eq!(
+        header_value(&headers, "content-length"),
+        Some(data.len().to_string().as_str()),
+        "content length"
+    );
+    assert!(body == data, "body");
+
+    // A range of the object.
+    let (status, headers, body) = http_get(addr, "/data.bin", Some("bytes=1000-5999")).await;
+    assert_eq!(status, 206, "range status code");
+    assert_eq!(
+        header_value(&headers, "content-length"),
+        Some("5000"),
+        "range content length"
+    );
+    assert_eq!(
+        header_value(&headers, "content-range"),
+        Some(format!("bytes 1000-5999/{}", data.len()).as_str()),
+        "content range"
+    );
+    assert!(body == data[1000..6000], "range body");
+
+    // A suffix of the object.
+    let (status, _, body) = http_get(addr, "/data.bin", Some("bytes=-10")).await;
+    assert_eq!(status, 206, "suffix status code");
+    assert!(body == data[data.len() - 10..], "suffix body");
+
+    // An unsatisfiable range.
+    let (status, _, _) = http_get(addr, "/data.bin", Some("bytes=999999-")).await;
+    assert_eq!(status, 416, "unsatisfiable range status code");
+
+    // Clean up.
+    server
+        .project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
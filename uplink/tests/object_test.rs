@@ -1,9 +1,13 @@
-use uplink::access::Grant;
+use uplink::access::{Grant, Permission, SharePrefix};
+use uplink::error::UplinkCode;
 use uplink::project::options;
-use uplink::{metadata, Project};
+use uplink::types::{BucketName, ObjectKey};
+use uplink::{metadata, object, Config, Error, Project};
 
-use std::io::Write;
-use std::time::Duration;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+use rand::{self, RngCore};
 
 mod common;
 
@@ -37,7 +41,7 @@ fn integration_object_stat() {
         String::from(metadata_custom_value),
     );
     upload
-        .set_custom_metadata(&mut custom_metadata)
+        .set_custom_metadata(&custom_metadata)
         .expect("setting custom metatada to the upload object");
 
     upload.commit().expect("upload object commit");
@@ -45,7 +49,7 @@ fn integration_object_stat() {
     // Check stat_object.
     let object_info = upload.info().expect("upload object info not to fail");
     let object_info_stat = project
-        .stat_object(&bucket_name, &object_key)
+        .stat_object(&bucket_name, object_key)
         .expect("stat an existing object not to fail");
 
     assert_eq!(
@@ -73,6 +77,14 @@ fn integration_object_stat() {
         object_info.metadata_custom.count(),
         "uploaded object has one custom metadata entry"
     );
+    assert!(
+        object_info.has_system_metadata() && object_info.has_custom_metadata(),
+        "uploaded object has the metadata"
+    );
+    assert!(
+        object_info_stat.has_system_metadata() && object_info_stat.has_custom_metadata(),
+        "stat object has the metadata"
+    );
     assert_eq!(
         1,
         object_info_stat.metadata_custom.count(),
@@ -125,7 +137,7 @@ fn integration_object_listing_metadata() {
         String::from(metadata_custom_value),
     );
     upload
-        .set_custom_metadata(&mut custom_metadata)
+        .set_custom_metadata(&custom_metadata)
         .expect("setting custom metatada to the upload object");
     upload.commit().expect("upload object commit");
 
@@ -139,6 +151,14 @@ fn integration_object_listing_metadata() {
     let object_info = res.expect("first and only listed object is an object not an error");
     assert_eq!(object_key, object_info.key, "listed object key");
     assert!(!object_info.is_prefix, "listed object is not a prefix");
+    assert!(
+        !object_info.has_system_metadata(),
+        "listed object system metadata isn't retrieved"
+    );
+    assert!(
+        !object_info.has_custom_metadata(),
+        "listed object custom metadata isn't retrieved"
+    );
     assert_eq!(
         Duration::ZERO,
         object_info.metadata_system.created,
@@ -149,7 +169,8 @@ fn integration_object_listing_metadata() {
         "listed object system metadata expires"
     );
     assert_eq!(
-        0, object_info.metadata_system.content_length,
+        Some(0),
+        object_info.metadata_system.content_length,
         "listed object system metadata content lenght"
     );
     assert_eq!(
@@ -175,6 +196,14 @@ fn integration_object_listing_metadata() {
     let object_info = res.expect("first and only listed object is an object not an error");
     assert_eq!(object_key, object_info.key, "listed object key");
     assert!(!object_info.is_prefix, "listed object is not a prefix");
+    assert!(
+        object_info.has_system_metadata(),
+        "listed object system metadata is retrieved"
+    );
+    assert!(
+        object_info.has_custom_metadata(),
+        "listed object custom metadata is retrieved"
+    );
     assert!(
         object_info.metadata_system.created != Duration::ZERO,
         "listed object system metadata created isn't 0"
@@ -184,7 +213,7 @@ fn integration_object_listing_metadata() {
         "listed object system metadata expires is None"
     );
     assert!(
-        object_info.metadata_system.content_length != 0,
+        object_info.metadata_system.content_length != Some(0),
         "listed object system metadata content lenght isn't 0"
     );
     assert_eq!(
@@ -594,87 +623,1550 @@ fn integration_object_move() {
 }
 
 #[test]
-fn integration_object_update_metadata() {
+fn integration_object_move_pending_upload() {
     let env = common::Environment::load();
     let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
     let project = &mut Project::open(&access_grant);
 
-    let bucket_name = common::generate_name("object-listing-metadata");
+    let bucket_name = common::generate_name("object-move-pending");
     let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
 
-    // Create an upload.
+    let object_key = "pending/test-data.txt";
+    let info = project
+        .begin_upload(&bucket_name, object_key, None)
+        .expect("begin upload");
+
+    match project.move_object(
+        &bucket_name,
+        object_key,
+        &bucket_name,
+        "pending/test-data-moved.txt",
+        None,
+    ) {
+        Err(Error::Uplink(err)) => {
+            assert_eq!(err.code(), UplinkCode::UploadPending, "move pending upload")
+        }
+        res => panic!("move pending upload: unexpected result: {:?}", res),
+    }
+
+    // Clean up.
+    project
+        .abort_upload(&bucket_name, object_key, &info.upload_id)
+        .expect("clean up: abort upload");
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_copy_replace_metadata() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-copy-metadata");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Upload an object with custom metadata.
     let object_key = "test-data.txt";
     let upload = &mut project
         .upload_object(&bucket_name, object_key, None)
         .expect("upload object");
-
-    // Set custom metdata to the uploading object.
-    let metadata_custom_key = "uplink-rust:field";
-    let metadata_custom_value = "value";
     let mut custom_metadata = metadata::Custom::with_capacity(1);
-    custom_metadata.insert(
-        String::from(metadata_custom_key),
-        String::from(metadata_custom_value),
-    );
+    custom_metadata.insert(String::from("uplink-rust:original"), String::from("value"));
     upload
-        .set_custom_metadata(&mut custom_metadata)
+        .set_custom_metadata(&custom_metadata)
         .expect("setting custom metatada to the upload object");
     upload.commit().expect("upload object commit");
 
-    // Stat object to check the initial metadata.
+    // Copy the object replacing its custom metadata.
+    let mut replaced_metadata = metadata::Custom::with_capacity(1);
+    replaced_metadata.insert(
+        String::from("uplink-rust:replaced"),
+        String::from("new-value"),
+    );
+    let object_copy_key = "test-data-copy.txt";
+    let copied = project
+        .copy_object(
+            &bucket_name,
+            object_key,
+            &bucket_name,
+            object_copy_key,
+            Some(&options::CopyObject {
+                custom_metadata: Some(replaced_metadata),
+            }),
+        )
+        .expect("copy object with custom metadata");
+    assert_eq!(
+        1,
+        copied.metadata_custom.count(),
+        "copied object custom metadata entries"
+    );
+
+    // Check the metadata of the copy and the original.
     let object = project
-        .stat_object(&bucket_name, object_key)
-        .expect("stat object");
-    assert_eq!(1, object.metadata_custom.count(), "custom metadata entries");
+        .stat_object(&bucket_name, object_copy_key)
+        .expect("stat copied object");
     assert_eq!(
-        metadata_custom_value,
+        1,
+        object.metadata_custom.count(),
+        "copied object custom metadata entries"
+    );
+    assert_eq!(
+        "new-value",
         object
             .metadata_custom
-            .get(metadata_custom_key)
+            .get("uplink-rust:replaced")
             .expect("metadata value"),
-        "initial metadata value"
-    );
-
-    // Update object's metadata.
-    let metadata_custom_value_override = "value-overridden";
-    assert!(
-        custom_metadata.insert(
-            String::from(metadata_custom_key),
-            String::from(metadata_custom_value_override)
-        ),
-        "insert metadata with an existing key",
+        "copied object metadata value"
     );
-    let metadata_custom_key_new = "uplink-rust:field-2";
-    let metadata_custom_value_new = "value-2";
-    custom_metadata.insert(
-        String::from(metadata_custom_key_new),
-        String::from(metadata_custom_value_new),
-    );
-    project
-        .update_object_metadata(&bucket_name, object_key, &mut custom_metadata, None)
-        .expect("update object metadata");
 
-    // Stat object to check that its metadata is updated.
     let object = project
         .stat_object(&bucket_name, object_key)
-        .expect("stat object");
-    assert_eq!(2, object.metadata_custom.count(), "custom metadata entries");
+        .expect("stat original object");
     assert_eq!(
-        metadata_custom_value_override,
+        "value",
         object
             .metadata_custom
-            .get(metadata_custom_key)
+            .get("uplink-rust:original")
             .expect("metadata value"),
-        "initial metadata value"
+        "original object metadata value"
     );
+
+    // Copy the object without options retains the custom metadata.
+    let copied = project
+        .copy_object(
+            &bucket_name,
+            object_key,
+            &bucket_name,
+            "test-data-copy-2.txt",
+            Some(&options::CopyObject::default()),
+        )
+        .expect("copy object retaining custom metadata");
     assert_eq!(
-        metadata_custom_value_new,
-        object
+        "value",
+        copied
             .metadata_custom
-            .get(metadata_custom_key_new)
+            .get("uplink-rust:original")
             .expect("metadata value"),
-        "initial metadata value"
+        "copied object retained metadata value"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_rename() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-rename");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let object_key = "test-data.txt";
+    project
+        .put_object(&bucket_name, object_key, b"Uplink Rust test object", None)
+        .expect("put object");
+
+    // Rename to the same key is invalid.
+    match project
+        .rename_object(&bucket_name, object_key, object_key)
+        .expect_err("rename object to the same key")
+    {
+        Error::InvalidArguments(args) => {
+            assert_eq!("(from_key,to_key)", args.names, "invalid arguments")
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Rename and check that the old key doesn't exist.
+    let object_rename_key = "test-data-renamed.txt";
+    project
+        .rename_object(&bucket_name, object_key, object_rename_key)
+        .expect("rename object");
+
+    let object = project
+        .stat_object(&bucket_name, object_rename_key)
+        .expect("stat renamed object");
+    assert_eq!(object_rename_key, object.key, "renamed object key");
+
+    match project
+        .stat_object(&bucket_name, object_key)
+        .expect_err("stat object with the old key")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_copy_and_move_typed() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket = BucketName::new(common::generate_name("object-typed")).expect("bucket name");
+    let (_bucket, _ok) = project.create_bucket(&bucket).expect("create bucket");
+
+    let key = ObjectKey::new("test-data.txt").expect("object key");
+    let data = b"Uplink Rust test object";
+    project
+        .put_object(&bucket, &key, data, None)
+        .expect("put object");
+
+    // Copy the object, which returns the same as the untyped method.
+    let copy_key = ObjectKey::new("test-data-copy.txt").expect("copy key");
+    let copied = project
+        .copy_object_typed((&bucket, &key), (&bucket, &copy_key), None)
+        .expect("copy object typed");
+    assert_eq!(copy_key.as_str(), copied.key, "copied object key");
+    assert_eq!(
+        Some(data.len() as u64),
+        copied.metadata_system.content_length,
+        "copied object content length"
+    );
+
+    // Move the copy and check that only the new key exists.
+    let moved_key = ObjectKey::new("moved/test-data.txt").expect("moved key");
+    project
+        .move_object_typed((&bucket, &copy_key), (&bucket, &moved_key), None)
+        .expect("move object typed");
+    assert_eq!(
+        data.to_vec(),
+        project
+            .get_object(&bucket, &moved_key, None)
+            .expect("get moved object"),
+        "moved object data"
+    );
+    match project
+        .stat_object(&bucket, &copy_key)
+        .expect_err("stat object with the old key")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Moving a missing object fails as with the untyped method.
+    let missing_key = ObjectKey::new("missing.txt").expect("missing key");
+    match project
+        .move_object_typed((&bucket, &missing_key), (&bucket, &moved_key), None)
+        .expect_err("move a missing object")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_delete_outcome() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-delete-outcome");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let data = b"Uplink Rust test object";
+    project
+        .put_object(&bucket_name, "full.txt", data, None)
+        .expect("put object");
+    project
+        .put_object(&bucket_name, "write-only.txt", data, None)
+        .expect("put object");
+
+    // A grant with read permission returns the deleted object.
+    match project
+        .delete_object_outcome(&bucket_name, "full.txt")
+        .expect("delete object with a full grant")
+    {
+        object::DeleteOutcome::Deleted(o) => assert_eq!("full.txt", o.key, "deleted object key"),
+        outcome => panic!("unexpected outcome: {:?}", outcome),
+    }
+
+    // A write-only grant doesn't return the deleted object.
+    let grant_write_only = access_grant
+        .share(
+            &Permission::write_only(),
+            Some(vec![
+                SharePrefix::full_bucket(&bucket_name).expect("share prefix creation")
+            ]),
+        )
+        .expect("write-only grant");
+    let project_write_only = &mut Project::open(&grant_write_only);
+    match project_write_only
+        .delete_object_outcome(&bucket_name, "write-only.txt")
+        .expect("delete object with a write-only grant")
+    {
+        object::DeleteOutcome::DeletedNoMetadata => {}
+        outcome => panic!("unexpected outcome: {:?}", outcome),
+    }
+    match project
+        .stat_object(&bucket_name, "write-only.txt")
+        .expect_err("stat object deleted with a write-only grant")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // A missing object is reported as not found or, by the satellites that delete objects
+    // idempotently, as deleted without metadata.
+    match project
+        .delete_object_outcome(&bucket_name, "does-not-exist.txt")
+        .expect("delete a missing object")
+    {
+        object::DeleteOutcome::NotFound | object::DeleteOutcome::DeletedNoMetadata => {}
+        outcome => panic!("unexpected outcome: {:?}", outcome),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_conditional_operations() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-conditional");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Upload if absent.
+    let obj = project
+        .upload_object_if_absent(&bucket_name, "lock", b"first", None)
+        .expect("upload absent object");
+    assert_eq!("lock", obj.key, "uploaded object key");
+    match project.upload_object_if_absent(&bucket_name, "lock", b"second", None) {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectAlreadyExists => {}
+        res => panic!("unexpected result: {:?}", res.map(|o| o.key)),
+    }
+    assert_eq!(
+        b"first".to_vec(),
+        project
+            .get_object(&bucket_name, "lock", None)
+            .expect("get object"),
+        "existing object isn't replaced"
+    );
+
+    // Delete if unchanged.
+    let mut upload = project
+        .upload_object(&bucket_name, "versioned", None)
+        .expect("upload object");
+    upload.write_all(b"data").expect("write object data");
+    let mut custom = metadata::Custom::with_capacity(1);
+    custom.insert(String::from("version"), String::from("1"));
+    upload
+        .set_custom_metadata(&custom)
+        .expect("set custom metadata");
+    upload.commit().expect("commit upload");
+
+    assert!(
+        !project
+            .delete_object_if_unchanged(&bucket_name, "versioned", "version", "2")
+            .expect("delete object with a different value"),
+        "object with a different value isn't deleted"
+    );
+    assert!(
+        !project
+            .delete_object_if_unchanged(&bucket_name, "versioned", "missing", "1")
+            .expect("delete object without the metadata key"),
+        "object without the metadata key isn't deleted"
+    );
+    project
+        .stat_object(&bucket_name, "versioned")
+        .expect("object still exists");
+
+    assert!(
+        project
+            .delete_object_if_unchanged(&bucket_name, "versioned", "version", "1")
+            .expect("delete object with the expected value"),
+        "object with the expected value is deleted"
+    );
+    match project.stat_object(&bucket_name, "versioned") {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {}
+        res => panic!("unexpected result: {:?}", res.map(|o| o.key)),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_download_parallel() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-download-parallel");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut data = vec![0u8; 50 * 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    project
+        .put_object(&bucket_name, "big.bin", &data, None)
+        .expect("put object");
+
+    let start = Instant::now();
+    let mut serial = Vec::with_capacity(data.len());
+    project
+        .download_object(&bucket_name, "big.bin", None)
+        .expect("download object")
+        .read_to_end(&mut serial)
+        .expect("read object");
+    let serial_elapsed = start.elapsed();
+    assert!(data == serial, "serially downloaded data");
+
+    let start = Instant::now();
+    let mut parallel = io::Cursor::new(Vec::with_capacity(data.len()));
+    let n = project
+        .download_object_parallel(&bucket_name, "big.bin", &mut parallel, 4, 8 * 1024 * 1024)
+        .expect("download object in parallel");
+    let parallel_elapsed = start.elapsed();
+    assert_eq!(data.len() as u64, n, "number of downloaded bytes");
+    assert!(data == parallel.into_inner(), "downloaded data in parallel");
+
+    // The leeway absorbs the variance of the network between both downloads.
+    assert!(
+        parallel_elapsed <= serial_elapsed + serial_elapsed / 4,
+        "parallel download ({:?}) isn't slower than the serial one ({:?})",
+        parallel_elapsed,
+        serial_elapsed
+    );
+
+    // Invalid parallelism.
+    match project.download_object_parallel(
+        &bucket_name,
+        "big.bin",
+        &mut io::Cursor::new(Vec::new()),
+        0,
+        1024,
+    ) {
+        Err(Error::InvalidArguments(args)) => {
+            assert_eq!("parallelism", args.names, "invalid argument name")
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_http_metadata() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-http-metadata");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut opts = options::Upload::default();
+    opts.content_type("text/html; charset=utf-8")
+        .expect("set content type");
+    opts.custom_metadata
+        .as_mut()
+        .expect("custom metadata created by the content type")
+        .set_cache_control("max-age=3600")
+        .expect("set cache control");
+    project
+        .put_object(&bucket_name, "index.html", b"<html></html>", Some(&opts))
+        .expect("put object");
+
+    let obj = project
+        .stat_object(&bucket_name, "index.html")
+        .expect("stat object");
+    assert_eq!(
+        Some("text/html; charset=utf-8"),
+        obj.metadata_custom.content_type(),
+        "content type"
+    );
+    assert_eq!(
+        Some("max-age=3600"),
+        obj.metadata_custom.cache_control(),
+        "cache control"
     );
+    assert_eq!(
+        None,
+        obj.metadata_custom.content_encoding(),
+        "content encoding"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_head() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-head");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut opts = options::Upload::default();
+    opts.content_type("text/html; charset=utf-8")
+        .expect("set content type");
+    let custom = opts
+        .custom_metadata
+        .as_mut()
+        .expect("custom metadata created by the content type");
+    custom
+        .set_cache_control("max-age=3600")
+        .expect("set cache control");
+    custom.insert(
+        String::from(metadata::http::KEY_ETAG),
+        String::from("0a1b2c"),
+    );
+    project
+        .put_object(&bucket_name, "index.html", b"<html></html>", Some(&opts))
+        .expect("put object");
+    project
+        .put_object(&bucket_name, "plain", b"data", None)
+        .expect("put object without metadata");
+
+    let head = project
+        .head_object(&bucket_name, "index.html")
+        .expect("head object");
+    assert_eq!(head.key, "index.html", "key");
+    assert_eq!(head.content_length, Some(13), "content length");
+    assert_eq!(head.expires, None, "expires");
+    assert_eq!(head.etag.as_deref(), Some("\"0a1b2c\""), "etag");
+    assert_eq!(
+        head.headers().collect::<Vec<_>>(),
+        [
+            ("Content-Type", "text/html; charset=utf-8"),
+            ("Cache-Control", "max-age=3600"),
+            ("ETag", "\"0a1b2c\""),
+        ],
+        "headers"
+    );
+
+    let head = project
+        .head_object(&bucket_name, "plain")
+        .expect("head object without metadata");
+    assert_eq!(
+        head.content_length,
+        Some(4),
+        "content length without metadata"
+    );
+    assert_eq!(head.etag, None, "etag without metadata");
+    assert_eq!(head.headers().count(), 0, "headers without metadata");
+
+    match project
+        .head_object(&bucket_name, "missing")
+        .expect_err("head missing object")
+    {
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_update_metadata() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-listing-metadata");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Create an upload.
+    let object_key = "test-data.txt";
+    let upload = &mut project
+        .upload_object(&bucket_name, object_key, None)
+        .expect("upload object");
+
+    // Set custom metdata to the uploading object.
+    let metadata_custom_key = "uplink-rust:field";
+    let metadata_custom_value = "value";
+    let mut custom_metadata = metadata::Custom::with_capacity(1);
+    custom_metadata.insert(
+        String::from(metadata_custom_key),
+        String::from(metadata_custom_value),
+    );
+    upload
+        .set_custom_metadata(&custom_metadata)
+        .expect("setting custom metatada to the upload object");
+    upload.commit().expect("upload object commit");
+
+    // Stat object to check the initial metadata.
+    let object = project
+        .stat_object(&bucket_name, object_key)
+        .expect("stat object");
+    assert_eq!(1, object.metadata_custom.count(), "custom metadata entries");
+    assert_eq!(
+        metadata_custom_value,
+        object
+            .metadata_custom
+            .get(metadata_custom_key)
+            .expect("metadata value"),
+        "initial metadata value"
+    );
+
+    // Update object's metadata.
+    let metadata_custom_value_override = "value-overridden";
+    assert!(
+        custom_metadata.insert(
+            String::from(metadata_custom_key),
+            String::from(metadata_custom_value_override)
+        ),
+        "insert metadata with an existing key",
+    );
+    let metadata_custom_key_new = "uplink-rust:field-2";
+    let metadata_custom_value_new = "value-2";
+    custom_metadata.insert(
+        String::from(metadata_custom_key_new),
+        String::from(metadata_custom_value_new),
+    );
+    project
+        .update_object_metadata(&bucket_name, object_key, &custom_metadata, None)
+        .expect("update object metadata");
+
+    // Stat object to check that its metadata is updated.
+    let object = project
+        .stat_object(&bucket_name, object_key)
+        .expect("stat object");
+    assert_eq!(2, object.metadata_custom.count(), "custom metadata entries");
+    assert_eq!(
+        metadata_custom_value_override,
+        object
+            .metadata_custom
+            .get(metadata_custom_key)
+            .expect("metadata value"),
+        "initial metadata value"
+    );
+    assert_eq!(
+        metadata_custom_value_new,
+        object
+            .metadata_custom
+            .get(metadata_custom_key_new)
+            .expect("metadata value"),
+        "initial metadata value"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_patch_metadata() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-patch-metadata");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Upload an object with custom metadata.
+    let object_key = "test-data.txt";
+    let upload = &mut project
+        .upload_object(&bucket_name, object_key, None)
+        .expect("upload object");
+
+    let metadata_custom_key = "uplink-rust:field";
+    let metadata_custom_value = "value";
+    let mut custom_metadata = metadata::Custom::with_capacity(1);
+    custom_metadata.insert(
+        String::from(metadata_custom_key),
+        String::from(metadata_custom_value),
+    );
+    upload
+        .set_custom_metadata(&custom_metadata)
+        .expect("setting custom metatada to the upload object");
+    upload.commit().expect("upload object commit");
+
+    // Patch a new key and check that the existing one survives.
+    let metadata_custom_key_new = "uplink-rust:field-2";
+    let metadata_custom_value_new = "value-2";
+    let mut patch = metadata::MetadataPatch::new();
+    patch.upsert(
+        String::from(metadata_custom_key_new),
+        String::from(metadata_custom_value_new),
+    );
+    let patched = project
+        .patch_object_metadata(&bucket_name, object_key, patch)
+        .expect("patch object metadata");
+    assert_eq!(2, patched.count(), "patched custom metadata entries");
+
+    let object = project
+        .stat_object(&bucket_name, object_key)
+        .expect("stat object");
+    assert_eq!(2, object.metadata_custom.count(), "custom metadata entries");
+    assert_eq!(
+        metadata_custom_value,
+        object
+            .metadata_custom
+            .get(metadata_custom_key)
+            .expect("metadata value"),
+        "existing metadata value"
+    );
+    assert_eq!(
+        metadata_custom_value_new,
+        object
+            .metadata_custom
+            .get(metadata_custom_key_new)
+            .expect("metadata value"),
+        "patched metadata value"
+    );
+
+    // Remove a key and check that it's gone.
+    let mut patch = metadata::MetadataPatch::new();
+    patch.remove(String::from(metadata_custom_key));
+    project
+        .patch_object_metadata(&bucket_name, object_key, patch)
+        .expect("patch object metadata");
+
+    let object = project
+        .stat_object(&bucket_name, object_key)
+        .expect("stat object");
+    assert_eq!(1, object.metadata_custom.count(), "custom metadata entries");
+    assert!(
+        object.metadata_custom.get(metadata_custom_key).is_none(),
+        "removed metadata key"
+    );
+    assert_eq!(
+        metadata_custom_value_new,
+        object
+            .metadata_custom
+            .get(metadata_custom_key_new)
+            .expect("metadata value"),
+        "remaining metadata value"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_put_and_get() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-put-get");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Round-trip a 1 MiB payload.
+    let object_key = "test-data.bin";
+    let mut data = vec![0u8; 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+
+    let object = project
+        .put_object(&bucket_name, object_key, &data, None)
+        .expect("put object");
+    assert_eq!(object_key, object.key, "object key");
+    assert_eq!(
+        Some(data.len() as u64),
+        object.metadata_system.content_length,
+        "object content length"
+    );
+
+    let downloaded = project
+        .get_object(&bucket_name, object_key, None)
+        .expect("get object");
+    assert!(
+        data == downloaded,
+        "downloaded data matches the uploaded one"
+    );
+
+    // Get an object bigger than the maximum size.
+    let opts = options::GetObject {
+        max_size: data.len() - 1,
+    };
+    match project
+        .get_object(&bucket_name, object_key, Some(&opts))
+        .expect_err("get an object bigger than the maximum size")
+    {
+        Error::InvalidArguments(args) => assert_eq!(args.names, "opts{max_size}", "names"),
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Put an object into a bucket with an invalid name.
+    match project
+        .put_object("Invalid_Bucket", object_key, &data, None)
+        .expect_err("put object into a bucket with an invalid name")
+    {
+        // Depending on the operation where the bucket name is validated, the error may vary.
+        Error::Uplink(_) => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_put_small() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-put-small");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Sizes around the default inline segment threshold, so they are uploaded with both paths.
+    let threshold = Config::DEFAULT_INLINE_SEGMENT_THRESHOLD;
+    for size in [0, 1, threshold - 1, threshold, threshold + 1] {
+        let key = format!("small-{}.bin", size);
+        let mut data = vec![0u8; size];
+        rand::thread_rng().fill_bytes(&mut data);
+
+        let object = project
+            .put_object(&bucket_name, &key, &data, None)
+            .expect("put small object");
+        assert_eq!(
+            Some(size as u64),
+            object.metadata_system.content_length,
+            "{}: object content length",
+            key
+        );
+        assert!(
+            data == project
+                .get_object(&bucket_name, &key, None)
+                .expect("get small object"),
+            "{}: downloaded data matches the uploaded one",
+            key
+        );
+    }
+
+    // Custom metadata is set on the back-to-back path too.
+    let mut custom_metadata = metadata::Custom::with_capacity(1);
+    custom_metadata.insert(String::from("kind"), String::from("tiny"));
+    let opts = options::Upload {
+        custom_metadata: Some(custom_metadata),
+        ..Default::default()
+    };
+    project
+        .put_object(&bucket_name, "tiny.txt", b"tiny", Some(&opts))
+        .expect("put small object with metadata");
+    let object = project
+        .stat_object(&bucket_name, "tiny.txt")
+        .expect("stat small object");
+    assert_eq!(
+        Some("tiny"),
+        object.metadata_custom.get("kind").map(String::as_str),
+        "small object custom metadata"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+// Benchmark-style test that compares the duration of uploading 1000 small objects with
+// `Project::put_object` and with the naive write and commit through `std::io::Write`.
+// Run it with `cargo test --test object_test -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark that uploads 2000 objects"]
+fn integration_object_compare_small_uploads() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-small-benchmark");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut data = vec![0u8; 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+
+    let start = Instant::now();
+    for i in 0..1000 {
+        let mut upload = project
+            .upload_object(&bucket_name, format!("naive/{}", i), None)
+            .expect("upload object");
+        upload.write_all(&data).expect("write object data");
+        upload.commit().expect("commit upload");
+        upload.info().expect("upload info");
+    }
+    let naive = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..1000 {
+        project
+            .put_object(&bucket_name, format!("put/{}", i), &data, None)
+            .expect("put object");
+    }
+    let put = start.elapsed();
+
+    println!(
+        "1000 objects of 1 KiB: naive {:?}, put_object {:?} ({:+.2}%)",
+        naive,
+        put,
+        (put.as_secs_f64() / naive.as_secs_f64() - 1.0) * 100.0
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_listing_limit() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-listing-limit");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    for key in ["a.txt", "b.txt", "c.txt"] {
+        project
+            .put_object(&bucket_name, key, b"data", None)
+            .expect("put object");
+    }
+
+    let opts = options::ListObjects::builder()
+        .system(true)
+        .limit(2)
+        .build()
+        .expect("list objects options");
+    let objects = project
+        .list_objects(&bucket_name, Some(&opts))
+        .expect("list objects")
+        .collect::<uplink::Result<Vec<_>>>()
+        .expect("list objects items");
+    assert_eq!(2, objects.len(), "number of listed objects");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_listing_checkpoint() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-listing-checkpoint");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+    let mut keys: Vec<String> = (0..30).map(|i| format!("crawl/{:02}.txt", i)).collect();
+    for key in &keys {
+        project
+            .put_object(&bucket_name, key, b"data", None)
+            .expect("put object");
+    }
+    project
+        .put_object(&bucket_name, "outside.txt", b"data", None)
+        .expect("put object outside of the prefix");
+
+    let mut opts = options::ListObjects::with_prefix("crawl/").expect("list objects options");
+    opts.recursive = true;
+    let mut objects = project
+        .list_objects(&bucket_name, Some(&opts))
+        .expect("list objects");
+    let mut crawled: Vec<String> = objects
+        .by_ref()
+        .take(10)
+        .map(|o| o.expect("listed object").key)
+        .collect();
+    let checkpoint = objects.checkpoint();
+    drop(objects);
+    assert_eq!(checkpoint.bucket(), bucket_name, "checkpoint bucket");
+    assert_eq!(checkpoint.prefix(), "crawl/", "checkpoint prefix");
+    assert_eq!(checkpoint.cursor(), crawled[9], "checkpoint cursor");
+
+    match project.resume_listing("other-bucket", &checkpoint) {
+        Err(Error::InvalidArguments(_)) => {}
+        res => panic!("resume with another bucket: unexpected result: {:?}", res),
+    }
+
+    let resumed: Vec<String> = project
+        .resume_listing(&bucket_name, &checkpoint)
+        .expect("resume listing")
+        .map(|o| o.expect("resumed object").key)
+        .collect();
+    assert_eq!(20, resumed.len(), "number of resumed objects");
+    assert!(
+        !resumed.iter().any(|key| crawled.contains(key)),
+        "resumed objects aren't listed again"
+    );
+
+    // All the objects of the prefix are listed exactly once.
+    crawled.extend(resumed);
+    crawled.sort_unstable();
+    keys.sort_unstable();
+    assert_eq!(crawled, keys, "crawled objects");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_list_all() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-list-all");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+    for key in ["a.txt", "b.txt", "c.txt"] {
+        project
+            .put_object(&bucket_name, key, b"Uplink Rust test object", None)
+            .expect("put object");
+    }
+
+    // Listing all the objects is equivalent to collecting the iterator.
+    let collected: Vec<String> = project
+        .list_objects(&bucket_name, None)
+        .and_then(|it| it.collect::<uplink::Result<Vec<_>>>())
+        .expect("collect objects")
+        .into_iter()
+        .map(|o| o.key)
+        .collect();
+    let all: Vec<String> = project
+        .list_objects_all(&bucket_name, None)
+        .expect("list all objects")
+        .into_iter()
+        .map(|o| o.key)
+        .collect();
+    assert_eq!(collected, all, "listed objects");
+    assert_eq!(3, all.len(), "number of listed objects");
+
+    // Listing all the buckets is equivalent to collecting the iterator.
+    let collected: Vec<String> = project
+        .list_buckets(None)
+        .and_then(|it| it.collect::<uplink::Result<Vec<_>>>())
+        .expect("collect buckets")
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    let all: Vec<String> = project
+        .list_buckets_all(None)
+        .expect("list all buckets")
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    assert_eq!(collected, all, "listed buckets");
+    assert!(
+        all.contains(&bucket_name),
+        "listed buckets contain the bucket"
+    );
+
+    // Listing all the objects of a non-existing bucket returns the error without items.
+    let err = project
+        .list_objects_all("does-not-exist", None)
+        .expect_err("list all objects of a non-existing bucket");
+    assert!(err.items.is_empty(), "no objects listed before the error");
+    match err.error {
+        Error::Uplink(err) if err.code() == UplinkCode::BucketNotFound => {}
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_list_keys() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-list-keys");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+    let mut custom_metadata = metadata::Custom::with_capacity(1);
+    custom_metadata.insert(String::from("key-1"), String::from("value-1"));
+    for key in ["a.txt", "b.txt", "c.txt"] {
+        let mut upload = project
+            .upload_object(&bucket_name, key, None)
+            .expect("upload object");
+        upload
+            .write_all(b"Uplink Rust test object")
+            .expect("write object data");
+        upload
+            .set_custom_metadata(&custom_metadata)
+            .expect("set custom metadata");
+        upload.commit().expect("commit upload");
+    }
+
+    // Listing keys ignores the metadata options and yields the same keys than listing objects.
+    let opts = options::ListObjects::builder()
+        .system(true)
+        .custom(true)
+        .build()
+        .expect("list objects options");
+    let objects: Vec<String> = project
+        .list_objects(&bucket_name, Some(&opts))
+        .expect("list objects")
+        .map(|res| res.expect("list objects item").key)
+        .collect();
+    let mut it = project
+        .list_objects_keys(&bucket_name, Some(&opts))
+        .expect("list objects keys");
+    let keys: Vec<String> = (&mut it)
+        .map(|res| res.expect("list objects keys item"))
+        .collect();
+    assert_eq!(objects, keys, "listed keys");
+    assert_eq!(3, it.yielded_count(), "yielded count");
+    assert_eq!(Some("c.txt"), it.last_seen_key(), "last seen key");
+    assert!(it.finished_with_error().is_none(), "finished without error");
+
+    // The limit is enforced as when listing objects.
+    let opts = options::ListObjects::builder()
+        .limit(2)
+        .build()
+        .expect("list objects options");
+    let keys = project
+        .list_objects_keys(&bucket_name, Some(&opts))
+        .expect("list objects keys with limit")
+        .collect::<uplink::Result<Vec<_>>>()
+        .expect("list objects keys items");
+    assert_eq!(vec!["a.txt", "b.txt"], keys, "listed keys with limit");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_list_filtered() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-list-filtered");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+    for key in [
+        "data.parquet",
+        "logs/2023-12.txt",
+        "logs/2024-01.txt",
+        "logs/2024-02.parquet",
+        "logs/2024-sub/03.txt",
+        "other/x.parquet",
+    ] {
+        project
+            .put_object(&bucket_name, key, b"Uplink Rust test object", None)
+            .expect("put object");
+    }
+
+    let list = |filter: object::KeyFilter, recursive: bool| -> Vec<String> {
+        let opts = options::ListObjects::builder()
+            .recursive(recursive)
+            .build()
+            .expect("list objects options");
+        let mut keys: Vec<String> = project
+            .list_objects_filtered(&bucket_name, filter, Some(&opts))
+            .expect("list objects filtered")
+            .map(|res| res.expect("list objects filtered item").key)
+            .collect();
+        keys.sort();
+        keys
+    };
+
+    // Glob with a literal prefix, which narrows the listing to "logs/".
+    let glob = || object::KeyFilter::Glob(String::from("logs/2024-*"));
+    assert_eq!(
+        vec![
+            "logs/2024-01.txt",
+            "logs/2024-02.parquet",
+            "logs/2024-sub/03.txt"
+        ],
+        list(glob(), true),
+        "recursive glob"
+    );
+    assert_eq!(
+        vec!["logs/2024-01.txt", "logs/2024-02.parquet", "logs/2024-sub/"],
+        list(glob(), false),
+        "non-recursive glob"
+    );
+
+    // Suffix, which doesn't narrow the listing.
+    let suffix = || object::KeyFilter::Suffix(String::from(".parquet"));
+    assert_eq!(
+        vec!["data.parquet", "logs/2024-02.parquet", "other/x.parquet"],
+        list(suffix(), true),
+        "recursive suffix"
+    );
+    assert_eq!(
+        vec!["data.parquet"],
+        list(suffix(), false),
+        "non-recursive suffix"
+    );
+
+    // Closure applied to an existing iterator.
+    let keys: Vec<String> = project
+        .list_objects(&bucket_name, None)
+        .expect("list objects")
+        .filter_keys(object::KeyFilter::Custom(Box::new(|key| {
+            key.ends_with('/')
+        })))
+        .map(|res| res.expect("list objects filter keys item").key)
+        .collect();
+    assert_eq!(vec!["logs/", "other/"], keys, "non-recursive custom filter");
+
+    // The limit applies to the matching objects.
+    let opts = options::ListObjects::builder()
+        .recursive(true)
+        .limit(1)
+        .build()
+        .expect("list objects options");
+    let keys: Vec<String> = project
+        .list_objects_filtered(&bucket_name, suffix(), Some(&opts))
+        .expect("list objects filtered with limit")
+        .map(|res| res.expect("list objects filtered item").key)
+        .collect();
+    assert_eq!(vec!["data.parquet"], keys, "filtered keys with limit");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_download_buf_read() {
+    use std::io::{BufRead, Read};
+
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-download-buf-read");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // An NDJSON object bigger than the download buffer, so lines span several fills.
+    let lines: Vec<String> = (0..5000)
+        .map(|i| {
+            format!(
+                r#"{{"id":{},"name":"record-{}","active":{}}}"#,
+                i,
+                i,
+                i % 2 == 0
+            )
+        })
+        .collect();
+    let data = lines.join("\n");
+    project
+        .put_object(&bucket_name, "data.ndjson", data.as_bytes(), None)
+        .expect("put object");
+
+    let download = project
+        .download_object(&bucket_name, "data.ndjson", None)
+        .expect("download object");
+    let mut downloaded_lines = Vec::with_capacity(lines.len());
+    let mut download_lines = download.lines();
+    for line in &mut download_lines {
+        downloaded_lines.push(line.expect("read line"));
+    }
+    assert_eq!(lines, downloaded_lines, "downloaded lines");
+
+    // EOF is sticky.
+    assert!(download_lines.next().is_none(), "no more lines after EOF");
+
+    // Mixing buffered and unbuffered reads returns the data in order.
+    let mut download = project
+        .download_object(&bucket_name, "data.ndjson", None)
+        .expect("download object");
+    let mut first_line = String::new();
+    download
+        .read_line(&mut first_line)
+        .expect("read first line");
+    assert_eq!(format!("{}\n", lines[0]), first_line, "first line");
+    let mut rest = String::new();
+    download.read_to_string(&mut rest).expect("read the rest");
+    assert_eq!(data[first_line.len()..], rest, "rest of the data");
+    assert!(
+        download.fill_buf().expect("fill buffer at EOF").is_empty(),
+        "fill buffer at EOF"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_upload_and_download_as_raw() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    assert!(!access_grant.as_raw().is_null(), "raw access is NULL");
+    let project = &mut Project::open(&access_grant);
+    assert!(!project.as_raw().is_null(), "raw project is NULL");
+
+    let bucket_name = common::generate_name("object-as-raw");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let mut upload = project
+        .upload_object(&bucket_name, "raw.txt", None)
+        .expect("upload object");
+    assert!(!upload.as_raw().is_null(), "raw upload is NULL");
+    upload.write_all(b"raw").expect("upload object write data");
+    upload.commit().expect("upload object commit");
+
+    let download = project
+        .download_object(&bucket_name, "raw.txt", None)
+        .expect("download object");
+    assert!(!download.as_raw().is_null(), "raw download is NULL");
+
+    // Clean up.
+    drop(download);
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn integration_object_compressed_round_trip() {
+    use std::io::Read;
+    use uplink::compression::{self, Compression};
+
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-compressed-round-trip");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Several MiB mixing random bytes and repetitive text, so the data is compressible but not
+    // trivially.
+    let mut data = vec![0; 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    for i in 0..200_000 {
+        data.extend_from_slice(format!("line {} of the compressible text\n", i % 1000).as_bytes());
+    }
+
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let key = format!("data.{}", compression.name());
+        let obj = project
+            .upload_object_compressed(&bucket_name, &key, &data[..], compression)
+            .expect("upload object compressed");
+        assert_eq!(
+            Compression::from_metadata(&obj.metadata_custom),
+            Some(compression),
+            "{}: recorded compression",
+            compression.name()
+        );
+        assert_eq!(
+            compression::uncompressed_size(&obj.metadata_custom),
+            Some(data.len() as u64),
+            "{}: recorded uncompressed size",
+            compression.name()
+        );
+
+        let obj = project
+            .stat_object(&bucket_name, &key)
+            .expect("stat object");
+        assert!(
+            obj.metadata_system
+                .content_length
+                .is_some_and(|l| l < data.len() as u64),
+            "{}: stored size {} isn't smaller than the original size {}",
+            compression.name(),
+            obj.metadata_system.content_length.unwrap_or(0),
+            data.len()
+        );
+        let compression = Compression::from_metadata(&obj.metadata_custom)
+            .expect("compression recorded in the metadata");
+
+        let mut download = project
+            .download_object_decompressed(&bucket_name, &key, compression)
+            .expect("download object decompressed");
+        let mut downloaded = Vec::with_capacity(data.len());
+        download
+            .read_to_end(&mut downloaded)
+            .expect("read decompressed data");
+        assert!(
+            downloaded == data,
+            "{}: decompressed data differs from the original data",
+            compression.name()
+        );
+    }
+
+    // Decompressing with the wrong algorithm fails.
+    let mut download = project
+        .download_object_decompressed(&bucket_name, "data.gzip", Compression::Zstd)
+        .expect("download object decompressed");
+    download
+        .read_to_end(&mut Vec::new())
+        .expect_err("decompress gzip data with zstd");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_download_close() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-download-close");
+    project.create_bucket(&bucket_name).expect("create bucket");
+    project
+        .put_object(&bucket_name, "data.bin", &[7; 64 * 1024], None)
+        .expect("put object");
+
+    // Closing a partially read download synchronously.
+    let mut download = project
+        .download_object(&bucket_name, "data.bin", None)
+        .expect("download object");
+    let mut buf = [0; 1024];
+    download
+        .read_exact(&mut buf)
+        .expect("read part of the data");
+    download.close().expect("close download");
+
+    // Dropping a partially read download doesn't exceed the close timeout.
+    let abandoned = object::Download::abandoned_closes();
+    let mut download = project
+        .download_object(&bucket_name, "data.bin", None)
+        .expect("download object");
+    download
+        .read_exact(&mut buf)
+        .expect("read part of the data");
+    let start = Instant::now();
+    drop(download);
+    assert!(
+        start.elapsed() <= object::Download::DROP_CLOSE_TIMEOUT + Duration::from_secs(1),
+        "drop waited longer than the close timeout"
+    );
+    assert_eq!(
+        object::Download::abandoned_closes(),
+        abandoned,
+        "close of a healthy connection isn't abandoned"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_download_not_found() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-download-not-found");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    match project.download_object(&bucket_name, "missing.txt", None) {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {}
+        res => panic!("unexpected result: {:?}", res.map(|_| ())),
+    }
+
+    // A lazy download of an existing object is returned without checking it.
+    project
+        .put_object(&bucket_name, "data.txt", b"hello", None)
+        .expect("put object");
+    let opts = options::Download {
+        lazy_open: true,
+        ..options::Download::new(0, -1)
+    };
+    let mut data = String::new();
+    project
+        .download_object(&bucket_name, "data.txt", Some(&opts))
+        .expect("lazy download object")
+        .read_to_string(&mut data)
+        .expect("read object's data");
+    assert_eq!(data, "hello", "object's data");
+
+    // A download started with a timeout checks the object inside of the bounded operation.
+    let opts = options::Download {
+        timeout: Some(Duration::from_secs(30)),
+        ..options::Download::new(0, -1)
+    };
+    match project.download_object(&bucket_name, "missing.txt", Some(&opts)) {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::ObjectNotFound => {}
+        res => panic!("unexpected result with timeout: {:?}", res.map(|_| ())),
+    }
+    let mut data = String::new();
+    project
+        .download_object(&bucket_name, "data.txt", Some(&opts))
+        .expect("download object with timeout")
+        .read_to_string(&mut data)
+        .expect("read object's data");
+    assert_eq!(data, "hello", "object's data with timeout");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with all the objects not to fail");
+}
+
+#[test]
+fn integration_object_list_page() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("object-list-page");
+    project.create_bucket(&bucket_name).expect("create bucket");
+    for key in ["a.txt", "b.txt", "c.txt"] {
+        project
+            .put_object(&bucket_name, key, b"data", None)
+            .expect("put object");
+    }
+
+    let page = project
+        .list_objects_page(&bucket_name, None, 2)
+        .expect("list the first page");
+    let keys: Vec<&str> = page.items.iter().map(|o| o.key.as_str()).collect();
+    assert_eq!(keys, ["a.txt", "b.txt"], "first page keys");
+    assert!(page.is_truncated, "first page is truncated");
+    assert_eq!(
+        page.next_cursor.as_deref(),
+        Some("b.txt"),
+        "first page cursor"
+    );
+
+    let opts = options::ListObjects::with_cursor(&page.next_cursor.unwrap())
+        .expect("list objects options");
+    let page = project
+        .list_objects_page(&bucket_name, Some(&opts), 2)
+        .expect("list the last page");
+    let keys: Vec<&str> = page.items.iter().map(|o| o.key.as_str()).collect();
+    assert_eq!(keys, ["c.txt"], "last page keys");
+    assert!(!page.is_truncated, "last page isn't truncated");
+    assert_eq!(page.next_cursor, None, "last page cursor");
 
     // Clean up.
     project
@@ -0,0 +1,247 @@
+use uplink::access::{Grant, Permission, SharePrefix};
+use uplink::api::ObjectStore;
+use uplink::project::{self, batch::BatchContext};
+use uplink::{Config, Error, Project};
+
+use std::thread;
+use std::time::Duration;
+
+mod common;
+
+// Regression test that verifies that a project keeps working after the access grant used for
+// opening it drops.
+#[test]
+fn integration_project_open_grant_dropped() {
+    let env = common::Environment::load();
+    let project;
+    {
+        let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+        project = Project::open(&grant);
+    }
+
+    let bucket_name = common::generate_name("project-grant-dropped");
+    project
+        .create_bucket(&bucket_name)
+        .expect("create bucket after dropping the grant");
+
+    // Give time to the Go runtime to run the garbage collector and use the project repeatedly.
+    for _ in 0..10 {
+        thread::sleep(Duration::from_millis(200));
+
+        let found = project
+            .list_buckets(None)
+            .expect("list buckets after dropping the grant")
+            .any(|res| res.expect("list bucket item isn't an error").name == bucket_name);
+        assert!(found, "listing buckets contains the created bucket");
+
+        let bucket = project
+            .stat_bucket(&bucket_name)
+            .expect("stat bucket after dropping the grant");
+        assert_eq!(bucket_name, bucket.name, "stat bucket name");
+    }
+
+    // Clean up.
+    project
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_project_open_from_serialized() {
+    let env = common::Environment::load();
+    let project = Project::open_from_serialized(&env.access_grant).expect("open project");
+
+    let bucket_name = common::generate_name("project-open-serialized");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    // The access grant of the project can be shared.
+    let grant = project.grant();
+    let shared = grant
+        .share(
+            &Permission::read_only(),
+            Some(vec![
+                SharePrefix::full_bucket(&bucket_name).expect("share prefix")
+            ]),
+        )
+        .expect("share the grant of the project");
+    let shared = shared.serialize().expect("serialize shared grant");
+
+    let config =
+        Config::new("uplink-rust-test", Duration::from_secs(10), None).expect("configuration");
+    let shared_project =
+        Project::open_from_serialized_with_config(&shared, &config).expect("open shared project");
+    let bucket = shared_project
+        .stat_bucket(&bucket_name)
+        .expect("stat bucket with the shared grant");
+    assert_eq!(bucket_name, bucket.name, "stat bucket name");
+    shared_project.close().expect("close shared project");
+
+    // Clean up.
+    project
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_project_open_from_serialized_errors() {
+    let env = common::Environment::load();
+
+    for access in ["invalid", "in\0valid"] {
+        match Project::open_from_serialized(access) {
+            Err(Error::Internal(internal)) => assert_eq!(
+                internal.ctx_msg,
+                project::OPEN_ERROR_CTX_GRANT,
+                "{:?}: error context",
+                access
+            ),
+            Err(err) => panic!("{:?}: unexpected error: {:?}", access, err),
+            Ok(_) => panic!("{:?}: unexpected project", access),
+        }
+    }
+
+    // The project cannot be opened with an invalid user agent.
+    let config = Config::new("uplink-rust (unterminated", Duration::from_secs(10), None)
+        .expect("configuration");
+    match Project::open_from_serialized_with_config(&env.access_grant, &config) {
+        Err(err @ Error::Internal(_)) => {
+            let inner = std::error::Error::source(&err).expect("inner error");
+            assert!(
+                matches!(inner.downcast_ref::<Error>(), Some(Error::Uplink(_))),
+                "inner uplink error: {:?}",
+                inner
+            );
+            match err {
+                Error::Internal(internal) => assert_eq!(
+                    internal.ctx_msg,
+                    project::OPEN_ERROR_CTX_PROJECT,
+                    "error context"
+                ),
+                _ => unreachable!(),
+            }
+        }
+        Err(err) => panic!("invalid user agent: unexpected error: {:?}", err),
+        Ok(_) => panic!("invalid user agent: unexpected project"),
+    }
+}
+
+#[test]
+fn integration_project_close() {
+    let env = common::Environment::load();
+    let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&grant);
+
+    let bucket_name = common::generate_name("project-close");
+    project.create_bucket(&bucket_name).expect("create bucket");
+    project
+        .delete_bucket(&bucket_name)
+        .expect("clean up: delete bucket");
+
+    project
+        .close()
+        .expect("close a project with established connections");
+
+    // A project which was never used.
+    Project::open(&grant)
+        .close()
+        .expect("close an unused project");
+}
+
+#[test]
+fn integration_project_object_store_delete() {
+    let env = common::Environment::load();
+    let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&grant);
+
+    let bucket_name = common::generate_name("project-store-delete");
+    ObjectStore::make_bucket(&project, &bucket_name).expect("make bucket");
+    ObjectStore::put(&project, &bucket_name, "key", b"data").expect("put object");
+    ObjectStore::delete(&project, &bucket_name, "key").expect("delete object");
+
+    // The access grant can read the objects, so the satellite reports the missing object.
+    ObjectStore::delete(&project, &bucket_name, "key").expect("delete a non-existing object");
+
+    ObjectStore::delete_bucket(&project, &bucket_name).expect("clean up: delete bucket");
+}
+
+#[test]
+fn integration_project_check_quota() {
+    let env = common::Environment::load();
+    let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&grant);
+
+    // The bundled uplink-c version doesn't expose the limits, so the check uses a probe upload.
+    assert!(
+        matches!(project.limits(), Err(Error::Unsupported(_))),
+        "project limits are unsupported"
+    );
+
+    let bucket_name = common::generate_name("project-quota");
+    project.create_bucket(&bucket_name).expect("create bucket");
+
+    let check = project
+        .check_quota(&bucket_name, 1024)
+        .expect("check quota");
+    assert_eq!(check, project::quota::QuotaCheck::Available, "quota check");
+
+    let pending = project
+        .list_uploads(&bucket_name, None)
+        .expect("list uploads")
+        .count();
+    assert_eq!(pending, 0, "the probe upload is aborted");
+
+    match project.check_quota(&common::generate_name("project-quota-missing"), 1024) {
+        Err(Error::Uplink(err)) => assert_eq!(
+            err.code(),
+            uplink::error::UplinkCode::BucketNotFound,
+            "missing bucket"
+        ),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up: delete bucket with objects");
+}
+
+#[test]
+fn integration_project_batch() {
+    let env = common::Environment::load();
+    let grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = Project::open(&grant);
+
+    let buckets = [
+        common::generate_name("project-batch"),
+        common::generate_name("project-batch"),
+    ];
+    let mut batch = BatchContext::new();
+    for bucket in &buckets {
+        batch
+            .ensure_bucket(bucket)
+            .put_object(bucket, "manifest.json", b"{}".to_vec(), None);
+    }
+    batch.ensure_bucket("Invalid_Bucket");
+
+    let report = batch.run(&project, 4);
+    let errors: Vec<_> = report.errors().collect();
+    assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+    assert_eq!(errors[0].0, 4, "failed operation");
+    assert!(
+        matches!(errors[0].1, Error::InvalidArguments(_)),
+        "invalid bucket name error: {:?}",
+        errors[0].1
+    );
+
+    for bucket in &buckets {
+        let obj = project
+            .stat_object(bucket, "manifest.json")
+            .expect("stat the uploaded manifest");
+        assert_eq!(obj.metadata_system.content_length, Some(2), "manifest size");
+    }
+
+    // Clean up.
+    for bucket in &buckets {
+        project
+            .delete_bucket_with_objects(bucket)
+            .expect("clean up: delete bucket with all the objects not to fail");
+    }
+}
@@ -0,0 +1,48 @@
+use uplink::error::UplinkCode;
+use uplink::testing::TestProject;
+use uplink::{Error, Project};
+
+use std::panic::{self, AssertUnwindSafe};
+
+/// Asserts that the bucket doesn't exist.
+fn assert_bucket_not_found(project: &Project, bucket: &str, ctx_msg: &str) {
+    match project.stat_bucket(bucket) {
+        Err(Error::Uplink(err)) if err.code() == UplinkCode::BucketNotFound => {}
+        res => panic!("{}: unexpected result: {:?}", ctx_msg, res),
+    }
+}
+
+#[test]
+fn integration_testing_test_project_cleanup() {
+    let checker = TestProject::from_env().expect("checker test project");
+
+    // The buckets are deleted with their objects, including the ones already deleted.
+    let (with_objects, deleted) = {
+        let tp = TestProject::from_env().expect("test project");
+        let with_objects = tp.create_bucket("testing-objects").expect("create bucket");
+        tp.project()
+            .put_object(&with_objects, "dir/key", b"data", None)
+            .expect("put object");
+
+        let deleted = tp.create_bucket("testing-deleted").expect("create bucket");
+        tp.project()
+            .delete_bucket(&deleted)
+            .expect("delete bucket before dropping");
+        tp.register_bucket("uplink-rust-testing-never-created");
+
+        (with_objects, deleted)
+    };
+    assert_bucket_not_found(checker.project(), &with_objects, "bucket with objects");
+    assert_bucket_not_found(checker.project(), &deleted, "already deleted bucket");
+
+    // The buckets are deleted when the test panics.
+    let mut created = None;
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let tp = TestProject::from_env().expect("test project");
+        created = Some(tp.create_bucket("testing-panic").expect("create bucket"));
+        panic!("test panic");
+    }));
+    assert!(res.is_err(), "closure panicked");
+    let created = created.expect("bucket created before panicking");
+    assert_bucket_not_found(checker.project(), &created, "bucket of a panicked test");
+}
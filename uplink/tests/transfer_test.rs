@@ -0,0 +1,184 @@
+use uplink::access::Grant;
+use uplink::project::options;
+use uplink::transfer::{self, TransferOptions};
+use uplink::{metadata, Project};
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use rand::{self, RngCore};
+
+mod common;
+
+#[test]
+fn integration_transfer_copy_object_and_prefix() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    // Two handles stand in for two projects.
+    let src = Project::open(&access_grant);
+    let dst = Project::open(&access_grant);
+
+    let src_bucket = common::generate_name("transfer-src");
+    let (_bucket, _ok) = src
+        .create_bucket(&src_bucket)
+        .expect("create source bucket");
+    let dst_bucket = common::generate_name("transfer-dst");
+    let (_bucket, _ok) = dst
+        .create_bucket(&dst_bucket)
+        .expect("create destination bucket");
+
+    // A small object with custom metadata and expiration time.
+    let mut small_data = vec![0u8; 100 * 1024];
+    rand::thread_rng().fill_bytes(&mut small_data);
+    let expires = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("current time")
+        + Duration::from_secs(24 * 60 * 60);
+    let mut custom = metadata::Custom::with_capacity(1);
+    custom.insert(String::from("origin"), String::from("uplink-rust"));
+    {
+        let mut upload = src
+            .upload_object(
+                &src_bucket,
+                "data/small.bin",
+                Some(&options::Upload {
+                    expires: Some(expires),
+                    ..Default::default()
+                }),
+            )
+            .expect("upload small object");
+        upload.write_all(&small_data).expect("write small object");
+        upload
+            .set_custom_metadata(&custom)
+            .expect("set custom metadata");
+        upload.commit().expect("commit small object");
+    }
+
+    // An object big enough for being copied with a multipart upload.
+    let mut big_data = vec![0u8; 11 * 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut big_data);
+    src.put_object(&src_bucket, "data/big.bin", &big_data, None)
+        .expect("put big object");
+
+    let multipart_opts = || TransferOptions {
+        multipart_threshold: TransferOptions::MIN_PART_SIZE,
+        part_size: TransferOptions::MIN_PART_SIZE,
+        ..Default::default()
+    };
+
+    // Copy a single object with a single upload.
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let obj = transfer::copy_object(
+        &src,
+        &src_bucket,
+        "data/small.bin",
+        &dst,
+        &dst_bucket,
+        "copied/small.bin",
+        TransferOptions {
+            buffer_size: 16 * 1024,
+            progress: Some(Box::new({
+                let reports = Arc::clone(&reports);
+                move |key: &str, n| reports.lock().unwrap().push((String::from(key), n))
+            })),
+            ..Default::default()
+        },
+    )
+    .expect("copy small object");
+    assert_eq!("copied/small.bin", obj.key, "copied object key");
+    assert_eq!(
+        Some(&String::from("uplink-rust")),
+        obj.metadata_custom.get("origin"),
+        "copied object custom metadata"
+    );
+    assert_eq!(
+        Some(expires.as_secs()),
+        obj.metadata_system.expires.map(|e| e.as_secs()),
+        "copied object expiration time"
+    );
+    {
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() > 1, "progress reports");
+        assert!(
+            reports.iter().all(|(key, _)| key == "copied/small.bin"),
+            "progress report keys"
+        );
+        assert_eq!(
+            Some(small_data.len() as u64),
+            reports.last().map(|(_, n)| *n),
+            "last progress report"
+        );
+    }
+    assert_eq!(
+        small_data,
+        dst.get_object(&dst_bucket, "copied/small.bin", None)
+            .expect("get copied small object"),
+        "copied small object data"
+    );
+
+    // Copy a single object with a multipart upload.
+    let obj = transfer::copy_object(
+        &src,
+        &src_bucket,
+        "data/big.bin",
+        &dst,
+        &dst_bucket,
+        "copied/big.bin",
+        multipart_opts(),
+    )
+    .expect("copy big object");
+    assert_eq!(
+        Some(big_data.len() as u64),
+        obj.metadata_system.content_length,
+        "copied big object size"
+    );
+    assert_eq!(
+        big_data,
+        dst.get_object(&dst_bucket, "copied/big.bin", None)
+            .expect("get copied big object"),
+        "copied big object data"
+    );
+
+    // Copy a prefix.
+    let copied = transfer::copy_prefix(
+        &src,
+        &src_bucket,
+        "data/",
+        &dst,
+        &dst_bucket,
+        multipart_opts(),
+    )
+    .expect("copy prefix");
+    let keys: Vec<&str> = copied.iter().map(|o| o.key.as_str()).collect();
+    assert_eq!(vec!["data/big.bin", "data/small.bin"], keys, "copied keys");
+    let obj = dst
+        .stat_object(&dst_bucket, "data/small.bin")
+        .expect("stat copied small object");
+    assert_eq!(
+        Some(&String::from("uplink-rust")),
+        obj.metadata_custom.get("origin"),
+        "copied prefix object custom metadata"
+    );
+
+    // Invalid options.
+    transfer::copy_object(
+        &src,
+        &src_bucket,
+        "data/small.bin",
+        &dst,
+        &dst_bucket,
+        "copied/invalid.bin",
+        TransferOptions {
+            part_size: 1024,
+            ..Default::default()
+        },
+    )
+    .expect_err("copy with an invalid part size");
+
+    // Clean up.
+    src.delete_bucket_with_objects(&src_bucket)
+        .expect("clean up: delete source bucket with all the objects not to fail");
+    dst.delete_bucket_with_objects(&dst_bucket)
+        .expect("clean up: delete destination bucket with all the objects not to fail");
+}
@@ -0,0 +1,104 @@
+use uplink::testing::TestProject;
+use uplink::tuning::MultipartTuning;
+use uplink::Error;
+
+use std::time::Instant;
+
+use rand::{self, RngCore};
+
+#[test]
+fn integration_tuning_upload_object_from_reader() {
+    let tp = TestProject::from_env().expect("test project");
+    let project = tp.project();
+    let bucket_name = tp.create_bucket("tuning").expect("create bucket");
+
+    // 3 parts of the minimum size, uploaded concurrently, and a smaller last part.
+    let mut data = vec![0u8; 3 * MultipartTuning::MIN_PART_SIZE as usize + 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    let tuning = MultipartTuning {
+        part_size: MultipartTuning::MIN_PART_SIZE,
+        max_concurrent_parts: 2,
+    };
+    let obj = project
+        .upload_object_from_reader(&bucket_name, "tuned.bin", &data[..], None, &tuning)
+        .expect("upload object from reader");
+    assert_eq!("tuned.bin", obj.key, "uploaded object key");
+    assert_eq!(
+        Some(data.len() as u64),
+        obj.metadata_system.content_length,
+        "uploaded object size"
+    );
+    assert_eq!(
+        data,
+        project
+            .get_object(&bucket_name, "tuned.bin", None)
+            .expect("get uploaded object"),
+        "uploaded object data"
+    );
+
+    // Invalid tuning.
+    match project.upload_object_from_reader(
+        &bucket_name,
+        "invalid.bin",
+        &data[..],
+        None,
+        &MultipartTuning {
+            part_size: 1024,
+            ..Default::default()
+        },
+    ) {
+        Err(Error::InvalidArguments(args)) => {
+            assert_eq!("tuning{part_size}", args.names, "invalid argument name")
+        }
+        res => panic!("unexpected result: {:?}", res.map(|o| o.key)),
+    }
+}
+
+// Benchmark-style test that compares the duration of uploading 100 MiB with two tunings.
+// Run it with `cargo test --test tuning_test -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark that uploads 200 MiB"]
+fn integration_tuning_compare_100mib_upload() {
+    let tp = TestProject::from_env().expect("test project");
+    let project = tp.project();
+    let bucket_name = tp.create_bucket("tuning-benchmark").expect("create bucket");
+
+    let mut data = vec![0u8; 100 * 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+
+    for (name, tuning) in [
+        (
+            "sequential-5mib-parts",
+            MultipartTuning {
+                part_size: MultipartTuning::MIN_PART_SIZE,
+                max_concurrent_parts: 1,
+            },
+        ),
+        (
+            "concurrent-16mib-parts",
+            MultipartTuning {
+                part_size: 16 * 1024 * 1024,
+                max_concurrent_parts: 4,
+            },
+        ),
+    ] {
+        let start = Instant::now();
+        let obj = project
+            .upload_object_from_reader(&bucket_name, name, &data[..], None, &tuning)
+            .expect("upload object from reader");
+        let elapsed = start.elapsed();
+        assert_eq!(
+            Some(data.len() as u64),
+            obj.metadata_system.content_length,
+            "{}: uploaded object size",
+            name
+        );
+
+        println!(
+            "{}: {:?} ({:.2} MiB/s)",
+            name,
+            elapsed,
+            100.0 / elapsed.as_secs_f64()
+        );
+    }
+}
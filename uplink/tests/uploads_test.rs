@@ -1,5 +1,5 @@
 use uplink::access::Grant;
-use uplink::error;
+use uplink::error::{self, UplinkCode};
 use uplink::project::options;
 use uplink::{metadata, Error, Project};
 
@@ -86,6 +86,62 @@ fn integration_upload_commit_and_abort() {
         .expect("clean up delete bucket with objects");
 }
 
+#[test]
+fn integration_upload_info_states() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &mut Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("upload-info-states");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // The information of open and committed uploads is available.
+    let upload = &mut project
+        .upload_object(&bucket_name, "committed.txt", None)
+        .expect("upload object");
+    upload
+        .write_all(b"Uplink Rust test object")
+        .expect("upload object write data");
+    assert_eq!(
+        "committed.txt",
+        upload.info().expect("info of an open upload").key,
+        "open upload key"
+    );
+    upload.commit().expect("upload object commit");
+    assert_eq!(
+        "committed.txt",
+        upload.info().expect("info of a committed upload").key,
+        "committed upload key"
+    );
+
+    // The information of aborted uploads isn't available.
+    let upload = &mut project
+        .upload_object(&bucket_name, "aborted.txt", None)
+        .expect("upload object");
+    upload
+        .write_all(b"Uplink Rust test object")
+        .expect("upload object write data");
+    upload.abort().expect("abort an uncommitted upload");
+    match upload.info().expect_err("info of an aborted upload") {
+        Error::InvalidArguments(error::Args { names, msg }) => {
+            assert_eq!("upload", names, "invalid argument");
+            assert_eq!("upload already aborted", msg, "error message");
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    // Committing an aborted upload fails and the upload remains aborted.
+    upload.commit().expect_err("commit an aborted upload");
+    upload
+        .info()
+        .expect_err("info of an aborted upload after commit");
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up delete bucket with objects");
+}
+
 #[test]
 fn integration_upload_multipart_commit() {
     let env = common::Environment::load();
@@ -106,7 +162,8 @@ fn integration_upload_multipart_commit() {
     assert_eq!(object_empty_key, object.key, "object key");
     assert!(!object.is_prefix, "object is prefix");
     assert_eq!(
-        0, object.metadata_system.content_length,
+        Some(0),
+        object.metadata_system.content_length,
         "object content length"
     );
 
@@ -129,6 +186,36 @@ fn integration_upload_multipart_commit() {
     // assert_eq!(upload_info.upload_id, item.upload_id, "pending upload key");
     assert_eq!(object_multipart_key, item.key, "pending upload key");
     assert!(!item.is_prefix, "pending upload is prefix");
+    assert!(
+        !item.has_system_metadata(),
+        "pending upload without requested system metadata"
+    );
+    assert!(
+        !item.has_custom_metadata(),
+        "pending upload without requested custom metadata"
+    );
+    assert!(it.next().is_none(), "only one pending upload in the list");
+
+    // List uploads requesting the system and custom metadata.
+    let mut list_opts = options::ListUploads::default();
+    list_opts.system = true;
+    list_opts.custom = true;
+    let mut it = project
+        .list_uploads(&bucket_name, Some(&list_opts))
+        .expect("list uploads with metadata");
+    let item = it
+        .next()
+        .expect("an item in the uploads list with metadata")
+        .expect("a pending upload with metadata");
+    assert_eq!(object_multipart_key, item.key, "pending upload key");
+    assert!(
+        item.has_system_metadata(),
+        "pending upload with requested system metadata"
+    );
+    assert!(
+        item.has_custom_metadata(),
+        "pending upload with requested custom metadata"
+    );
     assert!(it.next().is_none(), "only one pending upload in the list");
 
     // Uploading 2 parts in reverse order using `data`.
@@ -253,8 +340,8 @@ fn integration_upload_multipart_commit() {
         "downloaded object created at cannot be 0",
     );
     assert_eq!(
-        data.len(),
-        downloaded_object.metadata_system.content_length as usize,
+        Some(data.len() as u64),
+        downloaded_object.metadata_system.content_length,
         "uploaded object content length"
     );
 
@@ -274,6 +361,90 @@ fn integration_upload_multipart_commit() {
         .expect("clean up delete bucket with objects");
 }
 
+#[test]
+fn integration_upload_multipart_handle() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("upload");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    // Multipart upload with multiple parts.
+    let object_multipart_key = "test-multipart.txt";
+    let upload = project
+        .begin_multipart_upload(&bucket_name, object_multipart_key, None)
+        .expect("begin multipart upload");
+    assert_eq!(bucket_name, upload.bucket(), "upload bucket");
+    assert_eq!(object_multipart_key, upload.key(), "upload key");
+    assert_eq!(
+        upload.info().upload_id,
+        upload.upload_id(),
+        "upload ID of the info"
+    );
+
+    // Uploading 2 parts in reverse order using `data`.
+    // A part must be at least of 5 MiB.
+    let mut data = vec![0u8; 10 * 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    for (part_number, chunk) in [(1, &data[data.len() / 2..]), (0, &data[..data.len() / 2])] {
+        let mut part = upload.upload_part(part_number).expect("upload part");
+        part.write_all(chunk).expect("write data part");
+        part.commit().expect("commit part");
+    }
+
+    // List parts of the multipart pending upload.
+    let mut parts: Vec<(u32, usize)> = upload
+        .list_parts(None)
+        .expect("list upload parts")
+        .map(|part| {
+            let part = part.expect("a part in the pending upload");
+            (part.part_number, part.size)
+        })
+        .collect();
+    parts.sort_unstable();
+    assert_eq!(
+        vec![(0, data.len() / 2), (1, data.len() - data.len() / 2)],
+        parts,
+        "pending upload parts"
+    );
+
+    // Commit the upload and download the object to verify it.
+    let object_committed = upload.commit(None).expect("commit a multipart upload");
+    assert_eq!(object_multipart_key, object_committed.key, "object key");
+
+    let mut downloaded_data = Vec::with_capacity(data.len());
+    project
+        .download_object(&bucket_name, object_multipart_key, None)
+        .expect("download object")
+        .read_to_end(&mut downloaded_data)
+        .expect("download object read");
+    assert!(data == downloaded_data, "downloaded object data");
+
+    // Abort a multipart upload.
+    let upload = project
+        .begin_multipart_upload(&bucket_name, "test-aborted.txt", None)
+        .expect("begin multipart upload to abort");
+    upload.abort().expect("abort multipart upload");
+    match upload.commit(None) {
+        Err(Error::Uplink(_)) => {}
+        res => panic!("unexpected result committing an aborted upload: {:?}", res),
+    }
+    assert!(
+        project
+            .list_uploads(&bucket_name, None)
+            .expect("list uploads")
+            .next()
+            .is_none(),
+        "no pending uploads"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up delete bucket with objects");
+}
+
 #[test]
 fn integration_upload_multipart_abort_and_list_parts_cursor() {
     let env = common::Environment::load();
@@ -329,10 +500,7 @@ fn integration_upload_multipart_abort_and_list_parts_cursor() {
             &bucket_name,
             object_multipart_key,
             &upload_info.upload_id,
-            Some(&options::ListUploadParts {
-                cursor: 0,
-                ..Default::default()
-            }),
+            Some(&options::ListUploadParts::with_cursor(0)),
         )
         .expect("list upload parts");
 
@@ -354,10 +522,7 @@ fn integration_upload_multipart_abort_and_list_parts_cursor() {
             &bucket_name,
             object_multipart_key,
             &upload_info.upload_id,
-            Some(&options::ListUploadParts {
-                cursor: 0,
-                ..Default::default()
-            }),
+            Some(&options::ListUploadParts::with_cursor(0)),
         )
         .expect("list upload parts");
     assert!(it.next().is_none(), "no parts in the part iterator");
@@ -373,7 +538,7 @@ fn integration_upload_multipart_abort_and_list_parts_cursor() {
         .stat_object(&bucket_name, object_multipart_key)
         .expect_err("object not found");
     match err {
-        Error::Uplink(error::Uplink::ObjectNotFound(_)) => {}
+        Error::Uplink(err) if err.code() == UplinkCode::ObjectNotFound => {}
         _ => panic!("expected object not found error, found: {}", err),
     }
 
@@ -410,7 +575,7 @@ fn integration_upload_multipart_commit_custom_metadata() {
             &bucket_name,
             object_key,
             &upload_info.upload_id,
-            Some(&mut options::CommitUpload::new(&mut custom_metadata)),
+            Some(&options::CommitUpload::new(custom_metadata)),
         )
         .expect("commit upload empty object");
 
@@ -442,3 +607,133 @@ fn integration_upload_multipart_commit_custom_metadata() {
         .delete_bucket_with_objects(&bucket_name)
         .expect("clean up delete bucket with objects");
 }
+
+#[test]
+fn integration_upload_multipart_accumulated_metadata() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("multipart-metadata");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    let object_key = "test-accumulated-metadata.txt";
+    let mut upload = project
+        .begin_multipart_upload(&bucket_name, object_key, None)
+        .expect("begin multipart upload");
+
+    // Accumulate the custom metadata while the parts are uploaded.
+    let mut data = vec![0u8; 6 * 1024 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    for (part_number, chunk) in [(1, &data[..data.len() / 2]), (2, &data[data.len() / 2..])] {
+        let mut part = upload.upload_part(part_number).expect("upload part");
+        part.write_all(chunk).expect("write data part");
+        part.set_etag(format!("etag-{}", part_number).as_bytes())
+            .expect("set part etag");
+        part.commit().expect("commit part");
+        upload.metadata_mut().insert(
+            format!("part-{}-size", part_number),
+            chunk.len().to_string(),
+        );
+    }
+    upload.parts_manifest().expect("parts manifest");
+
+    let object = upload.commit(None).expect("commit a multipart upload");
+    assert_eq!(object_key, object.key, "object key");
+
+    let object = project
+        .stat_object(&bucket_name, object_key)
+        .expect("stat object");
+    let custom = &object.metadata_custom;
+    assert_eq!(
+        custom.get("part-1-size").map(String::as_str),
+        Some((data.len() / 2).to_string().as_str()),
+        "accumulated entry of part 1"
+    );
+    assert_eq!(
+        custom.get("part-2-size").map(String::as_str),
+        Some((data.len() - data.len() / 2).to_string().as_str()),
+        "accumulated entry of part 2"
+    );
+    assert_eq!(
+        custom.get(metadata::KEY_PARTS_MANIFEST).map(String::as_str),
+        Some(
+            format!(
+                r#"[{{"part_number":1,"size":{},"etag_hex":"{}"}},{{"part_number":2,"size":{},"etag_hex":"{}"}}]"#,
+                data.len() / 2,
+                "657461672d31",
+                data.len() - data.len() / 2,
+                "657461672d32"
+            )
+            .as_str()
+        ),
+        "parts manifest"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up delete bucket with objects");
+}
+
+#[test]
+fn integration_upload_abort_uploads() {
+    let env = common::Environment::load();
+    let access_grant = Grant::new(&env.access_grant).expect("access grant parsing");
+    let project = &Project::open(&access_grant);
+
+    let bucket_name = common::generate_name("abort-uploads");
+    let (_bucket, _ok) = project.create_bucket(&bucket_name).expect("create bucket");
+
+    for key in ["root.txt", "dir/a.txt", "dir/sub/b.txt"] {
+        project
+            .begin_multipart_upload(&bucket_name, key, None)
+            .expect("begin multipart upload");
+    }
+
+    match project.abort_uploads(&bucket_name, Some("dir")) {
+        Err(Error::InvalidArguments(error::Args { names, .. })) => {
+            assert_eq!(names, "prefix", "invalid argument name")
+        }
+        res => panic!(
+            "prefix without trailing slash: unexpected result: {:?}",
+            res
+        ),
+    }
+
+    // Abort the uploads under a prefix, including the ones of its sub-prefixes.
+    let summary = project
+        .abort_uploads(&bucket_name, Some("dir/"))
+        .expect("abort uploads under a prefix");
+    assert_eq!(summary.aborted, 2, "aborted uploads under the prefix");
+    assert!(summary.all_aborted(), "failed aborts: {:?}", summary.failed);
+
+    let mut opts = options::ListUploads::default();
+    opts.recursive = true;
+    let keys: Vec<String> = project
+        .list_uploads(&bucket_name, Some(&opts))
+        .expect("list uploads")
+        .map(|info| info.expect("pending upload").key)
+        .collect();
+    assert_eq!(keys, ["root.txt"], "pending uploads outside of the prefix");
+
+    // Abort the rest of the uploads.
+    let summary = project
+        .abort_uploads(&bucket_name, None)
+        .expect("abort all the uploads");
+    assert_eq!(summary.aborted, 1, "aborted uploads");
+    assert!(summary.all_aborted(), "failed aborts: {:?}", summary.failed);
+    assert!(
+        project
+            .list_uploads(&bucket_name, Some(&opts))
+            .expect("list uploads")
+            .next()
+            .is_none(),
+        "no pending uploads"
+    );
+
+    // Clean up.
+    project
+        .delete_bucket_with_objects(&bucket_name)
+        .expect("clean up delete bucket with objects");
+}